@@ -0,0 +1,137 @@
+#!/usr/bin/env python3
+"""Generates src/excel_parser/shared_strings/unicode_tables.rs.
+
+The tables hold the Unicode Character Database data needed for NFC/NFKC
+normalization and full case folding, taken from the `unicodedata` module of the running Python
+(its Unicode version is recorded in the output). Run from the repository
+root:
+
+    python3 scripts/gen_unicode_tables.py
+"""
+import platform
+import unicodedata
+
+OUT = "src/excel_parser/shared_strings/unicode_tables.rs"
+
+# Hangul syllables are (de)composed algorithmically.
+HANGUL = range(0xAC00, 0xD7A4)
+
+
+def esc(cp):
+    return "'\\u{%x}'" % cp
+
+
+def esc_str(cps):
+    return '"' + "".join("\\u{%x}" % cp for cp in cps) + '"'
+
+
+def rows(items, per_line):
+    lines = []
+    for i in range(0, len(items), per_line):
+        lines.append("    " + " ".join(items[i:i + per_line]))
+    return "\n".join(lines)
+
+
+canonical, compat, ccc, compositions = [], [], [], []
+for cp in range(0x110000):
+    if cp in HANGUL:
+        continue
+    ch = chr(cp)
+    if unicodedata.combining(ch):
+        ccc.append((cp, unicodedata.combining(ch)))
+    decomposition = unicodedata.decomposition(ch)
+    if not decomposition:
+        continue
+    parts = decomposition.split()
+    if parts[0].startswith("<"):
+        compat.append((cp, [int(p, 16) for p in parts[1:]]))
+        continue
+    mapping = [int(p, 16) for p in parts]
+    canonical.append((cp, mapping))
+    # Primary composites: pairs starting with a starter that NFC recomposes.
+    if (len(mapping) == 2 and unicodedata.combining(chr(mapping[0])) == 0
+            and unicodedata.normalize("NFC", ch) == ch):
+        compositions.append((mapping[0], mapping[1], cp))
+
+compositions.sort()
+
+# Characters that may change under normalization depending on context: not
+# stable on their own, reordered (non-zero combining class), or composing
+# with a preceding character. Strings without any of them are normalized.
+seconds = {b for _, b, _ in compositions} | set(range(0x1161, 0x1176)) | set(range(0x11A8, 0x11C3))
+
+
+def quick_check(form):
+    maybe = []
+    for cp in range(0x110000):
+        ch = chr(cp)
+        if 0xD800 <= cp <= 0xDFFF:
+            continue
+        if (unicodedata.combining(ch) or cp in seconds
+                or not unicodedata.is_normalized(form, ch)):
+            maybe.append(cp)
+    ranges = []
+    for cp in maybe:
+        if ranges and ranges[-1][1] == cp - 1:
+            ranges[-1][1] = cp
+        else:
+            ranges.append([cp, cp])
+    return ranges
+
+
+nfc_check, nfkc_check = quick_check("NFC"), quick_check("NFKC")
+
+# Full case folding where it differs from lowercasing (ß → ss, ς → σ, ...);
+# every other character folds to its lowercase.
+case_folding = []
+for cp in range(0x110000):
+    if 0xD800 <= cp <= 0xDFFF:
+        continue
+    ch = chr(cp)
+    if ch.casefold() != ch.lower():
+        case_folding.append((cp, [ord(c) for c in ch.casefold()]))
+
+with open(OUT, "w") as out:
+    out.write(f"""// Generated by scripts/gen_unicode_tables.py from Unicode {unicodedata.unidata_version} data
+// (the `unicodedata` module of Python {platform.python_version()}). Do not edit by hand;
+// regenerate from the repository root with a Python whose `unicodedata`
+// has the wanted Unicode version:
+//
+//     python3 scripts/gen_unicode_tables.py
+
+/// Canonical decompositions (one level), sorted by code point.
+pub(super) static CANONICAL: &[(char, &str)] = &[
+{rows([f"({esc(cp)}, {esc_str(m)})," for cp, m in canonical], 4)}
+];
+
+/// Compatibility decompositions (one level), sorted by code point.
+pub(super) static COMPATIBILITY: &[(char, &str)] = &[
+{rows([f"({esc(cp)}, {esc_str(m)})," for cp, m in compat], 4)}
+];
+
+/// Non‑zero canonical combining classes, sorted by code point.
+pub(super) static COMBINING_CLASS: &[(char, u8)] = &[
+{rows([f"({esc(cp)}, {c})," for cp, c in ccc], 6)}
+];
+
+/// Ranges of characters that may change under NFC, see `quick_check`
+/// in the generator.
+pub(super) static NFC_MAYBE: &[(char, char)] = &[
+{rows([f"({esc(a)}, {esc(b)})," for a, b in nfc_check], 5)}
+];
+
+/// Ranges of characters that may change under NFKC.
+pub(super) static NFKC_MAYBE: &[(char, char)] = &[
+{rows([f"({esc(a)}, {esc(b)})," for a, b in nfkc_check], 5)}
+];
+
+/// Full case foldings that differ from lowercasing, sorted by code point.
+pub(super) static CASE_FOLDING: &[(char, &str)] = &[
+{rows([f"({esc(cp)}, {esc_str(m)})," for cp, m in case_folding], 4)}
+];
+
+/// Primary composites `(first, second) → composite`, sorted by pair.
+pub(super) static COMPOSITIONS: &[((char, char), char)] = &[
+{rows([f"(({esc(a)}, {esc(b)}), {esc(c)})," for a, b, c in compositions], 3)}
+];
+""")
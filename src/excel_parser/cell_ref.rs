@@ -0,0 +1,121 @@
+// ---------------------------------------------------------------------------
+// A1 cell reference helpers
+// ---------------------------------------------------------------------------
+//
+// Coordinates are 1-based throughout the crate, matching the numbers stored in
+// the XML (`<row r="1">`, `<col min="1">`, `spans="1:5"`): `A1` is (1, 1).
+
+/// Maximum column number in the OOXML grid (`XFD`).
+pub(crate) const MAX_COL: u32 = 16_384;
+/// Maximum row number in the OOXML grid.
+pub(crate) const MAX_ROW: u32 = 1_048_576;
+
+/// Converts column letters (`"A"`, `"xfd"`) to a 1‑based column number.
+///
+/// Letters are case‑insensitive. Returns `None` for empty input, non‑letters or
+/// columns beyond [`MAX_COL`].
+pub(crate) fn column_number(letters: &str) -> Option<u32> {
+    if letters.is_empty() || letters.len() > 3 { return None; }
+    let mut col = 0u32;
+    for b in letters.bytes() {
+        if !b.is_ascii_alphabetic() { return None; }
+        col = col * 26 + u32::from(b.to_ascii_uppercase() - b'A' + 1);
+    }
+    (col <= MAX_COL).then_some(col)
+}
+
+/// Parses an A1 reference (`"B7"`, `"$B$7"`) into 1‑based `(row, col)`.
+///
+/// `$` anchors are ignored. Returns `None` if either part is missing or out of
+/// the grid.
+pub(crate) fn parse_a1(reference: &str) -> Option<(u32, u32)> {
+    let reference = reference.trim();
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let col = column_number(reference[..split].trim_matches('$'))?;
+    let row: u32 = reference[split..].parse().ok()?;
+    (1..=MAX_ROW).contains(&row).then_some((row, col))
+}
+
+/// Converts a 1‑based column number to its letters (`1` → `"A"`, `28` → `"AB"`).
+pub(crate) fn column_letters(mut col: u32) -> String {
+    let mut letters = Vec::with_capacity(3);
+    while col > 0 {
+        col -= 1;
+        letters.push(b'A' + (col % 26) as u8);
+        col /= 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
+}
+
+/// Formats 1‑based `(row, col)` as an A1 reference (`(7, 2)` → `"B7"`).
+pub(crate) fn format_a1(row: u32, col: u32) -> String {
+    format!("{}{}", column_letters(col), row)
+}
+
+/// A rectangular block of cells, inclusive and 1‑based (`A1:C10` is rows
+/// 1–10, columns 1–3). A single cell is a range whose corners coincide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CellRange {
+    /// Top row.
+    pub first_row: u32,
+    /// Leftmost column.
+    pub first_col: u32,
+    /// Bottom row.
+    pub last_row: u32,
+    /// Rightmost column.
+    pub last_col: u32,
+}
+
+impl CellRange {
+    /// Parses an A1 range (`"A1:C10"`, `"$B$2"`), normalizing the corners so
+    /// that `first` is the top‑left one.
+    ///
+    /// Returns `None` if either corner is not a valid cell reference.
+    pub(crate) fn parse(range: &str) -> Option<Self> {
+        let (first, last) = range.split_once(':').unwrap_or((range, range));
+        let (r1, c1) = parse_a1(first)?;
+        let (r2, c2) = parse_a1(last)?;
+        Some(Self { first_row: r1.min(r2), first_col: c1.min(c2), last_row: r1.max(r2), last_col: c1.max(c2) })
+    }
+
+    /// Parses an area of a formula reference: an A1 range, or whole rows
+    /// (`"$1:$3"`) or columns (`"$A:$C"`), which span the full grid.
+    ///
+    /// Returns `None` for anything else.
+    pub(crate) fn parse_area(area: &str) -> Option<Self> {
+        if let Some(range) = Self::parse(area) { return Some(range); }
+
+        // A lone `A` or `1` is a name or a number, not a column or row.
+        let (first, last) = area.split_once(':')?;
+        let (first, last) = (first.trim().trim_start_matches('$'), last.trim().trim_start_matches('$'));
+        if let (Ok(a), Ok(b)) = (first.parse::<u32>(), last.parse::<u32>()) {
+            if a == 0 || b == 0 || a.max(b) > MAX_ROW { return None; }
+            return Some(Self { first_row: a.min(b), first_col: 1, last_row: a.max(b), last_col: MAX_COL });
+        }
+        let (a, b) = (column_number(first)?, column_number(last)?);
+        Some(Self { first_row: 1, first_col: a.min(b), last_row: MAX_ROW, last_col: a.max(b) })
+    }
+
+    /// Number of rows in the range.
+    pub fn height(&self) -> u32 { self.last_row - self.first_row + 1 }
+
+    /// Number of columns in the range.
+    pub fn width(&self) -> u32 { self.last_col - self.first_col + 1 }
+
+    /// Returns `true` if the cell at 1‑based `(row, col)` lies in the range.
+    pub fn contains(&self, row: u32, col: u32) -> bool {
+        (self.first_row..=self.last_row).contains(&row) && (self.first_col..=self.last_col).contains(&col)
+    }
+}
+
+impl std::fmt::Display for CellRange {
+    /// Formats the range as `A1:C10`, or `B2` for a single cell.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format_a1(self.first_row, self.first_col))?;
+        if (self.first_row, self.first_col) != (self.last_row, self.last_col) {
+            write!(f, ":{}", format_a1(self.last_row, self.last_col))?;
+        }
+        Ok(())
+    }
+}
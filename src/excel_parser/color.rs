@@ -0,0 +1,44 @@
+use quick_xml::events::BytesStart;
+use super::xml_utils::attr_value;
+
+// ---------------------------------------------------------------------------
+// Color – SpreadsheetML color reference (CT_Color)
+// ---------------------------------------------------------------------------
+
+/// A color as written in SpreadsheetML (`<tabColor>`, `<color>`, `<fgColor>`, ...).
+///
+/// Colors are either explicit ARGB values or references into the workbook
+/// theme or the legacy indexed palette; references are kept unresolved, since
+/// resolving them requires the theme part or palette.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    /// Explicit color as `0xAARRGGBB` (`rgb="FFFF0000"` is opaque red).
+    Argb(u32),
+    /// Theme color index, lightened (positive) or darkened (negative) by `tint`
+    /// in `-1.0..=1.0`.
+    Theme { index: u32, tint: f64 },
+    /// Index into the legacy 64‑color palette.
+    Indexed(u32),
+    /// Application‑defined automatic color (`auto="1"`).
+    Auto,
+}
+
+impl Color {
+    /// Reads a color from the attributes of a color element.
+    ///
+    /// `rgb` takes precedence over `theme`, `indexed` and `auto`, which is the
+    /// order Excel resolves them in. Returns `None` if no attribute is usable.
+    pub(crate) fn from_element(e: &BytesStart<'_>) -> Option<Self> {
+        if let Some(argb) = attr_value(e, b"rgb").and_then(|v| u32::from_str_radix(&v, 16).ok()) {
+            return Some(Color::Argb(argb));
+        }
+        if let Some(index) = attr_value(e, b"theme").and_then(|v| v.parse().ok()) {
+            let tint = attr_value(e, b"tint").and_then(|v| v.parse().ok()).unwrap_or(0.0);
+            return Some(Color::Theme { index, tint });
+        }
+        if let Some(index) = attr_value(e, b"indexed").and_then(|v| v.parse().ok()) {
+            return Some(Color::Indexed(index));
+        }
+        attr_value(e, b"auto").is_some_and(|v| v == "1" || v == "true").then_some(Color::Auto)
+    }
+}
@@ -0,0 +1,348 @@
+use quick_xml::{Reader, events::Event};
+use rustc_hash::FxHashMap;
+use super::cell_ref::parse_a1;
+use super::xml_utils::{attr_value, push_entity};
+
+// ---------------------------------------------------------------------------
+// Comments – cell notes of a worksheet (xl/commentsN.xml)
+// ---------------------------------------------------------------------------
+
+/// A cell comment (note).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Comment {
+    /// Note text, with its formatting runs joined.
+    pub text: String,
+    /// Author name, from `<authors>`; `None` if the note has no valid
+    /// `authorId`.
+    pub author: Option<String>,
+    /// Whether the note is always shown rather than on hover. Only known
+    /// from the VML drawing of the notes (see [`Comments::apply_vml`]);
+    /// `false` without it.
+    pub visible: bool,
+    /// Where the note box is drawn, from the VML drawing; `None` without it.
+    pub anchor: Option<NoteAnchor>,
+}
+
+/// Position of a note box on the sheet (`<x:Anchor>` of its VML shape).
+///
+/// Rows and columns are 1‑based like cell positions; offsets are in pixels
+/// from the top left corner of that cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoteAnchor {
+    /// Column of the left edge.
+    pub left_col: u32,
+    /// Offset of the left edge into `left_col`.
+    pub left_offset: u32,
+    /// Row of the top edge.
+    pub top_row: u32,
+    /// Offset of the top edge into `top_row`.
+    pub top_offset: u32,
+    /// Column of the right edge.
+    pub right_col: u32,
+    /// Offset of the right edge into `right_col`.
+    pub right_offset: u32,
+    /// Row of the bottom edge.
+    pub bottom_row: u32,
+    /// Offset of the bottom edge into `bottom_row`.
+    pub bottom_offset: u32,
+}
+
+impl NoteAnchor {
+    /// Parses the text of `<x:Anchor>`: eight comma‑separated numbers,
+    /// zero‑based columns and rows each followed by an offset.
+    fn parse(text: &str) -> Option<Self> {
+        let mut values = text.split(',').map(|v| v.trim().parse::<u32>().ok());
+        let mut next = || values.next().flatten();
+        let anchor = Self {
+            left_col: next()? + 1,
+            left_offset: next()?,
+            top_row: next()? + 1,
+            top_offset: next()?,
+            right_col: next()? + 1,
+            right_offset: next()?,
+            bottom_row: next()? + 1,
+            bottom_offset: next()?,
+        };
+        Some(anchor)
+    }
+}
+
+/// Element of a VML note shape whose text is being read.
+#[derive(Clone, Copy, PartialEq)]
+enum VmlField {
+    None,
+    Anchor,
+    Row,
+    Column,
+}
+
+/// Cell comments (notes) of one worksheet.
+///
+/// Each worksheet with notes has a companion comments part, linked from the
+/// worksheet's relationships. A note's text may be split into formatted runs;
+/// the runs are concatenated into plain text, formatting and phonetic hints
+/// are dropped. How notes are drawn (shown or on hover, box position) is
+/// kept in a separate legacy VML drawing, read with
+/// [`apply_vml`](Self::apply_vml).
+///
+/// # XML Structure
+/// ```xml
+/// <comments>
+///   <authors><author>Ann</author></authors>
+///   <commentList>
+///     <comment ref="B2" authorId="0">
+///       <text><r><rPr><b/></rPr><t>Ann:</t></r><r><t> check this</t></r></text>
+///     </comment>
+///   </commentList>
+/// </comments>
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Comments {
+    /// `(row, col)` (1‑based) → note.
+    notes: FxHashMap<(u32, u32), Comment>,
+}
+
+impl Comments {
+    /// Parses a comments part.
+    ///
+    /// Comments with a missing or invalid `ref` are skipped.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/commentsN.xml` part.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    pub fn parse(xml: &[u8]) -> Result<Self, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        let config = reader.config_mut();
+        config.trim_text(false);
+        config.check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut notes = FxHashMap::default();
+        let mut authors: Vec<String> = Vec::new();
+        // Position and contents of the comment being read.
+        let mut current: Option<((u32, u32), Comment)> = None;
+        let mut in_author = false;
+        let mut in_text = false;
+        let mut in_phonetic = false;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) => match e.local_name().as_ref() {
+                    b"author" => {
+                        authors.push(String::new());
+                        in_author = true;
+                    }
+                    b"comment" => {
+                        let author = attr_value(e, b"authorId")
+                            .and_then(|id| authors.get(id.trim().parse::<usize>().ok()?).cloned());
+                        current = attr_value(e, b"ref")
+                            .and_then(|r| parse_a1(&r))
+                            .map(|pos| (pos, Comment { author, ..Comment::default() }));
+                    }
+                    b"t" if !in_phonetic => in_text = current.is_some(),
+                    b"rPh" => in_phonetic = true,
+                    _ => {}
+                },
+                Event::Empty(ref e) if e.local_name().as_ref() == b"author" => authors.push(String::new()),
+                Event::Text(ref e) if in_author => {
+                    if let Some(author) = authors.last_mut() { author.push_str(&String::from_utf8_lossy(e)); }
+                }
+                Event::GeneralRef(ref e) if in_author => {
+                    if let Some(author) = authors.last_mut() { push_entity(author, e); }
+                }
+                Event::Text(ref e) if in_text => {
+                    if let Some((_, note)) = current.as_mut() {
+                        note.text.push_str(&String::from_utf8_lossy(e));
+                    }
+                }
+                Event::GeneralRef(ref e) if in_text => {
+                    if let Some((_, note)) = current.as_mut() { push_entity(&mut note.text, e); }
+                }
+                Event::End(ref e) => match e.local_name().as_ref() {
+                    b"author" => in_author = false,
+                    b"t" => in_text = false,
+                    b"rPh" => in_phonetic = false,
+                    b"comment" => {
+                        if let Some((pos, note)) = current.take() { notes.insert(pos, note); }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(Self { notes })
+    }
+
+    /// Reads the VML drawing of the notes (`xl/drawings/vmlDrawingN.vml`,
+    /// the sheet's `vmlDrawing` relationship) and records, for each note,
+    /// whether it is always shown and where its box is anchored.
+    ///
+    /// Shapes that are not notes (form controls share the drawing) and
+    /// shapes of cells without a note are ignored.
+    ///
+    /// # XML Structure
+    /// ```xml
+    /// <xml xmlns:v="urn:schemas-microsoft-com:vml" xmlns:x="urn:schemas-microsoft-com:office:excel">
+    ///   <v:shape type="#_x0000_t202" style="visibility:hidden">
+    ///     <x:ClientData ObjectType="Note">
+    ///       <x:Anchor>2, 15, 0, 10, 4, 15, 4, 4</x:Anchor>
+    ///       <x:Row>1</x:Row>
+    ///       <x:Column>1</x:Column>
+    ///       <x:Visible/>
+    ///     </x:ClientData>
+    ///   </v:shape>
+    /// </xml>
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    pub fn apply_vml(&mut self, xml: &[u8]) -> Result<(), quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        // VML from Excel isn't always well‑formed XML (unclosed `<br>`).
+        reader.config_mut().check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut in_note = false;
+        let mut field = VmlField::None;
+        let mut text = String::new();
+        let (mut anchor, mut row, mut col, mut visible) = (None, None, None, false);
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) => match e.local_name().as_ref() {
+                    b"ClientData" => {
+                        in_note = attr_value(e, b"ObjectType").as_deref() == Some("Note");
+                        (anchor, row, col, visible) = (None, None, None, false);
+                    }
+                    b"Anchor" if in_note => field = VmlField::Anchor,
+                    b"Row" if in_note => field = VmlField::Row,
+                    b"Column" if in_note => field = VmlField::Column,
+                    b"Visible" if in_note => visible = true,
+                    _ => {}
+                },
+                Event::Text(ref e) if field != VmlField::None => text.push_str(&String::from_utf8_lossy(e)),
+                Event::End(ref e) => match e.local_name().as_ref() {
+                    b"Anchor" | b"Row" | b"Column" if field != VmlField::None => {
+                        match field {
+                            VmlField::Anchor => anchor = NoteAnchor::parse(&text),
+                            VmlField::Row => row = text.trim().parse::<u32>().ok(),
+                            VmlField::Column => col = text.trim().parse::<u32>().ok(),
+                            VmlField::None => {}
+                        }
+                        field = VmlField::None;
+                        text.clear();
+                    }
+                    b"ClientData" if in_note => {
+                        in_note = false;
+                        if let (Some(row), Some(col)) = (row, col)
+                            && let Some(note) = self.notes.get_mut(&(row + 1, col + 1))
+                        {
+                            note.visible = visible;
+                            note.anchor = anchor;
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Returns the note text of a cell.
+    ///
+    /// # Arguments
+    /// * `row`, `col` – 1‑based cell position.
+    pub fn get(&self, row: u32, col: u32) -> Option<&str> {
+        self.notes.get(&(row, col)).map(|note| note.text.as_str())
+    }
+
+    /// Returns the note of a cell with its author.
+    ///
+    /// # Arguments
+    /// * `row`, `col` – 1‑based cell position.
+    pub fn comment(&self, row: u32, col: u32) -> Option<&Comment> {
+        self.notes.get(&(row, col))
+    }
+
+    /// Number of notes.
+    pub fn len(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// Returns `true` if the sheet has no notes.
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// Returns all notes as `((row, col), text)`, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = ((u32, u32), &str)> {
+        self.notes.iter().map(|(&pos, note)| (pos, note.text.as_str()))
+    }
+
+    /// Returns all notes as `((row, col), comment)`, in no particular order.
+    pub fn comments(&self) -> impl Iterator<Item = ((u32, u32), &Comment)> {
+        self.notes.iter().map(|(&pos, note)| (pos, note))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMENTS: &str = r#"<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+  <authors><author>Reviewer</author><author>Иванов &amp; Co</author></authors>
+  <commentList>
+    <comment ref="B2" authorId="1"><text><r><rPr><b/></rPr><t>Иванов:</t></r><r><t xml:space="preserve"> проверить &lt;итог&gt;</t></r></text></comment>
+    <comment ref="A1" authorId="7"><text><t>Plain</t><rPh sb="0" eb="1"><t>reading</t></rPh></text></comment>
+    <comment ref="bogus" authorId="0"><text><t>Lost</t></text></comment>
+  </commentList>
+</comments>"#;
+
+    #[test]
+    fn parse_joins_runs_and_resolves_authors() {
+        let comments = Comments::parse(COMMENTS.as_bytes()).unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments.get(2, 2), Some("Иванов: проверить <итог>"));
+        assert_eq!(comments.comment(2, 2).unwrap().author.as_deref(), Some("Иванов & Co"));
+        // Phonetic runs are dropped; an unknown author id gives no author.
+        assert_eq!(comments.comment(1, 1), Some(&Comment { text: "Plain".into(), ..Comment::default() }));
+        assert_eq!(comments.get(3, 3), None);
+        assert!(Comments::parse(b"<comments/>").unwrap().is_empty());
+    }
+
+    #[test]
+    fn apply_vml_records_visibility_and_anchors_of_notes() {
+        let vml = r#"<xml xmlns:v="urn:schemas-microsoft-com:vml" xmlns:x="urn:schemas-microsoft-com:office:excel">
+  <v:shape><x:ClientData ObjectType="Note">
+    <x:Anchor>2, 15, 0, 10, 4, 15, 4, 4</x:Anchor><x:Row>1</x:Row><x:Column>1</x:Column><x:Visible/>
+  </x:ClientData></v:shape>
+  <v:shape><x:ClientData ObjectType="Note"><x:Anchor>1, 0, 0</x:Anchor><x:Row>0</x:Row><x:Column>0</x:Column></x:ClientData></v:shape>
+  <v:shape><x:ClientData ObjectType="Button"><x:Row>0</x:Row><x:Column>0</x:Column><x:Visible/></x:ClientData></v:shape>
+  <v:shape><x:ClientData ObjectType="Note"><x:Row>9</x:Row><x:Column>9</x:Column><x:Visible/><br></x:ClientData></v:shape>
+</xml>"#;
+        let mut comments = Comments::parse(COMMENTS.as_bytes()).unwrap();
+        comments.apply_vml(vml.as_bytes()).unwrap();
+
+        let b2 = comments.comment(2, 2).unwrap();
+        assert!(b2.visible);
+        assert_eq!(b2.anchor, Some(NoteAnchor {
+            left_col: 3, left_offset: 15, top_row: 1, top_offset: 10,
+            right_col: 5, right_offset: 15, bottom_row: 5, bottom_offset: 4,
+        }));
+        // A malformed anchor is dropped; the button shape doesn't touch A1.
+        let a1 = comments.comment(1, 1).unwrap();
+        assert!(!a1.visible && a1.anchor.is_none());
+        assert_eq!(comments.len(), 2);
+    }
+}
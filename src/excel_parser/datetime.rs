@@ -0,0 +1,330 @@
+use std::fmt;
+
+// ---------------------------------------------------------------------------
+// DateTime – calendar date and time of a cell value
+// ---------------------------------------------------------------------------
+
+/// A calendar date and time without time zone, as cells hold them.
+///
+/// Spreadsheets have no time zones: a date cell shows the same wall‑clock
+/// time wherever the file is opened, so this is a plain field container
+/// rather than an instant. Fields are not range‑checked beyond what parsing
+/// guarantees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DateTime {
+    /// Year (`2024`).
+    pub year: i32,
+    /// Month, `1..=12`.
+    pub month: u8,
+    /// Day of the month, `1..=31`.
+    pub day: u8,
+    /// Hour, `0..=23`.
+    pub hour: u8,
+    /// Minute, `0..=59`.
+    pub minute: u8,
+    /// Second, `0..=59`.
+    pub second: u8,
+    /// Millisecond, `0..=999`; finer fractions are truncated.
+    pub millisecond: u16,
+}
+
+impl DateTime {
+    /// Parses the ISO 8601 form used by `t="d"` cells: `2024-03-15`,
+    /// `2024-03-15T08:30:00`, `2024-03-15T08:30:00.250Z` or a time alone
+    /// (`08:30:00`, dated `1899-12-31` as Excel stores times of day).
+    ///
+    /// A trailing `Z` or UTC offset is accepted and ignored. Returns `None`
+    /// for anything else, including out‑of‑range fields.
+    pub(crate) fn parse_iso(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (date, time) = match text.split_once('T') {
+            Some((date, time)) => (Some(date), Some(time)),
+            None if text.contains(':') => (None, Some(text)),
+            None => (Some(text), None),
+        };
+
+        let mut dt = Self { year: 1899, month: 12, day: 31, ..Self::default() };
+        if let Some(date) = date {
+            let mut parts = date.splitn(3, '-');
+            dt.year = parts.next()?.parse().ok()?;
+            dt.month = parts.next()?.parse().ok()?;
+            dt.day = parts.next()?.parse().ok()?;
+            if !(1..=12).contains(&dt.month) || dt.day == 0 || dt.day > days_in_month(dt.year, dt.month) {
+                return None;
+            }
+        }
+        if let Some(time) = time {
+            let time = time.trim_end_matches('Z');
+            let time = time.rfind(['+', '-']).map_or(time, |offset| &time[..offset]);
+            let (hms, fraction) = time.split_once('.').unwrap_or((time, ""));
+            let mut parts = hms.splitn(3, ':');
+            dt.hour = parts.next()?.parse().ok()?;
+            dt.minute = parts.next()?.parse().ok()?;
+            dt.second = parts.next().map_or(Some(0), |s| s.parse().ok())?;
+            if dt.hour > 23 || dt.minute > 59 || dt.second > 59 { return None; }
+            if !fraction.is_empty() {
+                if !fraction.bytes().all(|b| b.is_ascii_digit()) { return None; }
+                let millis = format!("{fraction:0<3}");
+                dt.millisecond = millis[..3].parse().ok()?;
+            }
+        }
+        Some(dt)
+    }
+
+    /// Converts an Excel serial number (days since the epoch of `system`,
+    /// the time of day as fraction) to a date and time.
+    ///
+    /// In the 1900 system, Excel counts a 29 February 1900 that never
+    /// existed, carried over from Lotus 1‑2‑3 for compatibility: serial 60
+    /// is returned as that very date, as Excel displays it, and serials
+    /// below it are shifted by one day so that 1 is 1900‑01‑01. A serial
+    /// below 1 is a time of day and is dated 1899‑12‑31, like times in
+    /// `t="d"` cells. In the 1904 system serial 0 is 1904‑01‑01 and there is
+    /// no such day. The fraction is rounded to the millisecond.
+    ///
+    /// Returns `None` for negative serials and beyond 9999‑12‑31, which
+    /// Excel doesn't display as dates either.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{DateSystem, DateTime};
+    /// let date = DateTime::from_serial(45366.5, DateSystem::V1900).unwrap();
+    /// assert_eq!(date.to_string(), "2024-03-15T12:00:00");
+    /// // The same day in a workbook saved with the 1904 system:
+    /// assert_eq!(DateTime::from_serial(43904.5, DateSystem::V1904), Some(date));
+    /// ```
+    pub fn from_serial(serial: f64, system: DateSystem) -> Option<Self> {
+        if !(0.0..system.max_serial()).contains(&serial) { return None; }
+
+        let mut days = serial.trunc() as i64;
+        let mut millis = ((serial - serial.trunc()) * MILLIS_PER_DAY as f64).round() as i64;
+        if millis == MILLIS_PER_DAY {
+            days += 1;
+            millis = 0;
+        }
+
+        let (year, month, day) = match (system, days) {
+            (DateSystem::V1904, days) => civil_from_days(days + EPOCH_1904),
+            (DateSystem::V1900, 60) => (1900, 2, 29),
+            // Serial 0 is 1900‑01‑00, i.e. 1899‑12‑31.
+            (DateSystem::V1900, ..60) => civil_from_days(days + 1 + EPOCH_1900),
+            (DateSystem::V1900, days) => civil_from_days(days + EPOCH_1900),
+        };
+        Some(Self {
+            year,
+            month,
+            day,
+            hour: (millis / 3_600_000) as u8,
+            minute: (millis / 60_000 % 60) as u8,
+            second: (millis / 1000 % 60) as u8,
+            millisecond: (millis % 1000) as u16,
+        })
+    }
+
+    /// Converts back to an Excel serial number in `system`, the inverse of
+    /// [`from_serial`](Self::from_serial): in the 1900 system 1900‑02‑29 is
+    /// serial 60 and earlier dates are shifted back by a day. Dates before
+    /// the epoch give negative serials.
+    pub fn to_serial(self, system: DateSystem) -> f64 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let days = match system {
+            DateSystem::V1904 => days - EPOCH_1904,
+            DateSystem::V1900 if (self.year, self.month, self.day) == (1900, 2, 29) => 60,
+            DateSystem::V1900 => match days - EPOCH_1900 {
+                days @ ..61 => days - 1,
+                days => days,
+            },
+        };
+        let millis = i64::from(self.hour) * 3_600_000 + i64::from(self.minute) * 60_000
+            + i64::from(self.second) * 1000 + i64::from(self.millisecond);
+        days as f64 + millis as f64 / MILLIS_PER_DAY as f64
+    }
+}
+
+impl fmt::Display for DateTime {
+    /// Formats as ISO 8601, `2024-03-15T08:30:00`, with milliseconds only
+    /// when they are not zero.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second)?;
+        if self.millisecond != 0 { write!(f, ".{:03}", self.millisecond)?; }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DateSystem – epoch of serial numbers
+// ---------------------------------------------------------------------------
+
+/// Date system of a workbook: the day serial numbers count from.
+///
+/// Workbooks use the 1900 system unless `xl/workbook.xml` sets
+/// `<workbookPr date1904="1"/>`, as files created by early Excel for Mac
+/// do. The same serial is 1462 days (four years and a day) later in the
+/// 1904 system, so dates must be read with the workbook's system; see
+/// [`Workbook::date_system`](super::Workbook::date_system).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DateSystem {
+    /// Serial 1 is 1900‑01‑01, with the phantom 1900‑02‑29 as serial 60.
+    #[default]
+    V1900,
+    /// Serial 0 is 1904‑01‑01.
+    V1904,
+}
+
+impl DateSystem {
+    /// Serial just past 9999‑12‑31.
+    fn max_serial(self) -> f64 {
+        match self {
+            Self::V1900 => 2_958_466.0,
+            Self::V1904 => 2_957_004.0,
+        }
+    }
+}
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// 1899‑12‑30 in days since 1970‑01‑01: serial 0 for dates after the
+/// phantom leap day.
+const EPOCH_1900: i64 = -25_569;
+
+/// 1904‑01‑01 in days since 1970‑01‑01.
+const EPOCH_1904: i64 = -24_107;
+
+/// Converts days since 1970‑01‑01 to a proleptic Gregorian `(year, month,
+/// day)` (H. Hinnant's `civil_from_days`).
+fn civil_from_days(days: i64) -> (i32, u8, u8) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+    (year, month, day)
+}
+
+/// Converts a proleptic Gregorian date to days since 1970‑01‑01, the
+/// inverse of [`civil_from_days`].
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (i64::from(month) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Number of days in `month` of `year` in the proleptic Gregorian calendar.
+pub(crate) fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u8, day: u8) -> DateTime {
+        DateTime { year, month, day, ..DateTime::default() }
+    }
+
+    fn at(day: DateTime, hour: u8, minute: u8, second: u8, millisecond: u16) -> DateTime {
+        DateTime { hour, minute, second, millisecond, ..day }
+    }
+
+    #[test]
+    fn parses_iso_dates_and_times() {
+        assert_eq!(DateTime::parse_iso("2024-03-15"), Some(date(2024, 3, 15)));
+        assert_eq!(DateTime::parse_iso("2024-03-15T08:30:00"), Some(at(date(2024, 3, 15), 8, 30, 0, 0)));
+        assert_eq!(DateTime::parse_iso("2024-03-15T08:30:00.25Z"), Some(at(date(2024, 3, 15), 8, 30, 0, 250)));
+        assert_eq!(DateTime::parse_iso("2024-03-15T08:30+03:00"), Some(at(date(2024, 3, 15), 8, 30, 0, 0)));
+        assert_eq!(DateTime::parse_iso("08:30:05"), Some(at(date(1899, 12, 31), 8, 30, 5, 0)));
+        for text in ["2023-02-29", "2024-13-01", "2024-03-00", "24:00:00", "08:60", "2024-03", "soon", "08:30:00.x"] {
+            assert_eq!(DateTime::parse_iso(text), None, "{text}");
+        }
+        assert_eq!(DateTime::parse_iso("2024-02-29"), Some(date(2024, 2, 29)));
+    }
+
+    #[test]
+    fn serials_around_the_phantom_leap_day() {
+        let system = DateSystem::V1900;
+        assert_eq!(DateTime::from_serial(0.0, system), Some(date(1899, 12, 31)));
+        assert_eq!(DateTime::from_serial(1.0, system), Some(date(1900, 1, 1)));
+        assert_eq!(DateTime::from_serial(59.0, system), Some(date(1900, 2, 28)));
+        assert_eq!(DateTime::from_serial(60.0, system), Some(date(1900, 2, 29)));
+        assert_eq!(DateTime::from_serial(61.0, system), Some(date(1900, 3, 1)));
+        assert_eq!(DateTime::from_serial(45_366.0, system), Some(date(2024, 3, 15)));
+    }
+
+    #[test]
+    fn time_of_day_is_rounded_to_the_millisecond() {
+        let system = DateSystem::V1900;
+        assert_eq!(DateTime::from_serial(0.5, system), Some(at(date(1899, 12, 31), 12, 0, 0, 0)));
+        assert_eq!(DateTime::from_serial(45_366.75, system), Some(at(date(2024, 3, 15), 18, 0, 0, 0)));
+        // 08:30:00.250, stored as the nearest double.
+        let serial = 45_366.0 + (8.0 * 3600.0 + 30.0 * 60.0 + 0.25) / 86_400.0;
+        assert_eq!(DateTime::from_serial(serial, system), Some(at(date(2024, 3, 15), 8, 30, 0, 250)));
+        // Less than half a millisecond before midnight rounds to the next day.
+        assert_eq!(DateTime::from_serial(45_366.999_999_999, system), Some(date(2024, 3, 16)));
+        assert_eq!(DateTime::from_serial(59.999_999_999, system), Some(date(1900, 2, 29)));
+    }
+
+    #[test]
+    fn out_of_range_serials_are_not_dates() {
+        let system = DateSystem::V1900;
+        for serial in [-1.0, -0.000_1, 2_958_466.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(DateTime::from_serial(serial, system), None, "{serial}");
+        }
+        assert_eq!(DateTime::from_serial(2_958_465.5, system), Some(at(date(9999, 12, 31), 12, 0, 0, 0)));
+    }
+
+    #[test]
+    fn displays_as_iso() {
+        assert_eq!(date(2024, 3, 5).to_string(), "2024-03-05T00:00:00");
+        assert_eq!(at(date(987, 1, 2), 8, 30, 5, 40).to_string(), "0987-01-02T08:30:05.040");
+    }
+
+    #[test]
+    fn serials_in_the_1904_system() {
+        let system = DateSystem::V1904;
+        assert_eq!(DateTime::from_serial(0.0, system), Some(date(1904, 1, 1)));
+        assert_eq!(DateTime::from_serial(1.0, system), Some(date(1904, 1, 2)));
+        assert_eq!(DateTime::from_serial(59.0, system), Some(date(1904, 2, 29)));
+        assert_eq!(DateTime::from_serial(0.25, system), Some(at(date(1904, 1, 1), 6, 0, 0, 0)));
+        assert_eq!(DateTime::from_serial(2_957_003.5, system), Some(at(date(9999, 12, 31), 12, 0, 0, 0)));
+        for serial in [-1.0, 2_957_004.0] {
+            assert_eq!(DateTime::from_serial(serial, system), None, "{serial}");
+        }
+    }
+
+    #[test]
+    fn the_systems_are_1462_days_apart() {
+        for serial in [1462.0, 20_000.25, 45_366.5, 2_958_465.0] {
+            let in_1900 = DateTime::from_serial(serial, DateSystem::V1900);
+            assert_eq!(in_1900, DateTime::from_serial(serial - 1462.0, DateSystem::V1904), "{serial}");
+        }
+        let day = date(2024, 3, 15);
+        assert_eq!(day.to_serial(DateSystem::V1900) - day.to_serial(DateSystem::V1904), 1462.0);
+    }
+
+    #[test]
+    fn to_serial_inverts_from_serial() {
+        for system in [DateSystem::V1900, DateSystem::V1904] {
+            for serial in [0.0, 1.0, 59.0, 60.0, 61.0, 0.5, 45_366.75, 2_957_003.0] {
+                let dt = DateTime::from_serial(serial, system).unwrap();
+                assert_eq!(dt.to_serial(system), serial, "{system:?} {serial}");
+            }
+        }
+        assert_eq!(date(1900, 2, 29).to_serial(DateSystem::V1900), 60.0);
+        // Before the epoch.
+        assert_eq!(date(1903, 12, 31).to_serial(DateSystem::V1904), -1.0);
+    }
+}
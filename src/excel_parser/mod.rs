@@ -1,7 +1,46 @@
 mod zipfs;
 mod shared_strings;
+mod pivot_cache;
+mod relationships;
+mod comments;
+mod table;
+mod workbook;
+mod worksheet;
+mod workbook_info;
+mod styles;
+mod cell_ref;
+mod color;
+mod datetime;
+mod xml_utils;
+#[cfg(test)]
+mod test_util;
 
 pub use zipfs::ZipFs;
+pub use zipfs::{ZipFsBuilder, DuplicatePolicy};
 pub use zipfs::FilterSet;
 pub use zipfs::ZipFsError;
-pub use shared_strings::SharedStrings;
+pub use zipfs::BudgetExceeded;
+pub use zipfs::EntryMeta;
+pub use zipfs::{CrcMismatch, DuplicateEntry};
+pub use zipfs::EntryReader;
+pub use zipfs::ContentTypes;
+pub use zipfs::MemoryReport;
+pub use zipfs::{SourceReader, ZipSource};
+pub use shared_strings::{SharedStrings, SearchOptions, SearchView, LoadOptions, SearchIndex, SearchMatch, QueryError};
+pub use shared_strings::{CaseMatching, Algorithm};
+pub use shared_strings::{RichString, TextRun, RunFont, StringIter, SharedStringsIter, Normalization, CountMismatch};
+pub use pivot_cache::{PivotCacheDef, CacheField};
+pub use relationships::{Relationships, Relationship};
+pub use comments::{Comments, Comment, NoteAnchor};
+pub use table::{Table, TableColumn, TableRow};
+pub use workbook::{Workbook, PrintTitles};
+pub use workbook_info::{SheetInfo, SheetState, DefinedName, RangeRef};
+pub use worksheet::{Worksheet, WorksheetOptions, WorksheetError, Row, Cell, CellType, SheetProtection, ColInfo, SheetFormat, SheetPr, CondFormat, CfRule, Hyperlink};
+pub use worksheet::{CfValue, ColorScale, DataBar, IconSet};
+pub use worksheet::{AutoFilter, FilterColumn, FilterCriteria, CustomFilter, DateGroup};
+pub use worksheet::{CellValue, CellError, RowIter};
+pub use worksheet::{DataValidation, ValidationType, ValidationOperator, ValidationErrorStyle, ListSource};
+pub use datetime::{DateTime, DateSystem};
+pub use color::Color;
+pub use styles::{Styles, CellFormat, Fill, Dxf};
+pub use cell_ref::CellRange;
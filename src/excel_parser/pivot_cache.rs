@@ -0,0 +1,154 @@
+use quick_xml::{Reader, events::Event};
+use super::xml_utils::attr_value;
+
+// ---------------------------------------------------------------------------
+// PivotCacheDef – source field list of a pivot cache (xl/pivotCache/*.xml)
+// ---------------------------------------------------------------------------
+
+/// A single source field of a pivot cache.
+///
+/// Corresponds to a `<cacheField>` element. `shared_items` holds the distinct
+/// values Excel recorded for the field (the `v` attribute of each `<s>`, `<n>`,
+/// `<b>`, `<d>` or `<e>` item); missing values (`<m/>`) are kept as empty
+/// strings so that item indices stay aligned with the pivot cache records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheField {
+    /// Field (column) name as it appears in the pivot table source.
+    pub name: String,
+    /// Distinct values of the field, in document order.
+    pub shared_items: Vec<String>,
+}
+
+/// Parser for pivot cache definitions (`xl/pivotCache/pivotCacheDefinition*.xml`).
+///
+/// Only the field list is extracted; cache records, calculated items and
+/// grouping information are ignored. The parser is read‑only.
+///
+/// # XML Structure
+/// ```xml
+/// <pivotCacheDefinition>
+///   <cacheFields count="2">
+///     <cacheField name="Region"><sharedItems><s v="North"/><s v="South"/></sharedItems></cacheField>
+///     <cacheField name="Amount"><sharedItems containsNumber="1"/></cacheField>
+///   </cacheFields>
+/// </pivotCacheDefinition>
+/// ```
+#[derive(Debug)]
+pub struct PivotCacheDef;
+
+impl PivotCacheDef {
+    /// Glob matching pivot cache definition parts inside an `.xlsx` archive.
+    ///
+    /// Add it to the [`FilterSet`](super::FilterSet) passed to
+    /// [`ZipFs::new`](super::ZipFs::new) to make the parts available.
+    pub const GLOB: &'static str = "xl/pivotCache/pivotCacheDefinition*.xml";
+
+    /// Parses a pivot cache definition and returns its source fields.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `pivotCacheDefinition*.xml` part.
+    ///
+    /// # Returns
+    /// The cache fields in document order, or a `quick_xml::Error` if parsing fails.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::PivotCacheDef;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("xl/pivotCache/pivotCacheDefinition1.xml")?;
+    /// for field in PivotCacheDef::parse(&data)? {
+    ///     println!("{} ({} items)", field.name, field.shared_items.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse(xml: &[u8]) -> Result<Vec<CacheField>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut fields = Vec::new();
+        let mut current: Option<CacheField> = None;
+        // Items are only collected inside `<sharedItems>`; `<fieldGroup>`
+        // holds its own `<groupItems>` with the same item elements.
+        let mut in_shared_items = false;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) if e.name().as_ref() == b"cacheField" => {
+                    current = Some(CacheField {
+                        name: attr_value(e, b"name").unwrap_or_default(),
+                        shared_items: Vec::new(),
+                    });
+                }
+                Event::Empty(ref e) if e.name().as_ref() == b"cacheField" => {
+                    fields.push(CacheField {
+                        name: attr_value(e, b"name").unwrap_or_default(),
+                        shared_items: Vec::new(),
+                    });
+                }
+                Event::Start(ref e) if e.name().as_ref() == b"sharedItems" => in_shared_items = current.is_some(),
+                Event::Start(ref e) | Event::Empty(ref e) if in_shared_items => {
+                    if matches!(e.name().as_ref(), b"s" | b"n" | b"b" | b"d" | b"e" | b"m")
+                        && let Some(field) = current.as_mut()
+                    {
+                        field.shared_items.push(attr_value(e, b"v").unwrap_or_default());
+                    }
+                }
+                Event::End(ref e) if e.name().as_ref() == b"sharedItems" => in_shared_items = false,
+                Event::End(ref e) if e.name().as_ref() == b"cacheField" => {
+                    fields.extend(current.take());
+                    in_shared_items = false;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFINITION: &[u8] = br#"<pivotCacheDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+  <cacheSource type="worksheet"><worksheetSource ref="A1:C10" sheet="Data"/></cacheSource>
+  <cacheFields count="3">
+    <cacheField name="Region" numFmtId="0">
+      <sharedItems count="3"><s v="North"/><s v="South &amp; East"/><m/></sharedItems>
+    </cacheField>
+    <cacheField name="Amount" numFmtId="0"><sharedItems containsNumber="1" minValue="1" maxValue="9"/></cacheField>
+    <cacheField name="Date" numFmtId="14">
+      <sharedItems containsDate="1"><d v="2024-01-05T00:00:00"/><d v="2024-02-10T00:00:00"/></sharedItems>
+      <fieldGroup base="2">
+        <rangePr groupBy="months" startDate="2024-01-05T00:00:00" endDate="2024-02-11T00:00:00"/>
+        <groupItems count="3"><s v="&lt;1/5/2024"/><s v="Jan"/><s v="Feb"/></groupItems>
+      </fieldGroup>
+    </cacheField>
+  </cacheFields>
+</pivotCacheDefinition>"#;
+
+    #[test]
+    fn reads_field_names_in_order() {
+        let fields = PivotCacheDef::parse(DEFINITION).unwrap();
+        let names: Vec<_> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["Region", "Amount", "Date"]);
+    }
+
+    #[test]
+    fn keeps_missing_items_as_empty_strings() {
+        let fields = PivotCacheDef::parse(DEFINITION).unwrap();
+        assert_eq!(fields[0].shared_items, ["North", "South & East", ""]);
+        assert!(fields[1].shared_items.is_empty());
+    }
+
+    #[test]
+    fn ignores_group_items() {
+        let fields = PivotCacheDef::parse(DEFINITION).unwrap();
+        assert_eq!(fields[2].shared_items, ["2024-01-05T00:00:00", "2024-02-10T00:00:00"]);
+    }
+}
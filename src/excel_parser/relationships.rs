@@ -0,0 +1,155 @@
+use quick_xml::{Reader, events::Event};
+use super::xml_utils::attr_value;
+
+// ---------------------------------------------------------------------------
+// Relationships – parsed OPC relationship part (*.rels)
+// ---------------------------------------------------------------------------
+
+/// A single `<Relationship>` entry of a `.rels` part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relationship {
+    /// Relationship id referenced from the source part (e.g., `"rId3"`).
+    pub id: String,
+    /// Relationship type URI (e.g., `".../relationships/worksheet"`).
+    pub rel_type: String,
+    /// Target as written in the part: a path relative to the source part, or a
+    /// URI when [`external`](Self::external) is set.
+    pub target: String,
+    /// `true` if `TargetMode="External"`, i.e. the target lives outside the package.
+    pub external: bool,
+}
+
+impl Relationship {
+    /// Where the relationship points when declared by `source_part`: the
+    /// resolved archive path, or the target as written if it is external.
+    pub fn resolve(&self, source_part: &str) -> String {
+        match self.external {
+            true => self.target.clone(),
+            false => Relationships::resolve_target(source_part, &self.target),
+        }
+    }
+}
+
+/// Relationships of one package part, in document order.
+///
+/// Every part of an `.xlsx` package may have a companion `_rels/<name>.rels`
+/// part listing what it references (worksheets, shared strings, external
+/// files, ...). Sources refer to these entries by id (`r:id="rId1"`).
+///
+/// # XML Structure
+/// ```xml
+/// <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+///   <Relationship Id="rId1" Type=".../worksheet" Target="worksheets/sheet1.xml"/>
+///   <Relationship Id="rId2" Type=".../externalLinkPath" Target="file:///C:/other.xlsx" TargetMode="External"/>
+/// </Relationships>
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Relationships {
+    rels: Vec<Relationship>,
+}
+
+impl Relationships {
+    /// Parses a `.rels` part.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of the relationship part.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    pub fn parse(xml: &[u8]) -> Result<Self, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut rels = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) if e.local_name().as_ref() == b"Relationship" => {
+                    rels.push(Relationship {
+                        id: attr_value(e, b"Id").unwrap_or_default(),
+                        rel_type: attr_value(e, b"Type").unwrap_or_default(),
+                        target: attr_value(e, b"Target").unwrap_or_default(),
+                        external: attr_value(e, b"TargetMode").is_some_and(|m| m == "External"),
+                    });
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(Self { rels })
+    }
+
+    /// Returns the relationship with the given id.
+    pub fn get(&self, id: &str) -> Option<&Relationship> {
+        self.rels.iter().find(|r| r.id == id)
+    }
+
+    /// Returns all relationships whose type URI ends with `/{kind}`
+    /// (e.g., `"worksheet"`, `"externalLink"`), in document order.
+    pub fn by_type<'a>(&'a self, kind: &'a str) -> impl Iterator<Item = &'a Relationship> + 'a {
+        self.rels.iter().filter(move |r| {
+            r.rel_type.rsplit_once('/').is_some_and(|(_, last)| last == kind)
+        })
+    }
+
+    /// Returns all relationships in document order.
+    pub fn iter(&self) -> impl Iterator<Item = &Relationship> {
+        self.rels.iter()
+    }
+
+    /// Returns where the relationship `id` of `source_part` points: the
+    /// archive path of the target part, or the target as written (a URL or
+    /// file path) for external relationships.
+    ///
+    /// # Arguments
+    /// * `source_part` – path of the part these relationships belong to.
+    /// * `id` – the relationship id (`"rId3"`).
+    pub fn target_of(&self, source_part: &str, id: &str) -> Option<String> {
+        self.get(id).map(|rel| rel.resolve(source_part))
+    }
+
+    /// Returns the path of the `.rels` part describing `part`.
+    ///
+    /// `"xl/workbook.xml"` → `"xl/_rels/workbook.xml.rels"`.
+    pub fn rels_path(part: &str) -> String {
+        match part.rsplit_once('/') {
+            Some((dir, name)) => format!("{dir}/_rels/{name}.rels"),
+            None => format!("_rels/{part}.rels"),
+        }
+    }
+
+    /// Resolves a relationship target against the part that declares it.
+    ///
+    /// Relative targets are interpreted from the source part's directory and
+    /// `.`/`..` segments are collapsed; absolute targets (leading `/`) are taken
+    /// from the package root. The result is a normalized archive path suitable
+    /// for [`ZipFs::get_file`](super::ZipFs::get_file).
+    ///
+    /// `("xl/workbook.xml", "worksheets/sheet1.xml")` → `"xl/worksheets/sheet1.xml"`.
+    pub fn resolve_target(source_part: &str, target: &str) -> String {
+        let mut segments: Vec<&str> = Vec::new();
+        let relative = match target.strip_prefix('/') {
+            Some(absolute) => absolute,
+            None => {
+                if let Some((dir, _)) = source_part.rsplit_once('/') {
+                    segments.extend(dir.split('/'));
+                }
+                target
+            }
+        };
+
+        for segment in relative.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => { segments.pop(); }
+                s => segments.push(s),
+            }
+        }
+
+        segments.join("/")
+    }
+}
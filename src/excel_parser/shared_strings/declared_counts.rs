@@ -0,0 +1,112 @@
+use quick_xml::{Reader, events::Event};
+use super::super::xml_utils::attr_value;
+
+/// Shortest item that counts as a string, `<si></si>`; bounds how many
+/// items a part of a given size can hold.
+const MIN_ITEM_LEN: usize = 9;
+
+/// The `count` and `uniqueCount` attributes of `<sst>`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct DeclaredCounts {
+    count: Option<usize>,
+    unique_count: Option<usize>,
+}
+
+impl DeclaredCounts {
+    /// Reads the attributes of the `<sst>` start tag at the head of `xml`.
+    ///
+    /// Missing, unparsable or unreachable attributes read as `None`; errors
+    /// are left to the parse that follows.
+    pub(super) fn read(xml: &[u8]) -> Self {
+        let mut reader = Reader::from_reader(xml);
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e) | Event::Empty(e)) => {
+                    if e.name().as_ref() != b"sst" { return Self::default(); }
+                    let number = |name: &[u8]| attr_value(&e, name).and_then(|v| v.trim().parse().ok());
+                    return Self { count: number(b"count"), unique_count: number(b"uniqueCount") };
+                }
+                Ok(Event::Eof) | Err(_) => return Self::default(),
+                Ok(_) => buf.clear(),
+            }
+        }
+    }
+
+    /// Number of strings to reserve room for: the declared `uniqueCount`,
+    /// but no more than `limit` and than `xml` could hold, so a bogus value
+    /// can't force a huge allocation.
+    pub(super) fn capacity(self, xml_len: usize, limit: Option<usize>) -> usize {
+        let bound = limit.unwrap_or(usize::MAX).min(xml_len / MIN_ITEM_LEN);
+        self.unique_count.unwrap_or(0).min(bound)
+    }
+
+    /// Compares the declared counts with the `parsed` number of strings.
+    ///
+    /// `uniqueCount` is the number of items and must match; `count` is the
+    /// number of cell references to the table, which can be anything but
+    /// less than the number of distinct strings referenced, so it is only
+    /// flagged when it is below `parsed`. Absent attributes (both are
+    /// optional) are not checked.
+    pub(super) fn mismatch(self, parsed: usize) -> Option<CountMismatch> {
+        let unique_off = self.unique_count.is_some_and(|unique| unique != parsed);
+        let count_off = self.count.is_some_and(|count| count < parsed);
+        (unique_off || count_off).then_some(CountMismatch {
+            count: self.count,
+            unique_count: self.unique_count,
+            parsed,
+        })
+    }
+}
+
+/// Counts declared on `<sst>` that disagree with the parsed table, reported
+/// by [`SharedStrings::count_mismatch`](super::SharedStrings::count_mismatch).
+///
+/// Excel keeps both attributes exact, so a mismatch points to a truncated,
+/// corrupted or hand‑edited part, or to a writer that doesn't maintain them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountMismatch {
+    /// The `count` attribute: number of cell references to the table.
+    pub count: Option<usize>,
+    /// The `uniqueCount` attribute: number of strings in the table.
+    pub unique_count: Option<usize>,
+    /// Number of strings actually parsed.
+    pub parsed: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_takes_both_attributes_of_the_sst_tag() {
+        let read = |xml: &str| DeclaredCounts::read(xml.as_bytes());
+        let both = read(r#"<?xml version="1.0"?><sst count=" 7 " uniqueCount="3"><si/></sst>"#);
+        assert_eq!(both, DeclaredCounts { count: Some(7), unique_count: Some(3) });
+        assert_eq!(read(r#"<sst uniqueCount="2"/>"#), DeclaredCounts { count: None, unique_count: Some(2) });
+        assert_eq!(read(r#"<sst count="-1" uniqueCount="many">"#), DeclaredCounts::default());
+        assert_eq!(read(r#"<other count="1"><sst count="2"/></other>"#), DeclaredCounts::default());
+        assert_eq!(read(""), DeclaredCounts::default());
+    }
+
+    #[test]
+    fn capacity_is_bounded_by_the_limit_and_the_part_size() {
+        let declared = DeclaredCounts { count: None, unique_count: Some(1_000_000) };
+        assert_eq!(declared.capacity(9_000_000, None), 1_000_000);
+        assert_eq!(declared.capacity(9_000_000, Some(10)), 10);
+        assert_eq!(declared.capacity(90, None), 10);
+        assert_eq!(DeclaredCounts::default().capacity(90, None), 0);
+    }
+
+    #[test]
+    fn mismatch_flags_a_wrong_unique_count_or_a_count_below_it() {
+        let declared = |count, unique_count| DeclaredCounts { count, unique_count };
+        assert_eq!(declared(Some(10), Some(3)).mismatch(3), None);
+        assert_eq!(declared(Some(3), None).mismatch(3), None);
+        assert_eq!(declared(None, None).mismatch(3), None);
+        let expected = |count, unique_count| Some(CountMismatch { count, unique_count, parsed: 3 });
+        assert_eq!(declared(Some(10), Some(4)).mismatch(3), expected(Some(10), Some(4)));
+        assert_eq!(declared(Some(2), Some(3)).mismatch(3), expected(Some(2), Some(3)));
+        assert_eq!(declared(Some(2), None).mismatch(3), expected(Some(2), None));
+    }
+}
@@ -0,0 +1,61 @@
+/// Bounded Damerau–Levenshtein distance, used by
+/// [`SharedStrings::find_levenshtein`](super::SharedStrings::find_levenshtein).
+///
+/// Counts insertions, deletions, substitutions and transpositions of two
+/// adjacent characters (the optimal string alignment variant: a transposed
+/// pair is not edited again). Keeps its rows between calls, so one instance
+/// scans a whole table without allocating per string.
+#[derive(Debug, Default)]
+pub(super) struct EditDistance {
+    query: Vec<char>,
+    text: Vec<char>,
+    /// Rows of the distance matrix for the two previous text characters and
+    /// the current one.
+    before: Vec<usize>,
+    prev: Vec<usize>,
+    row: Vec<usize>,
+}
+
+impl EditDistance {
+    pub(super) fn new(query: &str) -> Self {
+        Self { query: query.chars().collect(), ..Self::default() }
+    }
+
+    /// Distance between the query and `text`, or `None` if it exceeds `max`.
+    pub(super) fn within(&mut self, text: &str, max: usize) -> Option<usize> {
+        self.text.clear();
+        self.text.extend(text.chars());
+        let (query, text) = (&self.query, &self.text);
+        // Each edit changes the length by at most one.
+        if query.len().abs_diff(text.len()) > max { return None; }
+
+        let width = query.len() + 1;
+        for row in [&mut self.before, &mut self.prev, &mut self.row] {
+            row.clear();
+            row.resize(width, 0);
+        }
+        self.prev.iter_mut().enumerate().for_each(|(j, cell)| *cell = j);
+
+        for i in 1..=text.len() {
+            self.row[0] = i;
+            let mut best = i;
+            for j in 1..width {
+                let cost = usize::from(text[i - 1] != query[j - 1]);
+                let mut d = (self.prev[j] + 1).min(self.row[j - 1] + 1).min(self.prev[j - 1] + cost);
+                if i > 1 && j > 1 && text[i - 1] == query[j - 2] && text[i - 2] == query[j - 1] {
+                    d = d.min(self.before[j - 2] + 1);
+                }
+                self.row[j] = d;
+                best = best.min(d);
+            }
+            // Distances never decrease down the matrix, so once a whole row
+            // is over the limit the final one is too.
+            if best > max { return None; }
+            std::mem::swap(&mut self.before, &mut self.prev);
+            std::mem::swap(&mut self.prev, &mut self.row);
+        }
+
+        let distance = self.prev[width - 1];
+        (distance <= max).then_some(distance)
+    }
+}
@@ -0,0 +1,172 @@
+use quick_xml::{Reader, events::Event};
+use std::mem::take;
+use super::super::xml_utils::{decode_escapes_owned, push_entity};
+use super::{LoadOptions, RichString};
+use super::rich_text::RunCollector;
+
+/// One `<si>` element as read by [`StringIter`].
+#[derive(Debug)]
+pub(super) struct Item {
+    pub(super) text: String,
+    /// Runs, with [`LoadOptions::rich_text`] and only for rich‑text items.
+    pub(super) rich: Option<RichString>,
+    /// Reading of the `<rPh>` runs, with [`LoadOptions::phonetic`].
+    pub(super) phonetic: Option<Box<str>>,
+}
+
+/// Streaming parser over the strings of a shared strings part, created by
+/// [`SharedStrings::iter_from`](super::SharedStrings::iter_from).
+///
+/// Yields each string as soon as its `<si>` element has been read, so a
+/// table can be exported or scanned without holding all of it in memory.
+/// Strings are parsed exactly as by [`SharedStrings::load`](super::SharedStrings::load)
+/// and come in table order, so the `n`‑th item is the string at index `n`.
+///
+/// After an error the iterator is exhausted; strings yielded before it
+/// remain valid.
+///
+/// # Example
+/// ```no_run
+/// # use excel_parser::SharedStrings;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("xl/sharedStrings.xml")?;
+/// let mut out = std::io::stdout().lock();
+/// for (index, s) in SharedStrings::iter_from(&data).enumerate() {
+///     use std::io::Write;
+///     writeln!(out, "{index}\t{}", s?)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct StringIter<'x> {
+    reader: Reader<&'x [u8]>,
+    buf: Vec<u8>,
+    options: LoadOptions,
+    runs: Option<RunCollector>,
+    /// Number of items read so far.
+    count: usize,
+    truncated: bool,
+    done: bool,
+}
+
+impl<'x> StringIter<'x> {
+    pub(super) fn new(xml: &'x [u8], options: &LoadOptions) -> Self {
+        let mut reader = Reader::from_reader(xml);
+        let config = reader.config_mut();
+
+        // Preserve all whitespace; Excel shared strings often require exact spaces.
+        // Invariant: text is kept verbatim regardless of `xml:space`, so this
+        // must stay `false` (trimming would break `<t> a </t>` without `preserve`).
+        config.trim_text(false);
+        // Skip expensive validation for known‑good Excel output.
+        config.check_end_names = false;
+        config.expand_empty_elements = false;
+
+        Self {
+            reader,
+            buf: Vec::new(),
+            options: options.clone(),
+            runs: options.rich_text.then(RunCollector::default),
+            count: 0,
+            truncated: false,
+            done: false,
+        }
+    }
+
+    /// Returns `true` if iteration stopped at [`LoadOptions::limit`] before
+    /// the end of the table. Meaningful once the iterator is exhausted.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Reads the next `<si>` element, or `None` at the end of the table or
+    /// the configured limit.
+    pub(super) fn next_item(&mut self) -> Result<Option<Item>, quick_xml::Error> {
+        if self.done { return Ok(None); }
+        let result = self.read_item();
+        if !matches!(result, Ok(Some(_))) { self.done = true; }
+        result
+    }
+
+    fn read_item(&mut self) -> Result<Option<Item>, quick_xml::Error> {
+        let options = &self.options;
+        let mut current = String::new();
+        let mut in_si = false;
+        let mut in_text = false;
+        // Phonetic runs (`<rPh>`) hold their own `<t>`, which is never part
+        // of the visible text; it is collected only on request.
+        let mut in_phonetic = false;
+        let mut in_phonetic_text = false;
+        let mut reading = String::new();
+
+        loop {
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Start(ref e) => {
+                    if in_si && let Some(runs) = &mut self.runs { runs.element(e, false); }
+                    match e.name().as_ref() {
+                        b"si" if options.limit.is_some_and(|limit| self.count >= limit) => {
+                            self.truncated = true;
+                            return Ok(None);
+                        }
+                        b"si" => { in_si = true; }
+                        b"rPh" if in_si => { in_phonetic = true; }
+                        b"t" if in_phonetic => { in_phonetic_text = options.phonetic; }
+                        b"t" if in_si => { in_text = true; }
+                        _ => {}
+                    }
+                },
+                Event::Empty(ref e) if in_si => {
+                    if let Some(runs) = &mut self.runs { runs.element(e, true); }
+                },
+                Event::End(ref e) => {
+                    if in_si && let Some(runs) = &mut self.runs { runs.end(e.name().as_ref()); }
+                    match e.name().as_ref() {
+                        b"si" if in_si => {
+                            self.count += 1;
+                            let decode = |text: String| {
+                                let text = match options.keep_escapes {
+                                    true => text,
+                                    false => decode_escapes_owned(text),
+                                };
+                                match options.normalization {
+                                    Some(form) => form.apply_owned(text),
+                                    None => text,
+                                }
+                            };
+                            let rich = self.runs.as_mut()
+                                .and_then(|runs| runs.finish(|text| decode(text).into_boxed_str()));
+                            let phonetic = (!reading.is_empty())
+                                .then(|| decode(take(&mut reading)).into_boxed_str());
+                            return Ok(Some(Item { text: decode(current), rich, phonetic }));
+                        }
+                        b"rPh" => { in_phonetic = false; }
+                        b"t" => { in_text = false; in_phonetic_text = false; }
+                        _ => {}
+                    }
+                },
+                Event::Text(e) if in_text => {
+                    let decoded = String::from_utf8_lossy(&e);
+                    current.push_str(&decoded);
+                    if let Some(runs) = &mut self.runs { runs.text().push_str(&decoded); }
+                },
+                Event::GeneralRef(e) if in_text => {
+                    push_entity(&mut current, &e);
+                    if let Some(runs) = &mut self.runs { push_entity(runs.text(), &e); }
+                },
+                Event::Text(e) if in_phonetic_text => reading.push_str(&String::from_utf8_lossy(&e)),
+                Event::GeneralRef(e) if in_phonetic_text => push_entity(&mut reading, &e),
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Iterator for StringIter<'_> {
+    type Item = Result<String, quick_xml::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_item().transpose().map(|item| item.map(|item| item.text))
+    }
+}
@@ -0,0 +1,117 @@
+use super::Normalization;
+
+/// Options controlling how [`SharedStrings`](super::SharedStrings) parses a table.
+///
+/// Options are set in a builder‑style fashion and passed to
+/// [`SharedStrings::load_with_options`](super::SharedStrings::load_with_options).
+/// The default parses the whole table, exactly like
+/// [`load`](super::SharedStrings::load).
+///
+/// # Example
+/// ```no_run
+/// # use excel_parser::{SharedStrings, LoadOptions};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("xl/sharedStrings.xml")?;
+/// let preview = SharedStrings::load_with_options(&data, &LoadOptions::new().limit(100))?;
+/// if preview.is_truncated() {
+///     println!("showing the first {} strings", preview.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    pub(super) limit: Option<usize>,
+    pub(super) rich_text: bool,
+    pub(super) phonetic: bool,
+    pub(super) keep_escapes: bool,
+    pub(super) lazy: bool,
+    pub(super) normalization: Option<Normalization>,
+    pub(super) trigram_index: bool,
+}
+
+impl LoadOptions {
+    /// Creates options that parse the whole table.
+    pub fn new() -> Self { Self::default() }
+
+    /// Stops parsing after `limit` strings (`<si>` elements).
+    ///
+    /// The rest of the XML is not read, so previewing the head of a very large
+    /// table costs time proportional to `limit`, not to the table size.
+    /// Indices of the loaded strings are unchanged; references to later
+    /// strings resolve to `None`.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Keeps the formatting of rich‑text items (`<r>` runs with `<rPr>`
+    /// properties) for [`SharedStrings::get_rich`](super::SharedStrings::get_rich).
+    ///
+    /// Off by default: plain text is all cell values need, and the runs cost
+    /// a second copy of the text of every rich item.
+    pub fn rich_text(mut self, keep: bool) -> Self {
+        self.rich_text = keep;
+        self
+    }
+
+    /// Keeps the phonetic readings (`<rPh>` runs, furigana) of Japanese
+    /// workbooks for [`SharedStrings::phonetic`](super::SharedStrings::phonetic).
+    ///
+    /// Readings are never part of the string text; without this option they
+    /// are skipped.
+    pub fn phonetic(mut self, keep: bool) -> Self {
+        self.phonetic = keep;
+        self
+    }
+
+    /// Decodes `_xHHHH_` escapes (`_x000D_` → carriage return) in the loaded
+    /// text. On by default; turn it off to get the text exactly as stored,
+    /// e.g. to write it back unchanged.
+    pub fn decode_escapes(mut self, decode: bool) -> Self {
+        self.keep_escapes = !decode;
+        self
+    }
+
+    /// Defers parsing of each string to its first access.
+    ///
+    /// Loading only records where each `<si>` element starts, which is much
+    /// faster than parsing every string and allocates no strings. The table keeps a copy of `xml`; a string is
+    /// parsed and kept the first time it is read. This pays off for large
+    /// tables of which only a part is ever read (cell lookups); a search
+    /// reads, and so parses, the whole table.
+    ///
+    /// Runs and phonetic readings are not available in this mode:
+    /// [`rich_text`](Self::rich_text) and [`phonetic`](Self::phonetic) are
+    /// ignored.
+    pub fn lazy(mut self, lazy: bool) -> Self {
+        self.lazy = lazy;
+        self
+    }
+
+    /// Normalizes every loaded string to `form`.
+    ///
+    /// Makes the stored text consistent when the workbook mixes encodings
+    /// (text pasted from different sources), so exact lookups such as
+    /// [`index_of`](super::SharedStrings::index_of) and searches with a query
+    /// normalized the same way find all of it. Note that this changes the
+    /// strings returned by [`get`](super::SharedStrings::get) too; to leave
+    /// them untouched, normalize per search with
+    /// [`SearchOptions::normalize`](super::SearchOptions::normalize) instead.
+    pub fn normalize(mut self, form: Normalization) -> Self {
+        self.normalization = Some(form);
+        self
+    }
+
+    /// Builds the trigram index right after loading, as
+    /// [`SharedStrings::build_trigram_index`](super::SharedStrings::build_trigram_index)
+    /// would on demand.
+    ///
+    /// Worth it for tables that are searched many times; with
+    /// [`lazy`](Self::lazy) it parses every string up front, which defeats
+    /// the point of lazy loading.
+    pub fn trigram_index(mut self, build: bool) -> Self {
+        self.trigram_index = build;
+        self
+    }
+}
@@ -0,0 +1,2349 @@
+mod declared_counts;
+mod edit_distance;
+mod iter;
+mod load_options;
+mod normalize;
+mod query;
+mod query_cache;
+mod reverse_index;
+mod rich_text;
+mod search_index;
+mod search_match;
+mod search_options;
+mod storage;
+mod trigram_index;
+mod unicode_tables;
+
+use declared_counts::DeclaredCounts;
+pub use declared_counts::CountMismatch;
+use edit_distance::EditDistance;
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+pub use iter::StringIter;
+pub use load_options::LoadOptions;
+pub use normalize::Normalization;
+use query::Expr;
+pub use query::QueryError;
+use query_cache::QueryCache;
+use reverse_index::ReverseIndex;
+pub use rich_text::{RichString, RunFont, TextRun};
+pub use search_index::SearchIndex;
+pub use search_match::SearchMatch;
+pub use search_options::{Algorithm, CaseMatching, SearchOptions, SearchView};
+use search_options::skim_matcher;
+pub use storage::SharedStringsIter;
+use storage::{Arena, LazyTable, Storage};
+use trigram_index::{Containment, TrigramIndex};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::num::NonZero;
+use std::ops::Index;
+use std::panic::resume_unwind;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Instant;
+
+/// Smallest number of strings worth handing to a thread in
+/// [`SharedStrings::fuzzy_find_parallel`]; smaller tables are searched serially.
+const PARALLEL_MIN_CHUNK: usize = 4096;
+
+// ---------------------------------------------------------------------------
+// SharedStrings – parsed table of shared strings from Excel (xl/sharedStrings.xml)
+// ---------------------------------------------------------------------------
+
+/// Table of shared strings extracted from an Excel workbook.
+///
+/// Excel stores repeated string values in a central location (`xl/sharedStrings.xml`)
+/// and references them by index from cell values. This struct parses that XML and
+/// provides efficient access to individual strings along with fuzzy search capability.
+///
+/// # Memory Optimization
+/// The text of all strings is stored in one contiguous buffer, with one end
+/// offset per string, so a table of a million short strings costs one large
+/// allocation instead of a million small ones. [`get()`][Self::get] returns
+/// slices of that buffer.
+///
+/// # Thread Safety
+/// The struct is `Send + Sync` because it contains only owned data and immutable
+/// references. Multiple threads can safely access a shared instance.
+///
+/// # Example
+/// ```no_run
+/// use excel_parser::SharedStrings;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("xl/sharedStrings.xml")?;
+/// let shared = SharedStrings::load(&data)?;
+///
+/// println!("Total shared strings: {}", shared.len());
+/// if let Some(first) = shared.get(0) {
+///     println!("First string: {}", first);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(from = "SavedStrings<Box<str>>"))]
+pub struct SharedStrings {
+    /// The strings, parsed up front or on first access
+    /// ([`LoadOptions::lazy`]).
+    strings: Storage,
+    /// Optional LRU of recent fuzzy search results, see
+    /// [`with_query_cache()`][Self::with_query_cache].
+    query_cache: Option<QueryCache>,
+    /// `true` if loading stopped at [`LoadOptions::limit`] before the end of the table.
+    truncated: bool,
+    /// Index → runs of the items with formatted runs, filled only with
+    /// [`LoadOptions::rich_text`].
+    rich: FxHashMap<usize, RichString>,
+    /// Index → phonetic reading of the items with `<rPh>` runs, filled only
+    /// with [`LoadOptions::phonetic`].
+    phonetic: FxHashMap<usize, Box<str>>,
+    /// String → index lookup, built by the first
+    /// [`index_of()`][Self::index_of] / [`indices_of()`][Self::indices_of].
+    reverse: OnceLock<ReverseIndex>,
+    /// Candidate filter for searches, built by
+    /// [`build_trigram_index()`][Self::build_trigram_index] or
+    /// [`LoadOptions::trigram_index`].
+    trigrams: OnceLock<TrigramIndex>,
+    /// Counts declared on `<sst>` that disagree with the parsed table.
+    count_mismatch: Option<CountMismatch>,
+}
+
+impl SharedStrings {
+    /// Number of strings matched between clock checks in
+    /// [`fuzzy_find_deadline()`][Self::fuzzy_find_deadline].
+    pub const DEADLINE_CHECK_INTERVAL: usize = 256;
+
+    /// Parses the shared strings XML content and builds the string table.
+    ///
+    /// This method reads `xl/sharedStrings.xml` from an Excel file (`.xlsx` is a ZIP
+    /// archive) and extracts all `<si>` (string item) elements. Each string may
+    /// contain multiple `<t>` (text) fragments that are concatenated together.
+    ///
+    /// # XML Structure
+    /// ```xml
+    /// <sst>
+    ///   <si><t>First string</t></si>
+    ///   <si><t>Second </t><t>string</t></si>
+    ///   <si><r><t>Rich </t></r><r><rPr><b/></rPr><t>text</t></r></si>
+    ///   <si><t>Mixed </t><r><t>content</t></r></si>
+    ///   <si><t>東京</t><rPh sb="0" eb="2"><t>トウキョウ</t></rPh><phoneticPr fontId="1"/></si>
+    ///   ...
+    /// </sst>
+    /// ```
+    ///
+    /// Every `<t>` inside an `<si>` contributes its text, whether it is a direct
+    /// child or nested in a rich‑text run (`<r>`). Items that mix both forms
+    /// (not produced by Excel, but seen in the wild) are handled the same way:
+    /// all fragments are concatenated in document order, so the fourth example
+    /// yields `"Mixed content"`. The `<t>` of a phonetic run (`<rPh>`, the
+    /// furigana of Japanese workbooks) is not displayed by Excel and is
+    /// skipped, so the last example yields `"東京"`; see
+    /// [`LoadOptions::phonetic`] to keep it.
+    ///
+    /// Characters XML can't carry are written by Excel as `_xHHHH_` escapes
+    /// (`line1_x000D_line2` holds a carriage return) and are decoded; a
+    /// literal `_xHHHH_` in the text is stored as `_x005F_xHHHH_` and comes
+    /// back unchanged. See [`LoadOptions::decode_escapes`] to keep the raw form.
+    ///
+    /// # Parsing Details
+    /// - `trim_text(false)` preserves all whitespace; Excel strings may contain
+    ///   meaningful leading/trailing spaces. The `xml:space` attribute is **not**
+    ///   consulted: `<t> a </t>` and `<t xml:space="preserve"> a </t>` both
+    ///   yield `" a "`. Excel writes `preserve` whenever spaces matter, and other
+    ///   writers often omit it, so keeping text verbatim is the safe reading.
+    /// - `check_end_names = false` skips expensive validation since Excel produces
+    ///   well‑formed XML.
+    /// - `expand_empty_elements = false` avoids creating empty events for
+    ///   self‑closing tags.
+    /// - A `current` buffer accumulates text from multiple `<t>` fragments within
+    ///   a single `<si>` element. Entity references (`&amp;`, `&#10;`) arrive as
+    ///   separate events between text chunks and are resolved into the same buffer.
+    /// - Items are read one at a time by [`StringIter`] (see
+    ///   [`iter_from()`][Self::iter_from]); loading collects them into the table.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of `xl/sharedStrings.xml`.
+    ///
+    /// # Returns
+    /// A `SharedStrings` instance containing all extracted strings, or a
+    /// `quick_xml::Error` if parsing fails.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML, I/O errors during reading,
+    /// or unsupported XML features.
+    ///
+    /// # Performance
+    /// The parser is single‑pass and runs in O(n) time where n is the XML size.
+    /// Memory usage is proportional to the number and length of unique strings.
+    pub fn load(xml: &[u8]) -> Result<Self, quick_xml::Error> {
+        Self::load_with_options(xml, &LoadOptions::default())
+    }
+
+    /// Parses the shared strings XML content as configured by `options`.
+    ///
+    /// Parsing is identical to [`load()`][Self::load]; `options` decide how
+    /// much of the table is read and what is kept besides the plain text. With
+    /// a [`limit`](LoadOptions::limit), the result holds at most that many
+    /// strings and [`is_truncated()`][Self::is_truncated] reports whether more
+    /// followed. With [`rich_text`](LoadOptions::rich_text), the formatting of
+    /// rich‑text items is available through [`get_rich()`][Self::get_rich],
+    /// with [`phonetic`](LoadOptions::phonetic) the readings of phonetic runs
+    /// through [`phonetic()`][Self::phonetic].
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of `xl/sharedStrings.xml`.
+    /// * `options` – loading options.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML in the part that was read.
+    pub fn load_with_options(xml: &[u8], options: &LoadOptions) -> Result<Self, quick_xml::Error> {
+        let declared = DeclaredCounts::read(xml);
+        let mut shared = Self::parse(xml, options, declared.capacity(xml.len(), options.limit))?;
+        // A limit leaves strings unparsed, so only a complete table can be checked.
+        if !shared.truncated { shared.count_mismatch = declared.mismatch(shared.len()); }
+        if options.trigram_index { shared.build_trigram_index(); }
+        Ok(shared)
+    }
+
+    /// Builds the table as `options` ask for, without the optional indices,
+    /// reserving room for `capacity` strings.
+    fn parse(xml: &[u8], options: &LoadOptions, capacity: usize) -> Result<Self, quick_xml::Error> {
+        if options.lazy {
+            let (table, truncated) = LazyTable::scan(xml, options, capacity)?;
+            let (rich, phonetic) = (FxHashMap::default(), FxHashMap::default());
+            return Ok(Self {
+                strings: Storage::Lazy(table),
+                query_cache: None,
+                truncated,
+                rich,
+                phonetic,
+                reverse: OnceLock::new(),
+                trigrams: OnceLock::new(),
+                count_mismatch: None,
+            });
+        }
+
+        let mut items = StringIter::new(xml, options);
+        let mut arena = Arena::with_capacity(capacity);
+        let mut count = 0;
+        let mut rich = FxHashMap::default();
+        let mut phonetic = FxHashMap::default();
+
+        while let Some(item) = items.next_item()? {
+            if let Some(runs) = item.rich { rich.insert(count, runs); }
+            if let Some(reading) = item.phonetic { phonetic.insert(count, reading); }
+            arena.push(&item.text);
+            count += 1;
+        }
+
+        arena.shrink_to_fit();
+        let strings = Storage::Eager(arena);
+        Ok(Self {
+            strings,
+            query_cache: None,
+            truncated: items.is_truncated(),
+            rich,
+            phonetic,
+            reverse: OnceLock::new(),
+            trigrams: OnceLock::new(),
+            count_mismatch: None,
+        })
+    }
+
+    /// Returns a streaming parser over the strings of a shared strings part.
+    ///
+    /// Unlike [`load()`][Self::load], no table is built: each string is
+    /// yielded as soon as it is parsed and can be dropped right after, so
+    /// exporting a large table (to CSV, JSON, ...) needs memory for one
+    /// string at a time. Parsing is the same as `load()`, which is built on
+    /// this iterator.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of `xl/sharedStrings.xml`.
+    ///
+    /// # Returns
+    /// An iterator of `Result<String, quick_xml::Error>` in table order; it
+    /// ends after the first error.
+    pub fn iter_from(xml: &[u8]) -> StringIter<'_> {
+        StringIter::new(xml, &LoadOptions::default())
+    }
+
+    /// Returns a streaming parser configured by `options`.
+    ///
+    /// [`limit`](LoadOptions::limit) ends the iteration after that many
+    /// strings (see [`StringIter::is_truncated`]) and
+    /// [`decode_escapes`](LoadOptions::decode_escapes) applies as in
+    /// [`load_with_options()`][Self::load_with_options]. Runs and readings
+    /// aren't yielded, so `rich_text` and `phonetic` have no effect.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of `xl/sharedStrings.xml`.
+    /// * `options` – loading options.
+    pub fn iter_from_with_options<'x>(xml: &'x [u8], options: &LoadOptions) -> StringIter<'x> {
+        StringIter::new(xml, options)
+    }
+
+    // -------------------------------------------------------------------------
+    // Public API
+    // -------------------------------------------------------------------------
+
+    /// Enables caching of [`fuzzy_find()`][Self::fuzzy_find] results for the
+    /// `capacity` most recently used `(query, threshold)` pairs.
+    ///
+    /// Applications that repeat identical searches (autocomplete that re‑issues
+    /// the same prefix, dashboards polling a fixed query) skip the full scan on
+    /// a hit. Only `fuzzy_find` and the helpers built on it
+    /// ([`fuzzy_find_strings()`][Self::fuzzy_find_strings],
+    /// [`fuzzy_find_indices()`][Self::fuzzy_find_indices]) consult the cache;
+    /// searches with a caller‑supplied matcher bypass it.
+    ///
+    /// # Memory
+    /// Each entry holds a copy of the query and its result vector (16 bytes per
+    /// match), so a low‑threshold query on a large table can cost as much as the
+    /// table's index itself. Keep `capacity` small.
+    ///
+    /// # Staleness
+    /// Cached results are valid for as long as the table is unchanged. The table
+    /// is immutable once loaded; any method that modifies it must call
+    /// [`clear_query_cache()`][Self::clear_query_cache].
+    ///
+    /// # Arguments
+    /// * `capacity` – maximum number of cached queries; `0` disables storing.
+    pub fn with_query_cache(mut self, capacity: usize) -> Self {
+        self.query_cache = Some(QueryCache::new(capacity));
+        self
+    }
+
+    /// Drops all cached search results. No‑op if caching is disabled.
+    pub fn clear_query_cache(&self) {
+        if let Some(cache) = &self.query_cache { cache.clear(); }
+    }
+
+    /// Returns every index whose string is exactly `s`, in ascending order.
+    ///
+    /// Excel normally de‑duplicates the table, but other writers don't, so a
+    /// value can be stored several times; cells referencing any of these
+    /// indices display `s`. Comparison is exact (case‑ and
+    /// whitespace‑sensitive) and scans the whole table.
+    ///
+    /// # Arguments
+    /// * `s` – the string to look for.
+    ///
+    /// This is a scan per call and needs no extra memory; for repeated
+    /// lookups use [`indices_of()`][Self::indices_of].
+    ///
+    /// # Returns
+    /// The matching indices; empty if `s` is not in the table.
+    pub fn positions_of(&self, s: &str) -> Vec<usize> {
+        self.strings.iter().enumerate()
+            .filter(|&(_, candidate)| candidate == s)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the first index whose string is exactly `s`.
+    ///
+    /// Maps a user‑supplied value back to the index cells refer to it by.
+    /// The first call builds a reverse index over the whole table (16 bytes
+    /// per string, and all strings are parsed in [lazy](LoadOptions::lazy)
+    /// mode); later lookups take O(log n). For a single lookup,
+    /// [`positions_of()`][Self::positions_of] avoids building it.
+    ///
+    /// # Arguments
+    /// * `s` – the string to look for; comparison is exact.
+    ///
+    /// # Returns
+    /// The lowest matching index, or `None` if `s` is not in the table.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::SharedStrings;
+    /// let shared = SharedStrings::load(b"<sst><si><t>a</t></si><si><t>b</t></si></sst>").unwrap();
+    /// assert_eq!(shared.index_of("b"), Some(1));
+    /// assert_eq!(shared.index_of("c"), None);
+    /// ```
+    pub fn index_of(&self, s: &str) -> Option<usize> {
+        self.reverse_index().lookup(&self.strings, s).next()
+    }
+
+    /// Returns every index whose string is exactly `s`, in ascending order.
+    ///
+    /// Same result as [`positions_of()`][Self::positions_of], answered from
+    /// the reverse index built by the first call (see
+    /// [`index_of()`][Self::index_of]).
+    ///
+    /// # Arguments
+    /// * `s` – the string to look for; comparison is exact.
+    ///
+    /// # Returns
+    /// The matching indices; empty if `s` is not in the table.
+    pub fn indices_of(&self, s: &str) -> Vec<usize> {
+        self.reverse_index().lookup(&self.strings, s).collect()
+    }
+
+    fn reverse_index(&self) -> &ReverseIndex {
+        self.reverse.get_or_init(|| ReverseIndex::build(&self.strings))
+    }
+
+    /// Returns the indices of the strings equal to `query`, in ascending order.
+    ///
+    /// Unlike [`index_of()`][Self::index_of], comparison follows `options`
+    /// ([`ignore_case`](SearchOptions::ignore_case),
+    /// [`collapse_whitespace`](SearchOptions::collapse_whitespace) for the
+    /// query), and the table is scanned on every call.
+    ///
+    /// # Arguments
+    /// * `query` – the text to look for.
+    /// * `options` – comparison options; the default compares exactly.
+    ///
+    /// # Returns
+    /// The matching indices; empty if none match.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, SearchOptions};
+    /// # fn demo(shared: &SharedStrings) {
+    /// let days = shared.find_exact("суббота", &SearchOptions::new().ignore_case(true));
+    /// # }
+    /// ```
+    pub fn find_exact(&self, query: &str, options: &SearchOptions) -> Vec<usize> {
+        self.find_by(query, options, |text, query| text == query)
+    }
+
+    /// Returns the indices of the strings containing `query`, in ascending order.
+    ///
+    /// A plain scan without scoring, much cheaper than a fuzzy search when
+    /// the caller needs literal containment (`"Курс"` in `"Курсовая работа"`).
+    /// An empty query matches every string.
+    ///
+    /// # Arguments
+    /// * `query` – the text to look for.
+    /// * `options` – comparison options; the default is case‑sensitive.
+    ///
+    /// # Returns
+    /// The matching indices; empty if none match.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, SearchOptions};
+    /// # fn demo(shared: &SharedStrings) {
+    /// for i in shared.find_substring("Курс", &SearchOptions::new()) {
+    ///     println!("{}", shared.get(i).unwrap());
+    /// }
+    /// # }
+    /// ```
+    pub fn find_substring(&self, query: &str, options: &SearchOptions) -> Vec<usize> {
+        self.find_by(query, options, |text, query| text.contains(query))
+    }
+
+    /// Returns the indices of the strings starting with `query`, in ascending order.
+    ///
+    /// # Arguments
+    /// * `query` – the prefix to look for.
+    /// * `options` – comparison options; the default is case‑sensitive.
+    ///
+    /// # Returns
+    /// The matching indices; empty if none match.
+    pub fn find_prefix(&self, query: &str, options: &SearchOptions) -> Vec<usize> {
+        self.find_by(query, options, |text, query| text.starts_with(query))
+    }
+
+    /// Scans the table, or the candidates of the trigram index, for strings
+    /// that satisfy `test` against the prepared query.
+    fn find_by(&self, query: &str, options: &SearchOptions, test: impl Fn(&str, &str) -> bool) -> Vec<usize> {
+        let options = options.for_query(query);
+        let query = options.prepare_query(query);
+        let mut results: Vec<_> = self.candidates(&query, Containment::Substring, &options)
+            .filter(|&(_, text)| test(&options.prepare_text(text), &query))
+            .map(|(i, _)| i)
+            .collect();
+        options.apply_limit(&mut results);
+        results
+    }
+
+    /// Builds the trigram index used to narrow searches down to candidate
+    /// strings; no‑op if it is already built.
+    ///
+    /// Without the index every search scores or compares each string of the
+    /// table. With it, the literal searches ([`find_exact()`][Self::find_exact],
+    /// [`find_substring()`][Self::find_substring],
+    /// [`find_prefix()`][Self::find_prefix]) only look at the strings that
+    /// contain every trigram of the query, and the fuzzy searches
+    /// ([`fuzzy_find()`][Self::fuzzy_find] and its helpers,
+    /// [`search()`][Self::search]) only at
+    /// the strings that contain every character of the query. Results are
+    /// unchanged: the index only skips strings that can't match.
+    ///
+    /// Building takes about as long as a few searches and costs 4 to 8
+    /// bytes per character of text (all strings are parsed in
+    /// [lazy](LoadOptions::lazy) mode), so it pays off when a table is
+    /// searched many times. Searches with
+    /// [`strip_diacritics`](SearchOptions::strip_diacritics) or
+    /// [`normalize`](SearchOptions::normalize) compare text the index doesn't
+    /// hold and still scan the whole table. To build the index while
+    /// loading, see [`LoadOptions::trigram_index`].
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, SearchOptions};
+    /// # fn demo(shared: &SharedStrings, queries: &[&str]) {
+    /// shared.build_trigram_index();
+    /// for query in queries {
+    ///     let hits = shared.find_substring(query, &SearchOptions::new().ignore_case(true));
+    ///     println!("{query}: {} strings", hits.len());
+    /// }
+    /// # }
+    /// ```
+    pub fn build_trigram_index(&self) {
+        self.trigrams.get_or_init(|| TrigramIndex::build(&self.strings));
+    }
+
+    /// Returns `true` if the trigram index has been built.
+    pub fn has_trigram_index(&self) -> bool {
+        self.trigrams.get().is_some()
+    }
+
+    /// Returns the strings and their trigram index as a [`SearchIndex`]
+    /// that can be saved and later restored with
+    /// [`from_index()`][Self::from_index], skipping both parsing and
+    /// indexing.
+    ///
+    /// Builds the trigram index of this table first if needed (see
+    /// [`build_trigram_index()`][Self::build_trigram_index]); the result is
+    /// a copy, so the table stays usable. All strings of a
+    /// [lazy](LoadOptions::lazy) table are parsed.
+    ///
+    /// # Example
+    /// ```ignore
+    /// # use excel_parser::SharedStrings;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let shared = SharedStrings::load(&std::fs::read("xl/sharedStrings.xml")?)?;
+    /// let saved = serde_json::to_vec(&shared.build_index())?;
+    /// let restored = SharedStrings::from_index(serde_json::from_slice(&saved)?);
+    /// assert!(restored.has_trigram_index());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn build_index(&self) -> SearchIndex {
+        self.build_trigram_index();
+        let strings = match &self.strings {
+            Storage::Eager(arena) => arena.clone(),
+            Storage::Lazy(_) => self.strings.iter().collect(),
+        };
+        let trigrams = self.trigrams.get().cloned().unwrap_or_default();
+        SearchIndex { strings, trigrams }
+    }
+
+    /// Creates a table from a [`SearchIndex`], with its trigram index
+    /// already built.
+    ///
+    /// The table has the same strings at the same indices as the one the
+    /// index was built from, without rich‑text runs and phonetic readings.
+    pub fn from_index(index: SearchIndex) -> Self {
+        Self {
+            strings: Storage::Eager(index.strings),
+            query_cache: None,
+            truncated: false,
+            rich: FxHashMap::default(),
+            phonetic: FxHashMap::default(),
+            reverse: OnceLock::new(),
+            trigrams: OnceLock::from(index.trigrams),
+            count_mismatch: None,
+        }
+    }
+
+    /// The strings a search for `query` under `options` has to look at, with
+    /// their indices in ascending order: the candidates of the trigram index
+    /// when it is built and applies, otherwise the whole table.
+    fn candidates<'a>(
+        &'a self,
+        query: &str,
+        containment: Containment,
+        options: &SearchOptions,
+    ) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+        let narrowed = self.trigrams.get()
+            .filter(|_| options.keeps_case_folded_text())
+            .and_then(|index| index.candidates(query, containment));
+        let all = narrowed.is_none().then(|| self.strings.iter().enumerate());
+        let some = narrowed.map(|ids| ids.into_iter().map(|i| (i, self.strings.text(i))));
+        all.into_iter().flatten().chain(some.into_iter().flatten())
+    }
+
+    /// Returns `true` if the table was cut short by [`LoadOptions::limit`].
+    ///
+    /// Always `false` for tables loaded with [`load()`][Self::load].
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns the `count` / `uniqueCount` attributes of `<sst>` if they
+    /// disagree with the strings that were parsed.
+    ///
+    /// Excel writes the number of strings as `uniqueCount` and the number of
+    /// cells referring to them as `count`. A table whose `uniqueCount`
+    /// differs from its length, or whose `count` is below it, was likely
+    /// truncated, corrupted or edited by hand, and cell indices near the end
+    /// of the table deserve suspicion. Loading still succeeds; this is for
+    /// reporting. `uniqueCount` is also used to size the table up front.
+    ///
+    /// # Returns
+    /// `None` if the counts agree or are absent, and for tables cut short
+    /// by [`LoadOptions::limit`] or not loaded from XML.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::SharedStrings;
+    /// let xml = br#"<sst count="5" uniqueCount="3"><si><t>a</t></si><si><t>b</t></si></sst>"#;
+    /// let shared = SharedStrings::load(xml).unwrap();
+    /// if let Some(m) = shared.count_mismatch() {
+    ///     eprintln!("warning: sst declares {:?} strings, found {}", m.unique_count, m.parsed);
+    /// }
+    /// ```
+    pub fn count_mismatch(&self) -> Option<CountMismatch> {
+        self.count_mismatch
+    }
+
+    /// Returns a reference to the shared string at the given index.
+    ///
+    /// Shared strings are indexed from 0 in the order they appear in the XML.
+    /// This matches the indices used in cell values (e.g., cell `A1` with value
+    /// index 5 refers to `shared.get(5)`).
+    ///
+    /// # Arguments
+    /// * `index` – zero‑based position of the string in the shared strings table.
+    ///
+    /// # Returns
+    /// `Some(&str)` if the index is valid, `None` otherwise.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::SharedStrings;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("xl/sharedStrings.xml")?;
+    /// let shared = SharedStrings::load(&data)?;
+    ///
+    /// if let Some(s) = shared.get(0) {
+    ///     assert_eq!(s, "First string");
+    /// }
+    /// assert!(shared.get(9999).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.strings.get(index)
+    }
+
+    /// Returns the formatted runs of the string at the given index.
+    ///
+    /// Only tables loaded with [`LoadOptions::rich_text`] keep runs, and only
+    /// for items written as runs (`<r>`); plain items have nothing beyond
+    /// [`get()`][Self::get].
+    ///
+    /// # Arguments
+    /// * `index` – zero‑based position of the string in the shared strings table.
+    ///
+    /// # Returns
+    /// `Some(&RichString)` for a rich‑text item, `None` for plain items,
+    /// invalid indices, or when runs weren't kept.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{SharedStrings, LoadOptions};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("xl/sharedStrings.xml")?;
+    /// let shared = SharedStrings::load_with_options(&data, &LoadOptions::new().rich_text(true))?;
+    ///
+    /// if let Some(rich) = shared.get_rich(0) {
+    ///     for run in rich.runs() {
+    ///         let bold = run.font.as_ref().is_some_and(|f| f.bold);
+    ///         println!("{:?} bold={}", run.text, bold);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_rich(&self, index: usize) -> Option<&RichString> {
+        self.rich.get(&index)
+    }
+
+    /// Returns the phonetic reading (furigana) of the string at the given index.
+    ///
+    /// The reading is the text of the item's `<rPh>` runs, concatenated in
+    /// document order; it is kept only for tables loaded with
+    /// [`LoadOptions::phonetic`].
+    ///
+    /// # Arguments
+    /// * `index` – zero‑based position of the string in the shared strings table.
+    ///
+    /// # Returns
+    /// `Some(&str)` for an item with a reading, `None` for items without
+    /// one, invalid indices, or when readings weren't kept.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, LoadOptions};
+    /// let xml = r#"<sst><si><t>東京</t><rPh sb="0" eb="2"><t>トウキョウ</t></rPh></si></sst>"#;
+    /// let shared = SharedStrings::load_with_options(xml.as_bytes(), &LoadOptions::new().phonetic(true)).unwrap();
+    /// assert_eq!(shared.get(0), Some("東京"));
+    /// assert_eq!(shared.phonetic(0), Some("トウキョウ"));
+    /// ```
+    pub fn phonetic(&self, index: usize) -> Option<&str> {
+        self.phonetic.get(&index).map(|s| &**s)
+    }
+
+    /// Returns the total number of shared strings in the table.
+    ///
+    /// This is the count of `<si>` elements in the source XML, which equals the
+    /// maximum valid index plus one.
+    ///
+    /// # Returns
+    /// A `usize` representing the number of unique shared strings.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::SharedStrings;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("xl/sharedStrings.xml")?;
+    /// let shared = SharedStrings::load(&data)?;
+    ///
+    /// println!("The workbook contains {} unique strings", shared.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns `true` if the table has no strings.
+    pub fn is_empty(&self) -> bool {
+        self.strings.len() == 0
+    }
+
+    /// Returns an iterator over all strings in index order.
+    ///
+    /// The `n`‑th item is the string at index `n`, so `enumerate()` pairs
+    /// every string with the index cells refer to it by. `&SharedStrings`
+    /// also implements `IntoIterator`, so a table can be used in a `for`
+    /// loop directly.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::SharedStrings;
+    /// let shared = SharedStrings::load(b"<sst><si><t>a</t></si><si><t>b</t></si></sst>").unwrap();
+    /// assert_eq!(shared.iter().collect::<Vec<_>>(), ["a", "b"]);
+    /// for (index, s) in shared.iter().enumerate() {
+    ///     println!("{index}: {s}");
+    /// }
+    /// ```
+    pub fn iter(&self) -> SharedStringsIter<'_> {
+        SharedStringsIter::new(&self.strings)
+    }
+
+    /// Returns the heap memory held by the table, in bytes.
+    ///
+    /// Counts the strings (for an eager table, the text buffer and one
+    /// offset per string; for a [lazy](LoadOptions::lazy) table, the copy of
+    /// the XML, the item offsets and the strings parsed so far), rich‑text
+    /// runs and phonetic readings, and the reverse index, trigram index and
+    /// query cache once built or enabled. Hash map sizes are estimated from
+    /// their capacity; allocator overhead is not included.
+    ///
+    /// Loading the same part both ways and comparing shows what lazy mode
+    /// saves; a lazy table grows toward (and past) the eager size as its
+    /// strings are read.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{SharedStrings, LoadOptions};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("xl/sharedStrings.xml")?;
+    /// let lazy = SharedStrings::load_with_options(&data, &LoadOptions::new().lazy(true))?;
+    /// println!("{} strings in {} KiB", lazy.len(), lazy.memory_usage() / 1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        let rich: usize = self.rich.values().map(RichString::heap_bytes).sum();
+        let phonetic: usize = self.phonetic.values().map(|s| s.len()).sum();
+        self.strings.heap_bytes()
+            + map_bytes(&self.rich) + rich
+            + map_bytes(&self.phonetic) + phonetic
+            + self.reverse.get().map_or(0, ReverseIndex::heap_bytes)
+            + self.trigrams.get().map_or(0, TrigramIndex::heap_bytes)
+            + self.query_cache.as_ref().map_or(0, QueryCache::heap_bytes)
+    }
+
+    /// Performs a fuzzy search across all shared strings.
+    ///
+    /// Uses the SkimMatcherV2 algorithm from the `fuzzy-matcher` crate, which
+    /// provides score‑based matching similar to fzf (command-line fuzzy finder).
+    /// Higher scores indicate better matches. The algorithm:
+    /// - Matches characters in order (sequential matching).
+    /// - Awards bonus points for consecutive matches and matches at word boundaries.
+    /// - Penalizes gaps between matched characters.
+    ///
+    /// # Scoring
+    /// - Exact match: very high score (often 100+).
+    /// - Case‑insensitive match: slightly lower than exact.
+    /// - Fuzzy match with gaps: lower score proportional to gap length.
+    /// - No match: not included in results.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern (can be exact text or a fuzzy pattern).
+    /// * `threshold` – minimum score to include a match. Use:
+    ///   - `0` to return all matches.
+    ///   - `30-50` for typical fuzzy matches.
+    ///   - `100+` for near‑exact matches.
+    ///
+    /// # Returns
+    /// A vector of `(index, score)` tuples, sorted by descending score.
+    /// The vector is empty if no strings meet the threshold.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::SharedStrings;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("xl/sharedStrings.xml")?;
+    /// let shared = SharedStrings::load(&data)?;
+    ///
+    /// // Find courses related to "math" (threshold 0 = all matches)
+    /// let results = shared.fuzzy_find("math", 0);
+    ///
+    /// for (idx, score) in results.iter().take(5) {
+    ///     if let Some(s) = shared.get(*idx) {
+    ///         println!("[{}] {} (score: {})", idx, s, score);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fuzzy_find(&self, query: &str, threshold: i64) -> Vec<(usize, i64)> {
+        if let Some(results) = self.query_cache.as_ref().and_then(|c| c.get(query, threshold)) {
+            return results;
+        }
+
+        let results = self.fuzzy_scan(skim_matcher(None), query, threshold, &SearchOptions::default());
+        if let Some(cache) = &self.query_cache { cache.insert(query, threshold, &results); }
+        results
+    }
+
+    /// Returns the `k` best fuzzy matches.
+    ///
+    /// Equivalent to taking the first `k` results of
+    /// [`fuzzy_find()`][Self::fuzzy_find] with threshold `0`, but the scan
+    /// keeps only the `k` best matches so far in a bounded heap instead of
+    /// collecting and sorting every match, which is what a result list
+    /// showing a handful of entries needs. The query cache is not used.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `k` – maximum number of results.
+    ///
+    /// # Returns
+    /// Up to `k` `(index, score)` tuples sorted by descending score; equal
+    /// scores are ordered by index, as in `fuzzy_find`.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::SharedStrings;
+    /// # fn demo(shared: &SharedStrings) {
+    /// for (idx, score) in shared.fuzzy_find_top_k("математика", 10) {
+    ///     println!("[{}] {} ({})", idx, shared.get(idx).unwrap(), score);
+    /// }
+    /// # }
+    /// ```
+    pub fn fuzzy_find_top_k(&self, query: &str, k: usize) -> Vec<(usize, i64)> {
+        if k == 0 { return Vec::new(); }
+
+        let matcher = skim_matcher(None);
+        // Min-heap of the best matches so far; the root is the worst of them
+        // (lowest score, then highest index).
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        for (i, s) in self.strings.iter().enumerate() {
+            let Some(score) = matcher.fuzzy_match(s, query).filter(|&score| score >= 0) else { continue };
+            let entry = Reverse((score, Reverse(i)));
+            if best.len() < k {
+                best.push(entry);
+            } else if best.peek().is_some_and(|worst| entry < *worst) {
+                best.pop();
+                best.push(entry);
+            }
+        }
+
+        best.into_sorted_vec().into_iter()
+            .map(|Reverse((score, Reverse(i)))| (i, score))
+            .collect()
+    }
+
+    /// Performs a fuzzy search on all available cores.
+    ///
+    /// The table is split into one contiguous chunk per core, each scored by
+    /// a scoped thread, and the per‑chunk results are
+    /// merged. Results are identical to [`fuzzy_find()`][Self::fuzzy_find],
+    /// including the order of equal scores, and the query cache is used the
+    /// same way. Tables shorter than a few thousand strings, or machines with
+    /// a single core, are searched serially since spawning threads would cost
+    /// more than it saves.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `threshold` – minimum matching score.
+    ///
+    /// # Returns
+    /// A vector of `(index, score)` tuples sorted by descending score.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::SharedStrings;
+    /// # fn demo(shared: &SharedStrings) {
+    /// let results = shared.fuzzy_find_parallel("Теория функций", 30);
+    /// assert_eq!(results, shared.fuzzy_find("Теория функций", 30));
+    /// # }
+    /// ```
+    pub fn fuzzy_find_parallel(&self, query: &str, threshold: i64) -> Vec<(usize, i64)> {
+        if let Some(results) = self.query_cache.as_ref().and_then(|c| c.get(query, threshold)) {
+            return results;
+        }
+
+        let len = self.strings.len();
+        let workers = thread::available_parallelism().map_or(1, NonZero::get)
+            .min(len / PARALLEL_MIN_CHUNK);
+        let results = if workers <= 1 {
+            self.fuzzy_scan(skim_matcher(None), query, threshold, &SearchOptions::default())
+        } else {
+            self.fuzzy_scan_chunked(query, threshold, workers)
+        };
+
+        if let Some(cache) = &self.query_cache { cache.insert(query, threshold, &results); }
+        results
+    }
+
+    /// The threaded scan of [`fuzzy_find_parallel()`][Self::fuzzy_find_parallel]:
+    /// scores one contiguous chunk of the table per worker.
+    fn fuzzy_scan_chunked(&self, query: &str, threshold: i64, workers: usize) -> Vec<(usize, i64)> {
+        let len = self.strings.len();
+        let chunk = len.div_ceil(workers);
+        let mut results: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..len).step_by(chunk)
+                .map(|start| scope.spawn(move || {
+                    let matcher = skim_matcher(None);
+                    (start..len.min(start + chunk)).filter_map(|i| {
+                        matcher.fuzzy_match(self.strings.text(i), query)
+                            .filter(|&score| score >= threshold)
+                            .map(|score| (i, score))
+                    }).collect::<Vec<_>>()
+                }))
+                .collect();
+
+            handles.into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_else(|panic| resume_unwind(panic)))
+                .collect()
+        });
+        // Chunks are joined in table order, so the stable sort keeps
+        // equal scores in index order, as the serial scan does.
+        results.sort_by_key(|&(_, score)| Reverse(score));
+        results
+    }
+
+    /// Performs a fuzzy search configured by `options`.
+    ///
+    /// The query is prepared by `options`, then scored by the matcher they
+    /// select ([`algorithm`](SearchOptions::algorithm),
+    /// [`case`](SearchOptions::case)); matchers are built once and shared, not
+    /// per call. When the options don't change how strings are compared, the
+    /// search is exactly [`fuzzy_find()`][Self::fuzzy_find], including the
+    /// query cache, so a cached entry is shared by all queries that prepare
+    /// to the same text. Otherwise the strings are prepared the same way as
+    /// they are scanned and the cache is not used.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `options` – matcher, case handling, preprocessing, threshold and
+    ///   limit, see [`SearchOptions`]; the default is `fuzzy_find` with
+    ///   threshold `0`.
+    ///
+    /// # Returns
+    /// A vector of `(index, score)` tuples sorted by descending score, at
+    /// most [`limit`](SearchOptions::limit) long.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, SearchOptions, CaseMatching, Algorithm};
+    /// # fn demo(shared: &SharedStrings) {
+    /// let options = SearchOptions::new()
+    ///     .case(CaseMatching::Smart)
+    ///     .algorithm(Algorithm::Clangd)
+    ///     .threshold(30)
+    ///     .limit(10);
+    /// for (idx, score) in shared.search("матан", &options) {
+    ///     println!("{score:4} {}", shared.get(idx).unwrap());
+    /// }
+    /// # }
+    /// ```
+    pub fn search(&self, query: &str, options: &SearchOptions) -> Vec<(usize, i64)> {
+        let options = options.for_query(query);
+        let query = options.prepare_query(query);
+        let mut results = match options.is_plain_fuzzy() {
+            true => self.fuzzy_find(&query, options.min_score()),
+            false => self.fuzzy_scan(options.matcher(), &query, options.min_score(), &options),
+        };
+        options.apply_limit(&mut results);
+        results
+    }
+
+    /// Performs a fuzzy search after preprocessing the query with `options`.
+    ///
+    /// Same as [`search()`][Self::search] with the
+    /// [`threshold`](SearchOptions::threshold) of `options` replaced by
+    /// `threshold`.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `threshold` – minimum matching score.
+    /// * `options` – query preprocessing, see [`SearchOptions`].
+    ///
+    /// # Returns
+    /// A vector of `(index, score)` tuples sorted by descending score.
+    pub fn fuzzy_find_with_options(
+        &self,
+        query: &str,
+        threshold: i64,
+        options: &SearchOptions,
+    ) -> Vec<(usize, i64)> {
+        self.search(query, &options.clone().threshold(threshold))
+    }
+
+    /// Performs a case‑insensitive fuzzy search for a query the caller has
+    /// already lowercased.
+    ///
+    /// The default matcher uses smart case: it scans the query for uppercase
+    /// letters on every call to decide whether to ignore case. In hot loops
+    /// that repeat lowercase queries, this variant skips that step by using a
+    /// matcher fixed to ignore case. For a lowercase query the results are the
+    /// same as [`fuzzy_find()`][Self::fuzzy_find]. The query cache is not used.
+    ///
+    /// # Arguments
+    /// * `query_lower` – the search pattern, already lowercase. Uppercase
+    ///   letters would be matched case‑insensitively too, so a mixed‑case query
+    ///   gives different results than with `fuzzy_find`; this is checked in
+    ///   debug builds only.
+    /// * `threshold` – minimum matching score.
+    ///
+    /// # Returns
+    /// A vector of `(index, score)` tuples sorted by descending score.
+    pub fn fuzzy_find_prelowered(&self, query_lower: &str, threshold: i64) -> Vec<(usize, i64)> {
+        debug_assert!(!query_lower.chars().any(char::is_uppercase), "query must be lowercase");
+
+        let matcher = skim_matcher(Some(CaseMatching::Ignore));
+        self.fuzzy_scan(matcher, query_lower, threshold, &SearchOptions::default())
+    }
+
+    /// Performs a fuzzy search using a pre‑configured matcher instance.
+    ///
+    /// This overload allows reusing a configured `SkimMatcherV2` across multiple
+    /// searches, which can be useful when you want to set matcher options once
+    /// (e.g., case sensitivity) and reuse it.
+    ///
+    /// Deprecated: [`SearchOptions`] covers the matcher settings
+    /// ([`case`](SearchOptions::case), [`algorithm`](SearchOptions::algorithm))
+    /// and reuses a shared matcher, so use [`search()`][Self::search].
+    ///
+    /// # Arguments
+    /// * `matcher` – an instance of `SkimMatcherV2` (implements `FuzzyMatcher`).
+    ///   Can be configured before passing (e.g., `SkimMatcherV2::default().case_sensitive(true)`).
+    /// * `query` – the search pattern.
+    /// * `threshold` – minimum matching score.
+    ///
+    /// # Returns
+    /// A vector of `(index, score)` tuples sorted by descending score.
+    ///
+    /// # See Also
+    /// [`fuzzy_find()`][Self::fuzzy_find] – simpler method using a default matcher.
+    #[deprecated(note = "configure the matcher with `SearchOptions` and use `search()`")]
+    pub fn fuzzy_find_with_matcher(
+        &self,
+        matcher: &SkimMatcherV2,
+        query: &str,
+        threshold: i64
+    ) -> Vec<(usize, i64)> {
+        self.fuzzy_scan(matcher, query, threshold, &SearchOptions::default())
+    }
+
+    /// Scores every string (or every candidate of the trigram index),
+    /// prepared by `options`, against `query`.
+    fn fuzzy_scan(
+        &self,
+        matcher: &dyn FuzzyMatcher,
+        query: &str,
+        threshold: i64,
+        options: &SearchOptions,
+    ) -> Vec<(usize, i64)> {
+        let mut results: Vec<_> = self.candidates(query, Containment::Chars, options)
+            .filter_map(|(i, s)| {
+                matcher.fuzzy_match(&options.prepare_text(s), query).map(|score| (i, score))
+            }).filter(|(_, score)| *score >= threshold).collect();
+
+        results.sort_by_key(|&(_, score)| Reverse(score));
+        results
+    }
+
+    /// Performs a fuzzy search that stops at a deadline.
+    ///
+    /// Intended for interactive use (search‑as‑you‑type) where a late answer is
+    /// worse than a partial one. Strings are scanned in table order and the
+    /// clock is checked every [`DEADLINE_CHECK_INTERVAL`](Self::DEADLINE_CHECK_INTERVAL)
+    /// strings, so the call may overrun `deadline` by the time needed to match
+    /// one batch.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `threshold` – minimum matching score.
+    /// * `deadline` – point in time after which scanning stops.
+    ///
+    /// # Returns
+    /// A tuple of the matches found so far (sorted by descending score, as in
+    /// [`fuzzy_find()`][Self::fuzzy_find]) and a flag that is `true` if the whole
+    /// table was scanned and `false` if the deadline cut the scan short.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::SharedStrings;
+    /// # use std::time::{Duration, Instant};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("xl/sharedStrings.xml")?;
+    /// let shared = SharedStrings::load(&data)?;
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(20);
+    /// let (results, completed) = shared.fuzzy_find_deadline("math", 0, deadline);
+    /// if !completed {
+    ///     println!("showing partial results ({} so far)", results.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fuzzy_find_deadline(
+        &self,
+        query: &str,
+        threshold: i64,
+        deadline: Instant,
+    ) -> (Vec<(usize, i64)>, bool) {
+        let matcher = skim_matcher(None);
+        let mut results = Vec::new();
+        let mut completed = true;
+
+        for (i, s) in self.strings.iter().enumerate() {
+            if i % Self::DEADLINE_CHECK_INTERVAL == 0 && Instant::now() >= deadline {
+                completed = false;
+                break;
+            }
+
+            if let Some(score) = matcher.fuzzy_match(s, query).filter(|&score| score >= threshold) {
+                results.push((i, score));
+            }
+        }
+
+        results.sort_by_key(|&(_, score)| Reverse(score));
+        (results, completed)
+    }
+
+    /// Performs a fuzzy search and returns the matching strings themselves.
+    ///
+    /// Same matching and ordering as [`fuzzy_find()`][Self::fuzzy_find], but each
+    /// result carries a borrowed reference to the matched string instead of its
+    /// index, so display code doesn't need a follow‑up [`get()`][Self::get] per hit.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `threshold` – minimum matching score.
+    ///
+    /// # Returns
+    /// A vector of `(string, score)` tuples sorted by descending score.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::SharedStrings;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("xl/sharedStrings.xml")?;
+    /// let shared = SharedStrings::load(&data)?;
+    ///
+    /// for (s, score) in shared.fuzzy_find_strings("math", 0).iter().take(5) {
+    ///     println!("{} (score: {})", s, score);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fuzzy_find_strings(&self, query: &str, threshold: i64) -> Vec<(&str, i64)> {
+        self.fuzzy_find(query, threshold).into_iter()
+            .map(|(i, score)| (self.strings.text(i), score)).collect()
+    }
+
+    /// Performs a fuzzy search and returns each result as a [`SearchMatch`]
+    /// with index, score and text.
+    ///
+    /// Same matching and ordering as [`fuzzy_find()`][Self::fuzzy_find];
+    /// the result type is what a front end needs and, with the `serde`
+    /// feature, serializes directly.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `threshold` – minimum matching score.
+    ///
+    /// # Returns
+    /// The matches sorted by descending score.
+    ///
+    /// # Example
+    /// ```ignore
+    /// # use excel_parser::SharedStrings;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let shared = SharedStrings::load(&std::fs::read("xl/sharedStrings.xml")?)?;
+    /// let body = serde_json::to_string(&shared.fuzzy_find_matches("Суббота", 30))?;
+    /// // [{"index":12,"score":87,"text":"Суббота"}, ...]
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fuzzy_find_matches(&self, query: &str, threshold: i64) -> Vec<SearchMatch<'_>> {
+        self.to_matches(self.fuzzy_find(query, threshold))
+    }
+
+    /// Attaches the text to `(index, score)` results of any search
+    /// ([`fuzzy_find_top_k()`][Self::fuzzy_find_top_k],
+    /// [`find_levenshtein()`][Self::find_levenshtein], ...), keeping their
+    /// order.
+    ///
+    /// Indices must come from this table; an index out of range gets empty
+    /// text.
+    pub fn to_matches(&self, results: impl IntoIterator<Item = (usize, i64)>) -> Vec<SearchMatch<'_>> {
+        results.into_iter()
+            .map(|(index, score)| SearchMatch { index, score, text: self.strings.text(index) })
+            .collect()
+    }
+
+    /// Runs a fuzzy search and returns one page of ranked results, as a
+    /// front‑end list needs them.
+    ///
+    /// Matches are ranked as in [`fuzzy_find()`][Self::fuzzy_find]. With
+    /// [`dedup`](SearchView::dedup), strings with identical text (the same
+    /// value stored at several indices) are reduced to their best‑ranked
+    /// occurrence before paginating, so pages never repeat a text.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `view` – threshold, de‑duplication and page bounds.
+    ///
+    /// # Returns
+    /// The requested page as `(index, score, text)` triples, and the total
+    /// number of ranked results (after de‑duplication) for computing the page
+    /// count.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, SearchView};
+    /// # fn demo(shared: &SharedStrings) {
+    /// let view = SearchView { dedup: true, offset: 0, limit: Some(10), ..Default::default() };
+    /// let (page, total) = shared.fuzzy_find_view("math", view);
+    /// println!("showing {} of {}", page.len(), total);
+    /// # }
+    /// ```
+    pub fn fuzzy_find_view(&self, query: &str, view: SearchView) -> (Vec<(usize, i64, &str)>, usize) {
+        let mut seen = FxHashSet::default();
+        let ranked: Vec<_> = self.fuzzy_find(query, view.threshold).into_iter()
+            .map(|(i, score)| (i, score, self.strings.text(i)))
+            .filter(|&(_, _, text)| !view.dedup || seen.insert(text))
+            .collect();
+
+        let total = ranked.len();
+        let page = ranked.into_iter()
+            .skip(view.offset)
+            .take(view.limit.unwrap_or(usize::MAX))
+            .collect();
+        (page, total)
+    }
+
+    /// Convenience method returning only the indices of matching strings.
+    ///
+    /// Equivalent to:
+    /// ```ignore
+    /// self.fuzzy_find(query, threshold)
+    ///     .into_iter()
+    ///     .map(|(i, _)| i)
+    ///     .collect()
+    /// ```
+    ///
+    /// Use this when you only need indices (e.g., for fetching the actual strings
+    /// via [`get()`][Self::get]) and don't need the scores.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `threshold` – minimum matching score.
+    ///
+    /// # Returns
+    /// A vector of indices whose strings matched the query, sorted by match quality.
+    pub fn fuzzy_find_indices(&self, query: &str, threshold: i64) -> Vec<usize> {
+        self.fuzzy_find(query, threshold).into_iter()
+            .map(|(i, _)| i).collect()
+    }
+
+    /// Performs a fuzzy search and also returns which characters matched.
+    ///
+    /// Same matching and ordering as [`fuzzy_find()`][Self::fuzzy_find], but
+    /// each result carries the positions of the matched characters, for
+    /// highlighting them in a result list. Computing positions costs a little
+    /// more than scoring alone, and the query cache is not used.
+    ///
+    /// # Arguments
+    /// * `query` – the search pattern.
+    /// * `threshold` – minimum matching score.
+    ///
+    /// # Returns
+    /// A vector of `(index, score, positions)` tuples sorted by descending
+    /// score. `positions` are ascending **character** indices into the string
+    /// (not byte offsets), one per query character.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::SharedStrings;
+    /// # fn demo(shared: &SharedStrings) {
+    /// for (idx, _, positions) in shared.fuzzy_find_indices_with_positions("мат", 30) {
+    ///     let highlighted: String = shared.get(idx).unwrap().chars().enumerate()
+    ///         .map(|(i, ch)| if positions.contains(&i) { ch.to_uppercase().to_string() } else { ch.to_string() })
+    ///         .collect();
+    ///     println!("{highlighted}");
+    /// }
+    /// # }
+    /// ```
+    pub fn fuzzy_find_indices_with_positions(&self, query: &str, threshold: i64) -> Vec<(usize, i64, Vec<usize>)> {
+        let matcher = skim_matcher(None);
+        let mut results: Vec<_> = self.strings.iter().enumerate()
+            .filter_map(|(i, s)| {
+                matcher.fuzzy_indices(s, query)
+                    .filter(|&(score, _)| score >= threshold)
+                    .map(|(score, positions)| (i, score, positions))
+            })
+            .collect();
+
+        results.sort_by_key(|&(_, score, _)| Reverse(score));
+        results
+    }
+
+    /// Finds strings within `max_distance` edits of `query`.
+    ///
+    /// The fuzzy matcher looks for the query's characters in order, which
+    /// suits abbreviations (`"мат ан"`) but handles typos poorly: a swapped or
+    /// wrong letter breaks the subsequence. This compares whole strings by
+    /// Damerau–Levenshtein distance instead, counting inserted, deleted and
+    /// substituted characters and swapped neighbours as one edit each, so
+    /// `"Суботта"` finds `"Суббота"` at distance 2.
+    ///
+    /// Comparison is by character and case‑sensitive; the query cache is not
+    /// used. Strings whose length differs from the query's by more than
+    /// `max_distance` are skipped without computing the distance.
+    ///
+    /// # Arguments
+    /// * `query` – the text to compare every string with.
+    /// * `max_distance` – largest number of edits a match may need.
+    ///
+    /// # Returns
+    /// A vector of `(index, score)` tuples like [`fuzzy_find()`][Self::fuzzy_find]
+    /// returns, with the negated distance as score: `0` for an exact match,
+    /// `-1` for one edit and so on. Sorted by descending score (closest
+    /// first), equal scores in table order, so the results work with
+    /// [`merge_ranked()`][Self::merge_ranked] too.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::SharedStrings;
+    /// # fn demo(shared: &SharedStrings) {
+    /// for (idx, score) in shared.find_levenshtein("Теорея вероятностей", 2) {
+    ///     println!("{} edits: {}", -score, shared.get(idx).unwrap());
+    /// }
+    /// # }
+    /// ```
+    pub fn find_levenshtein(&self, query: &str, max_distance: usize) -> Vec<(usize, i64)> {
+        let mut distance = EditDistance::new(query);
+        let mut results: Vec<_> = self.strings.iter().enumerate()
+            .filter_map(|(i, s)| distance.within(s, max_distance).map(|d| (i, -(d as i64))))
+            .collect();
+
+        results.sort_by_key(|&(_, score)| Reverse(score));
+        results
+    }
+
+    /// Searches with a query expression combining phrases, fuzzy terms,
+    /// `AND`, `OR` and negation, in one scan of the table.
+    ///
+    /// # Syntax
+    /// - `"теория функций"` – a phrase: the string must contain the text
+    ///   literally.
+    /// - `Головин` – a fuzzy term: the string must match it like
+    ///   [`fuzzy_find()`][Self::fuzzy_find] would (characters in order, gaps
+    ///   allowed; smart case for ASCII letters).
+    /// - `-term`, `-"phrase"`, `-(…)` – negation: the string must not match.
+    ///   A negated fuzzy term excludes every string containing its letters in
+    ///   order, so `-"лекция"` is usually what is meant.
+    /// - `a b` or `a AND b` – both must match; `a OR b` – either must match.
+    ///   `AND` binds tighter than `OR`, and parentheses group. Operators are
+    ///   recognized in upper case only, so `and` / `or` are ordinary words.
+    ///
+    /// `options` prepare every term and every string and select the matcher
+    /// as in [`search()`][Self::search], e.g.
+    /// [`ignore_case`](SearchOptions::ignore_case) makes phrases
+    /// case‑insensitive too; smart case looks at all terms together. The
+    /// [`threshold`](SearchOptions::threshold) and
+    /// [`limit`](SearchOptions::limit) apply to the combined score.
+    ///
+    /// # Arguments
+    /// * `expr` – the query expression.
+    /// * `options` – term and string preparation; the default compares as
+    ///   written.
+    ///
+    /// # Returns
+    /// `(index, score)` tuples of the matching strings sorted by descending
+    /// score, equal scores in table order. The score adds up the scores of
+    /// the matched terms (the best alternative for `OR`; negations add
+    /// nothing), so a query of only negations scores every match `0`.
+    ///
+    /// # Errors
+    /// Returns [`QueryError`] for an empty expression, an unclosed quote or
+    /// parenthesis, or an operator without a term to apply to.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{SharedStrings, SearchOptions};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let shared = SharedStrings::load(&std::fs::read("xl/sharedStrings.xml")?)?;
+    /// let options = SearchOptions::new().ignore_case(true);
+    /// for (idx, score) in shared.query(r#""теория функций" AND Головин -"лекция""#, &options)? {
+    ///     println!("{score:4} {}", shared.get(idx).unwrap());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query(&self, expr: &str, options: &SearchOptions) -> Result<Vec<(usize, i64)>, QueryError> {
+        let expr = Expr::parse(expr)?;
+        let options = options.for_query(&expr.terms().collect::<String>());
+        let expr = expr.prepare(&options);
+        let matcher = options.matcher();
+        let mut results: Vec<_> = self.strings.iter().enumerate()
+            .filter_map(|(i, s)| expr.score(matcher, &options.prepare_text(s)).map(|score| (i, score)))
+            .filter(|&(_, score)| score >= options.min_score())
+            .collect();
+
+        results.sort_by_key(|&(_, score)| Reverse(score));
+        options.apply_limit(&mut results);
+        Ok(results)
+    }
+
+    /// Merges per‑table search results into a single global ranking.
+    ///
+    /// When a query is fanned out across several tables (e.g. on different
+    /// threads) and the tables are conceptually concatenated, each table's
+    /// indices are local. This re‑bases them by the table's offset and sorts
+    /// everything by score, highest first.
+    ///
+    /// The sort is stable: equal scores keep table order, then the order
+    /// within each table.
+    ///
+    /// # Arguments
+    /// * `results` – one result vector per table, as returned by
+    ///   [`fuzzy_find()`][Self::fuzzy_find].
+    /// * `offsets` – global index of each table's first string; typically the
+    ///   running sum of the preceding tables' [`len()`][Self::len].
+    ///
+    /// # Returns
+    /// `(global_index, score)` pairs sorted by score descending.
+    ///
+    /// # Panics
+    /// Panics if `results` and `offsets` have different lengths.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::SharedStrings;
+    /// # fn demo(a: &SharedStrings, b: &SharedStrings) {
+    /// let (ra, rb) = std::thread::scope(|s| {
+    ///     let ha = s.spawn(|| a.fuzzy_find("math", 0));
+    ///     let hb = s.spawn(|| b.fuzzy_find("math", 0));
+    ///     (ha.join().unwrap(), hb.join().unwrap())
+    /// });
+    /// let merged = SharedStrings::merge_ranked(vec![ra, rb], &[0, a.len()]);
+    /// # }
+    /// ```
+    pub fn merge_ranked(results: Vec<Vec<(usize, i64)>>, offsets: &[usize]) -> Vec<(usize, i64)> {
+        assert_eq!(results.len(), offsets.len(), "one offset per result vector is required");
+
+        let total = results.iter().map(Vec::len).sum();
+        let mut merged = Vec::with_capacity(total);
+        for (table, &offset) in results.into_iter().zip(offsets) {
+            merged.extend(table.into_iter().map(|(i, score)| (i + offset, score)));
+        }
+
+        merged.sort_by_key(|&(_, score)| Reverse(score));
+        merged
+    }
+}
+
+impl<'a> IntoIterator for &'a SharedStrings {
+    type Item = &'a str;
+    type IntoIter = SharedStringsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// `shared[i]` is the string at index `i`.
+///
+/// # Panics
+/// Panics if `index` is out of range; use [`SharedStrings::get`] for
+/// indices read from a workbook, which may be.
+impl Index<usize> for SharedStrings {
+    type Output = str;
+
+    fn index(&self, index: usize) -> &str {
+        match self.strings.get(index) {
+            Some(s) => s,
+            None => panic!("shared string index {index} out of range for a table of {} strings", self.len()),
+        }
+    }
+}
+
+/// Approximate heap bytes of a hash map's table, not counting what the keys
+/// and values own.
+fn map_bytes<K, V>(map: &FxHashMap<K, V>) -> usize {
+    // One control byte per slot.
+    map.capacity() * (size_of::<(K, V)>() + 1)
+}
+
+// ---------------------------------------------------------------------------
+// serde support (feature "serde")
+// ---------------------------------------------------------------------------
+
+/// How a table is serialized: a plain sequence of its strings in table
+/// order, so indices survive a round trip unchanged and cell references into
+/// the table stay valid.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+struct SavedStrings<S>(Vec<S>);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SharedStrings {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedStrings(self.strings.iter().collect()).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<SavedStrings<Box<str>>> for SharedStrings {
+    fn from(saved: SavedStrings<Box<str>>) -> Self {
+        Self {
+            strings: Storage::Eager(saved.0.iter().map(|s| &**s).collect()),
+            query_cache: None,
+            truncated: false,
+            rich: FxHashMap::default(),
+            phonetic: FxHashMap::default(),
+            reverse: OnceLock::new(),
+            trigrams: OnceLock::new(),
+            count_mismatch: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::excel_parser::Color;
+
+    /// Builds a shared strings part holding `strings` as plain `<t>` items.
+    fn sst(strings: &[&str]) -> Vec<u8> {
+        let items: String = strings.iter().map(|s| format!("<si><t>{s}</t></si>")).collect();
+        format!(r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">{items}</sst>"#).into_bytes()
+    }
+
+    fn table(strings: &[&str]) -> SharedStrings {
+        SharedStrings::load(&sst(strings)).unwrap()
+    }
+
+    #[test]
+    fn fuzzy_find_strings_matches_fuzzy_find() {
+        let shared = table(&["Mathematics", "History", "Applied math", "Physics", "Mathematical analysis"]);
+        let by_index = shared.fuzzy_find("math", 0);
+        let by_string = shared.fuzzy_find_strings("math", 0);
+
+        assert_eq!(by_index.len(), 3);
+        assert_eq!(by_string.len(), by_index.len());
+        for (&(i, score), &(s, s_score)) in by_index.iter().zip(&by_string) {
+            assert_eq!(shared.get(i), Some(s));
+            assert_eq!(score, s_score);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_keeps_strings_and_indices() {
+        let shared = table(&["Plain", "With &quot;quotes&quot; and \\ slash", "Кириллица", "", "Plain"]);
+        let saved = serde_json::to_string(&shared).unwrap();
+        assert_eq!(saved, r#"["Plain","With \"quotes\" and \\ slash","Кириллица","","Plain"]"#);
+
+        let restored: SharedStrings = serde_json::from_str(&saved).unwrap();
+        assert_eq!(restored.len(), shared.len());
+        assert!(restored.iter().eq(shared.iter()));
+        assert_eq!(restored.get(2), Some("Кириллица"));
+
+        // A lazily loaded table serializes every string, parsed or not.
+        let lazy = SharedStrings::load_with_options(&sst(&["a", "b", "c"]), &LoadOptions::new().lazy(true)).unwrap();
+        assert_eq!(serde_json::to_string(&lazy).unwrap(), r#"["a","b","c"]"#);
+        assert!(serde_json::from_str::<SharedStrings>(r#"{"strings":[]}"#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn search_index_round_trips_through_json() {
+        let shared = table(&["Математика", "Физика", "Mathematics", "History"]);
+        let saved = serde_json::to_string(&shared.build_index()).unwrap();
+        assert!(saved.starts_with(r#"{"strings":["Математика","Физика","Mathematics","History"],"postings":[["#), "{saved}");
+
+        let restored = SharedStrings::from_index(serde_json::from_str(&saved).unwrap());
+        assert!(restored.has_trigram_index());
+        assert!(restored.iter().eq(shared.iter()));
+        let options = SearchOptions::default();
+        assert_eq!(restored.find_substring("ика", &options), shared.find_substring("ика", &options));
+
+        // Saved twice, the same table gives the same bytes.
+        assert_eq!(serde_json::to_string(&restored.build_index()).unwrap(), saved);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn search_index_rejects_postings_that_do_not_match_the_strings() {
+        let out_of_range = r#"{"strings":["abc"],"postings":[[1,[0,5]]]}"#;
+        let unordered = r#"{"strings":["abc","abd"],"postings":[[1,[1,0]]]}"#;
+        for saved in [out_of_range, unordered] {
+            let error = serde_json::from_str::<SearchIndex>(saved).unwrap_err();
+            assert!(error.to_string().contains("trigram index does not match the strings"), "{error}");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn search_matches_serialize_as_objects() {
+        let shared = table(&["Понедельник", "Суббота"]);
+        let matches = shared.to_matches([(1, 87)]);
+        assert_eq!(serde_json::to_string(&matches).unwrap(), r#"[{"index":1,"score":87,"text":"Суббота"}]"#);
+    }
+
+    #[test]
+    fn mixed_text_and_runs_concatenate_in_document_order() {
+        let xml = br#"<sst><si><t>Hello </t><r><rPr><b/></rPr><t>bold</t></r><t> tail</t></si><si><r><t>a</t></r><t>b</t></si></sst>"#;
+        let shared = SharedStrings::load(xml).unwrap();
+        assert_eq!(shared.get(0), Some("Hello bold tail"));
+        assert_eq!(shared.get(1), Some("ab"));
+    }
+
+    #[test]
+    fn fuzzy_find_deadline_reports_incomplete_scans() {
+        let strings: Vec<String> = (0..20_000).map(|i| format!("Lesson {i} mathematics")).collect();
+        let shared = table(&strings.iter().map(String::as_str).collect::<Vec<_>>());
+
+        let (partial, completed) = shared.fuzzy_find_deadline("math", 0, Instant::now());
+        assert!(!completed);
+        assert!(partial.len() < strings.len());
+
+        let far = Instant::now() + std::time::Duration::from_secs(3600);
+        let (all, completed) = shared.fuzzy_find_deadline("math", 0, far);
+        assert!(completed);
+        assert_eq!(all, shared.fuzzy_find("math", 0));
+    }
+
+    #[test]
+    fn query_cache_returns_fresh_results_and_is_consulted() {
+        let strings = ["Mathematics", "History", "Applied math", "Physics"];
+        let fresh = table(&strings);
+        let cached = table(&strings).with_query_cache(4);
+
+        let first = cached.fuzzy_find("math", 0);
+        assert_eq!(first, fresh.fuzzy_find("math", 0));
+        assert_eq!(cached.fuzzy_find("math", 0), first);
+
+        // Plant a marker result: a second call answered from the cache returns it.
+        let cache = cached.query_cache.as_ref().unwrap();
+        cache.insert("math", 0, &[(1, 7)]);
+        assert_eq!(cached.fuzzy_find("math", 0), [(1, 7)]);
+        assert_eq!(cached.fuzzy_find_strings("math", 0), [("History", 7)]);
+
+        cached.clear_query_cache();
+        assert_eq!(cached.fuzzy_find("math", 0), first);
+    }
+
+    #[test]
+    fn whitespace_is_kept_with_or_without_xml_space() {
+        let xml = br#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><si><t> a </t></si><si><t xml:space="preserve"> a </t></si></sst>"#;
+
+        let shared = SharedStrings::load(xml).unwrap();
+        assert_eq!(shared.get(0), Some(" a "));
+        assert_eq!(shared.get(1), Some(" a "));
+
+        let streamed: Vec<String> = SharedStrings::iter_from(xml).map(Result::unwrap).collect();
+        assert_eq!(streamed, [" a ", " a "]);
+    }
+
+    #[test]
+    fn merge_ranked_rebases_and_orders_globally() {
+        let first = table(&["Mathematics", "History", "Applied math"]);
+        let second = table(&["Physics", "Math", "Mathematical analysis"]);
+
+        let merged = SharedStrings::merge_ranked(
+            vec![first.fuzzy_find("math", 0), second.fuzzy_find("math", 0)],
+            &[0, first.len()],
+        );
+
+        let all = table(&["Mathematics", "History", "Applied math", "Physics", "Math", "Mathematical analysis"]);
+        let mut expected = all.fuzzy_find("math", 0);
+        expected.sort_by_key(|&(i, score)| (Reverse(score), i));
+        assert_eq!(merged, expected);
+        assert!(merged.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn merge_ranked_keeps_table_order_for_equal_scores() {
+        let merged = SharedStrings::merge_ranked(vec![vec![(1, 5), (0, 9)], vec![(0, 5), (2, 7)]], &[0, 10]);
+        assert_eq!(merged, [(0, 9), (12, 7), (1, 5), (10, 5)]);
+    }
+
+    #[test]
+    fn collapse_whitespace_scores_like_the_single_spaced_query() {
+        let shared = table(&["Теория функций комплексного переменного", "Функциональный анализ", "Теория вероятностей"]);
+        let collapse = SearchOptions::new().collapse_whitespace(true);
+
+        let single = shared.search("Теория функций", &collapse);
+        assert!(!single.is_empty());
+        assert_eq!(shared.search("Теория  функций", &collapse), single);
+        assert_eq!(shared.search("Теория \t\n функций", &collapse), single);
+        assert_eq!(shared.search("Теория функций", &SearchOptions::new()), single);
+
+        // Without the option the extra space must be matched too.
+        assert_ne!(shared.search("Теория  функций", &SearchOptions::new()), single);
+    }
+
+    #[test]
+    fn limit_stops_after_n_strings() {
+        let xml = sst(&["one", "two", "three", "four", "five"]);
+        let options = LoadOptions::new().limit(3);
+
+        let preview = SharedStrings::load_with_options(&xml, &options).unwrap();
+        assert_eq!(preview.len(), 3);
+        assert!(preview.is_truncated());
+        assert_eq!(preview.get(2), Some("three"));
+        assert_eq!(preview.get(3), None);
+
+        let mut items = SharedStrings::iter_from_with_options(&xml, &options);
+        assert_eq!(items.by_ref().count(), 3);
+        assert!(items.is_truncated());
+
+        let exact = SharedStrings::load_with_options(&xml, &LoadOptions::new().limit(5)).unwrap();
+        assert_eq!(exact.len(), 5);
+        assert!(!exact.is_truncated());
+        assert!(!table(&["one", "two"]).is_truncated());
+    }
+
+    #[test]
+    fn fuzzy_find_view_dedups_then_paginates() {
+        let shared = table(&["Math", "Applied math", "Math", "History", "Mathematics", "Applied math", "Math"]);
+
+        let ranked = shared.fuzzy_find("math", 0);
+        let mut seen = std::collections::HashSet::new();
+        let unique: Vec<_> = ranked.iter()
+            .map(|&(i, score)| (i, score, shared.get(i).unwrap()))
+            .filter(|&(_, _, text)| seen.insert(text))
+            .collect();
+        assert_eq!(unique.len(), 3);
+
+        let page = |offset, limit| shared.fuzzy_find_view("math", SearchView { dedup: true, offset, limit, ..Default::default() });
+        let (first, total) = page(0, Some(2));
+        assert_eq!(total, 3);
+        assert_eq!(first, unique[..2]);
+        let (rest, total) = page(2, Some(2));
+        assert_eq!(total, 3);
+        assert_eq!(rest, unique[2..]);
+        assert!(page(5, Some(2)).0.is_empty());
+
+        let (all, total) = shared.fuzzy_find_view("math", SearchView::default());
+        assert_eq!(total, ranked.len());
+        assert_eq!(all.len(), ranked.len());
+    }
+
+    #[test]
+    fn positions_of_returns_every_duplicate_in_order() {
+        let shared = table(&["Итого", "Name", "Итого", "итого", "Итого ", "Итого"]);
+
+        assert_eq!(shared.positions_of("Итого"), [0, 2, 5]);
+        assert_eq!(shared.positions_of("Name"), [1]);
+        assert!(shared.positions_of("Missing").is_empty());
+
+        let mut indexed = shared.indices_of("Итого");
+        indexed.sort_unstable();
+        assert_eq!(indexed, shared.positions_of("Итого"));
+    }
+
+    #[test]
+    fn fuzzy_find_prelowered_matches_fuzzy_find_for_lowercase_queries() {
+        let shared = table(&["Mathematics", "APPLIED MATH", "History", "math club", "MaTh", "Физика", "физмат"]);
+
+        for query in ["math", "mat", "hist", "a", "физ"] {
+            assert_eq!(shared.fuzzy_find_prelowered(query, 0), shared.fuzzy_find(query, 0), "{query}");
+        }
+        assert_eq!(shared.fuzzy_find_prelowered("math", 50), shared.fuzzy_find("math", 50));
+    }
+
+    #[test]
+    fn rich_text_keeps_runs_and_their_fonts() {
+        let xml = br#"<sst>
+<si><r><t>Total: </t></r><r><rPr><b/><i val="0"/><u/><strike/><color rgb="FFFF0000"/><sz val="11.5"/><rFont val="Calibri"/></rPr><t>42</t></r></si>
+<si><t>plain</t></si>
+<si><t>lead </t><r><rPr><u val="none"/><color theme="1" tint="-0.25"/></rPr><t>&amp; run</t></r></si>
+</sst>"#;
+        let shared = SharedStrings::load_with_options(xml, &LoadOptions::new().rich_text(true)).unwrap();
+
+        let runs = shared.get_rich(0).unwrap().runs();
+        assert_eq!(runs.len(), 2);
+        assert_eq!((&*runs[0].text, &runs[0].font), ("Total: ", &None));
+        assert_eq!(&*runs[1].text, "42");
+        assert_eq!(runs[1].font, Some(RunFont {
+            bold: true,
+            italic: false,
+            underline: true,
+            strike: true,
+            color: Some(Color::Argb(0xFFFF_0000)),
+            size: Some(11.5),
+            name: Some("Calibri".into()),
+        }));
+        let text: String = runs.iter().map(|run| &*run.text).collect();
+        assert_eq!(shared.get(0), Some(text.as_str()));
+
+        // Plain items have no rich form.
+        assert_eq!(shared.get_rich(1), None);
+
+        // Direct text becomes a run without properties.
+        let runs = shared.get_rich(2).unwrap().runs();
+        assert_eq!((&*runs[0].text, &runs[0].font), ("lead ", &None));
+        assert_eq!(&*runs[1].text, "& run");
+        let font = runs[1].font.as_ref().unwrap();
+        assert!(!font.underline);
+        assert_eq!(font.color, Some(Color::Theme { index: 1, tint: -0.25 }));
+        assert_eq!(shared.get(2), Some("lead & run"));
+
+        // Runs are only kept on request.
+        let plain = SharedStrings::load(xml).unwrap();
+        assert_eq!(plain.get(0), Some("Total: 42"));
+        assert_eq!(plain.get_rich(0), None);
+        assert_eq!(shared.get_rich(3), None);
+    }
+
+    #[test]
+    fn phonetic_runs_are_skipped_and_kept_on_request() {
+        let xml = r#"<sst>
+<si><t>東京都</t><rPh sb="0" eb="2"><t>トウキョウ</t></rPh><rPh sb="2" eb="3"><t>ト</t></rPh><phoneticPr fontId="1"/></si>
+<si><r><t>大</t></r><r><rPr><b/></rPr><t>阪</t></r><rPh sb="0" eb="2"><t>オオサカ</t></rPh></si>
+<si><t>plain</t></si>
+</sst>"#.as_bytes();
+
+        for options in [LoadOptions::new(), LoadOptions::new().lazy(true), LoadOptions::new().phonetic(true)] {
+            let shared = SharedStrings::load_with_options(xml, &options).unwrap();
+            assert!(shared.iter().eq(["東京都", "大阪", "plain"]), "{options:?}");
+        }
+        let streamed: Vec<String> = SharedStrings::iter_from(xml).map(Result::unwrap).collect();
+        assert_eq!(streamed, ["東京都", "大阪", "plain"]);
+
+        let shared = SharedStrings::load_with_options(xml, &LoadOptions::new().phonetic(true).rich_text(true)).unwrap();
+        assert_eq!(shared.phonetic(0), Some("トウキョウト"));
+        assert_eq!(shared.phonetic(1), Some("オオサカ"));
+        assert_eq!(shared.phonetic(2), None);
+        // The reading is not a run.
+        let runs: Vec<_> = shared.get_rich(1).unwrap().runs().iter().map(|run| &*run.text).collect();
+        assert_eq!(runs, ["大", "阪"]);
+
+        assert_eq!(SharedStrings::load(xml).unwrap().phonetic(0), None);
+    }
+
+    #[test]
+    fn decode_escapes_false_keeps_the_stored_text() {
+        let xml = sst(&["a_x000D__x000A_b", "_x005F_x000D_", "_xD83D__xDE00_", "snake_x_case"]);
+        let decoded = ["a\r\nb", "_x000D_", "😀", "snake_x_case"];
+        let raw = ["a_x000D__x000A_b", "_x005F_x000D_", "_xD83D__xDE00_", "snake_x_case"];
+
+        for lazy in [false, true] {
+            let options = LoadOptions::new().lazy(lazy);
+            assert!(SharedStrings::load_with_options(&xml, &options).unwrap().iter().eq(decoded), "lazy: {lazy}");
+            let options = options.decode_escapes(false);
+            assert!(SharedStrings::load_with_options(&xml, &options).unwrap().iter().eq(raw), "lazy: {lazy}");
+        }
+        let streamed: Vec<String> = SharedStrings::iter_from_with_options(&xml, &LoadOptions::new().decode_escapes(false))
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(streamed, raw);
+
+        // Runs are decoded like the text.
+        let xml = br#"<sst><si><r><t>x_x000D_</t></r></si></sst>"#;
+        let rich = SharedStrings::load_with_options(xml, &LoadOptions::new().rich_text(true)).unwrap();
+        assert_eq!(&*rich.get_rich(0).unwrap().runs()[0].text, "x\r");
+    }
+
+    #[test]
+    fn iter_from_yields_what_load_keeps() {
+        let xml = br#"<sst count="4" uniqueCount="4"><si><t>A &amp; B</t></si><si><r><t>rich </t></r><r><rPr><b/></rPr><t>run</t></r></si><si><t/></si><si><t>&#1071;&#x44F;</t><rPh><t>x</t></rPh></si></sst>"#;
+        let loaded = SharedStrings::load(xml).unwrap();
+        let streamed: Vec<String> = SharedStrings::iter_from(xml).map(Result::unwrap).collect();
+        assert_eq!(streamed, ["A & B", "rich run", "", "Яя"]);
+        assert!(loaded.iter().eq(&streamed));
+    }
+
+    #[test]
+    fn iter_from_stops_after_the_first_error() {
+        let xml = br#"<sst><si><t>first</t></si><si><t>second</t></si><!-- unclosed"#;
+        let mut items = SharedStrings::iter_from(xml);
+        assert_eq!(items.next().unwrap().unwrap(), "first");
+        assert_eq!(items.next().unwrap().unwrap(), "second");
+        assert!(items.next().unwrap().is_err());
+        assert!(items.next().is_none());
+        assert!(SharedStrings::load(xml).is_err());
+    }
+
+    #[test]
+    fn lazy_and_eager_loading_agree() {
+        let strings = ["Mathematics", "History", "", "Applied math", "  spaced  ", "Математика", "History"];
+        let xml = sst(&strings);
+        let eager = SharedStrings::load(&xml).unwrap();
+        let lazy = SharedStrings::load_with_options(&xml, &LoadOptions::new().lazy(true)).unwrap();
+
+        assert_eq!(lazy.len(), eager.len());
+        assert_eq!(lazy.get(5), eager.get(5));
+        assert_eq!(lazy.get(strings.len()), None);
+        assert!(lazy.iter().eq(eager.iter()));
+        assert_eq!(lazy.fuzzy_find("math", 0), eager.fuzzy_find("math", 0));
+        assert_eq!(lazy.indices_of("History"), eager.indices_of("History"));
+        assert_eq!(lazy.count_mismatch(), eager.count_mismatch());
+
+        for limit in [0, 3, strings.len()] {
+            let options = LoadOptions::new().limit(limit);
+            let eager = SharedStrings::load_with_options(&xml, &options).unwrap();
+            let lazy = SharedStrings::load_with_options(&xml, &options.lazy(true)).unwrap();
+            assert_eq!((lazy.len(), lazy.is_truncated()), (eager.len(), eager.is_truncated()), "limit {limit}");
+            assert!(lazy.iter().eq(eager.iter()), "limit {limit}");
+        }
+
+        // Runs and readings aren't kept in lazy mode.
+        let rich = br#"<sst><si><r><rPr><b/></rPr><t>bold</t></r><rPh><t>x</t></rPh></si></sst>"#;
+        let lazy = SharedStrings::load_with_options(rich, &LoadOptions::new().lazy(true).rich_text(true).phonetic(true)).unwrap();
+        assert_eq!((lazy.get(0), lazy.get_rich(0), lazy.phonetic(0)), (Some("bold"), None, None));
+    }
+
+    #[test]
+    fn index_of_agrees_with_a_scan() {
+        let strings: Vec<String> = (0..500).map(|i| format!("value {}", i * 7 % 60)).collect();
+        let strings: Vec<&str> = strings.iter().map(String::as_str).collect();
+        for lazy in [false, true] {
+            let shared = SharedStrings::load_with_options(&sst(&strings), &LoadOptions::new().lazy(lazy)).unwrap();
+            assert!(shared.reverse.get().is_none());
+            let before = shared.memory_usage();
+
+            for probe in ["value 0", "value 13", "value 59", "value 60", "", "Value 0"] {
+                let scan = shared.positions_of(probe);
+                assert_eq!(shared.indices_of(probe), scan, "{probe:?}");
+                assert_eq!(shared.index_of(probe), scan.first().copied(), "{probe:?}");
+            }
+            // The index is built once, on the first lookup.
+            assert!(shared.reverse.get().is_some());
+            assert!(shared.memory_usage() >= before + strings.len() * 16);
+        }
+        assert_eq!(table(&[]).index_of(""), None);
+    }
+
+    #[test]
+    fn literal_searches_compare_exactly_or_ignoring_case() {
+        let shared = table(&["Курсовая работа", "курс", "КУРС", "Дискурс", "Straße", "STRASSE", "Курс"]);
+        let exact = SearchOptions::new();
+        let folded = SearchOptions::new().ignore_case(true);
+
+        assert_eq!(shared.find_exact("Курс", &exact), [6]);
+        assert_eq!(shared.find_exact("Курс", &folded), [1, 2, 6]);
+        assert_eq!(shared.find_prefix("Курс", &exact), [0, 6]);
+        assert_eq!(shared.find_prefix("курс", &folded), [0, 1, 2, 6]);
+        assert_eq!(shared.find_substring("урс", &exact), [0, 1, 3, 6]);
+        assert_eq!(shared.find_substring("УРС", &folded), [0, 1, 2, 3, 6]);
+        // Full case folding: ß matches SS.
+        assert_eq!(shared.find_exact("strasse", &folded), [4, 5]);
+
+        assert_eq!(shared.find_substring("", &exact).len(), shared.len());
+        assert!(shared.find_exact("missing", &folded).is_empty());
+    }
+
+    #[test]
+    fn fuzzy_find_parallel_matches_fuzzy_find() {
+        // Enough strings for several chunks, with many equal scores across chunk borders.
+        let strings: Vec<String> = (0..5 * PARALLEL_MIN_CHUNK)
+            .map(|i| match i % 4 {
+                0 => format!("Mathematics {}", i % 10),
+                1 => "Applied math".to_owned(),
+                2 => format!("History {i}"),
+                _ => "Физика".to_owned(),
+            })
+            .collect();
+        let shared = table(&strings.iter().map(String::as_str).collect::<Vec<_>>());
+
+        for (query, threshold) in [("math", 0), ("math", 80), ("hist 1", 0), ("физ", 0), ("zzz", 0)] {
+            let serial = shared.fuzzy_find(query, threshold);
+            assert_eq!(shared.fuzzy_find_parallel(query, threshold), serial, "{query} {threshold}");
+            // Whatever the machine's core count, also split into uneven chunks.
+            for workers in [2, 3, 7] {
+                assert_eq!(shared.fuzzy_scan_chunked(query, threshold, workers), serial, "{query} {threshold} / {workers}");
+            }
+        }
+
+        // Small tables take the serial path.
+        let small = table(&["Mathematics", "History", "Applied math"]);
+        assert_eq!(small.fuzzy_find_parallel("math", 0), small.fuzzy_find("math", 0));
+    }
+
+    #[test]
+    fn fuzzy_find_top_k_is_the_head_of_fuzzy_find() {
+        let shared = table(&[
+            "Math", "Applied math", "History", "Math", "Mathematics", "Mathematical analysis", "math", "Physics", "Math",
+        ]);
+        let all = shared.fuzzy_find("math", 0);
+        assert!(all.len() > 5);
+        for k in [0, 1, 2, 3, 5, all.len(), all.len() + 10] {
+            assert_eq!(shared.fuzzy_find_top_k("math", k), all[..k.min(all.len())], "k = {k}");
+        }
+        // Equal scores keep index order.
+        let top = shared.fuzzy_find_top_k("Math", 4);
+        assert!(top.windows(2).all(|w| w[0].1 > w[1].1 || (w[0].1 == w[1].1 && w[0].0 < w[1].0)), "{top:?}");
+        assert!(shared.fuzzy_find_top_k("zzz", 4).is_empty());
+    }
+
+    #[test]
+    fn match_positions_are_character_indices_of_the_query() {
+        let shared = table(&["Математический анализ", "Прикладная математика", "История", "Applied math"]);
+        for query in ["мат", "анализ", "math", "ма ан"] {
+            let with_positions = shared.fuzzy_find_indices_with_positions(query, 0);
+            let plain: Vec<_> = with_positions.iter().map(|&(i, score, _)| (i, score)).collect();
+            assert_eq!(plain, shared.fuzzy_find(query, 0), "{query}");
+
+            let wanted: Vec<char> = query.chars().collect();
+            for (i, _, positions) in &with_positions {
+                let chars: Vec<char> = shared[*i].chars().collect();
+                assert!(positions.windows(2).all(|w| w[0] < w[1]), "{query}: {positions:?}");
+                let matched: Vec<char> = positions.iter().map(|&p| chars[p].to_lowercase().next().unwrap()).collect();
+                assert_eq!(matched, wanted, "{query} in {}", &shared[*i]);
+            }
+        }
+
+        let found = shared.fuzzy_find_indices_with_positions("анализ", 0);
+        assert_eq!(found[0].0, 0);
+        assert_eq!(found[0].2, (15..21).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn normalization_makes_encodings_compare_equal() {
+        // "Ёлка" precomposed and decomposed, and a full-width "ＡＢＣ".
+        let strings = ["\u{401}лка", "\u{415}\u{308}лка", "ＡＢＣ"];
+        let xml = sst(&strings);
+
+        let stored = table(&strings);
+        assert_eq!(stored.find_exact("Ёлка", &SearchOptions::new()), [0]);
+        let nfc = SearchOptions::new().normalize(Normalization::Nfc);
+        assert_eq!(stored.find_exact("Ёлка", &nfc), [0, 1]);
+        assert_eq!(stored.find_exact("\u{415}\u{308}лка", &nfc), [0, 1]);
+        assert_eq!(stored.search("Ёлка", &nfc).len(), 2);
+        assert!(stored.find_exact("ABC", &nfc).is_empty());
+        assert_eq!(stored.find_exact("ABC", &SearchOptions::new().normalize(Normalization::Nfkc)), [2]);
+        // Search options leave the stored strings alone.
+        assert_eq!(stored.get(1), Some(strings[1]));
+
+        for lazy in [false, true] {
+            let options = LoadOptions::new().normalize(Normalization::Nfkc).lazy(lazy);
+            let normalized = SharedStrings::load_with_options(&xml, &options).unwrap();
+            assert!(normalized.iter().eq(["Ёлка", "Ёлка", "ABC"]), "lazy: {lazy}");
+            assert_eq!(normalized.indices_of("Ёлка"), [0, 1]);
+        }
+    }
+
+    #[test]
+    fn case_folding_and_diacritic_stripping_apply_to_every_mode() {
+        let shared = table(&["Суббота", "Елкин А.", "STRASSE", "Ёлкин Б.", "среда"]);
+        let folded = SearchOptions::new().ignore_case(true);
+        let loose = folded.clone().strip_diacritics(true);
+
+        assert_eq!(shared.find_exact("суббота", &folded), [0]);
+        assert_eq!(shared.find_exact("straße", &folded), [2]);
+        assert!(shared.find_substring("ёлкин", &SearchOptions::new().strip_diacritics(true)).is_empty());
+        assert_eq!(shared.find_substring("ёлкин", &folded), [3]);
+        assert_eq!(shared.find_substring("ёлкин", &loose), [1, 3]);
+        assert_eq!(shared.find_prefix("ЕЛК", &loose), [1, 3]);
+
+        assert!(shared.search("СУББ", &SearchOptions::new()).is_empty());
+        let fuzzy: Vec<usize> = shared.search("субота", &folded).into_iter().map(|(i, _)| i).collect();
+        assert_eq!(fuzzy, [0]);
+        let fuzzy: Vec<usize> = shared.search("елкин", &loose).into_iter().map(|(i, _)| i).collect();
+        assert_eq!(fuzzy.len(), 2);
+        assert!(fuzzy.contains(&1) && fuzzy.contains(&3));
+    }
+
+    #[test]
+    fn find_levenshtein_scores_by_negated_edit_distance() {
+        let shared = table(&["Суббота", "Среда", "Суббота", "Субботник", "Сбубота", "суббота"]);
+
+        assert_eq!(shared.find_levenshtein("Суббота", 0), [(0, 0), (2, 0)]);
+        // A swapped neighbour is one edit; case is compared as written.
+        assert_eq!(shared.find_levenshtein("Суббота", 1), [(0, 0), (2, 0), (4, -1), (5, -1)]);
+        assert_eq!(shared.find_levenshtein("Суботта", 2), [(0, -2), (2, -2), (4, -2)]);
+        assert_eq!(shared.find_levenshtein("Суббота", 2).len(), 4);
+        assert_eq!(shared.find_levenshtein("Суббота", 3)[4..], [(3, -3)]);
+        assert!(shared.find_levenshtein("Понедельник", 3).is_empty());
+        assert!(table(&[]).find_levenshtein("Суббота", 5).is_empty());
+    }
+
+    #[test]
+    fn trigram_index_gives_the_results_of_a_full_scan() {
+        let words = ["Теория", "функций", "Головин", "лекция", "Math", "ab", "A", "ёлка", "  "];
+        let strings: Vec<String> = (0..300usize)
+            .map(|i| (0..i % 4 + 1).map(|k| words[(i * 7 + k * 3) % words.len()]).collect::<Vec<_>>().join(" "))
+            .collect();
+        let strings: Vec<&str> = strings.iter().map(String::as_str).collect();
+        let scanned = table(&strings);
+        let indexed = table(&strings);
+        indexed.build_trigram_index();
+        assert!(indexed.has_trigram_index() && !scanned.has_trigram_index());
+
+        let loaded = SharedStrings::load_with_options(&sst(&strings), &LoadOptions::new().trigram_index(true)).unwrap();
+        assert!(loaded.has_trigram_index());
+
+        let queries = ["", "a", "ab", "Теор", "теория ФУНКЦИЙ", "ция", "Головин лекция", "th", "  ", "xyz", "ё"];
+        let option_sets = [
+            SearchOptions::new(),
+            SearchOptions::new().ignore_case(true),
+            SearchOptions::new().case(CaseMatching::Smart),
+            SearchOptions::new().strip_diacritics(true),
+        ];
+        for shared in [&indexed, &loaded] {
+            for query in queries {
+                for options in &option_sets {
+                    assert_eq!(shared.find_exact(query, options), scanned.find_exact(query, options), "{query:?}");
+                    assert_eq!(shared.find_substring(query, options), scanned.find_substring(query, options), "{query:?}");
+                    assert_eq!(shared.find_prefix(query, options), scanned.find_prefix(query, options), "{query:?}");
+                    assert_eq!(shared.search(query, options), scanned.search(query, options), "{query:?}");
+                }
+                assert_eq!(shared.fuzzy_find(query, 0), scanned.fuzzy_find(query, 0), "{query:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn from_index_restores_the_strings_with_their_trigram_index() {
+        let xml = r#"<sst><si><r><rPr><b/></rPr><t>Математика</t></r></si><si><t>Физика</t></si><si><t>Математика</t></si></sst>"#.as_bytes();
+        for lazy in [false, true] {
+            let shared = SharedStrings::load_with_options(xml, &LoadOptions::new().lazy(lazy).rich_text(true)).unwrap();
+            let index = shared.build_index();
+            assert!(shared.has_trigram_index(), "lazy: {lazy}");
+            assert_eq!((index.len(), index.is_empty()), (3, false));
+
+            let restored = SharedStrings::from_index(index);
+            assert!(restored.has_trigram_index());
+            assert!(restored.iter().eq(shared.iter()));
+            assert_eq!(restored.indices_of("Математика"), [0, 2]);
+            assert_eq!(restored.find_substring("ика", &SearchOptions::new()), [0, 1, 2]);
+            // Runs are not part of the index; lazy tables don't keep them at all.
+            assert_eq!(shared.get_rich(0).is_some(), !lazy);
+            assert!(restored.get_rich(0).is_none());
+        }
+        assert!(table(&[]).build_index().is_empty());
+    }
+
+    #[test]
+    fn search_matches_carry_index_score_and_text() {
+        let shared = table(&["Понедельник", "Суббота", "Воскресенье", "Суббота"]);
+        let matches = shared.fuzzy_find_matches("Суб", 0);
+        let expected: Vec<SearchMatch> = shared.fuzzy_find("Суб", 0).into_iter()
+            .map(|(index, score)| SearchMatch { index, score, text: shared.get(index).unwrap() })
+            .collect();
+        assert_eq!(matches, expected);
+        assert_eq!(matches.iter().map(|m| m.index).collect::<Vec<_>>(), [1, 3]);
+
+        let listed = shared.to_matches([(2, -1), (0, 5)]);
+        assert_eq!(listed, [
+            SearchMatch { index: 2, score: -1, text: "Воскресенье" },
+            SearchMatch { index: 0, score: 5, text: "Понедельник" },
+        ]);
+    }
+
+    #[test]
+    fn iteration_and_indexing_follow_table_order() {
+        let strings = ["Понедельник", "", "Суббота", "Понедельник"];
+        for lazy in [false, true] {
+            let shared = SharedStrings::load_with_options(&sst(&strings), &LoadOptions::new().lazy(lazy)).unwrap();
+            assert!(!shared.is_empty());
+            assert!(shared.iter().eq(strings));
+            assert!(shared.iter().rev().eq(strings.iter().rev().copied()));
+            assert_eq!(shared.iter().len(), 4);
+            assert_eq!(shared.iter().nth(2), Some("Суббота"));
+
+            let mut collected = Vec::new();
+            for s in &shared {
+                collected.push(s);
+            }
+            assert_eq!(collected, strings);
+            for (i, expected) in strings.iter().enumerate() {
+                assert_eq!(&shared[i], *expected);
+            }
+        }
+
+        let empty = table(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter().next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "shared string index 2 out of range for a table of 2 strings")]
+    fn indexing_past_the_end_panics() {
+        let _ = &table(&["a", "b"])[2];
+    }
+
+    #[test]
+    fn count_mismatch_reports_declared_counts_that_disagree() {
+        let items = "<si><t>a</t></si><si><t>b</t></si>";
+        let load = |attrs: &str, options: &LoadOptions| {
+            SharedStrings::load_with_options(format!("<sst {attrs}>{items}</sst>").as_bytes(), options).unwrap()
+        };
+        for lazy in [false, true] {
+            let options = LoadOptions::new().lazy(lazy);
+            assert_eq!(load(r#"count="5" uniqueCount="2""#, &options).count_mismatch(), None);
+            assert_eq!(load("", &options).count_mismatch(), None);
+            assert_eq!(
+                load(r#"count="5" uniqueCount="3""#, &options).count_mismatch(),
+                Some(CountMismatch { count: Some(5), unique_count: Some(3), parsed: 2 }),
+                "lazy: {lazy}",
+            );
+            // Declaring far more strings than the part holds still loads.
+            let inflated = load(r#"uniqueCount="18446744073709551615""#, &options);
+            assert_eq!(inflated.len(), 2);
+            assert!(inflated.count_mismatch().is_some());
+        }
+        // A table cut short by the limit is not compared.
+        assert_eq!(load(r#"uniqueCount="2""#, &LoadOptions::new().limit(1)).count_mismatch(), None);
+        assert_eq!(load(r#"uniqueCount="9""#, &LoadOptions::new().limit(1)).count_mismatch(), None);
+    }
+
+    #[test]
+    fn memory_usage_grows_with_what_the_table_holds() {
+        let strings: Vec<String> = (0..200).map(|i| format!("Аудитория {i}, корпус {}", i % 7)).collect();
+        let strings: Vec<&str> = strings.iter().map(String::as_str).collect();
+        let xml = sst(&strings);
+        let text_bytes: usize = strings.iter().map(|s| s.len()).sum();
+
+        let eager = table(&strings);
+        let base = eager.memory_usage();
+        assert!(base >= text_bytes + strings.len() * size_of::<usize>(), "{base}");
+        assert!(table(&[]).memory_usage() < base);
+
+        eager.index_of("Аудитория 5, корпус 5");
+        let with_reverse = eager.memory_usage();
+        assert!(with_reverse > base);
+        eager.build_trigram_index();
+        assert!(eager.memory_usage() > with_reverse);
+
+        let lazy = SharedStrings::load_with_options(&xml, &LoadOptions::new().lazy(true)).unwrap();
+        let unread = lazy.memory_usage();
+        assert!(unread >= xml.len(), "{unread}");
+        lazy.get(0);
+        let one_read = lazy.memory_usage();
+        assert!(one_read > unread);
+        assert!(lazy.iter().count() == strings.len() && lazy.memory_usage() > one_read);
+        assert!(lazy.memory_usage() > base);
+
+        let rich_xml = br#"<sst><si><r><rPr><b/></rPr><t>bold</t></r><r><t> plain</t></r></si></sst>"#;
+        let plain = SharedStrings::load(rich_xml).unwrap();
+        let rich = SharedStrings::load_with_options(rich_xml, &LoadOptions::new().rich_text(true)).unwrap();
+        assert!(rich.memory_usage() > plain.memory_usage());
+        let cached = SharedStrings::load(rich_xml).unwrap().with_query_cache(16);
+        assert!(cached.memory_usage() > plain.memory_usage());
+    }
+
+    #[test]
+    fn query_combines_phrases_fuzzy_terms_and_negation() {
+        let shared = table(&[
+            "Теория функций, Головин, лекция",
+            "Теория функций, Головин, практика",
+            "Теория вероятностей, Головин",
+            "теория функций, Петров",
+            "Физика, Головин, лекция",
+        ]);
+        let indices = |expr: &str, options: &SearchOptions| -> Vec<usize> {
+            let mut found: Vec<usize> = shared.query(expr, options).unwrap().into_iter().map(|(i, _)| i).collect();
+            found.sort_unstable();
+            found
+        };
+        let options = SearchOptions::new();
+        assert_eq!(indices(r#""Теория функций" AND Головин -"лекция""#, &options), [1]);
+        assert_eq!(indices(r#""Теория функций" Головин"#, &options), [0, 1]);
+        assert_eq!(indices(r#""теория функций""#, &options), [3]);
+        assert_eq!(indices(r#""теория функций""#, &options.clone().ignore_case(true)), [0, 1, 3]);
+        assert_eq!(indices(r#"Петров OR "Физика""#, &options), [3, 4]);
+        assert_eq!(indices(r#"-Головин"#, &options), [3]);
+        assert_eq!(indices(r#"Головин -("лекция" OR "вероятностей")"#, &options), [1]);
+
+        // Negations add nothing to the score; an AND adds up its terms.
+        assert!(shared.query("-Петров", &options).unwrap().iter().all(|&(_, score)| score == 0));
+        let both = shared.query("Физика лекция", &options).unwrap();
+        let physics = shared.fuzzy_find("Физика", 0);
+        let lecture = shared.fuzzy_find("лекция", 0);
+        let score = |results: &[(usize, i64)]| results.iter().find(|&&(i, _)| i == 4).unwrap().1;
+        assert_eq!(score(&both), score(&physics) + score(&lecture));
+
+        let limited = shared.query("Головин", &options.clone().limit(2)).unwrap();
+        assert_eq!(limited, shared.query("Головин", &options).unwrap()[..2]);
+        assert_eq!(shared.query("a (b", &options), Err(QueryError::UnclosedParen(2)));
+        assert_eq!(shared.query(" ", &options), Err(QueryError::Empty));
+    }
+
+    #[test]
+    fn search_applies_the_matcher_threshold_and_limit_of_its_options() {
+        use fuzzy_matcher::clangd::ClangdMatcher;
+
+        let shared = table(&["Mathematics", "MATH", "Applied math", "History", "Mathematical analysis", "math"]);
+        let scan = |matcher: &dyn FuzzyMatcher, query: &str| -> Vec<(usize, i64)> {
+            let mut results: Vec<_> = shared.iter().enumerate()
+                .filter_map(|(i, s)| matcher.fuzzy_match(s, query).map(|score| (i, score)))
+                .collect();
+            results.sort_by_key(|&(_, score)| Reverse(score));
+            results
+        };
+
+        assert_eq!(shared.search("math", &SearchOptions::new()), shared.fuzzy_find("math", 0));
+        let clangd = SearchOptions::new().algorithm(Algorithm::Clangd);
+        assert_eq!(shared.search("math", &clangd), scan(&ClangdMatcher::default(), "math"));
+
+        let all = shared.search("math", &SearchOptions::new());
+        let threshold = all[all.len() / 2].1;
+        let above = shared.search("math", &SearchOptions::new().threshold(threshold));
+        assert!(!above.is_empty() && above.iter().all(|&(_, score)| score >= threshold));
+        assert_eq!(above[..], all[..above.len()]);
+        assert_eq!(shared.search("math", &SearchOptions::new().limit(2))[..], all[..2]);
+        assert!(shared.search("math", &SearchOptions::new().limit(0)).is_empty());
+
+        let found = |query: &str, case: CaseMatching| -> Vec<usize> {
+            let mut found: Vec<usize> = shared.search(query, &SearchOptions::new().case(case)).into_iter().map(|(i, _)| i).collect();
+            found.sort_unstable();
+            found
+        };
+        assert_eq!(found("math", CaseMatching::Respect), [2, 5]);
+        assert_eq!(found("math", CaseMatching::Smart), [0, 1, 2, 4, 5]);
+        assert_eq!(found("MATH", CaseMatching::Smart), [1]);
+        assert_eq!(found("MATH", CaseMatching::Ignore), [0, 1, 2, 4, 5]);
+
+        // The same options give the same results when reused.
+        let options = SearchOptions::new().case(CaseMatching::Ignore).limit(3);
+        assert_eq!(shared.search("Math", &options), shared.search("Math", &options));
+        assert_eq!(shared.fuzzy_find_with_options("math", 0, &SearchOptions::new()), all);
+    }
+}
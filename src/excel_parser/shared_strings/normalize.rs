@@ -0,0 +1,317 @@
+use std::borrow::Cow;
+use super::unicode_tables::{
+    CANONICAL, CASE_FOLDING, COMBINING_CLASS, COMPATIBILITY, COMPOSITIONS, NFC_MAYBE, NFKC_MAYBE,
+};
+
+/// Unicode normalization form, applied to strings before they are compared.
+///
+/// The same text can be encoded in several ways: `"ё"` is either the single
+/// character U+0451 or `"е"` followed by a combining diaeresis (U+0308).
+/// Both look identical but compare unequal, so a query typed in one form
+/// misses strings stored in the other. Normalizing both sides to the same
+/// form fixes that.
+///
+/// Used by [`LoadOptions::normalize`](super::LoadOptions::normalize) (stored
+/// strings) and [`SearchOptions::normalize`](super::SearchOptions::normalize)
+/// (queries and, per search, candidates).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Canonical composition (NFC): combining sequences are replaced by their
+    /// precomposed characters. Only changes the encoding, never the text.
+    Nfc,
+    /// Compatibility composition (NFKC): NFC, plus compatibility variants
+    /// are replaced by their plain equivalents (`"ﬁ"` → `"fi"`, full‑width
+    /// `"Ａ"` → `"A"`, `"²"` → `"2"`, no‑break space → space).
+    Nfkc,
+}
+
+impl Normalization {
+    /// Normalizes `s` to this form.
+    ///
+    /// Strings that are already normalized, which a quick scan can tell for
+    /// nearly all real text, are returned borrowed.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::Normalization;
+    /// assert_eq!(Normalization::Nfc.apply("е\u{308}ж"), "ёж");
+    /// assert_eq!(Normalization::Nfkc.apply("ﬁle²"), "file2");
+    /// ```
+    pub fn apply(self, s: &str) -> Cow<'_, str> {
+        let compat = self == Normalization::Nfkc;
+        let maybe = if compat { NFKC_MAYBE } else { NFC_MAYBE };
+        if s.chars().all(|ch| ch.is_ascii() || !in_ranges(maybe, ch)) {
+            return Cow::Borrowed(s);
+        }
+
+        let mut chars = Vec::with_capacity(s.len());
+        for ch in s.chars() { decompose(ch, compat, &mut chars); }
+        reorder(&mut chars);
+        compose(&mut chars);
+        Cow::Owned(chars.into_iter().collect())
+    }
+
+    /// [`apply`](Self::apply) for an owned string, reusing it when it is
+    /// already normalized.
+    pub(super) fn apply_owned(self, text: String) -> String {
+        let normalized = match self.apply(&text) {
+            Cow::Owned(normalized) => Some(normalized),
+            Cow::Borrowed(_) => None,
+        };
+        normalized.unwrap_or(text)
+    }
+}
+
+/// Full Unicode case folding of `s` (`"Straße"` → `"strasse"`), borrowing
+/// it when nothing changes.
+///
+/// Folding is lowercasing plus the mappings that make caseless comparison
+/// consistent: `"ß"` → `"ss"`, final `"ς"` → `"σ"` and so on.
+pub(super) fn case_fold(s: &str) -> Cow<'_, str> {
+    let Some(start) = s.find(|ch| !folds_to_itself(ch)) else { return Cow::Borrowed(s) };
+
+    let mut out = String::with_capacity(s.len());
+    out.push_str(&s[..start]);
+    for ch in s[start..].chars() {
+        match lookup(CASE_FOLDING, ch) {
+            Some(folded) => out.push_str(folded),
+            None => out.extend(ch.to_lowercase()),
+        }
+    }
+    Cow::Owned(out)
+}
+
+fn folds_to_itself(ch: char) -> bool {
+    if ch.is_ascii() { return !ch.is_ascii_uppercase(); }
+    lookup(CASE_FOLDING, ch).is_none() && ch.to_lowercase().eq([ch])
+}
+
+/// Removes combining marks from `s` (`"ёлка"` → `"елка"`, `"café"` →
+/// `"cafe"`), borrowing it when there are none.
+///
+/// Characters are canonically decomposed, every mark (non‑zero combining
+/// class) is dropped and the rest is recomposed, so the result is in NFC.
+pub(super) fn strip_diacritics(s: &str) -> Cow<'_, str> {
+    let plain = |ch: char| ch.is_ascii() || (combining_class(ch) == 0 && lookup(CANONICAL, ch).is_none());
+    if s.chars().all(plain) { return Cow::Borrowed(s); }
+
+    let mut chars = Vec::with_capacity(s.len());
+    for ch in s.chars() { decompose(ch, false, &mut chars); }
+    chars.retain(|&ch| combining_class(ch) == 0);
+    compose(&mut chars);
+    Cow::Owned(chars.into_iter().collect())
+}
+
+// Hangul syllables are composed from jamo arithmetically (Unicode §3.12).
+const S_BASE: u32 = 0xAC00;
+const L_BASE: u32 = 0x1100;
+const V_BASE: u32 = 0x1161;
+const T_BASE: u32 = 0x11A7;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+const N_COUNT: u32 = V_COUNT * T_COUNT;
+const S_COUNT: u32 = L_COUNT * N_COUNT;
+
+/// Appends the full decomposition of `ch` to `out`.
+fn decompose(ch: char, compat: bool, out: &mut Vec<char>) {
+    let s_index = (ch as u32).wrapping_sub(S_BASE);
+    if s_index < S_COUNT {
+        out.push(jamo(L_BASE + s_index / N_COUNT));
+        out.push(jamo(V_BASE + (s_index % N_COUNT) / T_COUNT));
+        if !s_index.is_multiple_of(T_COUNT) { out.push(jamo(T_BASE + s_index % T_COUNT)); }
+        return;
+    }
+
+    let mapping = lookup(CANONICAL, ch).or_else(|| compat.then(|| lookup(COMPATIBILITY, ch)).flatten());
+    match mapping {
+        Some(mapping) => mapping.chars().for_each(|part| decompose(part, compat, out)),
+        None => out.push(ch),
+    }
+}
+
+/// Sorts each run of combining marks by combining class (canonical ordering).
+fn reorder(chars: &mut [char]) {
+    let mut start = 0;
+    while start < chars.len() {
+        if combining_class(chars[start]) == 0 {
+            start += 1;
+            continue;
+        }
+        let len = chars[start..].iter().take_while(|&&ch| combining_class(ch) != 0).count();
+        chars[start..start + len].sort_by_key(|&ch| combining_class(ch));
+        start += len;
+    }
+}
+
+/// Canonical composition of a decomposed, reordered sequence, in place.
+fn compose(chars: &mut Vec<char>) {
+    let mut starter: Option<usize> = None;
+    let mut len = 0;
+    for i in 0..chars.len() {
+        let ch = chars[i];
+        let class = combining_class(ch);
+        if let Some(s) = starter {
+            // A character between the starter and `ch` blocks composition
+            // unless it is a combining mark of a lower class.
+            let last_class = combining_class(chars[len - 1]);
+            let unblocked = len == s + 1 || (last_class != 0 && last_class < class);
+            if unblocked && let Some(composite) = compose_pair(chars[s], ch) {
+                chars[s] = composite;
+                continue;
+            }
+        }
+        if class == 0 { starter = Some(len); }
+        chars[len] = ch;
+        len += 1;
+    }
+    chars.truncate(len);
+}
+
+/// The primary composite of `first` and `second`, if any.
+fn compose_pair(first: char, second: char) -> Option<char> {
+    let (first_u, second_u) = (first as u32, second as u32);
+    let l_index = first_u.wrapping_sub(L_BASE);
+    let v_index = second_u.wrapping_sub(V_BASE);
+    if l_index < L_COUNT && v_index < V_COUNT {
+        return Some(jamo(S_BASE + (l_index * V_COUNT + v_index) * T_COUNT));
+    }
+    let s_index = first_u.wrapping_sub(S_BASE);
+    let t_index = second_u.wrapping_sub(T_BASE);
+    if s_index < S_COUNT && s_index.is_multiple_of(T_COUNT) && (1..T_COUNT).contains(&t_index) {
+        return Some(jamo(first_u + t_index));
+    }
+
+    COMPOSITIONS.binary_search_by_key(&(first, second), |&(pair, _)| pair).ok()
+        .map(|i| COMPOSITIONS[i].1)
+}
+
+fn combining_class(ch: char) -> u8 {
+    if (ch as u32) < 0x300 { return 0; }
+    COMBINING_CLASS.binary_search_by_key(&ch, |&(c, _)| c).map_or(0, |i| COMBINING_CLASS[i].1)
+}
+
+fn lookup(table: &'static [(char, &'static str)], ch: char) -> Option<&'static str> {
+    table.binary_search_by_key(&ch, |&(c, _)| c).ok().map(|i| table[i].1)
+}
+
+fn in_ranges(ranges: &[(char, char)], ch: char) -> bool {
+    let i = ranges.partition_point(|&(_, end)| end < ch);
+    ranges.get(i).is_some_and(|&(start, _)| start <= ch)
+}
+
+/// A Hangul code point computed from valid table offsets.
+fn jamo(code: u32) -> char {
+    char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nfc(s: &str) -> String {
+        Normalization::Nfc.apply(s).into_owned()
+    }
+
+    fn nfkc(s: &str) -> String {
+        Normalization::Nfkc.apply(s).into_owned()
+    }
+
+    #[test]
+    fn tables_are_sorted_for_binary_search() {
+        assert!(CANONICAL.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(COMPATIBILITY.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(COMBINING_CLASS.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(CASE_FOLDING.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(COMPOSITIONS.windows(2).all(|w| w[0].0 < w[1].0));
+        for ranges in [NFC_MAYBE, NFKC_MAYBE] {
+            assert!(ranges.iter().all(|&(start, end)| start <= end));
+            assert!(ranges.windows(2).all(|w| w[0].1 < w[1].0));
+        }
+    }
+
+    #[test]
+    fn hangul_is_composed_arithmetically() {
+        // 한 = ᄒ + ᅡ + ᆫ (LVT), 하 = ᄒ + ᅡ (LV).
+        assert_eq!(nfc("\u{1112}\u{1161}\u{11ab}"), "\u{d55c}");
+        assert_eq!(nfc("\u{1112}\u{1161}"), "\u{d558}");
+        assert_eq!(nfc("\u{d558}\u{11ab}"), "\u{d55c}");
+        // An LVT syllable takes no second trailing consonant.
+        assert_eq!(nfc("\u{d55c}\u{11ab}"), "\u{d55c}\u{11ab}");
+        assert!(matches!(Normalization::Nfc.apply("한국어"), Cow::Borrowed(_)));
+
+        let mut jamo = Vec::new();
+        decompose('\u{d55c}', false, &mut jamo);
+        assert_eq!(jamo, ['\u{1112}', '\u{1161}', '\u{11ab}']);
+        assert_eq!(strip_diacritics("한국어"), "한국어");
+    }
+
+    #[test]
+    fn singletons_are_never_recomposed() {
+        // Angstrom, ohm and kelvin signs decompose to a single character.
+        assert_eq!(nfc("\u{212b}"), "\u{c5}");
+        assert_eq!(nfc("\u{2126}"), "\u{3a9}");
+        assert_eq!(nfc("\u{212a}"), "K");
+        assert_eq!(nfc("A\u{30a}"), "\u{c5}");
+        // Composition exclusions stay decomposed: क़ = क + ़.
+        assert_eq!(nfc("\u{958}"), "\u{915}\u{93c}");
+    }
+
+    #[test]
+    fn combining_marks_are_put_in_canonical_order() {
+        // Dot below (class 220) sorts before acute (230) whatever the input order.
+        assert_eq!(nfc("a\u{301}\u{323}"), "\u{1ea1}\u{301}");
+        assert_eq!(nfc("a\u{323}\u{301}"), "\u{1ea1}\u{301}");
+        assert_eq!(nfc("q\u{301}\u{323}"), "q\u{323}\u{301}");
+        // A mark of the same class blocks the second one.
+        assert_eq!(nfc("a\u{301}\u{301}"), "\u{e1}\u{301}");
+        // Marks without a base keep their relative order.
+        assert_eq!(nfc("\u{308}\u{301}"), "\u{308}\u{301}");
+    }
+
+    #[test]
+    fn nfkc_folds_compatibility_variants() {
+        for (text, folded) in [
+            ("ﬁ", "fi"),
+            ("Ａｂｃ", "Abc"),
+            ("x²", "x2"),
+            ("1\u{a0}000", "1 000"),
+            ("\u{338f}", "kg"),
+            ("\u{2460}", "1"),
+            // Half‑width katakana compose after folding: ﾊ + ﾟ → パ.
+            ("\u{ff8a}\u{ff9f}", "\u{30d1}"),
+            // ẛ with dot below folds to ṩ.
+            ("\u{1e9b}\u{323}", "\u{1e69}"),
+        ] {
+            assert_eq!(nfkc(text), folded, "{text:?}");
+        }
+        // NFC keeps the variants apart.
+        assert_eq!(nfc("ﬁ²\u{ff8a}\u{ff9f}"), "ﬁ²\u{ff8a}\u{ff9f}");
+        assert_eq!(nfc("\u{1e9b}\u{323}"), "\u{1e9b}\u{323}");
+    }
+
+    #[test]
+    fn normalizing_twice_changes_nothing() {
+        let all: String = (0..=0x3_0000).filter_map(char::from_u32).collect();
+        for form in [Normalization::Nfc, Normalization::Nfkc] {
+            for chunk in all.chars().collect::<Vec<_>>().chunks(64) {
+                let chunk: String = chunk.iter().collect();
+                let once = form.apply(&chunk).into_owned();
+                assert_eq!(form.apply(&once), once, "{form:?} {chunk:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn case_folding_and_diacritics() {
+        assert_eq!(case_fold("Straße"), "strasse");
+        assert_eq!(case_fold("ΣΊΣΥΦΟΣ"), "σίσυφοσ");
+        assert_eq!(case_fold("ОДИССЕЙ"), "одиссей");
+        assert!(matches!(case_fold("already folded"), Cow::Borrowed(_)));
+
+        assert_eq!(strip_diacritics("ёлка"), "елка");
+        assert_eq!(strip_diacritics("Café Ångström"), "Cafe Angstrom");
+        assert_eq!(strip_diacritics("\u{212b}"), "A");
+        assert!(matches!(strip_diacritics("plain"), Cow::Borrowed(_)));
+    }
+}
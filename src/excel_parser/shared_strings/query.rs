@@ -0,0 +1,284 @@
+use fuzzy_matcher::FuzzyMatcher;
+use thiserror::Error;
+use super::SearchOptions;
+
+/// Error type for [`SharedStrings::query`](super::SharedStrings::query)
+/// expressions. Positions are byte offsets into the expression.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    /// The expression has no terms.
+    #[error("Empty query")]
+    Empty,
+
+    /// A `"` phrase is never closed (position of the opening quote).
+    #[error("Unclosed quote at {0}")]
+    UnclosedQuote(usize),
+
+    /// A `(` is never closed (position of the parenthesis).
+    #[error("Unclosed parenthesis at {0}")]
+    UnclosedParen(usize),
+
+    /// A term was expected but something else was found: an operator with
+    /// nothing to combine, a stray `)`, or the end of the expression.
+    #[error("Expected a term at {at}, found {found}")]
+    ExpectedTerm { at: usize, found: String },
+}
+
+/// A parsed query expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Expr {
+    /// `"…"`: matches strings containing the text literally.
+    Phrase(String),
+    /// A bare word: matches strings the fuzzy matcher matches.
+    Fuzzy(String),
+    /// `-term`: matches strings the term doesn't match.
+    Not(Box<Expr>),
+    /// Terms side by side or joined by `AND`: all must match.
+    And(Vec<Expr>),
+    /// Terms joined by `OR`: at least one must match.
+    Or(Vec<Expr>),
+}
+
+impl Expr {
+    /// Parses a query expression, see [`SharedStrings::query`](super::SharedStrings::query)
+    /// for the syntax.
+    pub(super) fn parse(expr: &str) -> Result<Self, QueryError> {
+        let tokens = tokenize(expr)?;
+        if tokens.is_empty() { return Err(QueryError::Empty); }
+
+        let mut parser = Parser { tokens: &tokens, pos: 0, end: expr.len() };
+        let parsed = parser.or()?;
+        match parser.peek() {
+            None => Ok(parsed),
+            Some((at, token)) => Err(QueryError::ExpectedTerm { at, found: token.describe() }),
+        }
+    }
+
+    /// Applies `options` to the text of every term, as searches do to their
+    /// query.
+    pub(super) fn prepare(self, options: &SearchOptions) -> Self {
+        match self {
+            Expr::Phrase(text) => Expr::Phrase(options.prepare_query(&text).into_owned()),
+            Expr::Fuzzy(text) => Expr::Fuzzy(options.prepare_query(&text).into_owned()),
+            Expr::Not(inner) => Expr::Not(Box::new(inner.prepare(options))),
+            Expr::And(terms) => Expr::And(terms.into_iter().map(|term| term.prepare(options)).collect()),
+            Expr::Or(terms) => Expr::Or(terms.into_iter().map(|term| term.prepare(options)).collect()),
+        }
+    }
+
+    /// The text of every phrase and fuzzy term, negated or not.
+    pub(super) fn terms(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        match self {
+            Expr::Phrase(text) | Expr::Fuzzy(text) => Box::new(std::iter::once(text.as_str())),
+            Expr::Not(inner) => inner.terms(),
+            Expr::And(terms) | Expr::Or(terms) => Box::new(terms.iter().flat_map(Expr::terms)),
+        }
+    }
+
+    /// Score of `text` against the expression, or `None` if it doesn't match.
+    ///
+    /// Terms score as the fuzzy matcher scores them; `AND` adds the scores
+    /// of its terms, `OR` takes the best matching alternative, and a
+    /// negation contributes nothing.
+    pub(super) fn score(&self, matcher: &dyn FuzzyMatcher, text: &str) -> Option<i64> {
+        match self {
+            Expr::Phrase(phrase) => match text.contains(phrase.as_str()) {
+                true => Some(matcher.fuzzy_match(text, phrase).unwrap_or(0)),
+                false => None,
+            },
+            Expr::Fuzzy(term) => matcher.fuzzy_match(text, term),
+            Expr::Not(inner) => match inner.score(matcher, text) {
+                Some(_) => None,
+                None => Some(0),
+            },
+            Expr::And(terms) => terms.iter().map(|term| term.score(matcher, text)).sum(),
+            Expr::Or(terms) => terms.iter().filter_map(|term| term.score(matcher, text)).max(),
+        }
+    }
+}
+
+/// A token of a query expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Phrase(String),
+    And,
+    Or,
+    Minus,
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Word(word) => format!("'{word}'"),
+            Token::Phrase(phrase) => format!("\"{phrase}\""),
+            Token::And => "AND".into(),
+            Token::Or => "OR".into(),
+            Token::Minus => "'-'".into(),
+            Token::Open => "'('".into(),
+            Token::Close => "')'".into(),
+        }
+    }
+}
+
+/// Splits an expression into tokens with their byte positions.
+///
+/// `AND` and `OR` are operators only in upper case, so the lowercase words
+/// stay searchable. A `-` negates when it starts a token and is followed by
+/// a term; elsewhere it is part of a word (`"ТФКП-2"`).
+fn tokenize(expr: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.char_indices().peekable();
+    while let Some(&(start, ch)) = chars.peek() {
+        match ch {
+            _ if ch.is_whitespace() => { chars.next(); }
+            '(' | ')' => {
+                chars.next();
+                tokens.push((start, if ch == '(' { Token::Open } else { Token::Close }));
+            }
+            '"' => {
+                chars.next();
+                let body = &expr[start + 1..];
+                let len = body.find('"').ok_or(QueryError::UnclosedQuote(start))?;
+                tokens.push((start, Token::Phrase(body[..len].to_string())));
+                while chars.next_if(|&(i, _)| i <= start + len + 1).is_some() {}
+            }
+            '-' if expr[start + 1..].starts_with(|next: char| !next.is_whitespace() && next != ')') => {
+                chars.next();
+                tokens.push((start, Token::Minus));
+            }
+            _ => {
+                let mut end = expr.len();
+                while let Some(&(i, ch)) = chars.peek() {
+                    if ch.is_whitespace() || matches!(ch, '(' | ')' | '"') {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                let token = match &expr[start..end] {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    word => Token::Word(word.to_string()),
+                };
+                tokens.push((start, token));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over the tokens: `OR` binds looser than `AND`, which
+/// may be left out between terms.
+struct Parser<'t> {
+    tokens: &'t [(usize, Token)],
+    pos: usize,
+    /// Length of the expression, reported for errors at its end.
+    end: usize,
+}
+
+impl<'t> Parser<'t> {
+    fn peek(&self) -> Option<(usize, &'t Token)> {
+        self.tokens.get(self.pos).map(|(at, token)| (*at, token))
+    }
+
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let mut terms = vec![self.and()?];
+        while let Some((_, Token::Or)) = self.peek() {
+            self.pos += 1;
+            terms.push(self.and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Expr::Or(terms) })
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut terms = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                Some((_, Token::And)) => self.pos += 1,
+                Some((_, Token::Word(_) | Token::Phrase(_) | Token::Minus | Token::Open)) => {}
+                _ => break,
+            }
+            terms.push(self.unary()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Expr::And(terms) })
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        let Some((at, token)) = self.peek() else {
+            return Err(QueryError::ExpectedTerm { at: self.end, found: "end of query".into() });
+        };
+        self.pos += 1;
+        match token {
+            Token::Word(word) => Ok(Expr::Fuzzy(word.clone())),
+            Token::Phrase(phrase) => Ok(Expr::Phrase(phrase.clone())),
+            Token::Minus => Ok(Expr::Not(Box::new(self.unary()?))),
+            Token::Open => {
+                let inner = self.or()?;
+                match self.peek() {
+                    Some((_, Token::Close)) => {
+                        self.pos += 1;
+                        Ok(inner)
+                    }
+                    _ => Err(QueryError::UnclosedParen(at)),
+                }
+            }
+            Token::And | Token::Or | Token::Close => {
+                Err(QueryError::ExpectedTerm { at, found: token.describe() })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phrase(text: &str) -> Expr { Expr::Phrase(text.into()) }
+    fn fuzzy(text: &str) -> Expr { Expr::Fuzzy(text.into()) }
+    fn not(inner: Expr) -> Expr { Expr::Not(Box::new(inner)) }
+
+    #[test]
+    fn parse_builds_the_expression_tree() {
+        assert_eq!(Expr::parse("Головин"), Ok(fuzzy("Головин")));
+        assert_eq!(
+            Expr::parse(r#""теория функций" AND Головин -лекция"#),
+            Ok(Expr::And(vec![phrase("теория функций"), fuzzy("Головин"), not(fuzzy("лекция"))])),
+        );
+        // AND binds tighter than OR; parentheses group.
+        assert_eq!(
+            Expr::parse("a b OR c"),
+            Ok(Expr::Or(vec![Expr::And(vec![fuzzy("a"), fuzzy("b")]), fuzzy("c")])),
+        );
+        assert_eq!(
+            Expr::parse("a (b OR c)"),
+            Ok(Expr::And(vec![fuzzy("a"), Expr::Or(vec![fuzzy("b"), fuzzy("c")])])),
+        );
+        assert_eq!(Expr::parse(r#"-"a b" -(c)"#), Ok(Expr::And(vec![not(phrase("a b")), not(fuzzy("c"))])));
+        // Lowercase operators and inner or lone hyphens are words.
+        assert_eq!(Expr::parse("and or"), Ok(Expr::And(vec![fuzzy("and"), fuzzy("or")])));
+        assert_eq!(Expr::parse("ТФКП-2 - x"), Ok(Expr::And(vec![fuzzy("ТФКП-2"), fuzzy("-"), fuzzy("x")])));
+        assert_eq!(Expr::parse(r#""""#), Ok(phrase("")));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_expressions() {
+        assert_eq!(Expr::parse(""), Err(QueryError::Empty));
+        assert_eq!(Expr::parse("   "), Err(QueryError::Empty));
+        assert_eq!(Expr::parse(r#"a "b c"#), Err(QueryError::UnclosedQuote(2)));
+        assert_eq!(Expr::parse("a (b OR c"), Err(QueryError::UnclosedParen(2)));
+        assert_eq!(Expr::parse("((a)"), Err(QueryError::UnclosedParen(0)));
+        let expected = |at: usize, found: &str| Err(QueryError::ExpectedTerm { at, found: found.into() });
+        assert_eq!(Expr::parse("AND a"), expected(0, "AND"));
+        assert_eq!(Expr::parse("a OR"), expected(4, "end of query"));
+        assert_eq!(Expr::parse("a AND OR b"), expected(6, "OR"));
+        assert_eq!(Expr::parse("a)"), expected(1, "')'"));
+        assert_eq!(Expr::parse("()"), expected(1, "')'"));
+        assert_eq!(
+            QueryError::ExpectedTerm { at: 4, found: "end of query".into() }.to_string(),
+            "Expected a term at 4, found end of query",
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+
+/// One cached search: the query, its threshold and the sorted results.
+#[derive(Debug)]
+struct CacheEntry {
+    query: Box<str>,
+    threshold: i64,
+    results: Vec<(usize, i64)>,
+}
+
+/// Small LRU cache mapping `(query, threshold)` to fuzzy search results.
+///
+/// Entries live in a deque ordered from least to most recently used; lookups
+/// are linear, which is faster than hashing for the handful of entries this
+/// cache is meant to hold. A `Mutex` keeps [`SharedStrings`](super::SharedStrings)
+/// `Sync` while allowing searches through `&self` to update the cache.
+#[derive(Debug)]
+pub(super) struct QueryCache {
+    capacity: usize,
+    entries: Mutex<VecDeque<CacheEntry>>,
+}
+
+impl QueryCache {
+    /// Creates an empty cache holding at most `capacity` queries.
+    pub(super) fn new(capacity: usize) -> Self {
+        Self { capacity, entries: Mutex::new(VecDeque::with_capacity(capacity)) }
+    }
+
+    /// Returns a copy of the cached results and marks the entry as most recently used.
+    pub(super) fn get(&self, query: &str, threshold: i64) -> Option<Vec<(usize, i64)>> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let pos = entries.iter().position(|e| e.threshold == threshold && &*e.query == query)?;
+        let entry = entries.remove(pos)?;
+        let results = entry.results.clone();
+        entries.push_back(entry);
+        Some(results)
+    }
+
+    /// Stores results for a query, evicting the least recently used entry when full.
+    pub(super) fn insert(&self, query: &str, threshold: i64, results: &[(usize, i64)]) {
+        if self.capacity == 0 { return; }
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.retain(|e| !(e.threshold == threshold && &*e.query == query));
+        if entries.len() == self.capacity { entries.pop_front(); }
+        entries.push_back(CacheEntry { query: query.into(), threshold, results: results.to_vec() });
+    }
+
+    /// Heap bytes of the cached queries and results.
+    pub(super) fn heap_bytes(&self) -> usize {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let held: usize = entries.iter()
+            .map(|e| e.query.len() + e.results.capacity() * size_of::<(usize, i64)>())
+            .sum();
+        entries.capacity() * size_of::<CacheEntry>() + held
+    }
+
+    /// Drops all cached results.
+    pub(super) fn clear(&self) {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).clear();
+    }
+}
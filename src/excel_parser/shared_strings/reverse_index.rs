@@ -0,0 +1,41 @@
+use rustc_hash::FxBuildHasher;
+use std::hash::BuildHasher;
+use super::storage::Storage;
+
+/// String → index lookup for [`SharedStrings::index_of`](super::SharedStrings::index_of),
+/// built on first use.
+///
+/// Holds `(hash, index)` pairs sorted by hash, then index. A lookup binary
+/// searches the hash and compares the candidates with the table, so the
+/// text isn't copied and the index costs 16 bytes per string.
+#[derive(Debug)]
+pub(super) struct ReverseIndex {
+    entries: Box<[(u64, usize)]>,
+}
+
+impl ReverseIndex {
+    /// Indexes every string of the table (parsing all of a lazy table).
+    pub(super) fn build(strings: &Storage) -> Self {
+        let mut entries: Vec<_> = strings.iter().enumerate().map(|(i, s)| (hash(s), i)).collect();
+        entries.sort_unstable();
+        Self { entries: entries.into_boxed_slice() }
+    }
+
+    pub(super) fn heap_bytes(&self) -> usize {
+        size_of_val(&*self.entries)
+    }
+
+    /// Indices of the strings equal to `s`, in ascending order.
+    pub(super) fn lookup<'a>(&'a self, strings: &'a Storage, s: &'a str) -> impl Iterator<Item = usize> + 'a {
+        let key = hash(s);
+        let start = self.entries.partition_point(|&(h, _)| h < key);
+        self.entries[start..].iter()
+            .take_while(move |&&(h, _)| h == key)
+            .map(|&(_, i)| i)
+            .filter(move |&i| strings.get(i) == Some(s))
+    }
+}
+
+fn hash(s: &str) -> u64 {
+    FxBuildHasher.hash_one(s)
+}
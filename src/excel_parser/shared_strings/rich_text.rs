@@ -0,0 +1,160 @@
+use quick_xml::events::BytesStart;
+use super::super::color::Color;
+use super::super::xml_utils::attr_value;
+
+/// A shared string made of formatted runs (`<r>`), as kept by
+/// [`LoadOptions::rich_text`](super::LoadOptions::rich_text).
+///
+/// The plain text of the item is still returned by
+/// [`SharedStrings::get`](super::SharedStrings::get); this only adds the
+/// formatting. Concatenating the text of all runs gives the plain text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichString {
+    runs: Box<[TextRun]>,
+}
+
+impl RichString {
+    /// The runs in document order.
+    pub fn runs(&self) -> &[TextRun] {
+        &self.runs
+    }
+
+    /// Heap bytes of the runs, their text and font names.
+    pub(super) fn heap_bytes(&self) -> usize {
+        let text: usize = self.runs.iter().map(|run| {
+            let name = run.font.as_ref().and_then(|font| font.name.as_ref());
+            run.text.len() + name.map_or(0, |name| name.len())
+        }).sum();
+        size_of_val(&*self.runs) + text
+    }
+}
+
+/// A fragment of a [`RichString`] with uniform formatting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun {
+    /// Text of the run, entities and escapes resolved like the plain text.
+    pub text: Box<str>,
+    /// Run properties (`<rPr>`); `None` for a run without them or for text
+    /// written directly in the item (`<si><t>…</t><r>…</r></si>`), both of
+    /// which use the cell's font.
+    pub font: Option<RunFont>,
+}
+
+/// Font properties of a run (`<rPr>`), or of a cell font in the styles part
+/// (`<font>`, see [`Styles::font`](super::super::Styles::font)).
+///
+/// Only the commonly used properties are read; absent ones keep their
+/// default (`false` / `None`), meaning "as the cell's font" for a run and
+/// the application default for a cell font.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunFont {
+    /// `<b/>`
+    pub bold: bool,
+    /// `<i/>`
+    pub italic: bool,
+    /// `<u/>` of any style except `none`.
+    pub underline: bool,
+    /// `<strike/>`
+    pub strike: bool,
+    /// `<color>`
+    pub color: Option<Color>,
+    /// `<sz val>` in points.
+    pub size: Option<f64>,
+    /// `<rFont val>` in a run, `<name val>` in the styles part.
+    pub name: Option<Box<str>>,
+}
+
+impl RunFont {
+    /// Applies one child element of `<rPr>` or `<font>`; unknown elements
+    /// are ignored.
+    pub(crate) fn apply(&mut self, e: &BytesStart<'_>) {
+        match e.local_name().as_ref() {
+            b"b" => self.bold = flag(e),
+            b"i" => self.italic = flag(e),
+            b"strike" => self.strike = flag(e),
+            b"u" => self.underline = attr_value(e, b"val").is_none_or(|v| v != "none"),
+            b"color" => self.color = Color::from_element(e),
+            b"sz" => self.size = attr_value(e, b"val").and_then(|v| v.parse().ok()),
+            b"rFont" | b"name" => self.name = attr_value(e, b"val").map(String::into_boxed_str),
+            _ => {}
+        }
+    }
+}
+
+/// Reads a boolean property (`<b/>`, `<b val="0"/>`); a missing `val` means on.
+fn flag(e: &BytesStart<'_>) -> bool {
+    attr_value(e, b"val").is_none_or(|v| v != "0" && v != "false")
+}
+
+/// Collects the runs of one `<si>` while it is parsed.
+///
+/// Fed by the load loop: text goes to the open run, or to a run without
+/// properties when it is written directly in the item.
+#[derive(Debug, Default)]
+pub(super) struct RunCollector {
+    runs: Vec<TextRun>,
+    /// Text and properties of the `<r>` being read.
+    current: Option<(String, Option<RunFont>)>,
+    /// Text of a direct `<t>` child being read.
+    plain: String,
+    in_props: bool,
+    has_runs: bool,
+}
+
+impl RunCollector {
+    /// Handles a start or empty element inside the item.
+    pub(super) fn element(&mut self, e: &BytesStart<'_>, empty: bool) {
+        match e.local_name().as_ref() {
+            b"r" if !empty => {
+                self.has_runs = true;
+                self.current = Some((String::new(), None));
+            }
+            b"rPr" => {
+                if let Some((_, font)) = &mut self.current { *font = Some(RunFont::default()); }
+                self.in_props = !empty;
+            }
+            _ if self.in_props => {
+                if let Some((_, Some(font))) = &mut self.current { font.apply(e); }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles an end element inside the item.
+    pub(super) fn end(&mut self, name: &[u8]) {
+        match name {
+            b"rPr" => self.in_props = false,
+            b"r" => {
+                if let Some((text, font)) = self.current.take() {
+                    self.runs.push(TextRun { text: text.into_boxed_str(), font });
+                }
+            }
+            b"t" if self.current.is_none() && !self.plain.is_empty() => {
+                let text = std::mem::take(&mut self.plain).into_boxed_str();
+                self.runs.push(TextRun { text, font: None });
+            }
+            _ => {}
+        }
+    }
+
+    /// Text buffer of the run the next `<t>` text belongs to.
+    pub(super) fn text(&mut self) -> &mut String {
+        match &mut self.current {
+            Some((text, _)) => text,
+            None => &mut self.plain,
+        }
+    }
+
+    /// Returns the item's runs, their text passed through `decode`, or
+    /// `None` if it had no `<r>` (plain text needs no rich form), and resets
+    /// the collector for the next item.
+    pub(super) fn finish(&mut self, decode: impl Fn(String) -> Box<str>) -> Option<RichString> {
+        let rich = std::mem::take(self);
+        if !rich.has_runs { return None; }
+
+        let runs = rich.runs.into_iter()
+            .map(|run| TextRun { text: decode(run.text.into_string()), font: run.font })
+            .collect();
+        Some(RichString { runs })
+    }
+}
@@ -0,0 +1,88 @@
+use super::storage::Arena;
+use super::trigram_index::TrigramIndex;
+
+/// A table together with its search index, detached from the workbook so it
+/// can be saved and restored, created by
+/// [`SharedStrings::build_index`](super::SharedStrings::build_index).
+///
+/// Loading a workbook parses the whole shared strings part, and the trigram
+/// index takes about as long again. A command‑line tool run many times
+/// against the same workbook can instead write this structure to disk once
+/// (with the `serde` feature, in any serde format) and turn it back into a
+/// searchable table with [`SharedStrings::from_index`](super::SharedStrings::from_index),
+/// which neither parses nor indexes anything.
+///
+/// Only the plain text is kept; rich‑text runs and phonetic readings are
+/// not part of the index.
+///
+/// # Example
+/// ```ignore
+/// # use excel_parser::{SharedStrings, SearchIndex};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let cache = std::path::Path::new("schedule.index.json");
+/// let shared = match std::fs::read(cache) {
+///     Ok(saved) => SharedStrings::from_index(serde_json::from_slice::<SearchIndex>(&saved)?),
+///     Err(_) => {
+///         let shared = SharedStrings::load(&std::fs::read("xl/sharedStrings.xml")?)?;
+///         std::fs::write(cache, serde_json::to_vec(&shared.build_index())?)?;
+///         shared
+///     }
+/// };
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "SavedIndex<Box<str>, Box<[u32]>>"))]
+pub struct SearchIndex {
+    pub(super) strings: Arena,
+    pub(super) trigrams: TrigramIndex,
+}
+
+impl SearchIndex {
+    /// Number of strings in the indexed table.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns `true` if the indexed table has no strings.
+    pub fn is_empty(&self) -> bool {
+        self.strings.len() == 0
+    }
+}
+
+// ---------------------------------------------------------------------------
+// serde support (feature "serde")
+// ---------------------------------------------------------------------------
+
+/// How an index is serialized: the strings in table order, then the
+/// posting list of every trigram, sorted by key so that the same table
+/// always gives the same output.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedIndex<S, L> {
+    strings: Vec<S>,
+    postings: Vec<(u64, L)>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SearchIndex {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut postings: Vec<_> = self.trigrams.postings().collect();
+        postings.sort_unstable_by_key(|&(key, _)| key);
+        SavedIndex { strings: self.strings.iter().collect(), postings }.serialize(serializer)
+    }
+}
+
+/// Posting lists are checked against the table, so a damaged or mismatched
+/// file is rejected instead of producing wrong search results.
+#[cfg(feature = "serde")]
+impl TryFrom<SavedIndex<Box<str>, Box<[u32]>>> for SearchIndex {
+    type Error = &'static str;
+
+    fn try_from(saved: SavedIndex<Box<str>, Box<[u32]>>) -> Result<Self, Self::Error> {
+        let strings: Arena = saved.strings.iter().map(|s| &**s).collect();
+        let trigrams = TrigramIndex::from_postings(saved.postings, strings.len())
+            .ok_or("trigram index does not match the strings")?;
+        Ok(Self { strings, trigrams })
+    }
+}
@@ -0,0 +1,17 @@
+/// One search result with the matched string, as returned by
+/// [`SharedStrings::fuzzy_find_matches`](super::SharedStrings::fuzzy_find_matches)
+/// and [`SharedStrings::to_matches`](super::SharedStrings::to_matches).
+///
+/// Carries what a result list shows, so with the `serde` feature a search
+/// can be written straight to JSON for a web front end: it serializes as
+/// `{"index": 12, "score": 87, "text": "Суббота"}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SearchMatch<'a> {
+    /// Index of the string in the table, as cells refer to it.
+    pub index: usize,
+    /// Matching score, higher is better; see the search that produced it.
+    pub score: i64,
+    /// The matched string, borrowed from the table.
+    pub text: &'a str,
+}
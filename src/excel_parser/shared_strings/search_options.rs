@@ -0,0 +1,329 @@
+use fuzzy_matcher::{FuzzyMatcher, clangd::ClangdMatcher, skim::SkimMatcherV2};
+use std::borrow::Cow;
+use std::sync::LazyLock;
+use super::Normalization;
+use super::normalize::{case_fold, strip_diacritics};
+
+// Matchers are built once per configuration and shared by all searches and
+// threads; each keeps its scoring buffers per thread.
+static SKIM_SMART: LazyLock<SkimMatcherV2> = LazyLock::new(SkimMatcherV2::default);
+static SKIM_RESPECT: LazyLock<SkimMatcherV2> = LazyLock::new(|| SkimMatcherV2::default().respect_case());
+static SKIM_IGNORE: LazyLock<SkimMatcherV2> = LazyLock::new(|| SkimMatcherV2::default().ignore_case());
+static CLANGD_SMART: LazyLock<ClangdMatcher> = LazyLock::new(ClangdMatcher::default);
+static CLANGD_RESPECT: LazyLock<ClangdMatcher> = LazyLock::new(|| ClangdMatcher::default().respect_case());
+static CLANGD_IGNORE: LazyLock<ClangdMatcher> = LazyLock::new(|| ClangdMatcher::default().ignore_case());
+
+/// The shared Skim matcher for `case`; `None` is the matcher's own smart
+/// case, which only folds ASCII letters.
+pub(super) fn skim_matcher(case: Option<CaseMatching>) -> &'static SkimMatcherV2 {
+    match case {
+        None => &SKIM_SMART,
+        Some(CaseMatching::Respect) => &SKIM_RESPECT,
+        Some(CaseMatching::Ignore | CaseMatching::Smart) => &SKIM_IGNORE,
+    }
+}
+
+/// How letter case is compared, set with [`SearchOptions::case`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseMatching {
+    /// Case‑insensitive if the query has no uppercase letter, otherwise
+    /// case‑sensitive: `"суббота"` finds `"Суббота"`, `"Суббота"` doesn't
+    /// find `"СУББОТА"`.
+    Smart,
+    /// Case‑sensitive.
+    Respect,
+    /// Case‑insensitive, by Unicode case folding of both sides.
+    Ignore,
+}
+
+/// Fuzzy matching algorithm, set with [`SearchOptions::algorithm`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// The Skim (fzf‑like) matcher, used by [`fuzzy_find`](super::SharedStrings::fuzzy_find).
+    /// Rewards consecutive characters and word starts.
+    #[default]
+    Skim,
+    /// The clangd code‑completion matcher. Stricter about where matched
+    /// characters may fall, so it tends to return fewer, more prefix‑like
+    /// matches.
+    Clangd,
+}
+
+/// Options of a search: how the query and the strings are compared, which
+/// matcher scores them, and which results are kept.
+///
+/// Options are set in a builder‑style fashion and passed to
+/// [`SharedStrings::search`](super::SharedStrings::search),
+/// [`fuzzy_find_with_options`](super::SharedStrings::fuzzy_find_with_options),
+/// [`query`](super::SharedStrings::query) and the literal searches
+/// ([`find_exact`](super::SharedStrings::find_exact),
+/// [`find_substring`](super::SharedStrings::find_substring),
+/// [`find_prefix`](super::SharedStrings::find_prefix)). The default leaves
+/// the query untouched, so a default `SearchOptions` behaves exactly like
+/// [`fuzzy_find`](super::SharedStrings::fuzzy_find) with threshold `0`
+/// and compares literally.
+///
+/// The matcher for each combination of [`case`](Self::case) and
+/// [`algorithm`](Self::algorithm) is built once and shared by all searches.
+///
+/// # Example
+/// ```
+/// # use excel_parser::{SharedStrings, SearchOptions, CaseMatching};
+/// # fn demo(shared: &SharedStrings) {
+/// let options = SearchOptions::new()
+///     .case(CaseMatching::Smart)
+///     .collapse_whitespace(true)
+///     .threshold(40)
+///     .limit(20);
+/// let results = shared.search("теория  функций", &options);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    collapse_whitespace: bool,
+    case: Option<CaseMatching>,
+    strip_diacritics: bool,
+    normalization: Option<Normalization>,
+    threshold: i64,
+    limit: Option<usize>,
+    algorithm: Algorithm,
+}
+
+impl SearchOptions {
+    /// Creates options that leave the query unchanged.
+    pub fn new() -> Self { Self::default() }
+
+    /// Replaces every run of whitespace in the query with a single space.
+    ///
+    /// The Skim matcher scores each query character, so an accidental double
+    /// space (common in text pasted from cells) must be matched too and lowers
+    /// or loses the score. Leading and trailing whitespace is collapsed but not
+    /// removed.
+    pub fn collapse_whitespace(mut self, enabled: bool) -> Self {
+        self.collapse_whitespace = enabled;
+        self
+    }
+
+    /// Compares case‑insensitively (Unicode case folding of both sides).
+    ///
+    /// The query and each candidate string are case‑folded before comparing:
+    /// lowercased, plus the few foldings lowercasing misses (`"ß"` matches
+    /// `"SS"`, `"ς"` matches `"σ"`). The fuzzy matcher's own case handling
+    /// only covers ASCII letters, so this is what makes `"СУББ"` find
+    /// `"Суббота"`; a fuzzy search with this option bypasses the query cache.
+    ///
+    /// Shorthand for [`case`](Self::case)`(CaseMatching::Ignore)`; `false`
+    /// restores the default case handling.
+    pub fn ignore_case(mut self, enabled: bool) -> Self {
+        self.case = enabled.then_some(CaseMatching::Ignore);
+        self
+    }
+
+    /// Sets how letter case is compared.
+    ///
+    /// Without this option fuzzy searches use the matcher's smart case,
+    /// which folds ASCII letters only, and literal searches compare
+    /// case‑sensitively. [`CaseMatching::Smart`] extends smart case to all
+    /// scripts and to literal searches; [`CaseMatching::Ignore`] is
+    /// [`ignore_case`](Self::ignore_case). Case‑insensitive fuzzy searches
+    /// bypass the query cache.
+    pub fn case(mut self, case: CaseMatching) -> Self {
+        self.case = Some(case);
+        self
+    }
+
+    /// Minimum score a fuzzy match needs (default `0`, every match). See
+    /// [`fuzzy_find`](super::SharedStrings::fuzzy_find) for typical values.
+    /// Not used by literal searches.
+    pub fn threshold(mut self, threshold: i64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Returns at most `limit` results: the best ones for scored searches,
+    /// the first in table order for literal ones.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Selects the fuzzy matching algorithm (default [`Algorithm::Skim`]).
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Ignores diacritics: combining marks are removed from the query and
+    /// each candidate string before comparing.
+    ///
+    /// Meant above all for `"ё"`, which schedules spell as `"е"` about half
+    /// the time; combined with [`ignore_case`](Self::ignore_case), `"суббота"`
+    /// and `"субота"` (fuzzy) find `"Суббота"`, and `"Ёлкин"` finds
+    /// `"Елкин"`. Every mark goes, so `"й"` matches `"и"` as well. Works for
+    /// precomposed and decomposed text alike; a fuzzy search with this
+    /// option bypasses the query cache.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, SearchOptions};
+    /// # fn demo(shared: &SharedStrings) {
+    /// let options = SearchOptions::new().ignore_case(true).strip_diacritics(true);
+    /// let teachers = shared.find_substring("ёлкин", &options);
+    /// # }
+    /// ```
+    pub fn strip_diacritics(mut self, enabled: bool) -> Self {
+        self.strip_diacritics = enabled;
+        self
+    }
+
+    /// Normalizes the query and each candidate string to `form` before
+    /// comparing, so that e.g. a query typed with a precomposed `"ё"` finds
+    /// strings that store `"е"` plus a combining diaeresis.
+    ///
+    /// Candidates are normalized as they are scanned, which costs little for
+    /// text that is already normalized; a fuzzy search with this option
+    /// bypasses the query cache. If the table was loaded with
+    /// [`LoadOptions::normalize`](super::LoadOptions::normalize), normalizing
+    /// the query alone would do, but repeating it is harmless.
+    pub fn normalize(mut self, form: Normalization) -> Self {
+        self.normalization = Some(form);
+        self
+    }
+
+    /// Returns `true` if the options change candidate strings, which rules
+    /// out searches that match the stored strings directly.
+    pub(super) fn transforms_text(&self) -> bool {
+        matches!(self.case, Some(CaseMatching::Ignore | CaseMatching::Smart))
+            || self.strip_diacritics
+            || self.normalization.is_some()
+    }
+
+    /// Returns `true` if a fuzzy search with these options scores exactly
+    /// like [`fuzzy_find`](super::SharedStrings::fuzzy_find), so its results
+    /// and cache can be used.
+    pub(super) fn is_plain_fuzzy(&self) -> bool {
+        !self.transforms_text() && self.case.is_none() && self.algorithm == Algorithm::Skim
+    }
+
+    /// The options with [`CaseMatching::Smart`] settled for `query`: ignore
+    /// case for an all‑lowercase query, respect it otherwise.
+    pub(super) fn for_query(&self, query: &str) -> Self {
+        let case = match self.case {
+            Some(CaseMatching::Smart) if query.chars().any(char::is_uppercase) => Some(CaseMatching::Respect),
+            Some(CaseMatching::Smart) => Some(CaseMatching::Ignore),
+            case => case,
+        };
+        Self { case, ..self.clone() }
+    }
+
+    /// The shared matcher for the options' algorithm and case handling.
+    pub(super) fn matcher(&self) -> &'static dyn FuzzyMatcher {
+        match (self.algorithm, self.case) {
+            (Algorithm::Skim, case) => skim_matcher(case),
+            (Algorithm::Clangd, None) => &*CLANGD_SMART,
+            (Algorithm::Clangd, Some(CaseMatching::Respect)) => &*CLANGD_RESPECT,
+            (Algorithm::Clangd, Some(CaseMatching::Ignore | CaseMatching::Smart)) => &*CLANGD_IGNORE,
+        }
+    }
+
+    pub(super) fn min_score(&self) -> i64 {
+        self.threshold
+    }
+
+    /// Cuts `results` down to the limit, if any.
+    pub(super) fn apply_limit<T>(&self, results: &mut Vec<T>) {
+        if let Some(limit) = self.limit { results.truncate(limit); }
+    }
+
+    /// Returns `true` if candidate strings are compared as stored or
+    /// case‑folded, which the trigram index covers.
+    pub(super) fn keeps_case_folded_text(&self) -> bool {
+        !self.strip_diacritics && self.normalization.is_none()
+    }
+
+    /// Applies the options to `query`, borrowing it when nothing changes.
+    pub(super) fn prepare_query<'q>(&self, query: &'q str) -> Cow<'q, str> {
+        let query = self.collapse(query);
+        self.prepare_owned(query)
+    }
+
+    /// Applies the normalization, case and diacritics handling of the
+    /// options to a candidate string.
+    pub(super) fn prepare_text<'s>(&self, text: &'s str) -> Cow<'s, str> {
+        self.prepare_owned(Cow::Borrowed(text))
+    }
+
+    fn prepare_owned<'s>(&self, text: Cow<'s, str>) -> Cow<'s, str> {
+        let text = match self.normalization {
+            Some(form) => apply_step(text, |s| form.apply(s)),
+            None => text,
+        };
+        // Folding comes first: it can decompose (`"ǰ"` → `"j"` + caron),
+        // and stripping then removes the marks it produced.
+        let text = match self.case {
+            Some(CaseMatching::Ignore) => apply_step(text, case_fold),
+            _ => text,
+        };
+        match self.strip_diacritics {
+            true => apply_step(text, strip_diacritics),
+            false => text,
+        }
+    }
+
+    fn collapse<'q>(&self, query: &'q str) -> Cow<'q, str> {
+        if !self.collapse_whitespace || !has_whitespace_run(query) {
+            return Cow::Borrowed(query);
+        }
+
+        let mut out = String::with_capacity(query.len());
+        let mut prev_space = false;
+        for ch in query.chars() {
+            let space = ch.is_whitespace();
+            if !(space && prev_space) { out.push(if space { ' ' } else { ch }); }
+            prev_space = space;
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// Applies `step` to `text`, keeping `text` (and its borrow) when the step
+/// changes nothing.
+fn apply_step<'s>(text: Cow<'s, str>, step: impl Fn(&str) -> Cow<'_, str>) -> Cow<'s, str> {
+    match step(&text) {
+        Cow::Owned(changed) => Cow::Owned(changed),
+        Cow::Borrowed(_) => text,
+    }
+}
+
+/// Parameters of [`SharedStrings::fuzzy_find_view`](super::SharedStrings::fuzzy_find_view):
+/// filtering, de‑duplication and the page to return.
+///
+/// # Example
+/// ```
+/// # use excel_parser::SearchView;
+/// // Third page of 20 distinct texts.
+/// let view = SearchView { dedup: true, offset: 40, limit: Some(20), ..Default::default() };
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchView {
+    /// Minimum matching score.
+    pub threshold: i64,
+    /// Collapse results with identical text into the best‑scored one.
+    pub dedup: bool,
+    /// Number of ranked results to skip.
+    pub offset: usize,
+    /// Maximum number of results to return; `None` returns the rest.
+    pub limit: Option<usize>,
+}
+
+/// Returns `true` if collapsing would change `query`: it has two consecutive
+/// whitespace characters or whitespace other than a plain space.
+fn has_whitespace_run(query: &str) -> bool {
+    let mut prev_space = false;
+    query.chars().any(|ch| {
+        let space = ch.is_whitespace();
+        let run = space && (prev_space || ch != ' ');
+        prev_space = space;
+        run
+    })
+}
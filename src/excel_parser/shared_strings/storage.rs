@@ -0,0 +1,345 @@
+use quick_xml::errors::IllFormedError;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::Range;
+use std::sync::OnceLock;
+use super::LoadOptions;
+use super::iter::StringIter;
+
+/// Backing store of a [`SharedStrings`](super::SharedStrings) table.
+#[derive(Debug)]
+pub(super) enum Storage {
+    /// Every string parsed up front.
+    Eager(Arena),
+    /// Strings parsed on first access, see [`LoadOptions::lazy`].
+    Lazy(LazyTable),
+}
+
+impl Storage {
+    pub(super) fn len(&self) -> usize {
+        match self {
+            Storage::Eager(arena) => arena.len(),
+            Storage::Lazy(table) => table.offsets.len(),
+        }
+    }
+
+    pub(super) fn get(&self, index: usize) -> Option<&str> {
+        match self {
+            Storage::Eager(arena) => arena.get(index),
+            Storage::Lazy(table) => table.get(index),
+        }
+    }
+
+    /// The string at an index known to be valid (e.g. from a search result).
+    pub(super) fn text(&self, index: usize) -> &str {
+        self.get(index).unwrap_or_default()
+    }
+
+    /// Heap bytes held by the strings; for a lazy table, the XML, the
+    /// offsets and the strings parsed so far.
+    pub(super) fn heap_bytes(&self) -> usize {
+        match self {
+            Storage::Eager(arena) => arena.heap_bytes(),
+            Storage::Lazy(table) => table.heap_bytes(),
+        }
+    }
+
+    /// All strings in table order; materializes every string of a lazy table.
+    pub(super) fn iter(&self) -> impl Iterator<Item = &str> {
+        (0..self.len()).map(|i| self.text(i))
+    }
+}
+
+/// Iterator over the strings of a [`SharedStrings`](super::SharedStrings)
+/// table in index order, created by
+/// [`SharedStrings::iter`](super::SharedStrings::iter).
+///
+/// Strings are borrowed from the table. On a
+/// [lazy](super::LoadOptions::lazy) table each string is parsed when the
+/// iterator reaches it.
+#[derive(Debug, Clone)]
+pub struct SharedStringsIter<'a> {
+    strings: &'a Storage,
+    indices: Range<usize>,
+}
+
+impl<'a> SharedStringsIter<'a> {
+    pub(super) fn new(strings: &'a Storage) -> Self {
+        Self { strings, indices: 0..strings.len() }
+    }
+}
+
+impl<'a> Iterator for SharedStringsIter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.indices.next().map(|i| self.strings.text(i))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<&'a str> {
+        self.indices.nth(n).map(|i| self.strings.text(i))
+    }
+}
+
+impl DoubleEndedIterator for SharedStringsIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.indices.next_back().map(|i| self.strings.text(i))
+    }
+}
+
+impl ExactSizeIterator for SharedStringsIter<'_> {}
+
+impl FusedIterator for SharedStringsIter<'_> {}
+
+/// All strings of a table in one buffer.
+///
+/// A `Box<str>` per string costs a pointer and length plus the allocator's
+/// per‑block overhead, which for tables of short strings outweighs the text
+/// itself. Here the text is concatenated and each string costs one end
+/// offset.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Arena {
+    text: String,
+    /// End of each string in `text`; a string starts where the previous ends.
+    ends: Vec<usize>,
+}
+
+impl Arena {
+    /// An empty arena with room for the offsets of `strings` strings.
+    pub(super) fn with_capacity(strings: usize) -> Self {
+        Self { text: String::new(), ends: Vec::with_capacity(strings) }
+    }
+
+    pub(super) fn push(&mut self, s: &str) {
+        self.text.push_str(s);
+        self.ends.push(self.text.len());
+    }
+
+    /// Releases the spare capacity left by loading.
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.text.shrink_to_fit();
+        self.ends.shrink_to_fit();
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&str> {
+        let end = *self.ends.get(index)?;
+        let start = index.checked_sub(1).map_or(0, |prev| self.ends[prev]);
+        Some(&self.text[start..end])
+    }
+
+    fn heap_bytes(&self) -> usize {
+        self.text.capacity() + self.ends.capacity() * size_of::<usize>()
+    }
+
+    /// All strings in order.
+    #[cfg(feature = "serde")]
+    pub(super) fn iter(&self) -> impl Iterator<Item = &str> {
+        let starts = std::iter::once(0).chain(self.ends.iter().copied());
+        starts.zip(&self.ends).map(|(start, &end)| &self.text[start..end])
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Arena {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut arena = Arena::default();
+        iter.into_iter().for_each(|s| arena.push(s));
+        arena.shrink_to_fit();
+        arena
+    }
+}
+
+/// Shared strings part with the position of every `<si>`, parsing each
+/// string on first access.
+pub(super) struct LazyTable {
+    /// The whole part, owned by the table.
+    xml: Box<[u8]>,
+    /// Byte offset of each item in `xml`.
+    offsets: Box<[usize]>,
+    /// Strings parsed so far.
+    cells: Box<[OnceLock<Box<str>>]>,
+    /// Options the strings are parsed with (text handling only).
+    options: LoadOptions,
+}
+
+impl LazyTable {
+    /// Records the offset of each `<si>` element of `xml` without parsing
+    /// its content, and returns the table and whether `options.limit` cut it
+    /// short. Room is reserved for `capacity` items.
+    ///
+    /// The scan only looks at the tags that open and close items: text can't
+    /// contain a raw `<`, so the first `</si>` after an item's start ends it.
+    /// Anything else is checked when a string is parsed. Like
+    /// [`load`](super::SharedStrings::load), self‑closing `<si/>` elements
+    /// are not items.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` if an item is never closed.
+    pub(super) fn scan(
+        xml: &[u8],
+        options: &LoadOptions,
+        capacity: usize,
+    ) -> Result<(Self, bool), quick_xml::Error> {
+        let mut offsets = Vec::with_capacity(capacity);
+        let mut truncated = false;
+        let mut pos = 0;
+        while let Some(start) = find_tag(xml, pos, b"<si") {
+            pos = start + 3;
+            match xml.get(pos) {
+                Some(b'>') => {}
+                Some(b) if b.is_ascii_whitespace() && !is_empty_element(xml, pos) => {}
+                _ => continue,
+            }
+            if options.limit.is_some_and(|limit| offsets.len() >= limit) {
+                truncated = true;
+                break;
+            }
+            offsets.push(start);
+            pos = find_tag(xml, pos, b"</si>")
+                .ok_or_else(|| IllFormedError::MissingEndTag("si".into()))?;
+        }
+
+        let table = Self {
+            xml: xml.into(),
+            cells: offsets.iter().map(|_| OnceLock::new()).collect(),
+            offsets: offsets.into_boxed_slice(),
+            options: LoadOptions {
+                limit: None, rich_text: false, phonetic: false, trigram_index: false, ..options.clone()
+            },
+        };
+        Ok((table, truncated))
+    }
+
+    fn heap_bytes(&self) -> usize {
+        let parsed: usize = self.cells.iter().filter_map(OnceLock::get).map(|s| s.len()).sum();
+        self.xml.len()
+            + self.offsets.len() * size_of::<usize>()
+            + self.cells.len() * size_of::<OnceLock<Box<str>>>()
+            + parsed
+    }
+
+    /// Returns the string at `index`, parsing it on first access.
+    ///
+    /// An item that fails to parse reads as an empty string.
+    fn get(&self, index: usize) -> Option<&str> {
+        let cell = self.cells.get(index)?;
+        Some(cell.get_or_init(|| {
+            let item = &self.xml[self.offsets[index]..];
+            StringIter::new(item, &self.options).next_item().ok().flatten()
+                .map(|item| item.text.into_boxed_str())
+                .unwrap_or_default()
+        }))
+    }
+}
+
+/// Position of the next tag starting with `prefix` (which starts with `<`)
+/// at or after `from`.
+fn find_tag(xml: &[u8], from: usize, prefix: &[u8]) -> Option<usize> {
+    let mut pos = from;
+    while let Some(offset) = xml.get(pos..)?.iter().position(|&b| b == b'<') {
+        pos += offset;
+        if xml[pos..].starts_with(prefix) { return Some(pos); }
+        pos += 1;
+    }
+    None
+}
+
+/// Returns `true` if the tag whose attributes start at `pos` ends with `/>`.
+fn is_empty_element(xml: &[u8], pos: usize) -> bool {
+    xml[pos..].iter().position(|&b| b == b'>').is_some_and(|end| xml[pos + end - 1] == b'/')
+}
+
+impl fmt::Debug for LazyTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyTable")
+            .field("strings", &self.offsets.len())
+            .field("parsed", &self.cells.iter().filter(|cell| cell.get().is_some()).count())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::Normalization;
+
+    fn parsed(table: &LazyTable) -> usize {
+        table.cells.iter().filter(|cell| cell.get().is_some()).count()
+    }
+
+    #[test]
+    fn arena_slices_strings_out_of_one_buffer() {
+        let mut arena = Arena::with_capacity(4);
+        for s in ["", "ab", "", "Ünïcödé", "c"] { arena.push(s); }
+        assert_eq!(arena.len(), 5);
+        assert_eq!(arena.text, "abÜnïcödéc");
+        assert_eq!((arena.get(0), arena.get(1), arena.get(2)), (Some(""), Some("ab"), Some("")));
+        assert_eq!((arena.get(3), arena.get(4), arena.get(5)), (Some("Ünïcödé"), Some("c"), None));
+
+        let strings: Vec<_> = (0..arena.len()).map(|i| arena.get(i).unwrap()).collect();
+        let collected: Arena = strings.iter().copied().collect();
+        assert_eq!(collected.ends, arena.ends);
+        assert!(Storage::Eager(collected).iter().eq(strings));
+    }
+
+    #[test]
+    fn arena_heap_bytes_follow_capacity() {
+        let mut arena = Arena::with_capacity(1000);
+        assert_eq!(arena.heap_bytes(), 1000 * size_of::<usize>());
+        for i in 0..100 { arena.push(&format!("string {i}")); }
+        arena.shrink_to_fit();
+        assert_eq!(arena.heap_bytes(), arena.text.len() + 100 * size_of::<usize>());
+        assert_eq!(Arena::default().heap_bytes(), 0);
+    }
+
+    #[test]
+    fn lazy_scan_records_items_without_parsing_them() {
+        let xml = br#"<sst count="3"><si><t>a</t></si><si/><sir/><si xml:space="preserve"><r><t>b</t></r><r><t>c</t></r></si><si>
+<t>d &amp; e</t></si></sst>"#;
+        let (table, truncated) = LazyTable::scan(xml, &LoadOptions::new(), 0).unwrap();
+        assert!(!truncated);
+        assert_eq!(table.offsets.len(), 3);
+        assert_eq!(parsed(&table), 0);
+
+        assert_eq!(table.get(1), Some("bc"));
+        assert_eq!(parsed(&table), 1);
+        assert_eq!(table.get(2), Some("d & e"));
+        assert_eq!(table.get(0), Some("a"));
+        assert_eq!(table.get(3), None);
+        assert_eq!(parsed(&table), 3);
+    }
+
+    #[test]
+    fn lazy_scan_stops_at_the_limit_and_rejects_unclosed_items() {
+        let xml = b"<sst><si><t>a</t></si><si><t>b</t></si><si><t>c</t></si></sst>";
+        let (table, truncated) = LazyTable::scan(xml, &LoadOptions::new().limit(2), 0).unwrap();
+        assert!(truncated);
+        assert_eq!((table.get(1), table.get(2)), (Some("b"), None));
+        let (_, truncated) = LazyTable::scan(xml, &LoadOptions::new().limit(3), 0).unwrap();
+        assert!(!truncated);
+
+        assert!(LazyTable::scan(b"<sst><si><t>a</t></si><si><t>b</t></sst>", &LoadOptions::new(), 0).is_err());
+    }
+
+    #[test]
+    fn lazy_storage_matches_eager_storage() {
+        let xml = br#"<sst><si><t>one</t></si><si><t xml:space="preserve"> two </t></si><si><r><t>th</t></r><r><rPr><b/></rPr><t>ree</t></r></si><si><t>e&#769;_x000D_</t></si></sst>"#;
+        let options = LoadOptions::new().normalize(Normalization::Nfc);
+        let items: Vec<String> = StringIter::new(xml, &options).map(Result::unwrap).collect();
+        let eager: Arena = items.iter().map(String::as_str).collect();
+        let (lazy, _) = LazyTable::scan(xml, &options, 4).unwrap();
+        let (eager, lazy) = (Storage::Eager(eager), Storage::Lazy(lazy));
+
+        assert_eq!(lazy.len(), eager.len());
+        assert!(lazy.iter().eq(eager.iter()));
+        assert_eq!(lazy.get(3), Some("é\r"));
+        assert!(SharedStringsIter::new(&lazy).rev().eq(SharedStringsIter::new(&eager).rev()));
+    }
+}
@@ -0,0 +1,114 @@
+use rustc_hash::FxHashMap;
+use super::normalize::case_fold;
+use super::storage::Storage;
+
+/// How the text searched for has to appear in a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Containment {
+    /// As one contiguous piece (exact, prefix and substring searches).
+    Substring,
+    /// Character by character in any order and distance (fuzzy search).
+    Chars,
+}
+
+/// Trigram index of a table, see
+/// [`SharedStrings::build_trigram_index`](super::SharedStrings::build_trigram_index).
+///
+/// Maps every trigram (three consecutive characters) and every single
+/// character of the case‑folded strings to the ascending indices of the
+/// strings containing it. A string that contains a query must contain all
+/// of the query's trigrams, so intersecting their lists leaves a few
+/// candidates to check instead of the whole table. Folding makes one index
+/// serve case‑sensitive and case‑insensitive searches alike; the candidates
+/// are a superset of the matches either way.
+#[derive(Debug, Clone, Default)]
+pub(super) struct TrigramIndex {
+    postings: FxHashMap<u64, Box<[u32]>>,
+}
+
+impl TrigramIndex {
+    /// Indexes every string of the table (parsing all of a lazy table).
+    pub(super) fn build(strings: &Storage) -> Self {
+        let mut postings: FxHashMap<u64, Vec<u32>> = FxHashMap::default();
+        let mut chars = Vec::new();
+        let mut keys = Vec::new();
+        for (i, s) in strings.iter().enumerate() {
+            chars.clear();
+            chars.extend(case_fold(s).chars());
+            keys.clear();
+            keys.extend(chars.iter().map(|&ch| char_key(ch)));
+            keys.extend(chars.windows(3).map(trigram_key));
+            keys.sort_unstable();
+            keys.dedup();
+            // Indices are stored as `u32` to halve the index; a table can't
+            // come near that many strings before running out of memory.
+            keys.iter().for_each(|&key| postings.entry(key).or_default().push(i as u32));
+        }
+
+        let postings = postings.into_iter().map(|(key, list)| (key, list.into_boxed_slice())).collect();
+        Self { postings }
+    }
+
+    /// Approximate heap bytes: the posting lists and the map's table.
+    pub(super) fn heap_bytes(&self) -> usize {
+        let lists: usize = self.postings.values().map(|list| size_of_val(&**list)).sum();
+        lists + super::map_bytes(&self.postings)
+    }
+
+    /// Restores an index from the lists returned by [`postings`](Self::postings),
+    /// or `None` if they can't belong to a table of `len` strings (an index
+    /// out of range or a list out of order).
+    #[cfg(feature = "serde")]
+    pub(super) fn from_postings(postings: Vec<(u64, Box<[u32]>)>, len: usize) -> Option<Self> {
+        let valid = |list: &[u32]| {
+            list.windows(2).all(|pair| pair[0] < pair[1])
+                && list.last().is_none_or(|&last| (last as usize) < len)
+        };
+        if !postings.iter().all(|(_, list)| valid(list)) { return None; }
+        Some(Self { postings: postings.into_iter().collect() })
+    }
+
+    /// Every key with the ascending indices of the strings containing it.
+    #[cfg(feature = "serde")]
+    pub(super) fn postings(&self) -> impl Iterator<Item = (u64, &[u32])> {
+        self.postings.iter().map(|(&key, list)| (key, &**list))
+    }
+
+    /// Indices of the strings that may contain `query` the way `containment`
+    /// asks for, in ascending order; `None` if the query has nothing to
+    /// narrow by (it is empty) and every string is a candidate.
+    pub(super) fn candidates(&self, query: &str, containment: Containment) -> Option<Vec<usize>> {
+        let chars: Vec<char> = case_fold(query).chars().collect();
+        let mut keys: Vec<u64> = match containment {
+            Containment::Substring if chars.len() >= 3 => chars.windows(3).map(trigram_key).collect(),
+            _ => chars.iter().map(|&ch| char_key(ch)).collect(),
+        };
+        keys.sort_unstable();
+        keys.dedup();
+
+        let mut lists = Vec::with_capacity(keys.len());
+        for key in keys {
+            match self.postings.get(&key) {
+                Some(list) => lists.push(&**list),
+                None => return Some(Vec::new()),
+            }
+        }
+        // Starting from the shortest list keeps every later step small.
+        lists.sort_unstable_by_key(|list| list.len());
+        let (first, rest) = lists.split_first()?;
+        Some(first.iter()
+            .filter(|id| rest.iter().all(|list| list.binary_search(id).is_ok()))
+            .map(|&id| id as usize)
+            .collect())
+    }
+}
+
+/// Key of a single character; the top bit keeps it apart from trigrams.
+fn char_key(ch: char) -> u64 {
+    1 << 63 | ch as u64
+}
+
+/// Key of three characters, 21 bits each.
+fn trigram_key(chars: &[char]) -> u64 {
+    chars.iter().fold(0, |key, &ch| key << 21 | ch as u64)
+}
@@ -0,0 +1,76 @@
+/// Returns `true` if the built‑in number format `id` shows a date or time.
+///
+/// Ids 14–22 and 45–47 are the locale‑independent date and time formats;
+/// 27–36 and 50–58 are the date formats of East Asian locales, which are
+/// dates whatever the locale that renders them.
+pub(super) fn is_builtin_date(id: u32) -> bool {
+    matches!(id, 14..=22 | 27..=36 | 45..=47 | 50..=58)
+}
+
+/// Returns `true` if a custom format code shows a date or time.
+///
+/// Looks at the first section (positive numbers) for the date and time
+/// tokens `y`, `m`, `d`, `h`, `s` and `[h]`‑style elapsed times, skipping
+/// what isn't a token: quoted literals (`"Итого: "`), escaped characters
+/// (`\d`), padding (`_)`, `*-`), and bracketed colors, conditions and
+/// locales (`[Red]`, `[>=100]`, `[$-419]`). `General` and text (`@`)
+/// formats are not dates.
+pub(super) fn is_date_code(code: &str) -> bool {
+    let mut chars = code.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ';' => return false,
+            '"' => { chars.by_ref().find(|&c| c == '"'); }
+            '\\' | '_' | '*' => { chars.next(); }
+            '[' => {
+                let inner: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                if is_elapsed(&inner) { return true; }
+            }
+            'G' | 'g' if chars.as_str().get(..6).is_some_and(|rest| rest.eq_ignore_ascii_case("eneral")) => {
+                chars.nth(5);
+            }
+            'y' | 'Y' | 'm' | 'M' | 'd' | 'D' | 'h' | 'H' | 's' | 'S' => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Returns `true` for the body of an elapsed time token: `h`, `mm`, `ss`, ...
+fn is_elapsed(inner: &str) -> bool {
+    ['h', 'm', 's'].iter().any(|&unit| {
+        !inner.is_empty() && inner.chars().all(|c| c.eq_ignore_ascii_case(&unit))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_date_ids() {
+        for id in [14, 18, 22, 27, 36, 45, 47, 50, 58] {
+            assert!(is_builtin_date(id), "{id}");
+        }
+        for id in [0, 1, 4, 10, 13, 23, 26, 37, 44, 48, 49, 59, 164] {
+            assert!(!is_builtin_date(id), "{id}");
+        }
+    }
+
+    #[test]
+    fn date_codes() {
+        for code in ["yyyy-mm-dd", "d mmm yy", "h:mm AM/PM", "[h]:mm:ss", "[mm]:ss", "[$-419]dd.mm.yyyy", "[Red]dd/mm;@", "\"Дата: \"dd.mm"] {
+            assert!(is_date_code(code), "{code}");
+        }
+    }
+
+    #[test]
+    fn codes_that_are_not_dates() {
+        for code in [
+            "General", "0.00", "#,##0;[Red]-#,##0", "@", "0.00E+00", "\"days\" 0", "\\d 0", "0_d", "0*s",
+            "[Red]0.00", "[>=100]0", "0;dd.mm", "[$€-407] #,##0.00",
+        ] {
+            assert!(!is_date_code(code), "{code}");
+        }
+    }
+}
@@ -0,0 +1,409 @@
+mod builtin;
+mod format_code;
+mod number_format;
+
+use quick_xml::{Reader, events::{BytesStart, Event}};
+use rustc_hash::FxHashMap;
+use builtin::builtin_format;
+use format_code::{is_builtin_date, is_date_code};
+use super::color::Color;
+use super::datetime::DateSystem;
+use super::worksheet::CellValue;
+use super::shared_strings::RunFont;
+use super::xml_utils::attr_value;
+
+// ---------------------------------------------------------------------------
+// Styles – cell formats of a workbook (xl/styles.xml)
+// ---------------------------------------------------------------------------
+
+/// A cell format (`<xf>` inside `<cellXfs>`), referenced by a cell's `s`
+/// attribute (see [`Cell::style`](super::Cell::style)).
+///
+/// The ids index the other tables of the styles part; resolve them with
+/// [`Styles::number_format`], [`Styles::font`] and [`Styles::fill`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CellFormat {
+    /// Number format id: built‑in below 164, else declared in `<numFmts>`.
+    pub num_fmt_id: u32,
+    /// Index into the fonts.
+    pub font_id: u32,
+    /// Index into the fills.
+    pub fill_id: u32,
+    /// Index into the borders.
+    pub border_id: u32,
+}
+
+impl CellFormat {
+    fn from_element(e: &BytesStart<'_>) -> Self {
+        let id = |name: &[u8]| attr_value(e, name).and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+        Self {
+            num_fmt_id: id(b"numFmtId"),
+            font_id: id(b"fontId"),
+            fill_id: id(b"fillId"),
+            border_id: id(b"borderId"),
+        }
+    }
+}
+
+/// A cell background (`<fill>`).
+///
+/// Only pattern fills are read; a gradient fill has no pattern and no colors.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Fill {
+    /// `patternType` (`"solid"`, `"gray125"`, `"none"`, ...), if set.
+    pub pattern: Option<String>,
+    /// Pattern color (`<fgColor>`); the cell color for solid fills.
+    pub fg_color: Option<Color>,
+    /// Background color behind the pattern (`<bgColor>`).
+    pub bg_color: Option<Color>,
+}
+
+/// A differential format (`<dxf>` inside `<dxfs>`): the formatting a
+/// conditional formatting rule applies on top of the cell's own format,
+/// referenced by [`CfRule::dxf_id`](super::CfRule::dxf_id).
+///
+/// Only the parts the rule changes are present.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Dxf {
+    /// Font changes; unset members are left as they are.
+    pub font: Option<RunFont>,
+    /// Fill. Excel keeps the color of a solid differential fill in
+    /// [`bg_color`](Fill::bg_color), not `fg_color`.
+    pub fill: Option<Fill>,
+    /// Number format code, if the rule changes it.
+    pub num_fmt: Option<String>,
+}
+
+/// Table of the styles part being read.
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Other,
+    NumFmts,
+    Fonts,
+    Fills,
+    CellXfs,
+    Dxfs,
+    DxfFont,
+    DxfFill,
+    /// A child of `<dxf>` that is not read (`<border>`, `<alignment>`, ...).
+    DxfOther,
+}
+
+/// Cell formats of a workbook, parsed from `xl/styles.xml`.
+///
+/// Cells refer to a format by index (`<c s="3">`); each format in turn
+/// refers to a number format, a font and a fill. This resolves those
+/// references, which is what date detection and formatted output build on.
+/// Differential formats (`<dxfs>`, used by conditional formatting) are
+/// read into [`Dxf`]s; named cell styles and borders are not read.
+///
+/// # XML Structure
+/// ```xml
+/// <styleSheet>
+///   <numFmts><numFmt numFmtId="164" formatCode="dd.mm.yyyy"/></numFmts>
+///   <fonts><font><sz val="11"/><name val="Calibri"/></font></fonts>
+///   <fills><fill><patternFill patternType="none"/></fill></fills>
+///   <cellXfs>
+///     <xf numFmtId="0" fontId="0" fillId="0" borderId="0"/>
+///     <xf numFmtId="164" fontId="0" fillId="0" borderId="0" applyNumberFormat="1"/>
+///   </cellXfs>
+/// </styleSheet>
+/// ```
+///
+/// # Example
+/// ```no_run
+/// # use excel_parser::{Styles, Worksheet};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let styles = Styles::parse(&std::fs::read("xl/styles.xml")?)?;
+/// let sheet = Worksheet::parse(&std::fs::read("xl/worksheets/sheet1.xml")?)?;
+/// if let Some(cell) = sheet.cell(2, 1) {
+///     println!("A2 format: {:?}", styles.number_format(cell.style()));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Styles {
+    /// Custom number formats by id.
+    num_fmts: FxHashMap<u32, String>,
+    fonts: Vec<RunFont>,
+    fills: Vec<Fill>,
+    cell_xfs: Vec<CellFormat>,
+    dxfs: Vec<Dxf>,
+    /// Whether each cell format shows dates, parallel to `cell_xfs`.
+    date_xfs: Vec<bool>,
+    /// Date system serial numbers are read in.
+    date_system: DateSystem,
+}
+
+impl Styles {
+    /// Path of the styles part in Excel files; use
+    /// [`Workbook::styles_part`](super::Workbook::styles_part) to find it
+    /// in any workbook.
+    pub const PATH: &'static str = "xl/styles.xml";
+
+    /// Parses a styles part.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of `xl/styles.xml`.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    pub fn parse(xml: &[u8]) -> Result<Self, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut styles = Styles::default();
+        let mut section = Section::Other;
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) => styles.element(e, &mut section, false),
+                Event::Empty(ref e) => styles.element(e, &mut section, true),
+                Event::End(ref e) => match (e.local_name().as_ref(), section) {
+                    (b"numFmts" | b"fonts" | b"fills" | b"cellXfs" | b"dxfs", _) => section = Section::Other,
+                    (b"font", Section::DxfFont) | (b"fill", Section::DxfFill) => section = Section::Dxfs,
+                    (b"border" | b"alignment" | b"protection", Section::DxfOther) => section = Section::Dxfs,
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        styles.date_xfs = styles.cell_xfs.iter().map(|xf| styles.is_date_format(xf.num_fmt_id)).collect();
+        Ok(styles)
+    }
+
+    /// Handles one start or empty element.
+    fn element(&mut self, e: &BytesStart<'_>, section: &mut Section, empty: bool) {
+        match (e.local_name().as_ref(), *section) {
+            (b"numFmts", _) if !empty => *section = Section::NumFmts,
+            (b"fonts", _) if !empty => *section = Section::Fonts,
+            (b"fills", _) if !empty => *section = Section::Fills,
+            (b"cellXfs", _) if !empty => *section = Section::CellXfs,
+            (b"numFmt", Section::NumFmts) => {
+                if let Some(id) = attr_value(e, b"numFmtId").and_then(|v| v.trim().parse().ok()) {
+                    self.num_fmts.insert(id, attr_value(e, b"formatCode").unwrap_or_default());
+                }
+            }
+            (b"font", Section::Fonts) => self.fonts.push(RunFont::default()),
+            (_, Section::Fonts) => {
+                if let Some(font) = self.fonts.last_mut() { font.apply(e); }
+            }
+            (b"fill", Section::Fills) => self.fills.push(Fill::default()),
+            (b"patternFill", Section::Fills) => {
+                if let Some(fill) = self.fills.last_mut() { fill.pattern = attr_value(e, b"patternType"); }
+            }
+            (b"fgColor", Section::Fills) => {
+                if let Some(fill) = self.fills.last_mut() { fill.fg_color = Color::from_element(e); }
+            }
+            (b"bgColor", Section::Fills) => {
+                if let Some(fill) = self.fills.last_mut() { fill.bg_color = Color::from_element(e); }
+            }
+            (b"xf", Section::CellXfs) => self.cell_xfs.push(CellFormat::from_element(e)),
+            (b"dxfs", _) if !empty => *section = Section::Dxfs,
+            (b"dxf", Section::Dxfs) => self.dxfs.push(Dxf::default()),
+            (b"font", Section::Dxfs) => {
+                if let Some(dxf) = self.dxfs.last_mut() { dxf.font = Some(RunFont::default()); }
+                if !empty { *section = Section::DxfFont; }
+            }
+            (_, Section::DxfFont) => {
+                if let Some(font) = self.dxfs.last_mut().and_then(|d| d.font.as_mut()) { font.apply(e); }
+            }
+            (b"fill", Section::Dxfs) => {
+                if let Some(dxf) = self.dxfs.last_mut() { dxf.fill = Some(Fill::default()); }
+                if !empty { *section = Section::DxfFill; }
+            }
+            (name, Section::DxfFill) => {
+                if let Some(fill) = self.dxfs.last_mut().and_then(|d| d.fill.as_mut()) {
+                    match name {
+                        b"patternFill" => fill.pattern = attr_value(e, b"patternType"),
+                        b"fgColor" => fill.fg_color = Color::from_element(e),
+                        b"bgColor" => fill.bg_color = Color::from_element(e),
+                        _ => {}
+                    }
+                }
+            }
+            (b"numFmt", Section::Dxfs) => {
+                if let Some(dxf) = self.dxfs.last_mut() { dxf.num_fmt = attr_value(e, b"formatCode"); }
+            }
+            (b"border" | b"alignment" | b"protection", Section::Dxfs) if !empty => *section = Section::DxfOther,
+            _ => {}
+        }
+    }
+
+    /// Sets the date system used to turn serial numbers into dates, for
+    /// [`Cell::typed_value`](super::Cell::typed_value) and
+    /// [`format_value`](Self::format_value). Defaults to the 1900 system;
+    /// pass the workbook's [`Workbook::date_system`](super::Workbook::date_system),
+    /// or load the styles with [`Workbook::styles`](super::Workbook::styles),
+    /// which does so.
+    pub fn with_date_system(mut self, system: DateSystem) -> Self {
+        self.date_system = system;
+        self
+    }
+
+    // -------------------------------------------------------------------------
+    // Public API
+    // -------------------------------------------------------------------------
+
+    /// Returns the date system serial numbers are read in.
+    pub fn date_system(&self) -> DateSystem {
+        self.date_system
+    }
+
+    /// Returns the cell format with index `style` (a cell's `s` attribute).
+    pub fn cell_format(&self, style: u32) -> Option<&CellFormat> {
+        self.cell_xfs.get(style as usize)
+    }
+
+    /// Returns the number of cell formats.
+    pub fn len(&self) -> usize {
+        self.cell_xfs.len()
+    }
+
+    /// Returns `true` if the part declares no cell formats.
+    pub fn is_empty(&self) -> bool {
+        self.cell_xfs.is_empty()
+    }
+
+    /// Returns the number format code of cell format `style`
+    /// (`"General"`, `"0.00%"`, `"dd.mm.yyyy"`).
+    ///
+    /// Custom formats come from `<numFmts>`; built‑in ones are mapped to
+    /// their codes. `None` if `style` is out of range or refers to a
+    /// locale‑dependent built‑in format (ids 5–8, 23–36, 50–81) or an
+    /// undeclared custom one.
+    pub fn number_format(&self, style: u32) -> Option<&str> {
+        self.format_code(self.cell_format(style)?.num_fmt_id)
+    }
+
+    /// Returns the code of number format `id`, custom or built‑in.
+    pub fn format_code(&self, id: u32) -> Option<&str> {
+        self.num_fmts.get(&id).map(String::as_str).or_else(|| builtin_format(id))
+    }
+
+    /// Returns `true` if cell format `style` shows numbers as dates or times.
+    ///
+    /// Excel stores dates as serial numbers and tells them apart only by
+    /// their format, so this is what decides whether a number cell holds a
+    /// date; see [`Cell::typed_value`](super::Cell::typed_value).
+    ///
+    /// # Returns
+    /// `false` for out‑of‑range styles.
+    pub fn is_date(&self, style: u32) -> bool {
+        self.date_xfs.get(style as usize).copied().unwrap_or(false)
+    }
+
+    /// Returns `true` if number format `id` is a date or time format: one
+    /// of the built‑in date formats (ids 14–22, 45–47 and the East Asian
+    /// 27–36, 50–58), or a custom code with date or time tokens (`dd.mm.yyyy`,
+    /// `[h]:mm`) outside of literal text.
+    pub fn is_date_format(&self, id: u32) -> bool {
+        match self.num_fmts.get(&id) {
+            Some(code) => is_date_code(code),
+            None => is_builtin_date(id),
+        }
+    }
+
+    /// Returns the number format code used to display cell format `style`:
+    /// its [`number_format`](Self::number_format), or for locale‑dependent
+    /// built‑in formats a stand‑in (`mm-dd-yy` for dates, else `General`).
+    fn display_format(&self, style: u32) -> &str {
+        if let Some(code) = self.number_format(style) { return code; }
+        if self.is_date(style) { "mm-dd-yy" } else { "General" }
+    }
+
+    /// Formats a value the way Excel displays it in a cell with format
+    /// `style`.
+    ///
+    /// Numbers are rendered with the number format: decimals, thousands
+    /// separators, percentages, scientific notation, fractions, dates and
+    /// times, currency symbols and literal text, in the section for
+    /// positive, negative or zero values (or the section whose condition
+    /// holds). Text is placed into the text section (`@`), if any. Booleans
+    /// show as `TRUE`/`FALSE` and errors as their code; a formula shows its
+    /// cached result.
+    ///
+    /// Separators and names are those of the format code as stored (`,`
+    /// for thousands, `.` for decimals, English month and day names),
+    /// whatever the locale of the file. Colors, padding (`*`) and widths
+    /// don't apply to text, and a date format given a number Excel can't
+    /// show as a date falls back to `General` instead of `#####`.
+    ///
+    /// # Arguments
+    /// * `value` – the value to format, e.g. from [`Cell::value`](super::Cell::value).
+    /// * `style` – the cell format index (a cell's `s` attribute).
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{CellValue, Styles};
+    /// let styles = Styles::parse(r##"<styleSheet>
+    ///     <numFmts><numFmt numFmtId="164" formatCode="#,##0.00 &quot;₽&quot;"/></numFmts>
+    ///     <cellXfs><xf numFmtId="0"/><xf numFmtId="164"/></cellXfs>
+    /// </styleSheet>"##.as_bytes())?;
+    /// // Cell format 1 uses "#,##0.00 ₽":
+    /// assert_eq!(styles.format_value(&CellValue::Number(1234.5), 1), "1,234.50 ₽");
+    /// assert_eq!(styles.format_value(&CellValue::Number(-0.5), 0), "-0.5");
+    /// # Ok::<(), quick_xml::Error>(())
+    /// ```
+    pub fn format_value(&self, value: &CellValue<'_>, style: u32) -> String {
+        match value {
+            CellValue::Empty => String::new(),
+            CellValue::String(text) => number_format::format_text(text, self.display_format(style)),
+            CellValue::Number(n) => number_format::format_number(*n, self.display_format(style), self.date_system),
+            CellValue::DateTime(dt) => self.format_value(&CellValue::Number(dt.to_serial(self.date_system)), style),
+            CellValue::Formula { cached, .. } => self.format_value(cached, style),
+            CellValue::Bool(_) | CellValue::Error(_) => value.to_string(),
+        }
+    }
+
+    /// Returns the font of cell format `style`.
+    pub fn font(&self, style: u32) -> Option<&RunFont> {
+        self.fonts.get(self.cell_format(style)?.font_id as usize)
+    }
+
+    /// Returns the fill of cell format `style`.
+    pub fn fill(&self, style: u32) -> Option<&Fill> {
+        self.fills.get(self.cell_format(style)?.fill_id as usize)
+    }
+
+    /// Returns differential format `id`, as referenced by a conditional
+    /// formatting rule's [`dxf_id`](super::CfRule::dxf_id).
+    pub fn dxf(&self, id: u32) -> Option<&Dxf> {
+        self.dxfs.get(id as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dxf_reads_the_formats_of_conditional_rules() {
+        let xml = br#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+  <fonts count="1"><font><sz val="11"/><name val="Calibri"/></font></fonts>
+  <dxfs count="3">
+    <dxf><font><b/><color rgb="FF9C0006"/></font><fill><patternFill><bgColor rgb="FFFFC7CE"/></patternFill></fill></dxf>
+    <dxf><numFmt numFmtId="164" formatCode="0.0%"/><border><left style="thin"><color auto="1"/></left></border></dxf>
+    <dxf/>
+  </dxfs>
+</styleSheet>"#;
+        let styles = Styles::parse(xml).unwrap();
+
+        let red = styles.dxf(0).unwrap();
+        let font = red.font.as_ref().unwrap();
+        assert!(font.bold && !font.italic);
+        assert_eq!(font.color, Some(Color::Argb(0xFF9C0006)));
+        assert_eq!(red.fill, Some(Fill { pattern: None, fg_color: None, bg_color: Some(Color::Argb(0xFFFFC7CE)) }));
+        assert_eq!(red.num_fmt, None);
+
+        // The border's color does not leak into a font or fill.
+        assert_eq!(styles.dxf(1), Some(&Dxf { num_fmt: Some("0.0%".into()), ..Dxf::default() }));
+        assert_eq!(styles.dxf(2), Some(&Dxf::default()));
+        assert_eq!(styles.dxf(3), None);
+    }
+}
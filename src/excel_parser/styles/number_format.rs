@@ -0,0 +1,844 @@
+use std::iter::Peekable;
+use std::str::Chars;
+use super::super::datetime::{DateSystem, DateTime};
+
+// ---------------------------------------------------------------------------
+// Number format interpreter – renders values the way Excel displays them
+// ---------------------------------------------------------------------------
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+const WEEKDAYS: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+
+/// Comparison of a conditional section (`[>=100]`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Condition {
+    Lt(f64),
+    Le(f64),
+    Gt(f64),
+    Ge(f64),
+    Eq(f64),
+    Ne(f64),
+}
+
+impl Condition {
+    fn parse(text: &str) -> Option<Self> {
+        let (op, value): (fn(f64) -> Self, &str) = match text.as_bytes() {
+            [b'<', b'=', ..] => (Self::Le, &text[2..]),
+            [b'>', b'=', ..] => (Self::Ge, &text[2..]),
+            [b'<', b'>', ..] => (Self::Ne, &text[2..]),
+            [b'<', ..] => (Self::Lt, &text[1..]),
+            [b'>', ..] => (Self::Gt, &text[1..]),
+            [b'=', ..] => (Self::Eq, &text[1..]),
+            _ => return None,
+        };
+        value.trim().parse().ok().map(op)
+    }
+
+    fn matches(self, v: f64) -> bool {
+        match self {
+            Self::Lt(x) => v < x,
+            Self::Le(x) => v <= x,
+            Self::Gt(x) => v > x,
+            Self::Ge(x) => v >= x,
+            Self::Eq(x) => v == x,
+            Self::Ne(x) => v != x,
+        }
+    }
+}
+
+/// Unit of an elapsed time token (`[h]`, `[mm]`, `[ss]`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    Hour,
+    Minute,
+    Second,
+}
+
+/// A token of a format section.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Text shown as is: quoted text, escaped or plain characters, currency.
+    Literal(String),
+    /// Digit placeholder `0`, `#` or `?`.
+    Digit(char),
+    Point,
+    Comma,
+    Percent,
+    /// Exponent `E+` (`true`, always signed) or `E-`.
+    Exp(bool),
+    Slash,
+    /// `@`, the text of a string value.
+    Text,
+    General,
+    Year(usize),
+    Month(usize),
+    Day(usize),
+    Hour(usize),
+    Minute(usize),
+    Second(usize),
+    /// Fraction of a second, `.0` to `.000`.
+    SubSecond(usize),
+    /// `AM/PM`.
+    AmPm,
+    /// `A/P` or `a/p`; the flag tells upper case.
+    AP(bool),
+    Elapsed(Unit, usize),
+}
+
+/// One `;`‑separated section of a format code.
+#[derive(Debug, Default)]
+struct Section {
+    tokens: Vec<Token>,
+    condition: Option<Condition>,
+}
+
+impl Section {
+    fn is_date(&self) -> bool {
+        self.tokens.iter().any(|t| matches!(t,
+            Token::Year(_) | Token::Month(_) | Token::Day(_) | Token::Hour(_) | Token::Minute(_)
+            | Token::Second(_) | Token::Elapsed(..)))
+    }
+
+    fn has_text(&self) -> bool {
+        self.tokens.contains(&Token::Text)
+    }
+}
+
+/// Splits a format code into its sections and tokens.
+fn parse(code: &str) -> Vec<Section> {
+    let mut sections = vec![Section::default()];
+    let mut chars = code.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        let section = sections.last_mut().expect("at least one section");
+        let tokens = &mut section.tokens;
+        match ch {
+            ';' => sections.push(Section::default()),
+            '"' => tokens.push(Token::Literal(chars.by_ref().take_while(|&c| c != '"').collect())),
+            '\\' => tokens.extend(chars.next().map(|c| Token::Literal(c.to_string()))),
+            '_' => { chars.next(); tokens.push(Token::Literal(" ".into())); }
+            '*' => { chars.next(); }
+            '[' => {
+                let inner: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                if let Some(unit) = elapsed_unit(&inner) {
+                    tokens.push(Token::Elapsed(unit, inner.len()));
+                } else if let Some(currency) = inner.strip_prefix('$') {
+                    let symbol = currency.split('-').next().unwrap_or_default();
+                    if !symbol.is_empty() { tokens.push(Token::Literal(symbol.into())); }
+                } else if let Some(condition) = Condition::parse(&inner) {
+                    section.condition = Some(condition);
+                }
+                // Anything else is a color ([Red], [Color10]) – not rendered.
+            }
+            '0' | '#' | '?' => tokens.push(Token::Digit(ch)),
+            '.' => tokens.push(Token::Point),
+            ',' => tokens.push(Token::Comma),
+            '%' => tokens.push(Token::Percent),
+            '/' => tokens.push(Token::Slash),
+            '@' => tokens.push(Token::Text),
+            'E' | 'e' if matches!(chars.peek(), Some('+' | '-')) => {
+                tokens.push(Token::Exp(chars.next() == Some('+')));
+            }
+            'G' | 'g' if chars.clone().take(6).collect::<String>().eq_ignore_ascii_case("eneral") => {
+                chars.nth(5);
+                tokens.push(Token::General);
+            }
+            'A' | 'a' if starts_with_ignore_case(&chars, "m/pm") => {
+                chars.nth(3);
+                tokens.push(Token::AmPm);
+            }
+            'A' | 'a' if starts_with_ignore_case(&chars, "/p") => {
+                chars.nth(1);
+                tokens.push(Token::AP(ch == 'A'));
+            }
+            'y' | 'Y' => { let n = run_length(ch, &mut chars); tokens.push(Token::Year(n)); }
+            'm' | 'M' => { let n = run_length(ch, &mut chars); tokens.push(Token::Month(n)); }
+            'd' | 'D' => { let n = run_length(ch, &mut chars); tokens.push(Token::Day(n)); }
+            'h' | 'H' => { let n = run_length(ch, &mut chars); tokens.push(Token::Hour(n)); }
+            's' | 'S' => { let n = run_length(ch, &mut chars); tokens.push(Token::Second(n)); }
+            ch => tokens.push(Token::Literal(ch.to_string())),
+        }
+    }
+
+    for section in &mut sections {
+        if section.is_date() { resolve_date_tokens(&mut section.tokens); }
+    }
+    sections
+}
+
+/// Counts `ch` and the repetitions of it that follow (`yyyy` → 4).
+fn run_length(ch: char, chars: &mut Peekable<Chars<'_>>) -> usize {
+    let mut n = 1;
+    while chars.next_if(|c| c.eq_ignore_ascii_case(&ch)).is_some() { n += 1; }
+    n
+}
+
+fn starts_with_ignore_case(chars: &Peekable<Chars<'_>>, prefix: &str) -> bool {
+    let mut rest = chars.clone();
+    prefix.chars().all(|p| rest.next().is_some_and(|c| c.eq_ignore_ascii_case(&p)))
+}
+
+fn elapsed_unit(inner: &str) -> Option<Unit> {
+    let unit = match inner.chars().next()?.to_ascii_lowercase() {
+        'h' => Unit::Hour,
+        'm' => Unit::Minute,
+        's' => Unit::Second,
+        _ => return None,
+    };
+    let first = inner.chars().next()?;
+    inner.chars().all(|c| c.eq_ignore_ascii_case(&first)).then_some(unit)
+}
+
+/// Fixes up the tokens of a date section: `m` and `mm` next to hours or
+/// seconds are minutes, `.0`s after seconds are fractions of a second, and
+/// number tokens are plain text.
+fn resolve_date_tokens(tokens: &mut Vec<Token>) {
+    let is_hour = |t: &Token| matches!(t, Token::Hour(_) | Token::Elapsed(Unit::Hour, _));
+    let is_second = |t: &Token| matches!(t, Token::Second(_) | Token::Elapsed(Unit::Second, _));
+    let skip_literal = |t: &&Token| !matches!(t, Token::Literal(_));
+    for i in 0..tokens.len() {
+        let Token::Month(n @ 1..=2) = tokens[i] else { continue };
+        let after_hour = tokens[..i].iter().rev().find(skip_literal).is_some_and(is_hour);
+        let before_second = tokens[i + 1..].iter().find(skip_literal).is_some_and(is_second);
+        if after_hour || before_second { tokens[i] = Token::Minute(n); }
+    }
+
+    let mut resolved = Vec::with_capacity(tokens.len());
+    let mut iter = std::mem::take(tokens).into_iter().peekable();
+    while let Some(token) = iter.next() {
+        resolved.push(match token {
+            Token::Point if iter.peek() == Some(&Token::Digit('0')) => {
+                let mut n = 0;
+                while iter.next_if_eq(&Token::Digit('0')).is_some() { n += 1; }
+                Token::SubSecond(n.min(3))
+            }
+            Token::Digit(c) => Token::Literal(c.to_string()),
+            Token::Point => Token::Literal(".".into()),
+            Token::Comma => Token::Literal(",".into()),
+            Token::Percent => Token::Literal("%".into()),
+            Token::Slash => Token::Literal("/".into()),
+            token => token,
+        });
+    }
+    *tokens = resolved;
+}
+
+/// Picks the section for a number, and whether to prefix a minus sign.
+///
+/// Without conditions, one section formats all numbers, two sections split
+/// positive (and zero) from negative numbers, and a third takes zero; a
+/// negative section shows the absolute value. A fourth section is for text.
+/// With conditions, the first matching conditional section wins, else the
+/// first section without a condition.
+fn choose(sections: &[Section], v: f64) -> (Option<&Section>, bool) {
+    let numeric = &sections[..sections.len().min(3)];
+    if numeric.iter().any(|s| s.condition.is_some()) {
+        let section = numeric.iter()
+            .find(|s| s.condition.is_some_and(|c| c.matches(v)))
+            .or_else(|| numeric.iter().find(|s| s.condition.is_none()));
+        return (section, v < 0.0);
+    }
+    match (numeric.len(), v) {
+        (3.., 0.0) => (numeric.get(2), false),
+        (2.., ..0.0) => (numeric.get(1), false),
+        (_, v) => (numeric.first(), v < 0.0),
+    }
+}
+
+/// Formats a number with a format code.
+///
+/// Date sections read `v` as a serial number in `system`; a number that
+/// isn't a date there (e.g. negative) is shown in `General`.
+pub(super) fn format_number(v: f64, code: &str, system: DateSystem) -> String {
+    let sections = parse(code);
+    let (section, negative) = choose(&sections, v);
+    let Some(section) = section else { return general(v) };
+    if section.has_text() && sections.len() == 1 { return general(v); }
+
+    if section.is_date() {
+        return format_date(v, &section.tokens, system).unwrap_or_else(|| general(v));
+    }
+
+    let out = format_plain(v.abs(), &section.tokens);
+    if negative && out.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+        format!("-{out}")
+    } else {
+        out
+    }
+}
+
+/// Formats a text value: the text section (the fourth, or the only one if it
+/// has `@`) with `@` replaced by the text; without one the text is shown as is.
+pub(super) fn format_text(text: &str, code: &str) -> String {
+    let sections = parse(code);
+    let section = match sections.len() {
+        4.. => &sections[3],
+        1 if sections[0].has_text() => &sections[0],
+        _ => return text.to_owned(),
+    };
+    section.tokens.iter().map(|token| match token {
+        Token::Text => text,
+        Token::Literal(s) => s,
+        _ => "",
+    }).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Numbers
+// ---------------------------------------------------------------------------
+
+/// Formats in the `General` format: integers in full up to 11 digits, other
+/// numbers rounded to fit 11 characters, very large and very small numbers
+/// in scientific notation (`1.23457E+11`).
+pub(super) fn general(v: f64) -> String {
+    if v == 0.0 || !v.is_finite() { return "0".into(); }
+    let sign = if v < 0.0 { "-" } else { "" };
+    let a = v.abs();
+    if (1e-9..1e11).contains(&a) {
+        let int_len = if a < 1.0 { 1 } else { a.log10().floor() as usize + 1 };
+        let (int, frac) = round_digits(a, 10usize.saturating_sub(int_len));
+        let frac = frac.trim_end_matches('0');
+        let int = if int.is_empty() { "0" } else { &int };
+        if frac.is_empty() { format!("{sign}{int}") } else { format!("{sign}{int}.{frac}") }
+    } else {
+        let mut exp = a.log10().floor() as i32;
+        let (mut int, mut frac) = round_digits(a / 10f64.powi(exp), 5);
+        if int.len() > 1 {
+            exp += 1;
+            (int, frac) = round_digits(a / 10f64.powi(exp), 5);
+        }
+        let frac = frac.trim_end_matches('0');
+        let point = if frac.is_empty() { "" } else { "." };
+        let exp_sign = if exp < 0 { '-' } else { '+' };
+        format!("{sign}{int}{point}{frac}E{exp_sign}{:02}", exp.abs())
+    }
+}
+
+/// Rounds `a ≥ 0` half away from zero to `decimals` places and returns its
+/// integer digits (empty for zero) and exactly `decimals` fraction digits.
+///
+/// Works on the 15 significant digits Excel keeps, so that `2.675` rounds to
+/// `2.68` as in Excel rather than to the nearest double's `2.67`.
+fn round_digits(a: f64, decimals: usize) -> (String, String) {
+    if a == 0.0 || !a.is_finite() { return (String::new(), "0".repeat(decimals)); }
+
+    let sci = format!("{a:.14e}");
+    let (mantissa, exp) = sci.split_once('e').expect("scientific notation");
+    let mut digits: Vec<u8> = mantissa.bytes().filter(u8::is_ascii_digit).map(|b| b - b'0').collect();
+    let mut point = exp.parse::<i64>().expect("exponent") + 1;
+
+    // Digits to keep, counted from the first significant one.
+    let keep = point + decimals as i64;
+    if keep < 0 { return (String::new(), "0".repeat(decimals)); }
+    let keep = keep as usize;
+    if keep < digits.len() {
+        let round_up = digits[keep] >= 5;
+        digits.truncate(keep);
+        if round_up {
+            let mut i = keep;
+            loop {
+                if i == 0 {
+                    digits.insert(0, 1);
+                    point += 1;
+                    break;
+                }
+                i -= 1;
+                if digits[i] == 9 { digits[i] = 0; } else { digits[i] += 1; break; }
+            }
+        }
+    }
+
+    // Digit at `index` places after the decimal point of the significant
+    // digits, zero outside of them.
+    let digit = |index: i64| {
+        let d = usize::try_from(index).ok().and_then(|i| digits.get(i)).copied().unwrap_or(0);
+        char::from(b'0' + d)
+    };
+    let int: String = (0..point.max(0)).map(digit).collect();
+    let frac: String = (point..point + decimals as i64).map(digit).collect();
+    (int.trim_start_matches('0').to_owned(), frac)
+}
+
+/// Formats a non‑negative number with a number section.
+fn format_plain(a: f64, tokens: &[Token]) -> String {
+    if tokens.contains(&Token::General) {
+        return tokens.iter().map(|token| match token {
+            Token::General => general(a),
+            Token::Literal(s) => s.clone(),
+            _ => String::new(),
+        }).collect();
+    }
+
+    let mut tokens = tokens.to_vec();
+    let mut a = a * 100f64.powi(tokens.iter().filter(|t| **t == Token::Percent).count() as i32);
+    let grouping = resolve_commas(&mut tokens, &mut a);
+
+    if let Some(exp) = tokens.iter().position(|t| matches!(t, Token::Exp(_))) {
+        return format_scientific(a, &tokens, exp);
+    }
+    if let Some(slash) = fraction_bar(&tokens) {
+        return format_fraction(a, &tokens, slash);
+    }
+
+    let point = tokens.iter().position(|t| *t == Token::Point);
+    let (int_tokens, frac_tokens) = match point {
+        Some(p) => (&tokens[..p], &tokens[p + 1..]),
+        None => (&tokens[..], &[][..]),
+    };
+    if !tokens.iter().any(|t| matches!(t, Token::Digit(_))) {
+        return render_literals(&tokens);
+    }
+    let mut int_tokens = int_tokens.to_vec();
+    if point.is_some() && !int_tokens.iter().any(|t| matches!(t, Token::Digit(_))) {
+        int_tokens.push(Token::Digit('#'));
+    }
+
+    let decimals = frac_tokens.iter().filter(|t| matches!(t, Token::Digit(_))).count();
+    let (int, frac) = round_digits(a, decimals);
+    let mut out = render_integer(&int_tokens, &int, grouping);
+    if point.is_some() { out.push('.'); }
+    out.push_str(&render_fraction_digits(frac_tokens, &frac));
+    out
+}
+
+/// Removes the commas of a number section: commas between digit
+/// placeholders turn on thousands separators (the returned flag), commas
+/// after the last integer placeholder divide by a thousand each, others are
+/// literal.
+fn resolve_commas(tokens: &mut Vec<Token>, a: &mut f64) -> bool {
+    let mut grouping = false;
+    let mut resolved = Vec::with_capacity(tokens.len());
+    for (i, token) in tokens.iter().enumerate() {
+        if *token != Token::Comma {
+            resolved.push(token.clone());
+            continue;
+        }
+        let after_digit = tokens[..i].iter().rev().find(|t| **t != Token::Comma)
+            .is_some_and(|t| matches!(t, Token::Digit(_)));
+        let before_digit = matches!(tokens[i + 1..].iter().find(|t| **t != Token::Comma), Some(Token::Digit(_)));
+        match (after_digit, before_digit) {
+            (true, true) => grouping = true,
+            (true, false) => *a /= 1000.0,
+            _ => resolved.push(Token::Literal(",".into())),
+        }
+    }
+    *tokens = resolved;
+    grouping
+}
+
+/// Index of a `/` between digit placeholders (or a fixed denominator).
+fn fraction_bar(tokens: &[Token]) -> Option<usize> {
+    let slash = tokens.iter().position(|t| *t == Token::Slash)?;
+    let before = slash > 0 && matches!(tokens[slash - 1], Token::Digit(_));
+    let after = match tokens.get(slash + 1) {
+        Some(Token::Digit(_)) => true,
+        Some(Token::Literal(s)) => s.starts_with(|c: char| c.is_ascii_digit()),
+        _ => false,
+    };
+    (before && after).then_some(slash)
+}
+
+fn render_literals(tokens: &[Token]) -> String {
+    tokens.iter().map(|token| match token {
+        Token::Literal(s) => s.as_str(),
+        Token::Percent => "%",
+        Token::Point => ".",
+        Token::Slash => "/",
+        _ => "",
+    }).collect()
+}
+
+/// Renders the integer digits into the integer tokens of a section, right to
+/// left: each placeholder takes one digit and the leftmost takes all that are
+/// left. Missing digits show as `0` for `0`, a space for `?` and nothing for
+/// `#`; literals stay in place.
+fn render_integer(tokens: &[Token], digits: &str, grouping: bool) -> String {
+    let places = tokens.iter().filter(|t| matches!(t, Token::Digit(_))).count();
+    let mut digits = digits.chars().rev();
+    let mut out = Vec::new();
+    let mut seen = 0;
+    let mut emitted = 0;
+    let mut push_digit = |out: &mut Vec<char>, d: char| {
+        if grouping && emitted > 0 && emitted % 3 == 0 { out.push(','); }
+        out.push(d);
+        emitted += 1;
+    };
+
+    for token in tokens.iter().rev() {
+        match token {
+            Token::Digit(placeholder) => {
+                seen += 1;
+                let mut took = false;
+                if seen == places {
+                    for d in digits.by_ref() {
+                        push_digit(&mut out, d);
+                        took = true;
+                    }
+                } else if let Some(d) = digits.next() {
+                    push_digit(&mut out, d);
+                    took = true;
+                }
+                if !took {
+                    match placeholder {
+                        '0' => push_digit(&mut out, '0'),
+                        '?' => out.push(' '),
+                        _ => {}
+                    }
+                }
+            }
+            Token::Literal(s) => out.extend(s.chars().rev()),
+            Token::Percent => out.push('%'),
+            Token::Slash => out.push('/'),
+            _ => {}
+        }
+    }
+    out.iter().rev().collect()
+}
+
+/// Renders fraction digits into the tokens after the decimal point, left to
+/// right. Trailing zeros show as `0` for `0`, a space for `?` and nothing for
+/// `#`.
+fn render_fraction_digits(tokens: &[Token], digits: &str) -> String {
+    let significant = digits.trim_end_matches('0').len();
+    let mut digits = digits.chars().enumerate();
+    let mut out = String::new();
+    for token in tokens {
+        match token {
+            Token::Digit(placeholder) => {
+                let Some((i, d)) = digits.next() else { continue };
+                match placeholder {
+                    _ if i < significant => out.push(d),
+                    '0' => out.push('0'),
+                    '?' => out.push(' '),
+                    _ => {}
+                }
+            }
+            Token::Literal(s) => out.push_str(s),
+            Token::Percent => out.push('%'),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Formats in scientific notation (`0.00E+00`, `##0.0E+0`). With `#` in the
+/// integer part the exponent is a multiple of the number of integer
+/// placeholders (engineering notation).
+fn format_scientific(a: f64, tokens: &[Token], exp_at: usize) -> String {
+    let Token::Exp(always_signed) = tokens[exp_at] else { unreachable!("exponent token") };
+    let mantissa = &tokens[..exp_at];
+    let exponent = &tokens[exp_at + 1..];
+    let point = mantissa.iter().position(|t| *t == Token::Point);
+    let (int_tokens, frac_tokens) = match point {
+        Some(p) => (&mantissa[..p], &mantissa[p + 1..]),
+        None => (mantissa, &[][..]),
+    };
+    let int_places = int_tokens.iter().filter(|t| matches!(t, Token::Digit(_))).count().max(1) as i32;
+    let engineering = int_places > 1 && int_tokens.contains(&Token::Digit('#'));
+    let decimals = frac_tokens.iter().filter(|t| matches!(t, Token::Digit(_))).count();
+
+    let mut exp = if a == 0.0 { 0 } else { a.log10().floor() as i32 };
+    let (step, width) = if engineering { (int_places, int_places) } else { (1, int_places) };
+    exp = if engineering { exp.div_euclid(step) * step } else { exp - (int_places - 1) };
+    let (mut int, mut frac) = round_digits(a / 10f64.powi(exp), decimals);
+    if a != 0.0 && int.len() > width as usize {
+        exp += step;
+        (int, frac) = round_digits(a / 10f64.powi(exp), decimals);
+    }
+
+    let mut out = render_integer(int_tokens, &int, false);
+    if point.is_some() { out.push('.'); }
+    out.push_str(&render_fraction_digits(frac_tokens, &frac));
+    out.push('E');
+    if exp < 0 { out.push('-'); } else if always_signed { out.push('+'); }
+    out.push_str(&render_integer(exponent, &exp.unsigned_abs().to_string(), false));
+    out
+}
+
+/// Formats as a fraction (`# ?/?`, `?/8`, `# ??/100`). With an integer part
+/// the whole number is shown before the fraction; otherwise the fraction
+/// holds the whole value (`5/4`).
+fn format_fraction(a: f64, tokens: &[Token], slash: usize) -> String {
+    let num_start = tokens[..slash].iter().rposition(|t| !matches!(t, Token::Digit(_))).map_or(0, |i| i + 1);
+    let whole_tokens = &tokens[..num_start];
+    let num_tokens = &tokens[num_start..slash];
+    let den_tokens = &tokens[slash + 1..];
+    let has_whole = whole_tokens.iter().any(|t| matches!(t, Token::Digit(_)));
+
+    // A denominator of digits is fixed (`?/8`, `?/100`; its zeros were read
+    // as placeholders), otherwise placeholders set its width.
+    let fixed_len = match den_tokens.first() {
+        Some(Token::Literal(s)) if s.starts_with(|c: char| c.is_ascii_digit()) => den_tokens.iter()
+            .take_while(|t| match t {
+                Token::Literal(s) => s.chars().all(|c| c.is_ascii_digit()),
+                token => **token == Token::Digit('0'),
+            })
+            .count(),
+        _ => 0,
+    };
+    let fixed: String = den_tokens[..fixed_len].iter().map(|t| match t {
+        Token::Literal(s) => s.as_str(),
+        _ => "0",
+    }).collect();
+    let den_places = den_tokens[fixed_len..].iter().take_while(|t| matches!(t, Token::Digit(_))).count();
+    let den_rest = &den_tokens[fixed_len + den_places..];
+
+    let (mut whole, part) = if has_whole { (a.trunc(), a.fract()) } else { (0.0, a) };
+    let (mut num, den) = match fixed.parse::<u64>() {
+        Ok(den) if den > 0 => ((part * den as f64).round() as u64, den),
+        _ => approximate(part, 10u64.pow(den_places.clamp(1, 9) as u32) - 1),
+    };
+    if has_whole && num == den {
+        whole += 1.0;
+        num = 0;
+    }
+
+    let whole_digits = if whole == 0.0 { String::new() } else { format!("{whole:.0}") };
+    if has_whole && num == 0 {
+        let mut out = render_integer(whole_tokens, &whole_digits, false);
+        if whole == 0.0 && !out.contains(|c: char| c.is_ascii_digit()) {
+            out = out.trim_end().to_owned() + "0";
+        }
+        let width = num_tokens.len() + 1 + if fixed.is_empty() { den_places } else { fixed.len() };
+        return out + &" ".repeat(width) + &render_literals(den_rest);
+    }
+
+    let mut out = render_integer(whole_tokens, &whole_digits, false);
+    out.push_str(&render_integer(num_tokens, &num.to_string(), false));
+    out.push('/');
+    if fixed.is_empty() {
+        let den = den.to_string();
+        let pad = den_places.saturating_sub(den.len());
+        let filler = match den_tokens.first() {
+            Some(Token::Digit('?')) => " ",
+            _ => "",
+        };
+        out.push_str(&den);
+        out.push_str(&filler.repeat(pad));
+    } else {
+        out.push_str(&fixed);
+    }
+    out.push_str(&render_literals(den_rest));
+    out
+}
+
+/// Best rational approximation `p/q` of `x ≥ 0` with `q ≤ max_den`, from the
+/// continued fraction of `x`.
+fn approximate(x: f64, max_den: u64) -> (u64, u64) {
+    let (mut p0, mut q0, mut p1, mut q1) = (0u64, 1u64, 1u64, 0u64);
+    let mut rest = x;
+    loop {
+        let term = rest.floor();
+        if term > u64::MAX as f64 / 2.0 { break; }
+        let term = term as u64;
+        let Some(q2) = term.checked_mul(q1).and_then(|t| t.checked_add(q0)) else { break };
+        if q2 > max_den { break; }
+        (p0, q0, p1, q1) = (p1, q1, p0 + term * p1, q2);
+        let fract = rest - rest.floor();
+        if fract < 1e-12 { break; }
+        rest = 1.0 / fract;
+    }
+    if q1 == 0 { return ((x * max_den as f64).round() as u64, max_den); }
+
+    // The semiconvergent with the largest denominator allowed may be closer.
+    let k = (max_den - q0) / q1;
+    let (p2, q2) = (p0 + k * p1, q0 + k * q1);
+    let error = |p: u64, q: u64| (x - p as f64 / q as f64).abs();
+    if q2 > 0 && error(p2, q2) < error(p1, q1) { (p2, q2) } else { (p1, q1) }
+}
+
+// ---------------------------------------------------------------------------
+// Dates and times
+// ---------------------------------------------------------------------------
+
+/// Formats a serial number with a date section; `None` if it isn't a date
+/// in `system`.
+fn format_date(serial: f64, tokens: &[Token], system: DateSystem) -> Option<String> {
+    // Round to the precision shown: whole seconds unless there is a `.0`.
+    let sub_digits = tokens.iter().filter_map(|t| match t { Token::SubSecond(n) => Some(*n), _ => None }).max().unwrap_or(0);
+    let unit = 10i64.pow(3 - sub_digits as u32);
+    let total_ms = ((serial * 86_400_000.0) / unit as f64).round() as i64 * unit;
+    let dt = DateTime::from_serial(total_ms as f64 / 86_400_000.0, system)?;
+    let twelve_hour = tokens.iter().any(|t| matches!(t, Token::AmPm | Token::AP(_)));
+    let pm = dt.hour >= 12;
+
+    let mut out = String::new();
+    for token in tokens {
+        match *token {
+            Token::Literal(ref s) => out.push_str(s),
+            Token::Year(..=2) => out.push_str(&format!("{:02}", dt.year.rem_euclid(100))),
+            Token::Year(_) => out.push_str(&format!("{:04}", dt.year)),
+            Token::Month(1) => out.push_str(&dt.month.to_string()),
+            Token::Month(2) => out.push_str(&format!("{:02}", dt.month)),
+            Token::Month(n) => {
+                let name = MONTHS[usize::from(dt.month) - 1];
+                out.push_str(match n { 3 => &name[..3], 4 => name, _ => &name[..1] });
+            }
+            Token::Day(1) => out.push_str(&dt.day.to_string()),
+            Token::Day(2) => out.push_str(&format!("{:02}", dt.day)),
+            Token::Day(n) => {
+                // Counted from the serial as Excel does: in the 1900 system
+                // serial 1 is a Sunday, the phantom 1900‑02‑29 included;
+                // 1904‑01‑01 was a Friday.
+                let first_weekday = match system { DateSystem::V1900 => 6, DateSystem::V1904 => 5 };
+                let weekday = WEEKDAYS[(total_ms.div_euclid(86_400_000) + first_weekday).rem_euclid(7) as usize];
+                out.push_str(if n == 3 { &weekday[..3] } else { weekday });
+            }
+            Token::Hour(n) => {
+                let hour = if twelve_hour { (dt.hour + 11) % 12 + 1 } else { dt.hour };
+                out.push_str(&format!("{hour:0n$}", n = n.min(2)));
+            }
+            Token::Minute(n) => out.push_str(&format!("{:0n$}", dt.minute, n = n.min(2))),
+            Token::Second(n) => out.push_str(&format!("{:0n$}", dt.second, n = n.min(2))),
+            Token::SubSecond(n) => {
+                out.push('.');
+                out.push_str(&format!("{:03}", dt.millisecond)[..n]);
+            }
+            Token::AmPm => out.push_str(if pm { "PM" } else { "AM" }),
+            Token::AP(upper) => out.push(match (pm, upper) {
+                (true, true) => 'P',
+                (true, false) => 'p',
+                (false, true) => 'A',
+                (false, false) => 'a',
+            }),
+            Token::Elapsed(unit, width) => {
+                let total = total_ms.abs() / match unit {
+                    Unit::Hour => 3_600_000,
+                    Unit::Minute => 60_000,
+                    Unit::Second => 1000,
+                };
+                out.push_str(&format!("{total:0width$}"));
+            }
+            _ => {}
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks `format_number` against what Excel displays, row by row.
+    fn check(cases: &[(f64, &str, &str)]) {
+        for &(value, code, expected) in cases {
+            assert_eq!(format_number(value, code, DateSystem::V1900), expected, "{value} with {code:?}");
+        }
+    }
+
+    #[test]
+    fn general_numbers() {
+        check(&[
+            (0.0, "General", "0"),
+            (1234.5, "General", "1234.5"),
+            (-1234.5, "General", "-1234.5"),
+            (1.0 / 3.0, "General", "0.333333333"),
+            (12_345_678_901.0, "General", "12345678901"),
+            (123_456_789_012.0, "General", "1.23457E+11"),
+            (0.000_000_000_12, "General", "1.2E-10"),
+            (5.0, "@", "5"),
+        ]);
+    }
+
+    #[test]
+    fn decimals_and_placeholders() {
+        check(&[
+            (1234.567, "0.00", "1234.57"),
+            (2.675, "0.00", "2.68"),
+            (0.5, "#.##", ".5"),
+            (0.5, "0.##", "0.5"),
+            (5.0, "000", "005"),
+            (1.5, "0.0?", "1.5 "),
+            (0.0, "0.00", "0.00"),
+            (0.0, "#", ""),
+            (0.1234, "0.0%", "12.3%"),
+            (5.0, "0\" kg\"", "5 kg"),
+            (5.0, "\\$0", "$5"),
+            (1234.5, "[$€-407] #,##0.00", "€ 1,234.50"),
+        ]);
+    }
+
+    #[test]
+    fn thousands_separators_and_scaling() {
+        check(&[
+            (1_234_567.891, "#,##0.00", "1,234,567.89"),
+            (999.0, "#,##0", "999"),
+            (-1_234_567.0, "#,##0", "-1,234,567"),
+            (1_234_567.0, "#,##0,", "1,235"),
+            (1_234_567.0, "0.0,,", "1.2"),
+            (12_345.0, "0,\"K\"", "12K"),
+        ]);
+    }
+
+    #[test]
+    fn positive_negative_and_zero_sections() {
+        check(&[
+            (1234.5, "#,##0.00;(#,##0.00)", "1,234.50"),
+            (-1234.5, "#,##0.00;(#,##0.00)", "(1,234.50)"),
+            (0.0, "#,##0.00;(#,##0.00)", "0.00"),
+            (-5.0, "0;-0;\"zero\"", "-5"),
+            (0.0, "0;-0;\"zero\"", "zero"),
+            (0.0, "#,##0;-#,##0;-", "-"),
+            (-5.0, "0", "-5"),
+            (-5.0, "[Red]0;[Blue]0", "5"),
+        ]);
+    }
+
+    #[test]
+    fn conditional_sections() {
+        let code = "[>=100]\"big\";[<0]\"neg\";0";
+        check(&[(150.0, code, "big"), (100.0, code, "big"), (-3.0, code, "neg"), (5.0, code, "5")]);
+        let code = "[Red][<=100]0;[Blue][>100]0.0";
+        check(&[(50.0, code, "50"), (150.0, code, "150.0")]);
+    }
+
+    #[test]
+    fn fractions() {
+        check(&[
+            (1.25, "# ?/?", "1 1/4"),
+            (0.3333, "?/?", "1/3"),
+            (2.5, "# ?/2", "2 1/2"),
+            (1.2, "# ??/??", "1  1/5 "),
+            (-1.25, "# ?/?", "-1 1/4"),
+        ]);
+    }
+
+    #[test]
+    fn exponentials() {
+        check(&[
+            (12_345.0, "0.00E+00", "1.23E+04"),
+            (0.000_12, "0.0E+0", "1.2E-4"),
+            (12_345.0, "##0.0E+0", "12.3E+3"),
+            (123.0, "0.0E-00", "1.2E02"),
+            (-12_345.0, "0.00E+00", "-1.23E+04"),
+        ]);
+    }
+
+    #[test]
+    fn dates_and_times() {
+        check(&[
+            (45_000.5, "yyyy-mm-dd hh:mm", "2023-03-15 12:00"),
+            (45_000.75, "d mmm yy h:mm AM/PM", "15 Mar 23 6:00 PM"),
+            (45_000.0, "dddd, mmmm d", "Wednesday, March 15"),
+            (1.5, "[h]:mm", "36:00"),
+            (-1.0, "yyyy-mm-dd", "-1"),
+        ]);
+    }
+
+    #[test]
+    fn text_sections() {
+        for (text, code, expected) in [
+            ("abc", "@", "abc"),
+            ("abc", "\"Name: \"@", "Name: abc"),
+            ("abc", "0;0;0;\"<\"@\">\"", "<abc>"),
+            ("abc", "0.00", "abc"),
+            ("abc", "0;0", "abc"),
+        ] {
+            assert_eq!(format_text(text, code), expected, "{text:?} with {code:?}");
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use quick_xml::{Reader, events::Event};
+use super::cell_ref::CellRange;
+use super::worksheet::Cell;
+use super::xml_utils::{attr_value, decode_escapes, push_entity};
+
+// ---------------------------------------------------------------------------
+// Table – an Excel table (ListObject) of a worksheet (xl/tables/tableN.xml)
+// ---------------------------------------------------------------------------
+
+/// A column of a [`Table`] (`<tableColumn>`).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TableColumn {
+    /// Column id, unique within the table.
+    pub id: u32,
+    /// Header text; table formulas refer to the column by it
+    /// (`Sales[Amount]`).
+    pub name: String,
+    /// Aggregate shown in the totals row (`"sum"`, `"average"`, `"count"`,
+    /// `"countNums"`, `"max"`, `"min"`, `"stdDev"`, `"var"` or `"custom"`).
+    pub totals_row_function: Option<String>,
+    /// Text shown in the totals row instead of an aggregate (`"Total"`).
+    pub totals_row_label: Option<String>,
+    /// Formula of a `"custom"` totals row function.
+    pub totals_row_formula: Option<String>,
+    /// Formula filled down the column (`[@Price]*[@Qty]`), for calculated
+    /// columns.
+    pub calculated_formula: Option<String>,
+}
+
+/// An Excel table (ListObject): a named range with a header row, typed
+/// columns and an optional totals row.
+///
+/// Tables live in their own parts, linked from the worksheet's
+/// relationships; [`Workbook::worksheet`](super::Workbook::worksheet)
+/// attaches them to the [`Worksheet`](super::Worksheet) so their rows can be
+/// read with [`Worksheet::table_rows`](super::Worksheet::table_rows).
+///
+/// # XML Structure
+/// ```xml
+/// <table id="1" name="Table1" displayName="Sales" ref="A1:C10" totalsRowCount="1">
+///   <autoFilter ref="A1:C9"/>
+///   <tableColumns count="3">
+///     <tableColumn id="1" name="Region" totalsRowLabel="Total"/>
+///     <tableColumn id="2" name="Amount" totalsRowFunction="sum"/>
+///     <tableColumn id="3" name="Tax">
+///       <calculatedColumnFormula>Sales[[#This Row],[Amount]]*0.2</calculatedColumnFormula>
+///     </tableColumn>
+///   </tableColumns>
+///   <tableStyleInfo name="TableStyleMedium2" showRowStripes="1"/>
+/// </table>
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    /// Table id, unique within the workbook.
+    pub id: u32,
+    /// Internal name (`name`).
+    pub name: String,
+    /// Name shown in Excel and used in formulas (`displayName`).
+    pub display_name: String,
+    /// The whole table, header and totals rows included.
+    pub range: CellRange,
+    /// Number of header rows at the top of `range`: 1, or 0 if the header
+    /// row is turned off.
+    pub header_row_count: u32,
+    /// Number of totals rows at the bottom of `range` (0 or 1).
+    pub totals_row_count: u32,
+    /// Columns, left to right.
+    pub columns: Vec<TableColumn>,
+    /// Name of the table style (`"TableStyleMedium2"`), if any.
+    pub style: Option<String>,
+}
+
+impl Table {
+    /// Glob matching table parts inside an `.xlsx` archive.
+    pub const GLOB: &'static str = "xl/tables/*.xml";
+
+    /// Parses a table part.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/tables/tableN.xml` part.
+    ///
+    /// # Returns
+    /// `None` if the part has no `<table>` element with a valid `ref`.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    pub fn parse(xml: &[u8]) -> Result<Option<Self>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        let config = reader.config_mut();
+        config.trim_text(false);
+        config.check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut table: Option<Self> = None;
+        // Text of the column formula being read.
+        let mut formula: Option<String> = None;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) => match e.local_name().as_ref() {
+                    b"table" => {
+                        table = attr_value(e, b"ref").and_then(|r| CellRange::parse(&r)).map(|range| Self {
+                            id: attr_value(e, b"id").and_then(|v| v.trim().parse().ok()).unwrap_or_default(),
+                            name: attr_value(e, b"name").unwrap_or_default(),
+                            display_name: attr_value(e, b"displayName").unwrap_or_default(),
+                            range,
+                            header_row_count: attr_value(e, b"headerRowCount").and_then(|v| v.trim().parse().ok()).unwrap_or(1),
+                            totals_row_count: attr_value(e, b"totalsRowCount").and_then(|v| v.trim().parse().ok()).unwrap_or(0),
+                            columns: Vec::new(),
+                            style: None,
+                        });
+                    }
+                    b"tableColumn" => {
+                        if let Some(table) = table.as_mut() {
+                            table.columns.push(TableColumn {
+                                id: attr_value(e, b"id").and_then(|v| v.trim().parse().ok()).unwrap_or_default(),
+                                name: decode_escapes(&attr_value(e, b"name").unwrap_or_default()).into_owned(),
+                                totals_row_function: attr_value(e, b"totalsRowFunction").filter(|f| f != "none"),
+                                totals_row_label: attr_value(e, b"totalsRowLabel"),
+                                ..TableColumn::default()
+                            });
+                        }
+                    }
+                    b"calculatedColumnFormula" | b"totalsRowFormula" => formula = Some(String::new()),
+                    b"tableStyleInfo" => {
+                        if let Some(table) = table.as_mut() { table.style = attr_value(e, b"name"); }
+                    }
+                    _ => {}
+                },
+                Event::Text(ref e) => {
+                    if let Some(formula) = formula.as_mut() { formula.push_str(&String::from_utf8_lossy(e)); }
+                }
+                Event::GeneralRef(ref e) => {
+                    if let Some(formula) = formula.as_mut() { push_entity(formula, e); }
+                }
+                Event::End(ref e) => {
+                    let column = table.as_mut().and_then(|t| t.columns.last_mut());
+                    match (e.local_name().as_ref(), column) {
+                        (b"calculatedColumnFormula", Some(column)) => column.calculated_formula = formula.take(),
+                        (b"totalsRowFormula", Some(column)) => column.totals_row_formula = formula.take(),
+                        _ => {}
+                    }
+                    formula = None;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(table)
+    }
+
+    /// Returns the column with the given header, matched ignoring case as
+    /// Excel does.
+    pub fn column(&self, name: &str) -> Option<&TableColumn> {
+        self.columns.iter().find(|c| c.name.to_lowercase() == name.to_lowercase())
+    }
+
+    /// Returns the header row, or `None` if it is turned off.
+    pub fn header_range(&self) -> Option<CellRange> {
+        (self.header_row_count > 0).then(|| CellRange {
+            last_row: self.range.first_row + self.header_row_count - 1,
+            ..self.range
+        })
+    }
+
+    /// Returns the data rows, between the header and totals rows, or `None`
+    /// if the table has none.
+    pub fn data_range(&self) -> Option<CellRange> {
+        let first_row = self.range.first_row + self.header_row_count;
+        let last_row = self.range.last_row.checked_sub(self.totals_row_count)?;
+        (first_row <= last_row).then_some(CellRange { first_row, last_row, ..self.range })
+    }
+
+    /// Returns the totals row, or `None` if the table has none.
+    pub fn totals_range(&self) -> Option<CellRange> {
+        (self.totals_row_count > 0).then(|| CellRange {
+            first_row: self.range.last_row + 1 - self.totals_row_count,
+            ..self.range
+        })
+    }
+}
+
+/// A data row of a [`Table`], with its cells keyed by column header.
+///
+/// Returned by [`Worksheet::table_rows`](super::Worksheet::table_rows).
+#[derive(Debug, Clone)]
+pub struct TableRow<'a> {
+    row: u32,
+    columns: &'a [TableColumn],
+    cells: Vec<Option<&'a Cell>>,
+}
+
+impl<'a> TableRow<'a> {
+    pub(crate) fn new(row: u32, columns: &'a [TableColumn], cells: Vec<Option<&'a Cell>>) -> Self {
+        Self { row, columns, cells }
+    }
+
+    /// 1‑based sheet row number.
+    pub fn row(&self) -> u32 { self.row }
+
+    /// Returns the cell of the column with the given header (matched
+    /// ignoring case); `None` if there is no such column or the cell is
+    /// empty.
+    pub fn get(&self, column: &str) -> Option<&'a Cell> {
+        let column = column.to_lowercase();
+        let index = self.columns.iter().position(|c| c.name.to_lowercase() == column)?;
+        self.cells.get(index).copied().flatten()
+    }
+
+    /// Returns the cell of the column at zero‑based position `index`.
+    pub fn cell(&self, index: usize) -> Option<&'a Cell> {
+        self.cells.get(index).copied().flatten()
+    }
+
+    /// Returns `(header, cell)` for every column, left to right.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, Option<&'a Cell>)> + '_ {
+        self.columns.iter().map(|c| c.name.as_str()).zip(self.cells.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SALES: &str = r#"<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="3" name="Table1" displayName="Sales" ref="B2:D6" totalsRowCount="1">
+  <autoFilter ref="B2:D5"/>
+  <tableColumns count="3">
+    <tableColumn id="1" name="Region" totalsRowLabel="Total"/>
+    <tableColumn id="2" name="Amount_x000A_(RUB)" totalsRowFunction="sum"/>
+    <tableColumn id="3" name="Tax" totalsRowFunction="custom">
+      <calculatedColumnFormula>Sales[[#This Row],[Amount]]*0.2</calculatedColumnFormula>
+      <totalsRowFormula>SUBTOTAL(109,Sales[Tax])&amp;""</totalsRowFormula>
+    </tableColumn>
+  </tableColumns>
+  <tableStyleInfo name="TableStyleMedium2" showRowStripes="1"/>
+</table>"#;
+
+    fn range(first_row: u32, first_col: u32, last_row: u32, last_col: u32) -> CellRange {
+        CellRange { first_row, first_col, last_row, last_col }
+    }
+
+    #[test]
+    fn parse_reads_the_table_and_its_columns() {
+        let table = Table::parse(SALES.as_bytes()).unwrap().unwrap();
+        assert_eq!((table.id, table.name.as_str(), table.display_name.as_str()), (3, "Table1", "Sales"));
+        assert_eq!(table.range, range(2, 2, 6, 4));
+        assert_eq!((table.header_row_count, table.totals_row_count), (1, 1));
+        assert_eq!(table.style.as_deref(), Some("TableStyleMedium2"));
+
+        assert_eq!(table.columns[0], TableColumn {
+            id: 1,
+            name: "Region".into(),
+            totals_row_label: Some("Total".into()),
+            ..TableColumn::default()
+        });
+        assert_eq!(table.columns[1].name, "Amount\n(RUB)");
+        assert_eq!(table.columns[1].totals_row_function.as_deref(), Some("sum"));
+        let tax = table.column("TAX").unwrap();
+        assert_eq!(tax.calculated_formula.as_deref(), Some("Sales[[#This Row],[Amount]]*0.2"));
+        assert_eq!(tax.totals_row_formula.as_deref(), Some(r#"SUBTOTAL(109,Sales[Tax])&"""#));
+        assert!(table.column("Missing").is_none());
+
+        assert_eq!(Table::parse(br#"<table name="Broken"/>"#).unwrap(), None);
+    }
+
+    #[test]
+    fn ranges_split_header_data_and_totals_rows() {
+        let table = Table::parse(SALES.as_bytes()).unwrap().unwrap();
+        assert_eq!(table.header_range(), Some(range(2, 2, 2, 4)));
+        assert_eq!(table.data_range(), Some(range(3, 2, 5, 4)));
+        assert_eq!(table.totals_range(), Some(range(6, 2, 6, 4)));
+
+        let bare = Table { header_row_count: 0, totals_row_count: 0, ..table.clone() };
+        assert_eq!((bare.header_range(), bare.totals_range()), (None, None));
+        assert_eq!(bare.data_range(), Some(table.range));
+
+        let header_only = Table { range: range(2, 2, 2, 4), totals_row_count: 0, ..table };
+        assert_eq!(header_only.data_range(), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! Helpers shared by the unit tests: in‑memory archives built with the
+//! `zip` writer, so tests don't depend on fixture files.
+
+use std::io::{Cursor, Write};
+use zip::{AesMode, CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+/// Builds a ZIP archive holding `entries` (path, content), deflated.
+pub(crate) fn zip_archive(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let entries: Vec<_> = entries.iter().map(|&(path, data)| (path, data, CompressionMethod::Deflated)).collect();
+    zip_archive_with(&entries)
+}
+
+/// Builds a ZIP archive holding `entries` (path, content, compression).
+///
+/// Paths ending with `/` are added as directory entries.
+pub(crate) fn zip_archive_with(entries: &[(&str, &[u8], CompressionMethod)]) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for &(path, data, method) in entries {
+        let options = SimpleFileOptions::default().compression_method(method);
+        if path.ends_with('/') {
+            writer.add_directory(path, options).unwrap();
+        } else {
+            writer.start_file(path, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+/// Builds a ZIP archive holding `entries` (path, content), deflated and
+/// encrypted with AES‑256 under `password`.
+pub(crate) fn encrypted_zip_archive(entries: &[(&str, &[u8])], password: &str) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for &(path, data) in entries {
+        writer.start_file(path, SimpleFileOptions::default().with_aes_encryption(AesMode::Aes256, password)).unwrap();
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+/// Builds a ZIP archive holding `entries` (path, content), stored and
+/// encrypted with ZipCrypto under `password`.
+///
+/// Written by hand: the `zip` writer only encrypts with AES.
+pub(crate) fn zipcrypto_zip_archive(entries: &[(&str, &[u8])], password: &str) -> Vec<u8> {
+    let (mut archive, mut central) = (Vec::new(), Vec::new());
+    for &(path, data) in entries {
+        let crc = crc32fast::hash(data);
+        let mut keys = ZipCryptoKeys::new(password.as_bytes());
+        // Eleven arbitrary bytes, then the CRC's high byte to check the password.
+        let mut header = [0x5a; 12];
+        header[11] = (crc >> 24) as u8;
+        let encrypted: Vec<u8> = header.iter().chain(data).map(|&b| keys.encrypt(b)).collect();
+
+        let offset = archive.len() as u32;
+        // Version 2.0, flag 0x0001 (encrypted), method 0 (stored), time 0, date 1980‑01‑01.
+        let fields = |out: &mut Vec<u8>| {
+            for value in [20u16, 1, 0, 0, 0x21] { out.extend(value.to_le_bytes()); }
+            for value in [crc, encrypted.len() as u32, data.len() as u32] { out.extend(value.to_le_bytes()); }
+            out.extend((path.len() as u16).to_le_bytes());
+            out.extend(0u16.to_le_bytes());
+        };
+
+        archive.extend(0x0403_4b50u32.to_le_bytes());
+        fields(&mut archive);
+        archive.extend(path.as_bytes());
+        archive.extend(&encrypted);
+
+        central.extend(0x0201_4b50u32.to_le_bytes());
+        central.extend(20u16.to_le_bytes());
+        fields(&mut central);
+        // Comment length, disk, internal and external attributes.
+        for value in [0u16, 0, 0] { central.extend(value.to_le_bytes()); }
+        central.extend(0u32.to_le_bytes());
+        central.extend(offset.to_le_bytes());
+        central.extend(path.as_bytes());
+    }
+
+    let (central_offset, central_size) = (archive.len() as u32, central.len() as u32);
+    archive.extend(central);
+    archive.extend(0x0605_4b50u32.to_le_bytes());
+    for value in [0u16, 0, entries.len() as u16, entries.len() as u16] { archive.extend(value.to_le_bytes()); }
+    archive.extend(central_size.to_le_bytes());
+    archive.extend(central_offset.to_le_bytes());
+    archive.extend(0u16.to_le_bytes());
+    archive
+}
+
+/// The traditional PKWARE cipher state.
+struct ZipCryptoKeys([u32; 3]);
+
+impl ZipCryptoKeys {
+    fn new(password: &[u8]) -> Self {
+        let mut keys = Self([0x1234_5678, 0x2345_6789, 0x3456_7890]);
+        password.iter().for_each(|&b| keys.update(b));
+        keys
+    }
+
+    fn update(&mut self, plain: u8) {
+        let [k0, k1, k2] = &mut self.0;
+        *k0 = crc32_update(*k0, plain);
+        *k1 = k1.wrapping_add(*k0 & 0xff).wrapping_mul(134_775_813).wrapping_add(1);
+        *k2 = crc32_update(*k2, (*k1 >> 24) as u8);
+    }
+
+    fn encrypt(&mut self, plain: u8) -> u8 {
+        let t = (self.0[2] | 2) as u16;
+        let cipher = plain ^ (t.wrapping_mul(t ^ 1) >> 8) as u8;
+        self.update(plain);
+        cipher
+    }
+}
+
+/// One byte of CRC‑32 without the pre‑ and post‑inversion.
+fn crc32_update(crc: u32, byte: u8) -> u32 {
+    (0..8).fold(crc ^ u32::from(byte), |c, _| if c & 1 == 1 { (c >> 1) ^ 0xEDB8_8320 } else { c >> 1 })
+}
@@ -0,0 +1,961 @@
+use super::{ZipFs, SharedStrings, Styles, Table, comments::Comments, relationships::Relationships};
+use super::cell_ref::{column_number, parse_a1};
+use super::datetime::DateSystem;
+use super::workbook_info::{DefinedName, RangeRef, SheetInfo, WorkbookInfo, split_areas};
+use super::worksheet::{DataValidation, ListSource, Worksheet};
+
+// ---------------------------------------------------------------------------
+// Workbook – package-level metadata of an .xlsx file
+// ---------------------------------------------------------------------------
+
+/// Rows and columns repeated on every printed page of a sheet
+/// (the sheet‑scoped `_xlnm.Print_Titles` defined name).
+///
+/// Ranges are inclusive and 1‑based: rows `(1, 2)` means rows 1–2, columns
+/// `(1, 1)` means column `A`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PrintTitles {
+    /// Repeated rows (`$1:$2`), if any.
+    pub rows: Option<(u32, u32)>,
+    /// Repeated columns (`$A:$B`), if any.
+    pub cols: Option<(u32, u32)>,
+}
+
+/// Package‑level information about a workbook, assembled from several parts
+/// of the archive.
+///
+/// The workbook itself is described by `xl/workbook.xml` and its relationship
+/// part `xl/_rels/workbook.xml.rels`; related parts (external links, ...) are
+/// located by following those relationships. Parts that were not loaded into
+/// the [`ZipFs`] (e.g. filtered out) are treated as absent.
+///
+/// # Example
+/// ```no_run
+/// # use excel_parser::{ZipFs, FilterSet, Workbook};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let filter = FilterSet::new()
+///     .add_exact(Workbook::PATH)?
+///     .add_exact(Workbook::RELS_PATH)?
+///     .add_glob(Workbook::EXTERNAL_LINKS_GLOB)?;
+/// let fs = ZipFs::new(std::fs::File::open("report.xlsx")?, Some(filter), None)?;
+/// let workbook = Workbook::load(&fs)?;
+///
+/// for link in workbook.external_links() {
+///     println!("depends on {}", link);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Workbook {
+    /// Sheet list and defined names from `xl/workbook.xml`.
+    info: WorkbookInfo,
+    /// Targets (paths or URLs) of external workbook references, in the order
+    /// the workbook declares them.
+    external_links: Vec<String>,
+    /// Archive path of each sheet's part, parallel to `info.sheets`; `None`
+    /// if it couldn't be resolved from the workbook relationships.
+    sheet_parts: Vec<Option<String>>,
+    /// Relationships of each sheet's part, parallel to `info.sheets`; empty
+    /// if they were not loaded.
+    sheet_rels: Vec<Relationships>,
+    /// Archive path of the shared strings part, from the workbook relationships.
+    shared_strings_part: Option<String>,
+    /// Archive path of the styles part, from the workbook relationships.
+    styles_part: Option<String>,
+    /// Cell notes per sheet, parallel to `info.sheets`.
+    comments: Vec<Comments>,
+    /// Tables per sheet, parallel to `info.sheets`.
+    tables: Vec<Vec<Table>>,
+}
+
+impl Workbook {
+    /// Path of the workbook part.
+    pub const PATH: &'static str = "xl/workbook.xml";
+    /// Path of the workbook's relationship part.
+    pub const RELS_PATH: &'static str = "xl/_rels/workbook.xml.rels";
+    /// Glob matching the relationship parts of external links, which hold the
+    /// actual location of each referenced workbook.
+    pub const EXTERNAL_LINKS_GLOB: &'static str = "xl/externalLinks/_rels/*.rels";
+    /// Glob matching worksheet relationship parts, which link each sheet to
+    /// its comments (and other per‑sheet parts).
+    pub const WORKSHEET_RELS_GLOB: &'static str = "xl/worksheets/_rels/*.rels";
+    /// Glob matching comments parts.
+    pub const COMMENTS_GLOB: &'static str = "xl/comments*.xml";
+    /// Glob matching legacy VML drawings, which hold how notes are shown.
+    pub const VML_DRAWINGS_GLOB: &'static str = "xl/drawings/vmlDrawing*.vml";
+
+    /// Assembles workbook information from the parts loaded in `fs`.
+    ///
+    /// # Arguments
+    /// * `fs` – the loaded archive. Only parts matching [`PATH`](Self::PATH),
+    ///   [`RELS_PATH`](Self::RELS_PATH) and the related globs are consulted.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` if one of the consulted parts is malformed.
+    pub fn load(fs: &ZipFs) -> Result<Self, quick_xml::Error> {
+        let rels = match fs.get_file(Self::RELS_PATH) {
+            Some(xml) => Relationships::parse(xml)?,
+            None => Relationships::default(),
+        };
+
+        let info = match fs.get_file(Self::PATH) {
+            Some(xml) => WorkbookInfo::parse(xml)?,
+            None => WorkbookInfo::default(),
+        };
+
+        let sheet_parts: Vec<_> = info.sheets.iter()
+            .map(|sheet| {
+                let rel = rels.get(sheet.rel_id.as_deref()?)?;
+                Some(Relationships::resolve_target(Self::PATH, &rel.target))
+            })
+            .collect();
+
+        let part_of = |kind| rels.by_type(kind).next().map(|rel| rel.resolve(Self::PATH));
+        let shared_strings_part = part_of("sharedStrings");
+        let styles_part = part_of("styles");
+
+        let external_links = Self::load_external_links(fs, &rels)?;
+        let sheet_rels = Self::load_sheet_rels(fs, &sheet_parts)?;
+        let comments = Self::load_comments(fs, &sheet_parts, &sheet_rels)?;
+        let tables = Self::load_tables(fs, &sheet_parts, &sheet_rels)?;
+        Ok(Self { info, external_links, sheet_parts, sheet_rels, shared_strings_part, styles_part, comments, tables })
+    }
+
+    // -------------------------------------------------------------------------
+    // Public API
+    // -------------------------------------------------------------------------
+
+    /// Returns the sheet names in tab order.
+    ///
+    /// Empty if `xl/workbook.xml` was not loaded.
+    pub fn sheet_names(&self) -> Vec<&str> {
+        self.info.sheets.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// Returns the sheets in tab order, with their ids, relationship ids and
+    /// visibility.
+    ///
+    /// Hidden sheets are included; filter on [`SheetInfo::is_visible`] to
+    /// get the tabs a user sees. Empty if `xl/workbook.xml` was not loaded.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::Workbook;
+    /// # fn demo(workbook: &Workbook) {
+    /// for (position, sheet) in workbook.sheets().iter().enumerate() {
+    ///     println!("{position}: {} (id {:?}, {:?})", sheet.name, sheet.sheet_id, sheet.state);
+    /// }
+    /// # }
+    /// ```
+    pub fn sheets(&self) -> &[SheetInfo] {
+        &self.info.sheets
+    }
+
+    /// Returns the sheet named `sheet`, or `None` if there is none.
+    ///
+    /// Names are matched exactly; Excel itself keeps them unique ignoring
+    /// case, so a case‑insensitive lookup can be done over [`sheets()`](Self::sheets).
+    pub fn sheet(&self, sheet: &str) -> Option<&SheetInfo> {
+        self.info.sheets.get(self.sheet_index(sheet)?)
+    }
+
+    /// Parses the cell data of the sheet named `sheet`.
+    ///
+    /// The part is located through [`sheet_part()`](Self::sheet_part), so
+    /// this works whatever the part is called inside the archive, and must
+    /// have been loaded into `fs` (see [`Worksheet::GLOB`]). The sheet's
+    /// notes are attached, see [`Worksheet::comment`].
+    ///
+    /// # Arguments
+    /// * `fs` – the archive the workbook was loaded from.
+    /// * `sheet` – the sheet name as shown on its tab.
+    ///
+    /// # Returns
+    /// `None` if there is no such sheet, or its part can't be resolved or
+    /// wasn't loaded.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` if the sheet part is malformed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, Workbook};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let fs = ZipFs::new(std::fs::File::open("schedule.xlsx")?, None, None)?;
+    /// let workbook = Workbook::load(&fs)?;
+    /// if let Some(sheet) = workbook.worksheet(&fs, "Расписание")? {
+    ///     println!("{} rows", sheet.rows().len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn worksheet(&self, fs: &ZipFs, sheet: &str) -> Result<Option<Worksheet>, quick_xml::Error> {
+        let Some(xml) = self.sheet_part(sheet).and_then(|part| fs.get_file(part)) else { return Ok(None) };
+        let mut worksheet = Worksheet::parse(xml)?;
+        if let Some(notes) = self.comments(sheet) { worksheet = worksheet.with_comments(notes.clone()); }
+        if let Some(tables) = self.tables(sheet) { worksheet = worksheet.with_tables(tables.to_vec()); }
+        Ok(Some(worksheet))
+    }
+
+    /// Returns the archive path of a sheet's part (e.g.,
+    /// `"xl/worksheets/sheet1.xml"`), ready for [`ZipFs::get_file`].
+    ///
+    /// The path is resolved through the workbook relationships, so it is
+    /// correct even when sheet parts are not named after their tab position.
+    ///
+    /// # Arguments
+    /// * `sheet` – the sheet name as shown on its tab.
+    ///
+    /// # Returns
+    /// `None` if the sheet doesn't exist or [`RELS_PATH`](Self::RELS_PATH)
+    /// was not loaded.
+    pub fn sheet_part(&self, sheet: &str) -> Option<&str> {
+        self.sheet_parts.get(self.sheet_index(sheet)?)?.as_deref()
+    }
+
+    /// Returns the archive path of the shared strings part, as the workbook
+    /// relationships declare it.
+    ///
+    /// Excel names it `xl/sharedStrings.xml`, but other writers may not, so
+    /// look it up here rather than hard‑coding the path. `None` if the
+    /// workbook has no shared strings (all text is inline) or
+    /// [`RELS_PATH`](Self::RELS_PATH) was not loaded.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{SharedStrings, Workbook, ZipFs};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let fs = ZipFs::new(std::fs::File::open("report.xlsx")?, None, None)?;
+    /// let workbook = Workbook::load(&fs)?;
+    /// let shared: Option<SharedStrings> = workbook.shared_strings_part()
+    ///     .and_then(|part| fs.get_file(part))
+    ///     .map(SharedStrings::load)
+    ///     .transpose()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shared_strings_part(&self) -> Option<&str> {
+        self.shared_strings_part.as_deref()
+    }
+
+    /// Returns the archive path of the styles part, as the workbook
+    /// relationships declare it (`xl/styles.xml` in Excel files).
+    ///
+    /// `None` if the workbook has no styles part or
+    /// [`RELS_PATH`](Self::RELS_PATH) was not loaded.
+    pub fn styles_part(&self) -> Option<&str> {
+        self.styles_part.as_deref()
+    }
+
+    /// Returns the date system of the workbook's serial numbers: 1904 if
+    /// `<workbookPr date1904="1"/>` is set, else 1900 (also when
+    /// `xl/workbook.xml` was not loaded).
+    ///
+    /// Dates stored as numbers must be converted in this system; the same
+    /// serial is four years and a day apart between the two.
+    pub fn date_system(&self) -> DateSystem {
+        self.info.date_system
+    }
+
+    /// Parses the workbook's styles part, set to the workbook's
+    /// [date system](Self::date_system) for date conversion.
+    ///
+    /// The part is located through [`styles_part()`](Self::styles_part) and
+    /// must be loaded in `fs`.
+    ///
+    /// # Returns
+    /// Empty styles, in which every cell has the `General` format, if the
+    /// workbook has no styles part or it was not loaded.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` if the styles part is malformed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{SharedStrings, Workbook, ZipFs};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let fs = ZipFs::new(std::fs::File::open("report.xlsx")?, None, None)?;
+    /// # let shared = SharedStrings::load(fs.get_file("xl/sharedStrings.xml").unwrap())?;
+    /// let workbook = Workbook::load(&fs)?;
+    /// let styles = workbook.styles(&fs)?;
+    /// if let Some(sheet) = workbook.worksheet(&fs, "Data")? {
+    ///     for row in sheet.rows() {
+    ///         let line: Vec<String> = row.cells().iter().map(|c| c.formatted_value(&shared, &styles)).collect();
+    ///         println!("{}", line.join(";"));
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn styles(&self, fs: &ZipFs) -> Result<Styles, quick_xml::Error> {
+        let styles = match self.styles_part().and_then(|part| fs.get_file(part)) {
+            Some(xml) => Styles::parse(xml)?,
+            None => Styles::default(),
+        };
+        Ok(styles.with_date_system(self.date_system()))
+    }
+
+    /// Returns the relationships of a sheet's part: its comments, drawings,
+    /// tables, hyperlink targets and so on.
+    ///
+    /// Requires [`WORKSHEET_RELS_GLOB`](Self::WORKSHEET_RELS_GLOB) to be
+    /// loaded; a sheet whose relationships weren't loaded (or that has none)
+    /// returns an empty set. Resolve entries with
+    /// [`Relationship::resolve`](crate::excel_parser::relationships::Relationship::resolve)
+    /// against [`sheet_part()`](Self::sheet_part).
+    ///
+    /// # Returns
+    /// `None` if there is no such sheet.
+    pub fn sheet_rels(&self, sheet: &str) -> Option<&Relationships> {
+        self.sheet_rels.get(self.sheet_index(sheet)?)
+    }
+
+    /// Resolves an `r:id` used inside a sheet's part (by a hyperlink, a
+    /// drawing, a table part, ...) to where it points.
+    ///
+    /// # Arguments
+    /// * `sheet` – the sheet name as shown on its tab.
+    /// * `id` – the relationship id (`"rId1"`).
+    ///
+    /// # Returns
+    /// The archive path of the target part (`"xl/drawings/drawing1.xml"`), or
+    /// the target as written for external targets such as hyperlink URLs.
+    /// `None` if the sheet or id is unknown or the relationships weren't
+    /// loaded.
+    pub fn sheet_rel_target(&self, sheet: &str, id: &str) -> Option<String> {
+        let index = self.sheet_index(sheet)?;
+        self.sheet_rels[index].target_of(self.sheet_parts[index].as_deref()?, id)
+    }
+
+    /// Returns the archive paths of the parts of one kind related to a sheet,
+    /// in declaration order.
+    ///
+    /// # Arguments
+    /// * `sheet` – the sheet name as shown on its tab.
+    /// * `kind` – last segment of the relationship type (`"drawing"`,
+    ///   `"table"`, `"comments"`, `"vmlDrawing"`, `"pivotTable"`).
+    ///
+    /// # Returns
+    /// The resolved paths; empty if there are none or the sheet's
+    /// relationships weren't loaded.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::Workbook;
+    /// # fn demo(workbook: &Workbook) {
+    /// for part in workbook.sheet_related_parts("Data", "drawing") {
+    ///     println!("drawing: {part}");
+    /// }
+    /// # }
+    /// ```
+    pub fn sheet_related_parts(&self, sheet: &str, kind: &str) -> Vec<String> {
+        let Some(index) = self.sheet_index(sheet) else { return Vec::new() };
+        let Some(part) = self.sheet_parts[index].as_deref() else { return Vec::new() };
+        self.sheet_rels[index].by_type(kind)
+            .filter(|rel| !rel.external)
+            .map(|rel| rel.resolve(part))
+            .collect()
+    }
+
+    /// Returns the targets of the workbook's external links.
+    ///
+    /// Each entry is the location of another workbook this one references in
+    /// formulas, exactly as stored (typically a relative path or a `file:///`
+    /// URL). Useful for dependency analysis across a set of files.
+    ///
+    /// # Returns
+    /// The link targets in declaration order; empty if there are none or the
+    /// external link parts were not loaded.
+    pub fn external_links(&self) -> Vec<String> {
+        self.external_links.clone()
+    }
+
+    /// Returns the defined names of the workbook, in declaration order,
+    /// including built‑in (`_xlnm.`) and hidden ones.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::Workbook;
+    /// # fn demo(workbook: &Workbook) {
+    /// for name in workbook.defined_names().iter().filter(|n| !n.is_builtin()) {
+    ///     let scope = name.local_sheet_id.map_or("workbook", |i| &workbook.sheets()[i].name);
+    ///     println!("{} ({scope}) = {}", name.name, name.formula);
+    /// }
+    /// # }
+    /// ```
+    pub fn defined_names(&self) -> &[DefinedName] {
+        &self.info.defined_names
+    }
+
+    /// Looks up a defined name the way a formula on `sheet` would see it.
+    ///
+    /// Names are matched ignoring case, as Excel does. A name scoped to
+    /// `sheet` shadows a workbook‑scoped name of the same spelling.
+    ///
+    /// # Arguments
+    /// * `name` – the defined name (`"Totals"`, `"_xlnm.Print_Area"`).
+    /// * `sheet` – the sheet whose local names are searched first, or `None`
+    ///   to look at workbook‑scoped names only.
+    ///
+    /// # Returns
+    /// `None` if no name with that spelling is visible from `sheet`.
+    pub fn defined_name(&self, name: &str, sheet: Option<&str>) -> Option<&DefinedName> {
+        let local = sheet.and_then(|s| self.sheet_index(s));
+        let mut matches = self.info.defined_names.iter().filter(|n| n.name.eq_ignore_ascii_case(name));
+
+        local.and_then(|id| matches.clone().find(|n| n.local_sheet_id == Some(id)))
+            .or_else(|| matches.find(|n| n.local_sheet_id.is_none()))
+    }
+
+    /// Resolves a defined name to the sheet ranges it refers to, ready for
+    /// [`worksheet()`](Self::worksheet) and [`Worksheet::range`].
+    ///
+    /// The name is looked up as by [`defined_name()`](Self::defined_name).
+    /// References that don't name a sheet are taken to be on the sheet the
+    /// name is scoped to, or else on `sheet`.
+    ///
+    /// # Returns
+    /// `None` if the name doesn't exist, isn't a plain reference (see
+    /// [`DefinedName::ranges`]) or has a reference whose sheet can't be
+    /// determined.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{CellValue, SharedStrings, Workbook, ZipFs};
+    /// # fn demo(fs: &ZipFs, workbook: &Workbook, shared: &SharedStrings) -> Result<(), quick_xml::Error> {
+    /// for area in workbook.resolve_name("Totals", None).unwrap_or_default() {
+    ///     let sheet = area.sheet.as_deref().unwrap_or_default();
+    ///     if let Some(sheet) = workbook.worksheet(fs, sheet)? {
+    ///         let sum: f64 = sheet.range(&area.range)
+    ///             .filter_map(|c| match c.value(shared) { CellValue::Number(n) => Some(n), _ => None })
+    ///             .sum();
+    ///         println!("{area}: {sum}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolve_name(&self, name: &str, sheet: Option<&str>) -> Option<Vec<RangeRef>> {
+        let defined = self.defined_name(name, sheet)?;
+        let default_sheet = match defined.local_sheet_id {
+            Some(id) => Some(self.info.sheets.get(id)?.name.as_str()),
+            None => sheet,
+        };
+
+        defined.ranges()?.into_iter()
+            .map(|mut r| {
+                if r.sheet.is_none() { r.sheet = Some(default_sheet?.to_owned()); }
+                Some(r)
+            })
+            .collect()
+    }
+
+    /// Returns the print titles configured for a sheet.
+    ///
+    /// Print titles are stored as a `_xlnm.Print_Titles` defined name scoped to
+    /// the sheet, e.g. `'Sheet 1'!$1:$2,'Sheet 1'!$A:$A`. Either part may be
+    /// absent.
+    ///
+    /// # Arguments
+    /// * `sheet` – the sheet name as shown on its tab.
+    ///
+    /// # Returns
+    /// `Some(PrintTitles)` if the sheet exists and has print titles, `None`
+    /// otherwise (including when `xl/workbook.xml` was not loaded).
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{Workbook, PrintTitles};
+    /// # fn demo(workbook: &Workbook) {
+    /// if let Some(PrintTitles { rows: Some((first, last)), .. }) = workbook.print_titles("Data") {
+    ///     println!("rows {}..={} repeat on every page", first, last);
+    /// }
+    /// # }
+    /// ```
+    pub fn print_titles(&self, sheet: &str) -> Option<PrintTitles> {
+        let sheet_id = self.sheet_index(sheet)?;
+        let name = self.info.defined_names.iter()
+            .find(|n| n.local_sheet_id == Some(sheet_id) && n.name == "_xlnm.Print_Titles")?;
+
+        let mut titles = PrintTitles::default();
+        for area in split_areas(&name.formula) {
+            let range = area.rsplit_once('!').map_or(area, |(_, r)| r);
+            let (first, last) = range.split_once(':').unwrap_or((range, range));
+            let (first, last) = (first.trim_matches('$'), last.trim_matches('$'));
+
+            if let (Ok(a), Ok(b)) = (first.parse(), last.parse()) {
+                titles.rows = Some((a, b));
+            } else if let (Some(a), Some(b)) = (column_number(first), column_number(last)) {
+                titles.cols = Some((a, b));
+            }
+        }
+
+        (titles != PrintTitles::default()).then_some(titles)
+    }
+
+    /// Returns the text of the note (comment) attached to a cell.
+    ///
+    /// Notes are found through the relationships workbook → worksheet →
+    /// comments, so [`RELS_PATH`](Self::RELS_PATH),
+    /// [`WORKSHEET_RELS_GLOB`](Self::WORKSHEET_RELS_GLOB) and
+    /// [`COMMENTS_GLOB`](Self::COMMENTS_GLOB) must have been loaded. Use
+    /// [`Comments::parse`] directly to read a comments part on its own.
+    ///
+    /// # Arguments
+    /// * `sheet` – the sheet name as shown on its tab.
+    /// * `a1` – cell reference such as `"B2"` (`$` markers are ignored).
+    ///
+    /// # Returns
+    /// The note text with formatting runs joined, or `None` if the sheet or
+    /// cell doesn't exist or the cell has no note.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::Workbook;
+    /// # fn demo(workbook: &Workbook) {
+    /// if let Some(note) = workbook.cell_note("Data", "B2") {
+    ///     println!("B2: {}", note);
+    /// }
+    /// # }
+    /// ```
+    pub fn cell_note(&self, sheet: &str, a1: &str) -> Option<&str> {
+        let (row, col) = parse_a1(a1)?;
+        self.comments.get(self.sheet_index(sheet)?)?.get(row, col)
+    }
+
+    /// Returns the notes of a sheet, with their authors and, if
+    /// [`VML_DRAWINGS_GLOB`](Self::VML_DRAWINGS_GLOB) was loaded, their
+    /// visibility and position.
+    ///
+    /// Loaded like [`cell_note()`](Self::cell_note); empty if the sheet has
+    /// no notes or its parts were not loaded. `None` if there is no sheet
+    /// named `sheet`.
+    pub fn comments(&self, sheet: &str) -> Option<&Comments> {
+        self.comments.get(self.sheet_index(sheet)?)
+    }
+
+    /// Returns the options of a dropdown list rule, as Excel would offer
+    /// them.
+    ///
+    /// Options typed into the rule are returned as they are. Options taken
+    /// from a range, directly or through a defined name, are read from the
+    /// cells of that range, in row order; empty cells are left out.
+    ///
+    /// # Arguments
+    /// * `fs` – the loaded archive, with the parts needed by
+    ///   [`worksheet()`](Self::worksheet) for the source sheets.
+    /// * `sheet` – the sheet the rule is on, for references without a sheet.
+    /// * `rule` – a rule from [`Worksheet::data_validations`].
+    /// * `shared` – the workbook's shared strings table.
+    ///
+    /// # Returns
+    /// `None` if the rule is not a list, or its source is a formula other
+    /// than a defined name (`INDIRECT(...)`) or a sheet that can't be read.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` if a source sheet is malformed.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, Workbook, Worksheet, ZipFs};
+    /// # fn demo(fs: &ZipFs, workbook: &Workbook, shared: &SharedStrings) -> Result<(), quick_xml::Error> {
+    /// let xml = fs.get_file(workbook.sheet_part("Form").unwrap()).unwrap();
+    /// for rule in Worksheet::data_validations(xml)? {
+    ///     if let Some(options) = workbook.list_options(fs, "Form", &rule, shared)? {
+    ///         println!("{:?}: {}", rule.ranges, options.join(", "));
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_options(
+        &self,
+        fs: &ZipFs,
+        sheet: &str,
+        rule: &DataValidation,
+        shared: &SharedStrings,
+    ) -> Result<Option<Vec<String>>, quick_xml::Error> {
+        let ranges = match rule.list_source() {
+            Some(ListSource::Items(items)) => return Ok(Some(items)),
+            Some(ListSource::Range(range)) => vec![range],
+            Some(ListSource::Formula(formula)) => match self.resolve_name(&formula, Some(sheet)) {
+                Some(ranges) => ranges,
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+
+        let mut options = Vec::new();
+        for area in ranges {
+            let Some(source) = self.worksheet(fs, area.sheet.as_deref().unwrap_or(sheet))? else { return Ok(None) };
+            options.extend(source.range(&area.range)
+                .filter_map(|cell| cell.text(shared))
+                .filter(|text| !text.is_empty())
+                .map(str::to_owned));
+        }
+
+        Ok(Some(options))
+    }
+
+    /// Returns the tables (ListObjects) of a sheet, in the order its
+    /// relationships list them.
+    ///
+    /// Tables are found through the sheet's relationships, so
+    /// [`WORKSHEET_RELS_GLOB`](Self::WORKSHEET_RELS_GLOB) and
+    /// [`Table::GLOB`] must have been loaded. `None` if there is no sheet
+    /// named `sheet`.
+    pub fn tables(&self, sheet: &str) -> Option<&[Table]> {
+        self.tables.get(self.sheet_index(sheet)?).map(Vec::as_slice)
+    }
+
+    /// Finds a table anywhere in the workbook by its name as shown in Excel
+    /// (matched ignoring case, like formulas such as `Sales[Amount]` do).
+    ///
+    /// # Returns
+    /// The name of the sheet holding the table and the table, or `None`.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, Workbook, ZipFs};
+    /// # fn demo(fs: &ZipFs, workbook: &Workbook, shared: &SharedStrings) -> Result<(), quick_xml::Error> {
+    /// if let Some((sheet, sales)) = workbook.table("Sales")
+    ///     && let Some(sheet) = workbook.worksheet(fs, sheet)?
+    /// {
+    ///     println!("{} rows", sheet.table_rows(sales).count());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn table(&self, name: &str) -> Option<(&str, &Table)> {
+        let name = name.to_lowercase();
+        self.info.sheets.iter().zip(&self.tables)
+            .flat_map(|(sheet, tables)| tables.iter().map(move |t| (sheet.name.as_str(), t)))
+            .find(|(_, t)| t.display_name.to_lowercase() == name)
+    }
+
+    // -------------------------------------------------------------------------
+    // Internal helpers
+    // -------------------------------------------------------------------------
+
+    /// Zero‑based tab position of the sheet named `sheet`.
+    fn sheet_index(&self, sheet: &str) -> Option<usize> {
+        self.info.sheets.iter().position(|s| s.name == sheet)
+    }
+
+    /// Loads the relationships of every sheet part.
+    ///
+    /// Sheets whose parts or relationship parts were not loaded get an empty set.
+    fn load_sheet_rels(fs: &ZipFs, sheet_parts: &[Option<String>]) -> Result<Vec<Relationships>, quick_xml::Error> {
+        let mut all = Vec::with_capacity(sheet_parts.len());
+        for part in sheet_parts {
+            let rels = match part.as_deref().and_then(|part| fs.get_file(&Relationships::rels_path(part))) {
+                Some(xml) => Relationships::parse(xml)?,
+                None => Relationships::default(),
+            };
+            all.push(rels);
+        }
+
+        Ok(all)
+    }
+
+    /// Loads the comments part of every sheet, following the `comments`
+    /// relationship of each sheet part, and applies the sheet's VML
+    /// drawings to it.
+    ///
+    /// Sheets whose parts or relationships were not loaded get an empty table.
+    fn load_comments(
+        fs: &ZipFs,
+        sheet_parts: &[Option<String>],
+        sheet_rels: &[Relationships],
+    ) -> Result<Vec<Comments>, quick_xml::Error> {
+        let mut comments = Vec::with_capacity(sheet_parts.len());
+        for (part, rels) in sheet_parts.iter().zip(sheet_rels) {
+            let mut notes = Comments::default();
+            if let Some(part) = part
+                && let Some(rel) = rels.by_type("comments").next()
+                && let Some(xml) = fs.get_file(&rel.resolve(part))
+            {
+                notes = Comments::parse(xml)?;
+                for rel in rels.by_type("vmlDrawing") {
+                    if let Some(vml) = fs.get_file(&rel.resolve(part)) { notes.apply_vml(vml)?; }
+                }
+            }
+            comments.push(notes);
+        }
+
+        Ok(comments)
+    }
+
+    /// Reads the table parts linked from each sheet's relationships.
+    fn load_tables(
+        fs: &ZipFs,
+        sheet_parts: &[Option<String>],
+        sheet_rels: &[Relationships],
+    ) -> Result<Vec<Vec<Table>>, quick_xml::Error> {
+        let mut tables = Vec::with_capacity(sheet_parts.len());
+        for (part, rels) in sheet_parts.iter().zip(sheet_rels) {
+            let mut sheet_tables = Vec::new();
+            if let Some(part) = part {
+                for rel in rels.by_type("table") {
+                    if let Some(xml) = fs.get_file(&rel.resolve(part)) { sheet_tables.extend(Table::parse(xml)?); }
+                }
+            }
+            tables.push(sheet_tables);
+        }
+
+        Ok(tables)
+    }
+
+    /// Follows `externalLink` relationships of the workbook to each link part's
+    /// own `.rels` and collects its external targets.
+    ///
+    /// If the workbook relationships are unavailable, all loaded external link
+    /// `.rels` parts are used in path order instead.
+    fn load_external_links(fs: &ZipFs, rels: &Relationships) -> Result<Vec<String>, quick_xml::Error> {
+        let mut link_rels: Vec<String> = rels.by_type("externalLink")
+            .map(|r| Relationships::rels_path(&Relationships::resolve_target(Self::PATH, &r.target)))
+            .collect();
+
+        if link_rels.is_empty() {
+            link_rels = fs.list_files("xl/externalLinks/_rels").into_iter()
+                .filter(|p| p.ends_with(".rels"))
+                .map(str::to_owned)
+                .collect();
+            link_rels.sort();
+        }
+
+        let mut links = Vec::with_capacity(link_rels.len());
+        for path in &link_rels {
+            let Some(xml) = fs.get_file(path) else { continue };
+            let part_rels = Relationships::parse(xml)?;
+            links.extend(part_rels.iter().filter(|r| r.external).map(|r| r.target.clone()));
+        }
+
+        Ok(links)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::excel_parser::CellValue;
+    use crate::excel_parser::test_util::zip_archive;
+
+    const REL: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+    /// `workbook.xml` with the sheets `Data` and `Notes`, followed by
+    /// `definitions` (extra children of `<workbook>`).
+    fn workbook_xml(definitions: &str) -> String {
+        format!(r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="{REL}">
+  <sheets>
+    <sheet name="Data" sheetId="1" r:id="rId1"/>
+    <sheet name="Notes" sheetId="2" r:id="rId2"/>
+  </sheets>{definitions}
+</workbook>"#)
+    }
+
+    /// A relationships part; targets with a scheme are external.
+    fn rels_xml(rels: &[(&str, &str, &str)]) -> String {
+        let rels: String = rels.iter().map(|(id, kind, target)| {
+            let mode = if target.contains("://") { r#" TargetMode="External""# } else { "" };
+            format!(r#"<Relationship Id="{id}" Type="{REL}/{kind}" Target="{target}"{mode}/>"#)
+        }).collect();
+        format!(r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{rels}</Relationships>"#)
+    }
+
+    /// Loads a workbook from an archive holding `workbook.xml`, its
+    /// relationships (the two sheets plus `rels`) and `parts`.
+    fn load(definitions: &str, rels: &[(&str, &str, &str)], parts: &[(&str, String)]) -> (ZipFs, Workbook) {
+        let mut all_rels = vec![("rId1", "worksheet", "worksheets/sheet1.xml"), ("rId2", "worksheet", "worksheets/sheet2.xml")];
+        all_rels.extend_from_slice(rels);
+        let mut entries = vec![
+            (Workbook::PATH.to_owned(), workbook_xml(definitions)),
+            (Workbook::RELS_PATH.to_owned(), rels_xml(&all_rels)),
+        ];
+        entries.extend(parts.iter().map(|(path, xml)| (path.to_string(), xml.clone())));
+        let entries: Vec<_> = entries.iter().map(|(p, x)| (p.as_str(), x.as_bytes())).collect();
+
+        let fs = ZipFs::from_bytes(&zip_archive(&entries), None, None).unwrap();
+        let workbook = Workbook::load(&fs).unwrap();
+        (fs, workbook)
+    }
+
+    #[test]
+    fn external_links_returns_link_targets() {
+        let (_, workbook) = load(
+            "",
+            &[("rId3", "externalLink", "externalLinks/externalLink1.xml")],
+            &[
+                ("xl/externalLinks/externalLink1.xml", r#"<externalLink><externalBook r:id="rId1"/></externalLink>"#.to_owned()),
+                ("xl/externalLinks/_rels/externalLink1.xml.rels", rels_xml(&[("rId1", "externalLinkPath", "file:///C:/data/Prices.xlsx")])),
+            ],
+        );
+
+        assert_eq!(workbook.external_links(), ["file:///C:/data/Prices.xlsx"]);
+        assert_eq!(workbook.sheet_names(), ["Data", "Notes"]);
+    }
+
+    #[test]
+    fn external_links_is_empty_without_links() {
+        let (_, workbook) = load("", &[], &[]);
+        assert!(workbook.external_links().is_empty());
+    }
+
+    #[test]
+    fn date_system_follows_date1904() {
+        let styles_xml = r#"<styleSheet>
+  <numFmts><numFmt numFmtId="164" formatCode="yyyy-mm-dd"/></numFmts>
+  <cellXfs><xf numFmtId="0"/><xf numFmtId="164"/></cellXfs>
+</styleSheet>"#;
+        let styles = [("rId3", "styles", "styles.xml")];
+        let parts = [("xl/styles.xml", styles_xml.to_owned())];
+
+        for (definitions, system, shown) in [
+            ("", DateSystem::V1900, "2024-03-15"),
+            (r#"<workbookPr date1904="0"/>"#, DateSystem::V1900, "2024-03-15"),
+            (r#"<workbookPr date1904="1"/>"#, DateSystem::V1904, "2028-03-16"),
+            (r#"<workbookPr date1904="true"/>"#, DateSystem::V1904, "2028-03-16"),
+        ] {
+            let (fs, workbook) = load(definitions, &styles, &parts);
+            assert_eq!(workbook.date_system(), system, "{definitions}");
+            let styles = workbook.styles(&fs).unwrap();
+            assert_eq!(styles.format_value(&CellValue::Number(45_366.0), 1), shown, "{definitions}");
+        }
+    }
+
+    #[test]
+    fn print_titles_reads_sheet_scoped_rows_and_columns() {
+        let (_, workbook) = load(r#"
+  <definedNames>
+    <definedName name="_xlnm.Print_Titles" localSheetId="0">Data!$A:$B,Data!$1:$2</definedName>
+    <definedName name="_xlnm.Print_Titles" localSheetId="1">'Notes'!$3:$3</definedName>
+    <definedName name="Prices">Data!$C$1:$C$10</definedName>
+  </definedNames>"#, &[], &[]);
+
+        assert_eq!(workbook.print_titles("Data"), Some(PrintTitles { rows: Some((1, 2)), cols: Some((1, 2)) }));
+        assert_eq!(workbook.print_titles("Notes"), Some(PrintTitles { rows: Some((3, 3)), cols: None }));
+        assert_eq!(workbook.print_titles("Missing"), None);
+    }
+
+    #[test]
+    fn print_titles_is_none_without_the_name() {
+        let (_, workbook) = load(r#"<definedNames><definedName name="Prices">Data!$C$1:$C$10</definedName></definedNames>"#, &[], &[]);
+        assert_eq!(workbook.print_titles("Data"), None);
+    }
+
+    #[test]
+    fn cell_note_returns_the_note_of_a_cell() {
+        let comments = r#"<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+  <authors><author>Reviewer</author></authors>
+  <commentList>
+    <comment ref="B2" authorId="0"><text><r><rPr><b/></rPr><t>Reviewer:</t></r><r><t xml:space="preserve"> check this total</t></r></text></comment>
+  </commentList>
+</comments>"#;
+        let (_, workbook) = load("", &[], &[
+            ("xl/worksheets/_rels/sheet1.xml.rels", rels_xml(&[("rId1", "comments", "../comments1.xml")])),
+            ("xl/comments1.xml", comments.to_owned()),
+        ]);
+
+        assert_eq!(workbook.cell_note("Data", "B2"), Some("Reviewer: check this total"));
+        assert_eq!(workbook.cell_note("Data", "$B$2"), Some("Reviewer: check this total"));
+        assert_eq!(workbook.cell_note("Data", "A1"), None);
+        assert_eq!(workbook.cell_note("Notes", "B2"), None);
+        assert_eq!(workbook.cell_note("Missing", "B2"), None);
+    }
+
+    #[test]
+    fn worksheet_attaches_notes_with_their_authors_and_vml() {
+        let comments = r#"<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+  <authors><author>Reviewer</author></authors>
+  <commentList><comment ref="C3" authorId="0"><text><t>Source: 2023 report</t></text></comment></commentList>
+</comments>"#;
+        let vml = r#"<xml xmlns:x="urn:schemas-microsoft-com:office:excel"><shape><x:ClientData ObjectType="Note"><x:Row>2</x:Row><x:Column>2</x:Column><x:Visible/></x:ClientData></shape></xml>"#;
+        let sheet = r#"<worksheet><sheetData><row r="1"><c r="A1"><v>1</v></c></row></sheetData></worksheet>"#;
+        let (fs, workbook) = load("", &[], &[
+            ("xl/worksheets/sheet1.xml", sheet.to_owned()),
+            ("xl/worksheets/sheet2.xml", sheet.to_owned()),
+            ("xl/worksheets/_rels/sheet1.xml.rels", rels_xml(&[
+                ("rId1", "comments", "../comments1.xml"),
+                ("rId2", "vmlDrawing", "../drawings/vmlDrawing1.vml"),
+            ])),
+            ("xl/comments1.xml", comments.to_owned()),
+            ("xl/drawings/vmlDrawing1.vml", vml.to_owned()),
+        ]);
+
+        let data = workbook.worksheet(&fs, "Data").unwrap().unwrap();
+        let note = data.comment(3, 3).unwrap();
+        assert_eq!((note.text.as_str(), note.author.as_deref(), note.visible), ("Source: 2023 report", Some("Reviewer"), true));
+        // Notes are found on cells without a value, and only there.
+        assert!(data.cell(3, 3).is_none());
+        assert!(data.comment(1, 1).is_none());
+        assert_eq!(workbook.comments("Data").map(Comments::len), Some(1));
+
+        let notes = workbook.worksheet(&fs, "Notes").unwrap().unwrap();
+        assert!(notes.comment(3, 3).is_none());
+        assert!(workbook.comments("Notes").unwrap().is_empty());
+        assert!(workbook.comments("Missing").is_none());
+    }
+
+    #[test]
+    fn worksheet_reads_table_rows_by_column_header() {
+        let table = r#"<table id="1" name="Table1" displayName="Sales" ref="A1:B4" totalsRowCount="1">
+  <tableColumns count="2"><tableColumn id="1" name="Region"/><tableColumn id="2" name="Amount" totalsRowFunction="sum"/></tableColumns>
+</table>"#;
+        let sheet = r#"<worksheet><sheetData>
+  <row r="1"><c r="A1" t="inlineStr"><is><t>Region</t></is></c><c r="B1" t="inlineStr"><is><t>Amount</t></is></c></row>
+  <row r="2"><c r="A2" t="inlineStr"><is><t>North</t></is></c><c r="B2"><v>10</v></c></row>
+  <row r="4"><c r="B4"><f>SUBTOTAL(109,Sales[Amount])</f><v>10</v></c></row>
+</sheetData></worksheet>"#;
+        let (fs, workbook) = load("", &[], &[
+            ("xl/worksheets/sheet1.xml", sheet.to_owned()),
+            ("xl/worksheets/_rels/sheet1.xml.rels", rels_xml(&[("rId1", "table", "../tables/table1.xml")])),
+            ("xl/tables/table1.xml", table.to_owned()),
+        ]);
+
+        let (sheet_name, sales) = workbook.table("SALES").unwrap();
+        assert_eq!((sheet_name, sales.range.to_string().as_str()), ("Data", "A1:B4"));
+        assert_eq!(workbook.tables("Data").map(<[Table]>::len), Some(1));
+        assert_eq!(workbook.tables("Notes"), Some(&[][..]));
+
+        let data = workbook.worksheet(&fs, "Data").unwrap().unwrap();
+        assert_eq!(data.tables(), std::slice::from_ref(sales));
+        let shared = SharedStrings::load(b"<sst/>").unwrap();
+        let rows: Vec<_> = data.table_rows(data.table("sales").unwrap())
+            .map(|row| (row.row(), row.get("region").and_then(|c| c.text(&shared)), row.get("Amount").and_then(|c| c.text(&shared))))
+            .collect();
+        // Header and totals rows are skipped; blank rows are not.
+        assert_eq!(rows, [(2, Some("North"), Some("10")), (3, None, None)]);
+        let first = data.table_rows(sales).next().unwrap();
+        assert_eq!(first.iter().map(|(header, _)| header).collect::<Vec<_>>(), ["Region", "Amount"]);
+        assert!(first.get("Missing").is_none());
+    }
+
+    #[test]
+    fn list_options_reads_items_ranges_and_defined_names() {
+        let form = r#"<worksheet><sheetData/><dataValidations>
+  <dataValidation type="list" sqref="A1"><formula1>"Yes,No"</formula1></dataValidation>
+  <dataValidation type="list" sqref="A2"><formula1>$B$1:$B$4</formula1></dataValidation>
+  <dataValidation type="list" sqref="A3"><formula1>Statuses</formula1></dataValidation>
+  <dataValidation type="list" sqref="A4"><formula1>INDIRECT("Notes!A1")</formula1></dataValidation>
+  <dataValidation type="whole" sqref="A5"><formula1>1</formula1></dataValidation>
+</dataValidations></worksheet>"#;
+        let form = form.replace("<sheetData/>", r#"<sheetData>
+  <row r="1"><c r="B1" t="s"><v>0</v></c></row>
+  <row r="2"><c r="B2" t="inlineStr"><is><t></t></is></c></row>
+  <row r="3"><c r="B3"><v>3</v></c></row>
+</sheetData>"#);
+        let notes = r#"<worksheet><sheetData><row r="1"><c r="A1" t="inlineStr"><is><t>Open</t></is></c></row><row r="2"><c r="A2" t="inlineStr"><is><t>Closed</t></is></c></row></sheetData></worksheet>"#;
+        let (fs, workbook) = load(
+            r#"<definedNames><definedName name="Statuses">Notes!$A$1:$A$2</definedName></definedNames>"#,
+            &[],
+            &[("xl/worksheets/sheet1.xml", form.clone()), ("xl/worksheets/sheet2.xml", notes.to_owned())],
+        );
+        let shared = SharedStrings::load(b"<sst><si><t>Draft</t></si></sst>").unwrap();
+
+        let rules = Worksheet::data_validations(form.as_bytes()).unwrap();
+        let options: Vec<_> = rules.iter().map(|rule| workbook.list_options(&fs, "Data", rule, &shared).unwrap()).collect();
+        let owned = |items: &[&str]| Some(items.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        assert_eq!(options, [owned(&["Yes", "No"]), owned(&["Draft", "3"]), owned(&["Open", "Closed"]), None, None]);
+    }
+}
@@ -0,0 +1,240 @@
+use quick_xml::{Reader, events::Event};
+use super::cell_ref::CellRange;
+use super::datetime::DateSystem;
+use super::xml_utils::{attr_value, push_entity};
+
+// ---------------------------------------------------------------------------
+// WorkbookInfo – parsed contents of xl/workbook.xml
+// ---------------------------------------------------------------------------
+
+/// A defined name of the workbook (`<definedName>`): a named range,
+/// constant or formula.
+///
+/// Names are scoped to the whole workbook or to one sheet, where they take
+/// precedence over a workbook name of the same spelling. Built‑in names
+/// that Excel maintains itself (print areas, filter ranges) start with
+/// `_xlnm.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinedName {
+    /// Name as written (`"Totals"`, `"_xlnm.Print_Titles"`).
+    pub name: String,
+    /// Zero‑based position of the sheet the name is scoped to, i.e. its
+    /// index in [`Workbook::sheets`](super::Workbook::sheets), or `None`
+    /// for workbook scope.
+    pub local_sheet_id: Option<usize>,
+    /// The formula the name refers to (`"Data!$A$1:$C$10"`), without `=`.
+    pub formula: String,
+    /// Whether the name is hidden from Excel's name manager.
+    pub hidden: bool,
+}
+
+impl DefinedName {
+    /// Returns `true` for names Excel defines itself (`_xlnm.Print_Area`,
+    /// `_xlnm._FilterDatabase`, ...).
+    pub fn is_builtin(&self) -> bool {
+        self.name.starts_with("_xlnm.")
+    }
+
+    /// Returns the ranges the name refers to, if its formula is a plain
+    /// reference or a comma‑separated list of them (`Data!$A$1:$C$10`,
+    /// `'Q1, Q2'!$1:$1,'Q1, Q2'!$A:$A`).
+    ///
+    /// Whole rows and columns span the full grid. References without a
+    /// sheet keep [`RangeRef::sheet`] empty; see
+    /// [`Workbook::resolve_name`](super::Workbook::resolve_name) to fill it in.
+    ///
+    /// # Returns
+    /// `None` if the name is a constant or a formula (`0.2`,
+    /// `OFFSET(Data!$A$1,0,0,10)`), or refers to another workbook, several
+    /// sheets (`Q1:Q4!A1`) or a deleted range (`#REF!`).
+    pub fn ranges(&self) -> Option<Vec<RangeRef>> {
+        let ranges: Option<Vec<_>> = split_areas(&self.formula).map(RangeRef::parse).collect();
+        ranges.filter(|r| !r.is_empty())
+    }
+}
+
+/// A range of cells, possibly on another sheet, as a defined name refers
+/// to it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeRef {
+    /// Name of the sheet, unquoted; `None` if the reference names none.
+    pub sheet: Option<String>,
+    /// The cells.
+    pub range: CellRange,
+}
+
+impl RangeRef {
+    /// Parses one area: `Sheet!A1:B2`, `'My sheet'!$A:$A` or `A1`.
+    pub(crate) fn parse(area: &str) -> Option<Self> {
+        let (sheet, range) = match area.rsplit_once('!') {
+            Some((sheet, range)) => (Some(sheet), range),
+            None => (None, area),
+        };
+        let sheet = match sheet {
+            Some(quoted) if quoted.len() >= 2 && quoted.starts_with('\'') && quoted.ends_with('\'') => {
+                Some(quoted[1..quoted.len() - 1].replace("''", "'"))
+            }
+            Some(sheet) => Some(sheet.to_owned()),
+            None => None,
+        };
+        // Another workbook (`[1]Data`) or a 3D reference (`Q1:Q4`).
+        if sheet.as_deref().is_some_and(|s| s.starts_with('[') || s.contains(':')) { return None; }
+
+        Some(Self { sheet, range: CellRange::parse_area(range)? })
+    }
+}
+
+impl std::fmt::Display for RangeRef {
+    /// Formats as `'Sheet name'!A1:C10`, quoting the sheet name when needed.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(sheet) = &self.sheet {
+            if sheet.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+                write!(f, "{sheet}!")?;
+            } else {
+                write!(f, "'{}'!", sheet.replace('\'', "''"))?;
+            }
+        }
+        self.range.fmt(f)
+    }
+}
+
+/// Splits a defined‑name formula into comma‑separated areas, ignoring commas
+/// inside quoted sheet names (`'Q1, Q2'!$1:$1`).
+pub(crate) fn split_areas(formula: &str) -> impl Iterator<Item = &str> {
+    let mut in_quotes = false;
+    formula.split(move |c| {
+        if c == '\'' { in_quotes = !in_quotes; }
+        c == ',' && !in_quotes
+    }).map(str::trim).filter(|a| !a.is_empty())
+}
+
+/// Visibility of a sheet tab (`state` attribute of `<sheet>`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SheetState {
+    /// Shown (no `state`, or `state="visible"`).
+    #[default]
+    Visible,
+    /// `state="hidden"`: hidden, but the user can unhide it from Excel.
+    Hidden,
+    /// `state="veryHidden"`: hidden and only unhideable through VBA.
+    VeryHidden,
+}
+
+impl SheetState {
+    /// Maps the value of the `state` attribute; unknown values are treated
+    /// as visible.
+    fn from_attr(state: &str) -> Self {
+        match state {
+            "hidden" => Self::Hidden,
+            "veryHidden" => Self::VeryHidden,
+            _ => Self::Visible,
+        }
+    }
+}
+
+/// A sheet of the workbook, as listed in `xl/workbook.xml`.
+///
+/// Sheets are listed in tab order, which is their position in
+/// [`Workbook::sheets`](super::Workbook::sheets).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetInfo {
+    /// Name shown on the sheet tab (`"Расписание"`).
+    pub name: String,
+    /// The `sheetId` attribute: a number that stays with the sheet when tabs
+    /// are reordered, unlike its position. `None` if missing or invalid.
+    pub sheet_id: Option<u32>,
+    /// Id of the workbook relationship pointing at the sheet part (`r:id`).
+    pub rel_id: Option<String>,
+    /// Tab visibility.
+    pub state: SheetState,
+}
+
+impl SheetInfo {
+    /// Returns `true` if the tab is shown.
+    pub fn is_visible(&self) -> bool {
+        self.state == SheetState::Visible
+    }
+}
+
+/// Sheet list, defined names and date system of `xl/workbook.xml`.
+///
+/// # XML Structure
+/// ```xml
+/// <workbook>
+///   <workbookPr date1904="1"/>
+///   <sheets>
+///     <sheet name="Data" sheetId="1" r:id="rId1"/>
+///     <sheet name="Lookup" sheetId="3" state="hidden" r:id="rId2"/>
+///   </sheets>
+///   <definedNames>
+///     <definedName name="_xlnm.Print_Titles" localSheetId="0">Data!$1:$1</definedName>
+///   </definedNames>
+/// </workbook>
+/// ```
+#[derive(Debug, Default)]
+pub(crate) struct WorkbookInfo {
+    /// Sheets in tab order.
+    pub(crate) sheets: Vec<SheetInfo>,
+    /// Defined names in document order.
+    pub(crate) defined_names: Vec<DefinedName>,
+    /// Date system of serial numbers (`date1904` of `<workbookPr>`).
+    pub(crate) date_system: DateSystem,
+}
+
+impl WorkbookInfo {
+    /// Parses `xl/workbook.xml`.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    pub(crate) fn parse(xml: &[u8]) -> Result<Self, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut info = WorkbookInfo::default();
+        let mut current: Option<DefinedName> = None;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) if e.name().as_ref() == b"sheet" => {
+                    info.sheets.push(SheetInfo {
+                        name: attr_value(e, b"name").unwrap_or_default(),
+                        sheet_id: attr_value(e, b"sheetId").and_then(|v| v.trim().parse().ok()),
+                        rel_id: attr_value(e, b"r:id"),
+                        state: attr_value(e, b"state").map_or_else(SheetState::default, |v| SheetState::from_attr(&v)),
+                    });
+                }
+                Event::Start(ref e) | Event::Empty(ref e) if e.name().as_ref() == b"workbookPr" => {
+                    if matches!(attr_value(e, b"date1904").as_deref().map(str::trim), Some("1" | "true")) {
+                        info.date_system = DateSystem::V1904;
+                    }
+                }
+                Event::Start(ref e) if e.name().as_ref() == b"definedName" => {
+                    current = Some(DefinedName {
+                        name: attr_value(e, b"name").unwrap_or_default(),
+                        local_sheet_id: attr_value(e, b"localSheetId").and_then(|v| v.parse().ok()),
+                        formula: String::new(),
+                        hidden: matches!(attr_value(e, b"hidden").as_deref(), Some("1" | "true")),
+                    });
+                }
+                Event::Text(ref e) => {
+                    if let Some(name) = current.as_mut() {
+                        name.formula.push_str(&String::from_utf8_lossy(e));
+                    }
+                }
+                Event::GeneralRef(ref e) => {
+                    if let Some(name) = current.as_mut() { push_entity(&mut name.formula, e); }
+                }
+                Event::End(ref e) if e.name().as_ref() == b"definedName" => {
+                    info.defined_names.extend(current.take());
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(info)
+    }
+}
@@ -0,0 +1,269 @@
+use super::super::{SharedStrings, Styles};
+use super::super::cell_ref::format_a1;
+use super::super::datetime::DateTime;
+use super::{CellError, CellValue};
+
+/// Cell type as declared by the `t` attribute of `<c>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CellType {
+    /// `t="n"` or no `t` attribute: a number stored as text in `<v>`.
+    #[default]
+    Number,
+    /// `t="s"`: `<v>` holds an index into the shared strings table.
+    SharedString,
+    /// `t="inlineStr"`: the text is stored in the cell itself (`<is>`).
+    InlineString,
+    /// `t="str"`: the cached string result of a formula.
+    FormulaString,
+    /// `t="b"`: `<v>` is `0` or `1`.
+    Bool,
+    /// `t="e"`: `<v>` holds an error code such as `#N/A`.
+    Error,
+    /// `t="d"`: `<v>` holds an ISO 8601 date/time.
+    Date,
+}
+
+impl CellType {
+    /// Maps the value of the `t` attribute; unknown values are treated as numbers.
+    pub(crate) fn from_attr(t: &[u8]) -> Self {
+        match t {
+            b"s" => Self::SharedString,
+            b"inlineStr" => Self::InlineString,
+            b"str" => Self::FormulaString,
+            b"b" => Self::Bool,
+            b"e" => Self::Error,
+            b"d" => Self::Date,
+            _ => Self::Number,
+        }
+    }
+}
+
+/// A single worksheet cell as stored in the XML (`<c>`).
+///
+/// The value is kept in its raw textual form; shared string references are
+/// resolved on demand against a [`SharedStrings`] table, so a parsed sheet does
+/// not borrow from the table it refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub(crate) row: u32,
+    pub(crate) col: u32,
+    pub(crate) cell_type: CellType,
+    /// Index of the cell format (`s`), `0` for the default format.
+    pub(crate) style: u32,
+    /// Text of `<v>`, or the concatenated text of `<is>` for inline strings.
+    pub(crate) raw: Option<Box<str>>,
+    /// Text of `<f>`, if the cell has a formula of its own.
+    pub(crate) formula: Option<Box<str>>,
+}
+
+impl Cell {
+    /// 1‑based row number.
+    #[inline]
+    pub fn row(&self) -> u32 { self.row }
+
+    /// 1‑based column number (`A` = 1).
+    #[inline]
+    pub fn col(&self) -> u32 { self.col }
+
+    /// A1 reference of the cell (`"B7"`).
+    pub fn reference(&self) -> String { format_a1(self.row, self.col) }
+
+    /// Declared cell type.
+    #[inline]
+    pub fn cell_type(&self) -> CellType { self.cell_type }
+
+    /// Index of the cell's format in the styles part (the `s` attribute),
+    /// `0` for the default format. Resolve it with [`Styles`](super::super::Styles).
+    #[inline]
+    pub fn style(&self) -> u32 { self.style }
+
+    /// Raw value text: `<v>` contents, or the inline string text.
+    /// `None` for cells without a value (e.g., styled empty cells).
+    #[inline]
+    pub fn raw(&self) -> Option<&str> { self.raw.as_deref() }
+
+    /// Formula text (`<f>`) without the leading `=`, if the cell has one.
+    #[inline]
+    pub fn formula(&self) -> Option<&str> { self.formula.as_deref() }
+
+    /// Returns the cell's typed value.
+    ///
+    /// Shared strings are looked up in `shared`; numbers, booleans, error
+    /// codes and ISO dates are parsed. A value that doesn't parse as its
+    /// declared type (a corrupt or hand‑edited part) is returned as
+    /// [`CellValue::String`] with the raw text, so nothing is lost. Cells
+    /// with a formula return [`CellValue::Formula`] with the parsed cached
+    /// value. No number format is applied, so dates stored as serial
+    /// numbers come back as numbers.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, Worksheet, CellValue};
+    /// # fn demo(sheet: &Worksheet, shared: &SharedStrings) {
+    /// let total: f64 = sheet.rows().iter()
+    ///     .filter_map(|row| row.cells().iter().find(|c| c.col() == 3))
+    ///     .filter_map(|cell| cell.value(shared).as_f64())
+    ///     .sum();
+    /// # }
+    /// ```
+    pub fn value<'a>(&'a self, shared: &'a SharedStrings) -> CellValue<'a> {
+        let value = self.raw.as_deref().map_or(CellValue::Empty, |raw| self.parse_value(raw, shared));
+        match self.formula.as_deref() {
+            Some(text) => CellValue::Formula { text, cached: Box::new(value) },
+            None => value,
+        }
+    }
+
+    /// Returns the cell's typed value, telling dates apart from numbers by
+    /// the cell's format.
+    ///
+    /// Same as [`value()`](Self::value), except that a number (or a
+    /// formula's cached number) whose format is a date or time format
+    /// (see [`Styles::is_date`]) is converted from its serial number to
+    /// [`CellValue::DateTime`], in the date system of `styles` (see
+    /// [`Styles::with_date_system`]). Serials Excel can't show as a date
+    /// (negative or past the year 9999) stay numbers.
+    ///
+    /// # Arguments
+    /// * `shared` – the workbook's shared strings table.
+    /// * `styles` – the workbook's styles, with the workbook's date system.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{CellValue, SharedStrings, Styles, Worksheet};
+    /// # fn demo(sheet: &Worksheet, shared: &SharedStrings, styles: &Styles) {
+    /// if let Some(CellValue::DateTime(date)) = sheet.cell(2, 1).map(|c| c.typed_value(shared, styles)) {
+    ///     println!("{:02}.{:02}.{}", date.day, date.month, date.year);
+    /// }
+    /// # }
+    /// ```
+    pub fn typed_value<'a>(&'a self, shared: &'a SharedStrings, styles: &Styles) -> CellValue<'a> {
+        let value = self.value(shared);
+        if self.cell_type != CellType::Number || !styles.is_date(self.style) { return value; }
+
+        let to_date = |value| match value {
+            CellValue::Number(serial) => DateTime::from_serial(serial, styles.date_system()).map_or(value, CellValue::DateTime),
+            value => value,
+        };
+        match value {
+            CellValue::Formula { text, cached } => CellValue::Formula { text, cached: Box::new(to_date(*cached)) },
+            value => to_date(value),
+        }
+    }
+
+    /// Returns the cell's value as Excel displays it, with the cell's
+    /// number format applied (`"15.03.2024"`, `"12.50%"`, `"1,234.50 ₽"`).
+    ///
+    /// Use it for exports meant to be read by people; see
+    /// [`Styles::format_value`] for what is rendered. Use
+    /// [`typed_value()`](Self::typed_value) to get at the data itself.
+    ///
+    /// # Arguments
+    /// * `shared` – the workbook's shared strings table.
+    /// * `styles` – the workbook's styles.
+    ///
+    /// # Returns
+    /// An empty string for cells without a value.
+    pub fn formatted_value(&self, shared: &SharedStrings, styles: &Styles) -> String {
+        styles.format_value(&self.value(shared), self.style)
+    }
+
+    /// Interprets `raw` according to the cell type.
+    fn parse_value<'a>(&self, raw: &'a str, shared: &'a SharedStrings) -> CellValue<'a> {
+        let parsed = match self.cell_type {
+            CellType::SharedString => return raw.trim().parse().ok()
+                .and_then(|i| shared.get(i))
+                .map_or(CellValue::Empty, CellValue::String),
+            CellType::InlineString | CellType::FormulaString => return CellValue::String(raw),
+            CellType::Number => raw.trim().parse().ok().map(CellValue::Number),
+            CellType::Bool => parse_bool(raw).map(CellValue::Bool),
+            CellType::Error => Some(CellValue::Error(CellError::from_code(raw))),
+            CellType::Date => DateTime::parse_iso(raw).map(CellValue::DateTime),
+        };
+        parsed.unwrap_or(CellValue::String(raw))
+    }
+
+    /// Returns the cell's value as display text.
+    ///
+    /// Numbers, dates and error codes are returned exactly as stored, shared
+    /// strings are looked up in `shared`, inline and formula strings are
+    /// returned as is, and booleans become `TRUE`/`FALSE` (a value that isn't
+    /// a boolean is returned as stored). No number formatting is applied.
+    ///
+    /// # Returns
+    /// `None` if the cell has no value or refers to a missing shared string.
+    pub fn text<'a>(&'a self, shared: &'a SharedStrings) -> Option<&'a str> {
+        let raw = self.raw.as_deref()?;
+        match self.cell_type {
+            CellType::SharedString => shared.get(raw.trim().parse().ok()?),
+            CellType::Bool => Some(match parse_bool(raw) {
+                Some(true) => "TRUE",
+                Some(false) => "FALSE",
+                None => raw,
+            }),
+            _ => Some(raw),
+        }
+    }
+}
+
+/// Parses the value of a `t="b"` cell: `1`/`0` as Excel writes it, or
+/// `true`/`false` in any case as some other writers do.
+fn parse_bool(raw: &str) -> Option<bool> {
+    match raw.trim() {
+        "1" => Some(true),
+        "0" => Some(false),
+        s if s.eq_ignore_ascii_case("true") => Some(true),
+        s if s.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
+
+/// A worksheet row (`<row>`) with its non‑empty cells in document order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Row {
+    pub(crate) index: u32,
+    pub(crate) spans: Option<(u32, u32)>,
+    pub(crate) hidden: bool,
+    pub(crate) height: Option<f64>,
+    pub(crate) outline_level: u8,
+    pub(crate) collapsed: bool,
+    pub(crate) cells: Vec<Cell>,
+}
+
+impl Row {
+    /// 1‑based row number.
+    #[inline]
+    pub fn index(&self) -> u32 { self.index }
+
+    /// Column bounds declared by the row's `spans` attribute, as an inclusive
+    /// 1‑based `(first, last)` pair.
+    ///
+    /// `spans` is an optimization hint written by Excel (`spans="1:5"`); it is
+    /// not validated against the actual cells. `None` if the attribute is
+    /// absent or malformed.
+    #[inline]
+    pub fn spans(&self) -> Option<(u32, u32)> { self.spans }
+
+    /// Whether the row is hidden (`hidden="1"`), by an active filter or by
+    /// the user; Excel doesn't tell the two apart.
+    #[inline]
+    pub fn is_hidden(&self) -> bool { self.hidden }
+
+    /// Row height in points (`ht`), if set; other rows have the sheet's
+    /// default height (see [`SheetFormat`](super::SheetFormat)).
+    #[inline]
+    pub fn height(&self) -> Option<f64> { self.height }
+
+    /// Outline (grouping) level, `0` if the row is not grouped.
+    #[inline]
+    pub fn outline_level(&self) -> u8 { self.outline_level }
+
+    /// Whether the outline group this row closes is collapsed.
+    #[inline]
+    pub fn is_collapsed(&self) -> bool { self.collapsed }
+
+    /// Cells present in the XML, in document order. Columns without a `<c>`
+    /// element are not represented.
+    #[inline]
+    pub fn cells(&self) -> &[Cell] { &self.cells }
+}
@@ -0,0 +1,169 @@
+use quick_xml::{Reader, events::{BytesStart, Event}};
+use super::super::xml_utils::attr_value;
+
+/// Settings of a range of columns (`<col>` inside `<cols>`).
+///
+/// Excel writes one entry per run of adjacent columns sharing the same
+/// settings; columns without an entry use the sheet defaults.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColInfo {
+    /// First column of the range (1‑based, `A` = 1).
+    pub min: u32,
+    /// Last column of the range, inclusive.
+    pub max: u32,
+    /// Column width in characters of the default font, if set.
+    pub width: Option<f64>,
+    /// The columns are hidden.
+    pub hidden: bool,
+    /// Outline (grouping) level, `0` if the columns are not grouped.
+    pub outline_level: u8,
+    /// Whether the outline group these columns close is collapsed.
+    pub collapsed: bool,
+}
+
+impl ColInfo {
+    /// Reads all `<col>` entries of a worksheet part, in document order.
+    pub(super) fn parse_all(xml: &[u8]) -> Result<Vec<Self>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+        let mut buf = Vec::new();
+        let mut cols = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) => match e.local_name().as_ref() {
+                    b"col" => cols.extend(Self::from_element(e)),
+                    // Columns are declared before the cell data; nothing to find past it.
+                    b"sheetData" => break,
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(cols)
+    }
+
+    /// Reads a `<col>` element; `None` if `min`/`max` are missing or invalid.
+    fn from_element(e: &BytesStart<'_>) -> Option<Self> {
+        let min: u32 = attr_value(e, b"min")?.parse().ok()?;
+        let max: u32 = attr_value(e, b"max")?.parse().ok()?;
+        if min == 0 || min > max { return None; }
+
+        Some(Self {
+            min,
+            max,
+            width: attr_value(e, b"width").and_then(|w| w.parse().ok()),
+            hidden: flag(e, b"hidden"),
+            outline_level: attr_value(e, b"outlineLevel").and_then(|v| v.parse().ok()).unwrap_or(0),
+            collapsed: flag(e, b"collapsed"),
+        })
+    }
+
+    /// Returns `true` if column `col` (1‑based) is in the range.
+    pub fn contains(&self, col: u32) -> bool {
+        (self.min..=self.max).contains(&col)
+    }
+}
+
+/// Default row and column sizes of a worksheet (`<sheetFormatPr>`).
+///
+/// Rows and columns without their own size ([`Row::height`](super::Row::height),
+/// [`ColInfo::width`]) use these.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SheetFormat {
+    /// Height of rows without `ht`, in points.
+    pub default_row_height: Option<f64>,
+    /// Width of columns without `<col>`, in characters, if set; otherwise
+    /// derived from `base_col_width`.
+    pub default_col_width: Option<f64>,
+    /// Default column width in characters, not counting padding
+    /// (`baseColWidth`, 8 if unset).
+    pub base_col_width: u32,
+    /// Rows are hidden unless listed in the cell data (`zeroHeight`), so
+    /// only rows present in the XML, and not marked hidden, are shown.
+    pub zero_height: bool,
+    /// Deepest outline level of the rows.
+    pub outline_level_row: u8,
+    /// Deepest outline level of the columns.
+    pub outline_level_col: u8,
+}
+
+impl SheetFormat {
+    /// Reads the `<sheetFormatPr>` element of a worksheet part; defaults if
+    /// it is absent.
+    pub(super) fn parse(xml: &[u8]) -> Result<Self, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+        let mut buf = Vec::new();
+        let mut format = SheetFormat { base_col_width: 8, ..SheetFormat::default() };
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) => match e.local_name().as_ref() {
+                    b"sheetFormatPr" => {
+                        let number = |name: &[u8]| attr_value(e, name).and_then(|v| v.trim().parse::<f64>().ok());
+                        format.default_row_height = number(b"defaultRowHeight");
+                        format.default_col_width = number(b"defaultColWidth");
+                        format.base_col_width = number(b"baseColWidth").map_or(8, |w| w as u32);
+                        format.zero_height = flag(e, b"zeroHeight");
+                        format.outline_level_row = number(b"outlineLevelRow").map_or(0, |l| l as u8);
+                        format.outline_level_col = number(b"outlineLevelCol").map_or(0, |l| l as u8);
+                        break;
+                    }
+                    // `<sheetFormatPr>` precedes the columns and cell data.
+                    b"cols" | b"sheetData" => break,
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(format)
+    }
+}
+
+/// Reads a boolean attribute, `false` if it is absent.
+fn flag(e: &BytesStart<'_>, name: &[u8]) -> bool {
+    attr_value(e, name).is_some_and(|v| v == "1" || v == "true")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_hidden_and_outlined_columns() {
+        let xml = br#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+            <cols>
+                <col min="1" max="1" width="20.5" customWidth="1"/>
+                <col min="2" max="2" width="9.140625" hidden="1"/>
+                <col min="3" max="5" width="12" outlineLevel="1"/>
+                <col min="6" max="6" collapsed="1"/>
+                <col min="0" max="3" hidden="1"/>
+            </cols>
+            <sheetData/>
+        </worksheet>"#;
+
+        let cols = ColInfo::parse_all(xml).unwrap();
+        assert_eq!(cols, [
+            ColInfo { min: 1, max: 1, width: Some(20.5), ..Default::default() },
+            ColInfo { min: 2, max: 2, width: Some(9.140625), hidden: true, ..Default::default() },
+            ColInfo { min: 3, max: 5, width: Some(12.0), outline_level: 1, ..Default::default() },
+            ColInfo { min: 6, max: 6, collapsed: true, ..Default::default() },
+        ]);
+        assert!(cols[2].contains(4) && !cols[2].contains(6));
+    }
+
+    #[test]
+    fn stops_at_the_cell_data() {
+        let xml = br#"<worksheet><sheetData><row r="1"/></sheetData><col min="1" max="1" hidden="1"/></worksheet>"#;
+        assert!(ColInfo::parse_all(xml).unwrap().is_empty());
+    }
+}
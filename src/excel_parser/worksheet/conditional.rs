@@ -0,0 +1,388 @@
+use quick_xml::{Reader, events::{BytesStart, Event}};
+use super::super::cell_ref::CellRange;
+use super::super::color::Color;
+use super::super::xml_utils::{attr_value, push_entity};
+
+/// A conditional formatting block: a set of rules applied to cell ranges.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CondFormat {
+    /// Space‑separated list of ranges the rules apply to (`"A1:A10 C1:C10"`).
+    pub sqref: String,
+    /// The ranges of `sqref`, parsed; invalid entries are skipped.
+    pub ranges: Vec<CellRange>,
+    /// Rules in document order.
+    pub rules: Vec<CfRule>,
+}
+
+/// One rule (`<cfRule>`) of a conditional formatting block.
+///
+/// Which fields are set depends on [`kind`](Self::kind): comparisons
+/// (`cellIs`) have an operator and formulas, text rules a `text`, top/bottom
+/// rules a `rank`, and so on. Color scales, data bars and icon sets carry
+/// their settings instead of a differential format.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CfRule {
+    /// Rule type as written (`"cellIs"`, `"expression"`, `"colorScale"`,
+    /// `"dataBar"`, `"iconSet"`, `"top10"`, `"containsText"`, ...).
+    pub kind: String,
+    /// The rule's first `<formula>`, if any.
+    pub formula: Option<String>,
+    /// All `<formula>`s of the rule: both bounds of `between`, or the
+    /// formulas Excel generates for text and date rules.
+    pub formulas: Vec<String>,
+    /// Index of the differential format (`dxfId`) in the styles part, if the
+    /// rule applies one; see [`Styles::dxf`](super::super::Styles::dxf).
+    pub dxf_id: Option<u32>,
+    /// Evaluation order across the sheet; lower values go first.
+    pub priority: Option<u32>,
+    /// Rules with a lower priority are not applied when this one matches.
+    pub stop_if_true: bool,
+    /// Comparison of `cellIs` (`"between"`, `"greaterThan"`, ...) and text
+    /// rules (`"containsText"`, `"beginsWith"`, ...).
+    pub operator: Option<String>,
+    /// Text looked for by text rules.
+    pub text: Option<String>,
+    /// Period of `timePeriod` rules (`"today"`, `"last7Days"`, ...).
+    pub time_period: Option<String>,
+    /// Number of items (or percent, with `percent`) of `top10` rules.
+    pub rank: Option<u32>,
+    /// `rank` is a percentage.
+    pub percent: bool,
+    /// `top10` picks the bottom values instead of the top ones.
+    pub bottom: bool,
+    /// `aboveAverage` rules match values above the average (`true`, the
+    /// default) or below it.
+    pub above_average: bool,
+    /// `aboveAverage` rules also match values equal to the average.
+    pub equal_average: bool,
+    /// Number of standard deviations from the average, for `aboveAverage`
+    /// rules.
+    pub std_dev: Option<u32>,
+    /// Settings of a `colorScale` rule.
+    pub color_scale: Option<ColorScale>,
+    /// Settings of a `dataBar` rule.
+    pub data_bar: Option<DataBar>,
+    /// Settings of an `iconSet` rule.
+    pub icon_set: Option<IconSet>,
+}
+
+/// A threshold of a color scale, data bar or icon set (`<cfvo>`).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CfValue {
+    /// How `value` is read: `"min"`, `"max"`, `"num"`, `"percent"`,
+    /// `"percentile"` or `"formula"`.
+    pub kind: String,
+    /// Number, percentage or formula; `None` for `min` and `max`.
+    pub value: Option<String>,
+    /// For icon sets, whether values equal to the threshold reach it (the
+    /// default) rather than only greater ones.
+    pub gte: bool,
+}
+
+/// A two‑ or three‑color scale (`<colorScale>`): cells are shaded from the
+/// first color to the last by where their value falls among the thresholds.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColorScale {
+    /// Thresholds, lowest first.
+    pub values: Vec<CfValue>,
+    /// Color at each threshold, parallel to `values`.
+    pub colors: Vec<Color>,
+}
+
+/// A data bar (`<dataBar>`): a bar drawn in the cell, as long as the value
+/// is between the two thresholds.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DataBar {
+    /// Value of an empty bar.
+    pub min: CfValue,
+    /// Value of a full bar.
+    pub max: CfValue,
+    /// Bar color.
+    pub color: Option<Color>,
+    /// Shortest bar, in percent of the cell width (default 10).
+    pub min_length: u32,
+    /// Longest bar, in percent of the cell width (default 90).
+    pub max_length: u32,
+    /// Whether the cell value is shown next to the bar.
+    pub show_value: bool,
+}
+
+/// An icon set (`<iconSet>`): an icon picked by which threshold the value
+/// reaches.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IconSet {
+    /// Name of the set (`"3TrafficLights1"`, the default, `"5Arrows"`, ...).
+    pub name: String,
+    /// Thresholds, lowest first; the first is where the first icon starts.
+    pub values: Vec<CfValue>,
+    /// Icons are in reverse order.
+    pub reverse: bool,
+    /// Whether the cell value is shown next to the icon.
+    pub show_value: bool,
+}
+
+impl CfRule {
+    /// Reads the attributes of a `<cfRule>` element.
+    fn from_element(e: &BytesStart<'_>) -> Self {
+        let number = |name: &[u8]| attr_value(e, name).and_then(|v| v.trim().parse().ok());
+        Self {
+            kind: attr_value(e, b"type").unwrap_or_default(),
+            dxf_id: number(b"dxfId"),
+            priority: number(b"priority"),
+            stop_if_true: flag(e, b"stopIfTrue", false),
+            operator: attr_value(e, b"operator"),
+            text: attr_value(e, b"text"),
+            time_period: attr_value(e, b"timePeriod"),
+            rank: number(b"rank"),
+            percent: flag(e, b"percent", false),
+            bottom: flag(e, b"bottom", false),
+            above_average: flag(e, b"aboveAverage", true),
+            equal_average: flag(e, b"equalAverage", false),
+            std_dev: number(b"stdDev"),
+            ..Self::default()
+        }
+    }
+
+    /// Adds a `<cfvo>` threshold to the rule's scale, bar or icon set.
+    fn push_value(&mut self, value: CfValue) {
+        if let Some(scale) = self.color_scale.as_mut() {
+            scale.values.push(value);
+        } else if let Some(bar) = self.data_bar.as_mut() {
+            // The first threshold of a bar is its minimum, the second its maximum.
+            if bar.min.kind.is_empty() { bar.min = value; } else { bar.max = value; }
+        } else if let Some(set) = self.icon_set.as_mut() {
+            set.values.push(value);
+        }
+    }
+
+    /// Adds a `<color>` to the rule's scale or bar.
+    fn push_color(&mut self, color: Option<Color>) {
+        if let Some(scale) = self.color_scale.as_mut() {
+            scale.colors.extend(color);
+        } else if let Some(bar) = self.data_bar.as_mut() {
+            bar.color = color;
+        }
+    }
+}
+
+impl CfValue {
+    /// Reads a `<cfvo>` element.
+    fn from_element(e: &BytesStart<'_>) -> Self {
+        Self {
+            kind: attr_value(e, b"type").unwrap_or_default(),
+            value: attr_value(e, b"val"),
+            gte: flag(e, b"gte", true),
+        }
+    }
+}
+
+impl CondFormat {
+    /// Reads all `<conditionalFormatting>` blocks of a worksheet part.
+    pub(super) fn parse_all(xml: &[u8]) -> Result<Vec<Self>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        let config = reader.config_mut();
+        config.trim_text(false);
+        config.check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut formats = Vec::new();
+        let mut current: Option<CondFormat> = None;
+        // Text of the `<formula>` being read.
+        let mut formula: Option<String> = None;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                // Matched by full name: `x14:conditionalFormatting` in `<extLst>`
+                // has a different layout and is not read.
+                Event::Start(ref e) | Event::Empty(ref e) => {
+                    let rule = current.as_mut().and_then(|cf| cf.rules.last_mut());
+                    match (e.name().as_ref(), rule) {
+                        (b"conditionalFormatting", _) => {
+                            let sqref = attr_value(e, b"sqref").unwrap_or_default();
+                            current = Some(CondFormat {
+                                ranges: sqref.split_whitespace().filter_map(CellRange::parse).collect(),
+                                sqref,
+                                rules: Vec::new(),
+                            });
+                        }
+                        (b"cfRule", _) => {
+                            if let Some(cf) = current.as_mut() { cf.rules.push(CfRule::from_element(e)); }
+                        }
+                        (b"formula", Some(_)) => formula = Some(String::new()),
+                        (b"colorScale", Some(rule)) => rule.color_scale = Some(ColorScale::default()),
+                        (b"dataBar", Some(rule)) => {
+                            let number = |name: &[u8], default| attr_value(e, name).and_then(|v| v.trim().parse().ok()).unwrap_or(default);
+                            rule.data_bar = Some(DataBar {
+                                min_length: number(b"minLength", 10),
+                                max_length: number(b"maxLength", 90),
+                                show_value: flag(e, b"showValue", true),
+                                ..DataBar::default()
+                            });
+                        }
+                        (b"iconSet", Some(rule)) => {
+                            rule.icon_set = Some(IconSet {
+                                name: attr_value(e, b"iconSet").unwrap_or_else(|| "3TrafficLights1".to_owned()),
+                                values: Vec::new(),
+                                reverse: flag(e, b"reverse", false),
+                                show_value: flag(e, b"showValue", true),
+                            });
+                        }
+                        (b"cfvo", Some(rule)) => rule.push_value(CfValue::from_element(e)),
+                        (b"color", Some(rule)) => rule.push_color(Color::from_element(e)),
+                        _ => {}
+                    }
+                }
+                Event::Text(ref e) => {
+                    if let Some(f) = formula.as_mut() { f.push_str(&String::from_utf8_lossy(e)); }
+                }
+                Event::GeneralRef(ref e) => {
+                    if let Some(f) = formula.as_mut() { push_entity(f, e); }
+                }
+                Event::End(ref e) => match e.name().as_ref() {
+                    b"formula" => {
+                        let rule = current.as_mut().and_then(|cf| cf.rules.last_mut());
+                        if let (Some(rule), Some(f)) = (rule, formula.take()) {
+                            if rule.formula.is_none() { rule.formula = Some(f.clone()); }
+                            rule.formulas.push(f);
+                        }
+                    }
+                    b"conditionalFormatting" => formats.extend(current.take()),
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(formats)
+    }
+
+    /// Returns `true` if the block applies to the cell at 1‑based
+    /// `(row, col)`.
+    pub fn applies_to(&self, row: u32, col: u32) -> bool {
+        self.ranges.iter().any(|r| r.contains(row, col))
+    }
+}
+
+/// Reads a boolean attribute, `default` if it is absent.
+fn flag(e: &BytesStart<'_>, name: &[u8], default: bool) -> bool {
+    attr_value(e, name).map_or(default, |v| v == "1" || v == "true")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cell_is_and_expression_rules() {
+        let xml = br#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+            <sheetData/>
+            <conditionalFormatting sqref="B2:B10 D2:D10">
+                <cfRule type="cellIs" dxfId="0" priority="2" operator="between"><formula>10</formula><formula>20</formula></cfRule>
+                <cfRule type="expression" dxfId="1" priority="1" stopIfTrue="1"><formula>AND($A2&lt;&gt;"",$C2&gt;0)</formula></cfRule>
+            </conditionalFormatting>
+            <conditionalFormatting sqref="E1"><cfRule type="duplicateValues" priority="3"/></conditionalFormatting>
+        </worksheet>"#;
+
+        let formats = CondFormat::parse_all(xml).unwrap();
+        assert_eq!(formats.len(), 2);
+
+        let first = &formats[0];
+        assert_eq!(first.sqref, "B2:B10 D2:D10");
+        assert!(first.applies_to(5, 4) && !first.applies_to(5, 3) && !first.applies_to(1, 2));
+        assert_eq!(first.rules.len(), 2);
+
+        let cell_is = &first.rules[0];
+        assert_eq!(cell_is.kind, "cellIs");
+        assert_eq!(cell_is.operator.as_deref(), Some("between"));
+        assert_eq!(cell_is.formula.as_deref(), Some("10"));
+        assert_eq!(cell_is.formulas, ["10", "20"]);
+        assert_eq!((cell_is.dxf_id, cell_is.priority, cell_is.stop_if_true), (Some(0), Some(2), false));
+
+        let expression = &first.rules[1];
+        assert_eq!(expression.kind, "expression");
+        assert_eq!(expression.formula.as_deref(), Some(r#"AND($A2<>"",$C2>0)"#));
+        assert_eq!((expression.dxf_id, expression.priority, expression.stop_if_true), (Some(1), Some(1), true));
+
+        let duplicates = &formats[1].rules[0];
+        assert_eq!(duplicates.kind, "duplicateValues");
+        assert_eq!((duplicates.formula.as_deref(), duplicates.dxf_id), (None, None));
+    }
+
+    #[test]
+    fn reads_scales_bars_and_icon_sets() {
+        let xml = br#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+            <conditionalFormatting sqref="A1:A10">
+                <cfRule type="colorScale" priority="1"><colorScale>
+                    <cfvo type="min"/><cfvo type="percentile" val="50"/><cfvo type="max"/>
+                    <color rgb="FFF8696B"/><color theme="4" tint="0.5"/><color rgb="FF63BE7B"/>
+                </colorScale></cfRule>
+            </conditionalFormatting>
+            <conditionalFormatting sqref="B1:B10">
+                <cfRule type="dataBar" priority="2"><dataBar minLength="0" showValue="0">
+                    <cfvo type="num" val="0"/><cfvo type="formula" val="$Z$1"/><color rgb="FF638EC6"/>
+                </dataBar></cfRule>
+                <cfRule type="dataBar" priority="3"><dataBar><cfvo type="min"/><cfvo type="max"/></dataBar></cfRule>
+            </conditionalFormatting>
+            <conditionalFormatting sqref="C1:C10">
+                <cfRule type="iconSet" priority="4"><iconSet iconSet="5Arrows" reverse="1">
+                    <cfvo type="percent" val="0"/><cfvo type="percent" val="40" gte="0"/>
+                </iconSet></cfRule>
+                <cfRule type="iconSet" priority="5"><iconSet><cfvo type="percent" val="0"/></iconSet></cfRule>
+            </conditionalFormatting>
+        </worksheet>"#;
+        let formats = CondFormat::parse_all(xml).unwrap();
+        let value = |kind: &str, value: Option<&str>, gte| CfValue { kind: kind.into(), value: value.map(Into::into), gte };
+
+        let scale = formats[0].rules[0].color_scale.as_ref().unwrap();
+        assert_eq!(scale.values, [value("min", None, true), value("percentile", Some("50"), true), value("max", None, true)]);
+        assert_eq!(scale.colors, [Color::Argb(0xFFF8696B), Color::Theme { index: 4, tint: 0.5 }, Color::Argb(0xFF63BE7B)]);
+        assert!(formats[0].rules[0].data_bar.is_none() && formats[0].rules[0].icon_set.is_none());
+
+        let bar = formats[1].rules[0].data_bar.as_ref().unwrap();
+        assert_eq!(bar, &DataBar {
+            min: value("num", Some("0"), true),
+            max: value("formula", Some("$Z$1"), true),
+            color: Some(Color::Argb(0xFF638EC6)),
+            min_length: 0,
+            max_length: 90,
+            show_value: false,
+        });
+        let defaults = formats[1].rules[1].data_bar.as_ref().unwrap();
+        assert_eq!((defaults.min_length, defaults.max_length, defaults.show_value, defaults.color), (10, 90, true, None));
+
+        let icons = formats[2].rules[0].icon_set.as_ref().unwrap();
+        assert_eq!(icons.name, "5Arrows");
+        assert_eq!(icons.values, [value("percent", Some("0"), true), value("percent", Some("40"), false)]);
+        assert!(icons.reverse && icons.show_value);
+        assert_eq!(formats[2].rules[1].icon_set.as_ref().unwrap().name, "3TrafficLights1");
+    }
+
+    #[test]
+    fn reads_text_top_and_average_criteria() {
+        let xml = br#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+            <conditionalFormatting sqref="A1:A10 bogus">
+                <cfRule type="containsText" dxfId="0" priority="1" operator="containsText" text="&quot;x&quot;"><formula>NOT(ISERROR(SEARCH("""x""",A1)))</formula></cfRule>
+                <cfRule type="timePeriod" dxfId="1" priority="2" timePeriod="last7Days"><formula>AND(TODAY()-FLOOR(A1,1)&lt;=6,FLOOR(A1,1)&lt;=TODAY())</formula></cfRule>
+                <cfRule type="top10" dxfId="2" priority="3" rank="10" percent="1" bottom="1"/>
+                <cfRule type="aboveAverage" dxfId="3" priority="4" aboveAverage="0" equalAverage="1" stdDev="2"/>
+                <cfRule type="aboveAverage" priority="5"/>
+            </conditionalFormatting>
+            <extLst><x14:conditionalFormatting><x14:cfRule type="dataBar"/></x14:conditionalFormatting></extLst>
+        </worksheet>"#;
+        let formats = CondFormat::parse_all(xml).unwrap();
+        assert_eq!(formats.len(), 1);
+        assert_eq!(formats[0].ranges.len(), 1);
+        let rules = &formats[0].rules;
+
+        assert_eq!((rules[0].operator.as_deref(), rules[0].text.as_deref()), (Some("containsText"), Some(r#""x""#)));
+        assert_eq!(rules[0].formula.as_deref(), Some(r#"NOT(ISERROR(SEARCH("""x""",A1)))"#));
+        assert_eq!(rules[1].time_period.as_deref(), Some("last7Days"));
+        assert_eq!(rules[1].formula.as_deref(), Some("AND(TODAY()-FLOOR(A1,1)<=6,FLOOR(A1,1)<=TODAY())"));
+        assert_eq!((rules[2].rank, rules[2].percent, rules[2].bottom), (Some(10), true, true));
+        assert_eq!((rules[3].above_average, rules[3].equal_average, rules[3].std_dev), (false, true, Some(2)));
+        assert_eq!((rules[4].above_average, rules[4].equal_average, rules[4].std_dev), (true, false, None));
+    }
+}
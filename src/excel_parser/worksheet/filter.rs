@@ -0,0 +1,297 @@
+use quick_xml::{Reader, events::{BytesStart, Event}};
+use super::super::cell_ref::CellRange;
+use super::super::xml_utils::attr_value;
+
+/// The filter of a worksheet (`<autoFilter>`): the filtered range and the
+/// criteria of the columns that are filtered.
+///
+/// Criteria are kept as written; which rows they hide is recorded by Excel
+/// on the rows themselves (see [`Row::is_hidden`](super::Row::is_hidden)).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoFilter {
+    /// The filtered range, header row included.
+    pub range: CellRange,
+    /// Columns with criteria or button settings, in document order.
+    pub columns: Vec<FilterColumn>,
+}
+
+/// Criteria of one filtered column (`<filterColumn>`).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FilterColumn {
+    /// Zero‑based position of the column in the filter range (`colId`).
+    pub col_id: u32,
+    /// What the column is filtered by.
+    pub criteria: FilterCriteria,
+    /// Whether the column's filter button is hidden.
+    pub hidden_button: bool,
+}
+
+/// What a column is filtered by.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum FilterCriteria {
+    /// No criteria (a column that only has its button settings).
+    #[default]
+    None,
+    /// Values ticked in the filter list (`<filters>`).
+    Values {
+        /// Displayed texts of the shown values.
+        values: Vec<String>,
+        /// Whether blank cells are shown.
+        blank: bool,
+        /// Shown dates, grouped by year, month, ...
+        dates: Vec<DateGroup>,
+    },
+    /// One or two comparisons (`<customFilters>`).
+    Custom {
+        /// Both comparisons must hold, rather than either.
+        and: bool,
+        /// The comparisons.
+        filters: Vec<CustomFilter>,
+    },
+    /// Top or bottom items (`<top10>`).
+    Top10 {
+        /// Top items, rather than bottom ones.
+        top: bool,
+        /// `value` is a percentage of the items.
+        percent: bool,
+        /// Number (or percentage) of items shown.
+        value: f64,
+    },
+    /// A filter computed by Excel (`<dynamicFilter>`): `"aboveAverage"`,
+    /// `"today"`, `"lastMonth"`, `"Q1"`, ...
+    Dynamic(String),
+    /// Cells of a fill or font color (`<colorFilter>`).
+    Color {
+        /// Differential format holding the color, see
+        /// [`Styles::dxf`](super::super::Styles::dxf).
+        dxf_id: Option<u32>,
+        /// The fill color is matched, rather than the font color.
+        cell_color: bool,
+    },
+    /// Cells showing a conditional formatting icon (`<iconFilter>`).
+    Icon {
+        /// Name of the icon set (`"3Arrows"`).
+        icon_set: String,
+        /// Zero‑based icon of the set; `None` for cells without an icon.
+        icon_id: Option<u32>,
+    },
+}
+
+/// A comparison of a custom filter (`<customFilter>`).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CustomFilter {
+    /// `"equal"` (the default), `"notEqual"`, `"greaterThan"`,
+    /// `"greaterThanOrEqual"`, `"lessThan"` or `"lessThanOrEqual"`.
+    pub operator: String,
+    /// Value compared with; text may use `*` and `?` wildcards.
+    pub value: String,
+}
+
+/// A group of dates shown by a value filter (`<dateGroupItem>`), e.g. all
+/// of March 2024.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DateGroup {
+    /// Year.
+    pub year: u32,
+    /// Month (1–12), for groupings by month or finer.
+    pub month: Option<u32>,
+    /// Day of the month, for groupings by day or finer.
+    pub day: Option<u32>,
+    /// Hour, for groupings by hour or finer.
+    pub hour: Option<u32>,
+    /// Minute, for groupings by minute or finer.
+    pub minute: Option<u32>,
+    /// Second, for groupings by second.
+    pub second: Option<u32>,
+}
+
+impl AutoFilter {
+    /// Reads the `<autoFilter>` of a worksheet part; `None` if the sheet
+    /// has none or its `ref` is invalid.
+    pub(super) fn parse(xml: &[u8]) -> Result<Option<Self>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut filter: Option<AutoFilter> = None;
+        let mut in_filter = false;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) => Self::element(&mut filter, &mut in_filter, e, false),
+                Event::Empty(ref e) => Self::element(&mut filter, &mut in_filter, e, true),
+                Event::End(ref e) if e.local_name().as_ref() == b"autoFilter" => in_filter = false,
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(filter)
+    }
+
+    /// Handles one start or empty element.
+    fn element(filter: &mut Option<Self>, in_filter: &mut bool, e: &BytesStart<'_>, empty: bool) {
+        match e.local_name().as_ref() {
+            b"autoFilter" if filter.is_none() => {
+                *filter = attr_value(e, b"ref")
+                    .and_then(|r| CellRange::parse(&r))
+                    .map(|range| AutoFilter { range, columns: Vec::new() });
+                *in_filter = filter.is_some() && !empty;
+            }
+            b"filterColumn" if *in_filter => {
+                if let Some(f) = filter.as_mut() {
+                    f.columns.push(FilterColumn {
+                        col_id: number(e, b"colId").unwrap_or_default(),
+                        criteria: FilterCriteria::None,
+                        hidden_button: flag(e, b"hiddenButton", false),
+                    });
+                }
+            }
+            name if *in_filter => {
+                if let Some(column) = filter.as_mut().and_then(|f| f.columns.last_mut()) { column.criteria.apply(name, e); }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the criteria of sheet column `col` (1‑based), if it is
+    /// filtered.
+    pub fn column(&self, col: u32) -> Option<&FilterColumn> {
+        let col_id = col.checked_sub(self.range.first_col)?;
+        self.columns.iter().find(|c| c.col_id == col_id && c.criteria != FilterCriteria::None)
+    }
+}
+
+impl FilterCriteria {
+    /// Applies one element inside a `<filterColumn>`.
+    fn apply(&mut self, name: &[u8], e: &BytesStart<'_>) {
+        match name {
+            b"filters" => {
+                *self = Self::Values { values: Vec::new(), blank: flag(e, b"blank", false), dates: Vec::new() };
+            }
+            b"filter" => {
+                if let Self::Values { values, .. } = self { values.extend(attr_value(e, b"val")); }
+            }
+            b"dateGroupItem" => {
+                if let Self::Values { dates, .. } = self { dates.extend(DateGroup::from_element(e)); }
+            }
+            b"customFilters" => *self = Self::Custom { and: flag(e, b"and", false), filters: Vec::new() },
+            b"customFilter" => {
+                if let Self::Custom { filters, .. } = self {
+                    filters.push(CustomFilter {
+                        operator: attr_value(e, b"operator").unwrap_or_else(|| "equal".to_owned()),
+                        value: attr_value(e, b"val").unwrap_or_default(),
+                    });
+                }
+            }
+            b"top10" => {
+                *self = Self::Top10 {
+                    top: flag(e, b"top", true),
+                    percent: flag(e, b"percent", false),
+                    value: attr_value(e, b"val").and_then(|v| v.trim().parse().ok()).unwrap_or_default(),
+                };
+            }
+            b"dynamicFilter" => *self = Self::Dynamic(attr_value(e, b"type").unwrap_or_default()),
+            b"colorFilter" => *self = Self::Color { dxf_id: number(e, b"dxfId"), cell_color: flag(e, b"cellColor", true) },
+            b"iconFilter" => {
+                *self = Self::Icon { icon_set: attr_value(e, b"iconSet").unwrap_or_default(), icon_id: number(e, b"iconId") };
+            }
+            _ => {}
+        }
+    }
+}
+
+impl DateGroup {
+    /// Reads a `<dateGroupItem>`, keeping the parts its `dateTimeGrouping`
+    /// selects; `None` without a year.
+    fn from_element(e: &BytesStart<'_>) -> Option<Self> {
+        let depth = match attr_value(e, b"dateTimeGrouping").as_deref() {
+            Some("year") => 0,
+            Some("month") => 1,
+            Some("day") => 2,
+            Some("hour") => 3,
+            Some("minute") => 4,
+            _ => 5,
+        };
+        let part = |level: usize, name: &[u8]| if depth >= level { number(e, name) } else { None };
+        Some(Self {
+            year: number(e, b"year")?,
+            month: part(1, b"month"),
+            day: part(2, b"day"),
+            hour: part(3, b"hour"),
+            minute: part(4, b"minute"),
+            second: part(5, b"second"),
+        })
+    }
+}
+
+/// Reads a numeric attribute.
+fn number(e: &BytesStart<'_>, name: &[u8]) -> Option<u32> {
+    attr_value(e, name).and_then(|v| v.trim().parse().ok())
+}
+
+/// Reads a boolean attribute, `default` if it is absent.
+fn flag(e: &BytesStart<'_>, name: &[u8], default: bool) -> bool {
+    attr_value(e, name).map_or(default, |v| v == "1" || v == "true")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(filter: &str) -> Option<AutoFilter> {
+        let xml = format!(r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/>{filter}</worksheet>"#);
+        AutoFilter::parse(xml.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn parse_reads_every_kind_of_criteria() {
+        let filter = parse(r#"<autoFilter ref="B1:I20">
+            <filterColumn colId="0"><filters blank="1"><filter val="North"/><filter val="South"/>
+                <dateGroupItem year="2024" month="3" day="15" dateTimeGrouping="month"/><dateGroupItem month="1"/></filters></filterColumn>
+            <filterColumn colId="1"><customFilters and="1"><customFilter operator="greaterThan" val="10"/><customFilter val="A*"/></customFilters></filterColumn>
+            <filterColumn colId="2"><top10 top="0" percent="1" val="25"/></filterColumn>
+            <filterColumn colId="3"><dynamicFilter type="aboveAverage"/></filterColumn>
+            <filterColumn colId="4"><colorFilter dxfId="2" cellColor="0"/></filterColumn>
+            <filterColumn colId="5"><iconFilter iconSet="3Arrows" iconId="1"/></filterColumn>
+            <filterColumn colId="6" hiddenButton="1"/>
+        </autoFilter>"#).unwrap();
+        assert_eq!(filter.range, CellRange::parse("B1:I20").unwrap());
+
+        let criteria: Vec<_> = filter.columns.iter().map(|c| c.criteria.clone()).collect();
+        assert_eq!(criteria, [
+            FilterCriteria::Values {
+                values: vec!["North".into(), "South".into()],
+                blank: true,
+                dates: vec![DateGroup { year: 2024, month: Some(3), ..DateGroup::default() }],
+            },
+            FilterCriteria::Custom { and: true, filters: vec![
+                CustomFilter { operator: "greaterThan".into(), value: "10".into() },
+                CustomFilter { operator: "equal".into(), value: "A*".into() },
+            ] },
+            FilterCriteria::Top10 { top: false, percent: true, value: 25.0 },
+            FilterCriteria::Dynamic("aboveAverage".into()),
+            FilterCriteria::Color { dxf_id: Some(2), cell_color: false },
+            FilterCriteria::Icon { icon_set: "3Arrows".into(), icon_id: Some(1) },
+            FilterCriteria::None,
+        ]);
+        assert!(filter.columns[6].hidden_button && !filter.columns[0].hidden_button);
+    }
+
+    #[test]
+    fn column_maps_sheet_columns_to_filtered_columns() {
+        let filter = parse(r#"<autoFilter ref="C1:E9"><filterColumn colId="1"><dynamicFilter type="today"/></filterColumn><filterColumn colId="2" hiddenButton="1"/></autoFilter>"#).unwrap();
+        assert_eq!(filter.column(4).map(|c| c.col_id), Some(1));
+        // Columns with only button settings, and columns outside the range, have no criteria.
+        assert!(filter.column(5).is_none() && filter.column(3).is_none() && filter.column(1).is_none());
+    }
+
+    #[test]
+    fn parse_ignores_missing_or_invalid_filters() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse(r#"<autoFilter ref="nowhere"><filterColumn colId="0"/></autoFilter>"#), None);
+        assert_eq!(parse(r#"<autoFilter ref="A1:B2"/>"#), Some(AutoFilter { range: CellRange::parse("A1:B2").unwrap(), columns: Vec::new() }));
+    }
+}
@@ -0,0 +1,59 @@
+use quick_xml::{Reader, events::{BytesStart, Event}};
+use super::super::cell_ref::CellRange;
+use super::super::xml_utils::attr_value;
+
+/// A hyperlink of a worksheet (`<hyperlink>` inside `<hyperlinks>`).
+///
+/// Links to a web page or file keep their address in the sheet's
+/// relationship part and refer to it by [`rel_id`](Self::rel_id), resolved
+/// with [`Workbook::sheet_rel_target`](super::super::Workbook::sheet_rel_target);
+/// links to a place in the workbook carry it in [`location`](Self::location).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink {
+    /// Cells the link is attached to (`ref`).
+    pub range: CellRange,
+    /// Id of the sheet relationship holding the external target (`r:id`).
+    pub rel_id: Option<String>,
+    /// Place inside the workbook (`"'Лист2'!A1"`, a defined name), or a
+    /// fragment appended to the external target.
+    pub location: Option<String>,
+    /// Text to display, if it differs from the cell text.
+    pub display: Option<String>,
+    /// Tooltip shown on hover.
+    pub tooltip: Option<String>,
+}
+
+impl Hyperlink {
+    /// Reads all `<hyperlink>` entries of a worksheet part, in document order.
+    pub(super) fn parse_all(xml: &[u8]) -> Result<Vec<Self>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+        let mut buf = Vec::new();
+        let mut links = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) if e.local_name().as_ref() == b"hyperlink" => {
+                    links.extend(Self::from_element(e));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(links)
+    }
+
+    /// Reads a `<hyperlink>` element; `None` if `ref` is missing or invalid.
+    fn from_element(e: &BytesStart<'_>) -> Option<Self> {
+        Some(Self {
+            range: CellRange::parse(&attr_value(e, b"ref")?)?,
+            rel_id: attr_value(e, b"r:id"),
+            location: attr_value(e, b"location"),
+            display: attr_value(e, b"display"),
+            tooltip: attr_value(e, b"tooltip"),
+        })
+    }
+}
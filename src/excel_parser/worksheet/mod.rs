@@ -0,0 +1,1032 @@
+mod cell;
+mod columns;
+mod conditional;
+mod filter;
+mod hyperlinks;
+mod options;
+mod properties;
+mod protection;
+mod reader;
+mod validation;
+mod value;
+
+pub use cell::{Cell, CellType, Row};
+pub use columns::{ColInfo, SheetFormat};
+pub use conditional::{CondFormat, CfRule, CfValue, ColorScale, DataBar, IconSet};
+pub use filter::{AutoFilter, FilterColumn, FilterCriteria, CustomFilter, DateGroup};
+pub use hyperlinks::Hyperlink;
+pub use options::WorksheetOptions;
+pub use properties::SheetPr;
+pub use protection::SheetProtection;
+pub use validation::{DataValidation, ValidationType, ValidationOperator, ValidationErrorStyle, ListSource};
+pub use value::{CellValue, CellError};
+pub use reader::RowIter;
+use reader::RowReader;
+use super::{Comment, Comments, SharedStrings, Table, TableRow};
+use super::cell_ref::CellRange;
+use thiserror::Error;
+
+/// Error type for worksheet parsing with [`WorksheetOptions`].
+#[derive(Error, Debug)]
+pub enum WorksheetError {
+    /// The worksheet XML is malformed.
+    #[error("XML error: {0}")]
+    Xml(#[from] quick_xml::Error),
+
+    /// A cell's column does not follow the previous cell of its row
+    /// (strict order mode only).
+    #[error("Cell in row {row}, column {col} does not follow column {prev_col}")]
+    OutOfOrder { row: u32, col: u32, prev_col: u32 },
+}
+
+// ---------------------------------------------------------------------------
+// Worksheet – parsed cell data of a sheet (xl/worksheets/sheetN.xml)
+// ---------------------------------------------------------------------------
+
+/// Cell data of one worksheet.
+///
+/// Parses the `<sheetData>` section of a worksheet part into rows of
+/// [`Cell`]s. Values are kept in their raw form (see [`Cell::raw`]); shared
+/// string references are resolved against a [`SharedStrings`] table on demand.
+///
+/// All coordinates are 1‑based, as in the XML: `A1` is row 1, column 1.
+///
+/// # XML Structure
+/// ```xml
+/// <worksheet>
+///   <sheetData>
+///     <row r="1">
+///       <c r="A1" t="s"><v>0</v></c>
+///       <c r="B1"><v>42.5</v></c>
+///       <c r="C1" t="inlineStr"><is><t>inline</t></is></c>
+///     </row>
+///   </sheetData>
+/// </worksheet>
+/// ```
+///
+/// # Example
+/// ```no_run
+/// # use excel_parser::{SharedStrings, Worksheet};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let shared = SharedStrings::load(&std::fs::read("xl/sharedStrings.xml")?)?;
+/// let sheet = Worksheet::parse(&std::fs::read("xl/worksheets/sheet1.xml")?)?;
+///
+/// for row in sheet.text_grid(&shared) {
+///     println!("{}", row.join("\t"));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Worksheet {
+    /// Rows present in the XML, in document order.
+    rows: Vec<Row>,
+    /// Whether rows, and the cells of every row, are in strictly increasing
+    /// order, so cells can be looked up by binary search.
+    ordered: bool,
+    /// Cell notes, attached with [`with_comments`](Self::with_comments).
+    comments: Comments,
+    /// Tables on the sheet, attached with [`with_tables`](Self::with_tables).
+    tables: Vec<Table>,
+    /// Used range declared by the `<dimension>` element, if any.
+    declared: Option<CellRange>,
+}
+
+impl Worksheet {
+    /// Glob matching worksheet parts inside an `.xlsx` archive.
+    pub const GLOB: &'static str = "xl/worksheets/*.xml";
+
+    /// Parses the cell data of a worksheet part.
+    ///
+    /// Only `<row>` and `<c>` elements are interpreted; formulas, styles and
+    /// all other sheet settings are ignored. Rows or cells without an `r`
+    /// attribute are placed right after the previous one.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    pub fn parse(xml: &[u8]) -> Result<Self, quick_xml::Error> {
+        let mut reader = RowReader::new(xml);
+        let mut rows = Vec::new();
+        while let Some(row) = reader.next_row()? {
+            rows.push(row);
+        }
+
+        Ok(Self::from_rows(rows, reader.dimension()))
+    }
+
+    /// Returns a streaming parser over the rows of a worksheet part.
+    ///
+    /// Unlike [`parse()`](Self::parse), no grid is built: each row is
+    /// yielded as soon as it is parsed and can be dropped right after, so
+    /// processing a sheet of a million rows needs memory for one row at a
+    /// time (besides `xml` itself). Parsing is the same as `parse()`.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Returns
+    /// An iterator of `Result<Row, quick_xml::Error>` in document order; it
+    /// ends after the first error.
+    pub fn rows_streaming(xml: &[u8]) -> RowIter<'_> {
+        RowIter::new(xml)
+    }
+
+    /// Parses the cell data of a worksheet part as configured by `options`.
+    ///
+    /// Same as [`parse()`](Self::parse), plus the validations enabled in
+    /// `options`.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    /// * `options` – parsing options.
+    ///
+    /// # Errors
+    /// * `WorksheetError::Xml` – malformed XML.
+    /// * `WorksheetError::OutOfOrder` – cells of a row are not in increasing
+    ///   column order (only with [`strict_order`](WorksheetOptions::strict_order)).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{Worksheet, WorksheetOptions};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let xml = std::fs::read("xl/worksheets/sheet1.xml")?;
+    /// let sheet = Worksheet::parse_with_options(&xml, &WorksheetOptions::new().strict_order(true))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_with_options(xml: &[u8], options: &WorksheetOptions) -> Result<Self, WorksheetError> {
+        let mut reader = RowReader::new(xml);
+        let mut rows = Vec::new();
+        while let Some(row) = reader.next_row()? {
+            if options.strict_order { check_order(&row)?; }
+            if options.skip_hidden_rows && row.hidden { continue; }
+            rows.push(row);
+        }
+
+        Ok(Self::from_rows(rows, reader.dimension()))
+    }
+
+    fn from_rows(rows: Vec<Row>, declared: Option<CellRange>) -> Self {
+        let ordered = rows.windows(2).all(|w| w[0].index < w[1].index)
+            && rows.iter().all(|row| {
+                row.cells.iter().all(|c| c.row == row.index)
+                    && row.cells.windows(2).all(|w| w[0].col < w[1].col)
+            });
+        Self { rows, ordered, comments: Comments::default(), tables: Vec::new(), declared }
+    }
+
+    // -------------------------------------------------------------------------
+    // Public API
+    // -------------------------------------------------------------------------
+
+    /// Returns the rows present in the XML, in document order.
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// Returns the rows that are not hidden, in document order.
+    ///
+    /// Rows hidden by a filter (see [`auto_filter()`](Self::auto_filter))
+    /// and rows hidden by the user are both skipped, as Excel records them
+    /// the same way.
+    pub fn visible_rows(&self) -> impl Iterator<Item = &Row> {
+        self.rows.iter().filter(|r| !r.hidden)
+    }
+
+    /// Returns the cell at 1‑based `row` and `col`.
+    ///
+    /// Sheets written in order (as Excel always does) are searched by
+    /// bisection; others, e.g. hand‑edited parts with rows out of order, are
+    /// scanned, and the first cell found at the position wins.
+    ///
+    /// # Arguments
+    /// * `row` – 1‑based row number.
+    /// * `col` – 1‑based column number (`A` = 1).
+    ///
+    /// # Returns
+    /// `None` if the sheet has no `<c>` element at that position.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, Worksheet};
+    /// # fn demo(sheet: &Worksheet, shared: &SharedStrings) {
+    /// // B3
+    /// if let Some(text) = sheet.cell(3, 2).and_then(|cell| cell.text(shared)) {
+    ///     println!("B3 = {text}");
+    /// }
+    /// # }
+    /// ```
+    pub fn cell(&self, row: u32, col: u32) -> Option<&Cell> {
+        if !self.ordered {
+            return self.rows.iter().flat_map(|r| &r.cells).find(|c| c.row == row && c.col == col);
+        }
+
+        let row = &self.rows[self.rows.binary_search_by_key(&row, |r| r.index).ok()?];
+        row.cells.binary_search_by_key(&col, |c| c.col).ok().map(|i| &row.cells[i])
+    }
+
+    /// Returns the cells present in `range`, row by row in document order.
+    ///
+    /// Only cells with a `<c>` element are yielded, so whole rows or columns
+    /// (as defined names often refer to) cost no more than the cells in them.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{CellRange, SharedStrings, Worksheet};
+    /// # fn demo(sheet: &Worksheet, shared: &SharedStrings) {
+    /// let range = CellRange { first_row: 2, first_col: 1, last_row: 10, last_col: 3 }; // A2:C10
+    /// for cell in sheet.range(&range) {
+    ///     println!("{} = {:?}", cell.reference(), cell.text(shared));
+    /// }
+    /// # }
+    /// ```
+    pub fn range<'a>(&'a self, range: &CellRange) -> impl Iterator<Item = &'a Cell> + 'a {
+        let range = *range;
+        let rows = if self.ordered {
+            let start = self.rows.partition_point(|r| r.index < range.first_row);
+            let end = self.rows.partition_point(|r| r.index <= range.last_row);
+            &self.rows[start..end]
+        } else {
+            &self.rows[..]
+        };
+
+        rows.iter()
+            .flat_map(|r| &r.cells)
+            .filter(move |c| range.contains(c.row, c.col))
+    }
+
+    /// Attaches the notes of the sheet, read from its comments part, so
+    /// they can be looked up with [`comment()`](Self::comment).
+    ///
+    /// [`Workbook::worksheet`](super::Workbook::worksheet) does this for
+    /// you; a sheet parsed on its own has no notes.
+    pub fn with_comments(mut self, comments: Comments) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    /// Returns the note (comment) attached to a cell, with its author.
+    ///
+    /// Notes are kept apart from the cell data, so a note is found whether
+    /// or not the cell has a value.
+    ///
+    /// # Arguments
+    /// * `row`, `col` – 1‑based cell position.
+    ///
+    /// # Returns
+    /// `None` if the cell has no note or no notes were attached.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{Workbook, ZipFs};
+    /// # fn demo(fs: &ZipFs, workbook: &Workbook) -> Result<(), quick_xml::Error> {
+    /// if let Some(sheet) = workbook.worksheet(fs, "Data")?
+    ///     && let Some(note) = sheet.comment(2, 2)
+    /// {
+    ///     println!("B2 ({}): {}", note.author.as_deref().unwrap_or("?"), note.text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn comment(&self, row: u32, col: u32) -> Option<&Comment> {
+        self.comments.comment(row, col)
+    }
+
+    /// Returns the notes attached to the sheet.
+    pub fn comments(&self) -> &Comments {
+        &self.comments
+    }
+
+    /// Attaches the tables of the sheet, read from its table parts, so they
+    /// can be found with [`tables()`](Self::tables) and read with
+    /// [`table_rows()`](Self::table_rows).
+    ///
+    /// [`Workbook::worksheet`](super::Workbook::worksheet) does this for
+    /// you; a sheet parsed on its own has no tables.
+    pub fn with_tables(mut self, tables: Vec<Table>) -> Self {
+        self.tables = tables;
+        self
+    }
+
+    /// Returns the tables attached to the sheet.
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    /// Returns the table with the given name (as shown in Excel), matched
+    /// ignoring case.
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        let name = name.to_lowercase();
+        self.tables.iter().find(|t| t.display_name.to_lowercase() == name)
+    }
+
+    /// Returns the data rows of a table, with their cells keyed by column
+    /// header.
+    ///
+    /// Header and totals rows are skipped. Every row of the table's range
+    /// is yielded, blank ones included, so the sequence matches what Excel
+    /// shows.
+    ///
+    /// # Arguments
+    /// * `table` – one of [`tables()`](Self::tables), or any table whose
+    ///   range is on this sheet.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, Worksheet};
+    /// # fn demo(sheet: &Worksheet, shared: &SharedStrings) {
+    /// if let Some(sales) = sheet.table("Sales") {
+    ///     for row in sheet.table_rows(sales) {
+    ///         let region = row.get("Region").and_then(|c| c.text(shared));
+    ///         let amount = row.get("Amount").and_then(|c| c.text(shared));
+    ///         println!("{}: {region:?} {amount:?}", row.row());
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn table_rows<'a>(&'a self, table: &'a Table) -> impl Iterator<Item = TableRow<'a>> + 'a {
+        let width = table.columns.len() as u32;
+        table.data_range().into_iter()
+            .flat_map(|range| range.first_row..=range.last_row)
+            .map(move |row| {
+                let cols = table.range.first_col..table.range.first_col + width;
+                TableRow::new(row, &table.columns, cols.map(|col| self.cell(row, col)).collect())
+            })
+    }
+
+    /// Reads the used range a worksheet part declares (`<dimension>`),
+    /// without parsing its cells.
+    ///
+    /// Cheap enough to size buffers before parsing, but only as reliable as
+    /// the writer: the range may be missing, `A1` for a filled sheet, or
+    /// stale. [`dimensions()`](Self::dimensions) checks it against the cells.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Returns
+    /// `None` if the part has no `<dimension>` before `<sheetData>` or its
+    /// `ref` is invalid.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    pub fn dimension(xml: &[u8]) -> Result<Option<CellRange>, quick_xml::Error> {
+        RowReader::read_dimension(xml)
+    }
+
+    /// Returns the used range declared by the sheet's `<dimension>`
+    /// element, as written.
+    pub fn declared_dimension(&self) -> Option<CellRange> {
+        self.declared
+    }
+
+    /// Returns the bounding box of the cells present in the sheet, computed
+    /// by scanning every row.
+    ///
+    /// Every `<c>` element counts, including cells that only carry a style
+    /// and no value, as Excel's used range does.
+    ///
+    /// # Returns
+    /// The smallest range containing all cells, or `None` for a sheet
+    /// without cells.
+    pub fn used_range(&self) -> Option<CellRange> {
+        self.rows.iter().flat_map(|r| &r.cells).fold(None, |range, c| {
+            Some(match range {
+                None => CellRange { first_row: c.row, first_col: c.col, last_row: c.row, last_col: c.col },
+                Some(r) => CellRange {
+                    first_row: r.first_row.min(c.row),
+                    first_col: r.first_col.min(c.col),
+                    last_row: r.last_row.max(c.row),
+                    last_col: r.last_col.max(c.col),
+                },
+            })
+        })
+    }
+
+    /// Returns the used range of the sheet.
+    ///
+    /// The range declared by `<dimension>` is trusted when it contains every
+    /// cell present; otherwise (no `<dimension>`, or a wrong one such as the
+    /// `A1` many writers emit) the bounding box is computed from the cells,
+    /// as [`used_range()`](Self::used_range) does.
+    ///
+    /// # Returns
+    /// The used range, or `None` for a sheet without cells.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::Worksheet;
+    /// # fn demo(sheet: &Worksheet) {
+    /// if let Some(used) = sheet.dimensions() {
+    ///     println!("{used}: {} rows × {} columns", used.height(), used.width());
+    /// }
+    /// # }
+    /// ```
+    pub fn dimensions(&self) -> Option<CellRange> {
+        let used = self.used_range()?;
+        Some(match self.declared {
+            Some(d) if d.contains(used.first_row, used.first_col) && d.contains(used.last_row, used.last_col) => d,
+            _ => used,
+        })
+    }
+
+    /// Renders every cell as text into a dense grid.
+    ///
+    /// The grid is anchored at `A1`: `grid[r][c]` holds the cell at row `r + 1`,
+    /// column `c + 1`. Each cell is rendered with [`Cell::text`] (numbers as
+    /// stored, shared and inline strings resolved); missing cells become
+    /// empty strings. Intended for simple text exports where cell types don't
+    /// matter.
+    ///
+    /// The grid extends to the last row and the last column holding text;
+    /// cells without a value (formatting only) don't widen it. Every row has
+    /// the same length, so the grid costs one `String` per position of that
+    /// rectangle: a lone value at `XFD3` gives 3 rows of 16 384 entries.
+    ///
+    /// # Arguments
+    /// * `shared` – the workbook's shared strings table.
+    ///
+    /// # Returns
+    /// One `Vec<String>` per row, all of equal length. Empty for a sheet
+    /// without text.
+    pub fn text_grid(&self, shared: &SharedStrings) -> Vec<Vec<String>> {
+        let mut grid = Vec::new();
+        for cell in self.rows.iter().flat_map(|r| &r.cells) {
+            if let (Some(text), Some(row), Some(col)) = (cell.text(shared), cell.row.checked_sub(1), cell.col.checked_sub(1)) {
+                place(&mut grid, row as usize, col as usize, text);
+            }
+        }
+
+        let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+        for row in &mut grid { row.resize(width, String::new()); }
+        grid
+    }
+
+    /// Renders the cells Excel shows as text into a grid, leaving out hidden
+    /// rows and columns.
+    ///
+    /// Like [`text_grid()`](Self::text_grid), but rows marked hidden (by a
+    /// filter or by the user) and columns hidden in `columns` are dropped,
+    /// and the others close up: `grid[0][0]` is the first visible row and
+    /// column from `A1`. Rows are not padded: each ends at its last cell with
+    /// text, and the grid at the last row with text.
+    ///
+    /// # Arguments
+    /// * `shared` – the workbook's shared strings table.
+    /// * `columns` – the sheet's column settings, from [`columns()`](Self::columns).
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{SharedStrings, Worksheet};
+    /// # fn demo(xml: &[u8], shared: &SharedStrings) -> Result<(), quick_xml::Error> {
+    /// let sheet = Worksheet::parse(xml)?;
+    /// for row in sheet.visible_text_grid(shared, &Worksheet::columns(xml)?) {
+    ///     println!("{}", row.join("\t"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn visible_text_grid(&self, shared: &SharedStrings, columns: &[ColInfo]) -> Vec<Vec<String>> {
+        // Distinct hidden rows in order; row 0 isn't a valid position.
+        let mut hidden_rows: Vec<u32> = self.rows.iter()
+            .filter(|r| r.hidden && r.index > 0)
+            .map(|r| r.index)
+            .collect();
+        hidden_rows.sort_unstable();
+        hidden_rows.dedup();
+        let hidden_cols = HiddenColumns::new(columns);
+
+        let mut grid = Vec::new();
+        for cell in self.rows.iter().filter(|r| !r.hidden).flat_map(|r| &r.cells) {
+            // Zero‑based positions once the hidden rows and columns before the cell are removed.
+            let (Some(text), Some(row), Some(col), Some(cols_before)) =
+                (cell.text(shared), cell.row.checked_sub(1), cell.col.checked_sub(1), hidden_cols.before(cell.col))
+            else { continue };
+            let row = row as usize - hidden_rows.partition_point(|&h| h < cell.row);
+            place(&mut grid, row, col as usize - cols_before, text);
+        }
+
+        grid
+    }
+
+    /// Reads the protection settings of a worksheet part.
+    ///
+    /// Only the `<sheetProtection>` element is read, so this is cheap to call
+    /// without parsing the cell data.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Returns
+    /// `Some(SheetProtection)` if the sheet is protected, `None` otherwise.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::Worksheet;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let xml = std::fs::read("xl/worksheets/sheet1.xml")?;
+    /// if let Some(p) = Worksheet::protection(&xml)? {
+    ///     println!("locked cells: {}, password: {}", p.sheet, p.password_hash.is_some());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn protection(xml: &[u8]) -> Result<Option<SheetProtection>, quick_xml::Error> {
+        SheetProtection::parse(xml)
+    }
+
+    /// Reads the hyperlinks (`<hyperlinks>`) of a worksheet part.
+    ///
+    /// The list follows the cell data, so the whole part is scanned.
+    /// Entries without a valid `ref` are skipped.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Returns
+    /// Hyperlinks in document order; external addresses are only referenced
+    /// by relationship id, see [`Hyperlink`].
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{Workbook, Worksheet, ZipFs};
+    /// # fn demo(fs: &ZipFs, workbook: &Workbook) -> Result<(), quick_xml::Error> {
+    /// let xml = fs.get_file(workbook.sheet_part("Data").unwrap()).unwrap();
+    /// for link in Worksheet::hyperlinks(xml)? {
+    ///     let target = link.rel_id.as_deref().and_then(|id| workbook.sheet_rel_target("Data", id));
+    ///     println!("{}: {:?} {:?}", link.range, target, link.location);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn hyperlinks(xml: &[u8]) -> Result<Vec<Hyperlink>, quick_xml::Error> {
+        Hyperlink::parse_all(xml)
+    }
+
+    /// Reads the column settings (`<cols>`) of a worksheet part.
+    ///
+    /// Only the part before `<sheetData>` is read, so this is cheap to call
+    /// without parsing the cell data. Entries with missing or invalid
+    /// `min`/`max` are skipped.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Returns
+    /// Column ranges in document order; empty if the sheet uses default
+    /// column settings throughout.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::Worksheet;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let xml = std::fs::read("xl/worksheets/sheet1.xml")?;
+    /// for col in Worksheet::columns(&xml)?.iter().filter(|c| c.hidden) {
+    ///     println!("columns {}..={} are hidden", col.min, col.max);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn columns(xml: &[u8]) -> Result<Vec<ColInfo>, quick_xml::Error> {
+        ColInfo::parse_all(xml)
+    }
+
+    /// Reads the sheet properties (`<sheetPr>`) of a worksheet part: the tab
+    /// color and the VBA code name.
+    ///
+    /// Only the beginning of the part is read, so this is cheap to call
+    /// without parsing the cell data.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Returns
+    /// The properties; fields are `None` when not set.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{Worksheet, Color};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let xml = std::fs::read("xl/worksheets/sheet1.xml")?;
+    /// if let Some(Color::Argb(argb)) = Worksheet::sheet_properties(&xml)?.tab_color {
+    ///     println!("tab color #{:06X}", argb & 0xFF_FFFF);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sheet_properties(xml: &[u8]) -> Result<SheetPr, quick_xml::Error> {
+        SheetPr::parse(xml)
+    }
+
+    /// Reads the default row height and column width of a worksheet part
+    /// (`<sheetFormatPr>`), which apply to rows and columns without their
+    /// own size.
+    ///
+    /// Only the beginning of the part is read, so this is cheap to call
+    /// without parsing the cell data.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::Worksheet;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let xml = std::fs::read("xl/worksheets/sheet1.xml")?;
+    /// let format = Worksheet::sheet_format(&xml)?;
+    /// let sheet = Worksheet::parse(&xml)?;
+    /// for row in sheet.rows() {
+    ///     println!("row {}: {:?} pt", row.index(), row.height().or(format.default_row_height));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sheet_format(xml: &[u8]) -> Result<SheetFormat, quick_xml::Error> {
+        SheetFormat::parse(xml)
+    }
+
+    /// Reads the conditional formatting rules of a worksheet part.
+    ///
+    /// Rules are returned as written, without evaluating them: cell value,
+    /// formula, text, date, top/bottom and average rules with their
+    /// criteria, and color scales, data bars and icon sets with their
+    /// thresholds and colors. The formats rules apply are referenced by
+    /// [`CfRule::dxf_id`] and resolved with
+    /// [`Styles::dxf`](super::Styles::dxf). Extension rules stored in
+    /// `<extLst>` (`x14:conditionalFormatting`) are not read.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Returns
+    /// One [`CondFormat`] per `<conditionalFormatting>` element, in document
+    /// order, each with its rules.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::Worksheet;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let xml = std::fs::read("xl/worksheets/sheet1.xml")?;
+    /// for cf in Worksheet::conditional_formats(&xml)? {
+    ///     for rule in &cf.rules {
+    ///         println!("{}: {} {:?}", cf.sqref, rule.kind, rule.formula);
+    ///         if let Some(scale) = &rule.color_scale {
+    ///             println!("  from {:?} to {:?}", scale.colors.first(), scale.colors.last());
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn conditional_formats(xml: &[u8]) -> Result<Vec<CondFormat>, quick_xml::Error> {
+        CondFormat::parse_all(xml)
+    }
+
+    /// Reads the data validation rules of a worksheet part: dropdown lists,
+    /// numeric, date and text length constraints, and custom formulas.
+    ///
+    /// Rules Excel 2010 stores in `<extLst>` (`x14:dataValidation`, used for
+    /// lists that refer to another sheet) are included, after the others.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Returns
+    /// The rules in document order; use [`DataValidation::applies_to`] to
+    /// find the rule of a cell.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    ///
+    /// # XML Structure
+    /// ```xml
+    /// <dataValidations count="2">
+    ///   <dataValidation type="list" allowBlank="1" showErrorMessage="1" sqref="B2:B100">
+    ///     <formula1>"Yes,No,Maybe"</formula1>
+    ///   </dataValidation>
+    ///   <dataValidation type="whole" operator="between" sqref="C2:C100" error="1 to 10">
+    ///     <formula1>1</formula1>
+    ///     <formula2>10</formula2>
+    ///   </dataValidation>
+    /// </dataValidations>
+    /// ```
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ListSource, Worksheet};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let xml = std::fs::read("xl/worksheets/sheet1.xml")?;
+    /// let rules = Worksheet::data_validations(&xml)?;
+    /// if let Some(ListSource::Items(options)) = rules.iter()
+    ///     .find(|rule| rule.applies_to(2, 2))
+    ///     .and_then(|rule| rule.list_source())
+    /// {
+    ///     println!("B2 accepts {}", options.join(" / "));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn data_validations(xml: &[u8]) -> Result<Vec<DataValidation>, quick_xml::Error> {
+        DataValidation::parse_all(xml)
+    }
+
+    /// Reads the filter of a worksheet part (`<autoFilter>`): the filtered
+    /// range and each filtered column's criteria.
+    ///
+    /// The filter's effect is stored on the rows it hides, so exports can
+    /// match what Excel shows without evaluating the criteria: skip rows
+    /// with [`Row::is_hidden`], use [`visible_rows()`](Self::visible_rows),
+    /// or parse with [`WorksheetOptions::skip_hidden_rows`]. Filters of
+    /// tables are kept in the table parts and not read here.
+    ///
+    /// # Arguments
+    /// * `xml` – raw bytes of a `xl/worksheets/sheetN.xml` part.
+    ///
+    /// # Returns
+    /// `None` if the sheet has no filter.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    ///
+    /// # XML Structure
+    /// ```xml
+    /// <autoFilter ref="A1:D20">
+    ///   <filterColumn colId="1">
+    ///     <filters blank="1"><filter val="East"/><filter val="West"/></filters>
+    ///   </filterColumn>
+    ///   <filterColumn colId="3">
+    ///     <customFilters and="1">
+    ///       <customFilter operator="greaterThanOrEqual" val="100"/>
+    ///       <customFilter operator="lessThan" val="200"/>
+    ///     </customFilters>
+    ///   </filterColumn>
+    /// </autoFilter>
+    /// ```
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{FilterCriteria, Worksheet};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let xml = std::fs::read("xl/worksheets/sheet1.xml")?;
+    /// if let Some(filter) = Worksheet::auto_filter(&xml)? {
+    ///     for column in &filter.columns {
+    ///         if let FilterCriteria::Values { values, .. } = &column.criteria {
+    ///             println!("column {} shows {}", filter.range.first_col + column.col_id, values.join(", "));
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn auto_filter(xml: &[u8]) -> Result<Option<AutoFilter>, quick_xml::Error> {
+        AutoFilter::parse(xml)
+    }
+}
+
+/// Stores `text` at zero‑based `(row, col)` of a grid, growing the grid and
+/// the row only as far as needed.
+fn place(grid: &mut Vec<Vec<String>>, row: usize, col: usize, text: &str) {
+    if grid.len() <= row { grid.resize_with(row + 1, Vec::new); }
+    let cells = &mut grid[row];
+    if cells.len() <= col { cells.resize(col + 1, String::new()); }
+    cells[col] = text.to_owned();
+}
+
+/// Hidden columns of a sheet, merged into sorted, disjoint runs.
+struct HiddenColumns {
+    /// `(min, max, hidden)`: a run and the number of hidden columns up to its end.
+    runs: Vec<(u32, u32, usize)>,
+}
+
+impl HiddenColumns {
+    fn new(columns: &[ColInfo]) -> Self {
+        let mut ranges: Vec<(u32, u32)> = columns.iter().filter(|c| c.hidden).map(|c| (c.min, c.max)).collect();
+        ranges.sort_unstable();
+
+        let mut runs: Vec<(u32, u32, usize)> = Vec::with_capacity(ranges.len());
+        for (min, max) in ranges {
+            match runs.last_mut() {
+                Some(last) if min <= last.1.saturating_add(1) => {
+                    if max > last.1 {
+                        last.2 += (max - last.1) as usize;
+                        last.1 = max;
+                    }
+                }
+                _ => {
+                    let before = runs.last().map_or(0, |r| r.2);
+                    runs.push((min, max, before + (max - min) as usize + 1));
+                }
+            }
+        }
+        Self { runs }
+    }
+
+    /// Returns the number of hidden columns before `col`, or `None` if `col`
+    /// is hidden itself.
+    fn before(&self, col: u32) -> Option<usize> {
+        match self.runs.partition_point(|r| r.0 <= col).checked_sub(1).map(|i| self.runs[i]) {
+            Some((_, max, _)) if max >= col => None,
+            Some((_, _, hidden)) => Some(hidden),
+            None => Some(0),
+        }
+    }
+}
+
+/// Checks that the cells of `row` have strictly increasing columns.
+fn check_order(row: &Row) -> Result<(), WorksheetError> {
+    match row.cells.windows(2).find(|w| w[1].col <= w[0].col) {
+        Some(w) => Err(WorksheetError::OutOfOrder { row: row.index, col: w[1].col, prev_col: w[0].col }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wraps `<row>` elements into a worksheet part.
+    fn sheet(rows: &str) -> Worksheet {
+        let xml = format!(r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>{rows}</sheetData></worksheet>"#);
+        Worksheet::parse(xml.as_bytes()).unwrap()
+    }
+
+    fn shared() -> SharedStrings {
+        SharedStrings::load(b"<sst><si><t>Name</t></si><si><t>Total</t></si></sst>").unwrap()
+    }
+
+    #[test]
+    fn text_grid_renders_every_type_as_text() {
+        let sheet = sheet(r#"
+            <row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="inlineStr"><is><t>Inline</t></is></c><c r="C1"><v>12.5</v></c></row>
+            <row r="2"><c r="A2" t="b"><v>1</v></c><c r="B2" t="e"><v>#DIV/0!</v></c><c r="C2" t="str"><f>A1&amp;"!"</f><v>Name!</v></c></row>
+            <row r="3"><c r="A3" s="1"/><c r="C3" t="s"><v>1</v></c></row>"#);
+
+        assert_eq!(sheet.text_grid(&shared()), [
+            vec!["Name", "Inline", "12.5"],
+            vec!["TRUE", "#DIV/0!", "Name!"],
+            vec!["", "", "Total"],
+        ]);
+    }
+
+    #[test]
+    fn text_grid_pads_rows_to_the_last_column_with_text() {
+        let sheet = sheet(r#"
+            <row r="2"><c r="B2" t="inlineStr"><is><t>x</t></is></c><c r="E2" s="3"/></row>
+            <row r="4"><c r="D4"><v>1</v></c></row>
+            <row r="6"><c r="A6" s="1"/></row>"#);
+
+        let grid = sheet.text_grid(&shared());
+        assert_eq!(grid, [
+            vec!["", "", "", ""],
+            vec!["", "x", "", ""],
+            vec!["", "", "", ""],
+            vec!["", "", "", "1"],
+        ]);
+    }
+
+    #[test]
+    fn text_grid_is_as_wide_as_its_farthest_cell() {
+        let sheet = sheet(r#"<row r="1"><c r="A1"><v>1</v></c></row><row r="3"><c r="XFD3"><v>7</v></c></row>"#);
+        let grid = sheet.text_grid(&shared());
+        assert_eq!(grid.len(), 3);
+        assert!(grid.iter().all(|row| row.len() == 16_384));
+        assert_eq!((grid[0][0].as_str(), grid[2][16_383].as_str()), ("1", "7"));
+        assert_eq!(grid.iter().flatten().filter(|s| !s.is_empty()).count(), 2);
+    }
+
+    #[test]
+    fn text_grid_skips_cells_at_row_zero() {
+        let sheet = sheet(r#"<row r="0"><c><v>1</v></c></row><row r="1"><c r="A1"><v>2</v></c></row>"#);
+        assert_eq!(sheet.text_grid(&shared()), [vec!["2"]]);
+    }
+
+    #[test]
+    fn strict_order_rejects_out_of_order_cells() {
+        let xml = br#"<worksheet><sheetData>
+            <row r="1"><c r="A1"><v>1</v></c><c r="B1"><v>2</v></c></row>
+            <row r="2"><c r="C2"><v>3</v></c><c r="A2"><v>4</v></c></row>
+        </sheetData></worksheet>"#;
+
+        let strict = Worksheet::parse_with_options(xml, &WorksheetOptions::new().strict_order(true));
+        assert!(matches!(strict, Err(WorksheetError::OutOfOrder { row: 2, col: 1, prev_col: 3 })));
+
+        let tolerant = Worksheet::parse(xml).unwrap();
+        assert_eq!(tolerant.rows().len(), 2);
+        assert_eq!(tolerant.rows()[1].cells.len(), 2);
+    }
+
+    #[test]
+    fn strict_order_rejects_repeated_columns() {
+        let xml = br#"<worksheet><sheetData><row r="1"><c r="B1"><v>1</v></c><c r="B1"><v>2</v></c></row></sheetData></worksheet>"#;
+        let strict = Worksheet::parse_with_options(xml, &WorksheetOptions::new().strict_order(true));
+        assert!(matches!(strict, Err(WorksheetError::OutOfOrder { row: 1, col: 2, prev_col: 2 })));
+    }
+
+    #[test]
+    fn visible_text_grid_closes_up_hidden_rows_and_columns() {
+        let sheet = sheet(r#"
+            <row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1"><v>1</v></c><c r="C1"><v>2</v></c><c r="E1"><v>4</v></c></row>
+            <row r="2" hidden="1"><c r="A2"><v>hidden</v></c></row>
+            <row r="3"><c r="C3" t="s"><v>1</v></c></row>"#);
+        let columns = [
+            ColInfo { min: 2, max: 2, hidden: true, ..Default::default() },
+            ColInfo { min: 4, max: 4, hidden: true, ..Default::default() },
+            ColInfo { min: 3, max: 4, width: Some(12.0), ..Default::default() },
+        ];
+
+        assert_eq!(sheet.visible_text_grid(&shared(), &columns), [vec!["Name", "2", "4"], vec!["", "Total"]]);
+    }
+
+    #[test]
+    fn visible_text_grid_merges_overlapping_hidden_ranges() {
+        let sheet = sheet(r#"<row r="1"><c r="A1"><v>1</v></c><c r="F1"><v>6</v></c><c r="XFD1"><v>last</v></c></row>"#);
+        let hidden = |min, max| ColInfo { min, max, hidden: true, ..Default::default() };
+        let columns = [hidden(2, 4), hidden(3, 5), hidden(7, 16383), hidden(100, 200)];
+
+        assert_eq!(sheet.visible_text_grid(&shared(), &columns), [vec!["1", "6", "last"]]);
+        assert_eq!(sheet.visible_text_grid(&shared(), &[hidden(1, 16384)]), Vec::<Vec<String>>::new());
+    }
+
+    #[test]
+    fn visible_text_grid_skips_cells_at_row_zero() {
+        let sheet = sheet(r#"<row r="0" hidden="1"/><row r="0"><c><v>0</v></c></row><row r="1"><c r="B1"><v>1</v></c></row>"#);
+        assert_eq!(sheet.visible_text_grid(&shared(), &[]), [vec!["", "1"]]);
+    }
+
+    #[test]
+    fn hidden_rows_can_be_skipped_when_reading_a_filtered_sheet() {
+        let xml = br#"<worksheet><sheetData>
+            <row r="1"><c r="A1"><v>1</v></c></row>
+            <row r="2" hidden="1"><c r="A2"><v>2</v></c></row>
+            <row r="3" hidden="true"><c r="A3"><v>3</v></c></row>
+            <row r="4" hidden="0"><c r="A4"><v>4</v></c></row>
+        </sheetData><autoFilter ref="A1:A4"><filterColumn colId="0"><filters><filter val="1"/><filter val="4"/></filters></filterColumn></autoFilter></worksheet>"#;
+
+        let all = Worksheet::parse(xml).unwrap();
+        assert_eq!(all.rows().iter().map(Row::is_hidden).collect::<Vec<_>>(), [false, true, true, false]);
+        assert_eq!(all.visible_rows().map(|r| r.index).collect::<Vec<_>>(), [1, 4]);
+        assert!(all.cell(2, 1).is_some());
+
+        let visible = Worksheet::parse_with_options(xml, &WorksheetOptions::new().skip_hidden_rows(true)).unwrap();
+        assert_eq!(visible.rows().iter().map(|r| r.index).collect::<Vec<_>>(), [1, 4]);
+        assert!(visible.cell(2, 1).is_none());
+
+        let filter = Worksheet::auto_filter(xml).unwrap().unwrap();
+        assert!(matches!(&filter.column(1).unwrap().criteria, FilterCriteria::Values { values, .. } if values == &["1", "4"]));
+    }
+
+    #[test]
+    fn dimensions_trusts_the_declared_range_only_when_it_covers_the_cells() {
+        let part = |dimension: &str| format!(r#"<worksheet>{dimension}<sheetData>
+            <row r="2"><c r="C2"><v>1</v></c></row>
+            <row r="5"><c r="B5"><v>2</v></c><c r="F5"><v>3</v></c></row>
+        </sheetData></worksheet>"#);
+        let range = |a1: &str| CellRange::parse(a1);
+        let used = range("B2:F5");
+
+        for (dimension, declared, expected) in [
+            (r#"<dimension ref="A1:H20"/>"#, range("A1:H20"), range("A1:H20")),
+            (r#"<dimension ref="B2:F5"/>"#, used, used),
+            // The `A1` many writers emit, a stale range and no range at all.
+            (r#"<dimension ref="A1"/>"#, range("A1"), used),
+            (r#"<dimension ref="C1:F5"/>"#, range("C1:F5"), used),
+            (r#"<dimension ref="not a range"/>"#, None, used),
+            ("", None, used),
+        ] {
+            let xml = part(dimension);
+            assert_eq!(Worksheet::dimension(xml.as_bytes()).unwrap(), declared, "{dimension}");
+            let sheet = Worksheet::parse(xml.as_bytes()).unwrap();
+            assert_eq!(sheet.declared_dimension(), declared, "{dimension}");
+            assert_eq!(sheet.used_range(), used, "{dimension}");
+            assert_eq!(sheet.dimensions(), expected, "{dimension}");
+        }
+
+        let empty = br#"<worksheet><dimension ref="A1"/><sheetData/></worksheet>"#;
+        assert_eq!(Worksheet::dimension(empty).unwrap(), range("A1"));
+        let sheet = Worksheet::parse(empty).unwrap();
+        assert_eq!((sheet.used_range(), sheet.dimensions()), (None, None));
+        // A dimension after the sheet data is not the sheet's.
+        assert_eq!(Worksheet::dimension(br#"<worksheet><sheetData/><dimension ref="A1:B2"/></worksheet>"#).unwrap(), None);
+    }
+}
@@ -0,0 +1,37 @@
+/// Options controlling how a [`Worksheet`](super::Worksheet) is parsed.
+///
+/// Options are set in a builder‑style fashion and passed to
+/// [`Worksheet::parse_with_options`](super::Worksheet::parse_with_options).
+/// The default is as lenient as [`Worksheet::parse`](super::Worksheet::parse).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorksheetOptions {
+    pub(super) strict_order: bool,
+    pub(super) skip_hidden_rows: bool,
+}
+
+impl WorksheetOptions {
+    /// Creates the default, lenient options.
+    pub fn new() -> Self { Self::default() }
+
+    /// Rejects rows whose cells are not in strictly increasing column order.
+    ///
+    /// Excel always writes cells left to right, and consumers (including
+    /// [`Row::cells`](super::Row::cells) users that binary‑search a row) may
+    /// rely on it. Out‑of‑order or duplicate references only appear in corrupt
+    /// or hand‑edited files; by default they are kept as found.
+    pub fn strict_order(mut self, enabled: bool) -> Self {
+        self.strict_order = enabled;
+        self
+    }
+
+    /// Leaves out rows that are hidden (`hidden="1"`), so the sheet holds
+    /// what Excel shows with its filter applied.
+    ///
+    /// Excel records rows hidden by a filter and rows hidden by the user
+    /// the same way; both are skipped. Cell lookups then find nothing in
+    /// hidden rows.
+    pub fn skip_hidden_rows(mut self, enabled: bool) -> Self {
+        self.skip_hidden_rows = enabled;
+        self
+    }
+}
@@ -0,0 +1,75 @@
+use quick_xml::{Reader, events::Event};
+use super::super::color::Color;
+use super::super::xml_utils::attr_value;
+
+/// Sheet‑level properties (`<sheetPr>`) of a worksheet.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SheetPr {
+    /// Color of the sheet tab (`<tabColor>`), if set.
+    pub tab_color: Option<Color>,
+    /// Name of the sheet's VBA module (`codeName`), if set. Unlike the tab
+    /// name, it stays stable when the sheet is renamed.
+    pub code_name: Option<String>,
+}
+
+impl SheetPr {
+    /// Reads the `<sheetPr>` element of a worksheet part; all fields are
+    /// `None` if it is absent.
+    pub(super) fn parse(xml: &[u8]) -> Result<Self, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+        let mut buf = Vec::new();
+        let mut props = SheetPr::default();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) => match e.local_name().as_ref() {
+                    b"sheetPr" => props.code_name = attr_value(e, b"codeName"),
+                    b"tabColor" => props.tab_color = Color::from_element(e),
+                    // `<sheetPr>` is the first child of `<worksheet>`; stop once past it.
+                    b"dimension" | b"sheetViews" | b"sheetFormatPr" | b"cols" | b"sheetData" => break,
+                    _ => {}
+                },
+                Event::End(ref e) if e.local_name().as_ref() == b"sheetPr" => break,
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(props)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_tab_color_and_code_name() {
+        let xml = br#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+            <sheetPr codeName="Sheet1"><tabColor rgb="FFFF0000"/><pageSetUpPr fitToPage="1"/></sheetPr>
+            <dimension ref="A1"/><sheetData/>
+        </worksheet>"#;
+
+        assert_eq!(SheetPr::parse(xml).unwrap(), SheetPr {
+            tab_color: Some(Color::Argb(0xFFFF_0000)),
+            code_name: Some("Sheet1".into()),
+        });
+    }
+
+    #[test]
+    fn reads_a_theme_tab_color() {
+        let xml = br#"<worksheet><sheetPr><tabColor theme="4" tint="-0.25"/></sheetPr><sheetData/></worksheet>"#;
+        let props = SheetPr::parse(xml).unwrap();
+        assert_eq!(props.tab_color, Some(Color::Theme { index: 4, tint: -0.25 }));
+        assert_eq!(props.code_name, None);
+    }
+
+    #[test]
+    fn ignores_colors_past_the_sheet_properties() {
+        let xml = br#"<worksheet><dimension ref="A1"/><sheetData/><tabColor rgb="FF00FF00"/></worksheet>"#;
+        assert_eq!(SheetPr::parse(xml).unwrap(), SheetPr::default());
+    }
+}
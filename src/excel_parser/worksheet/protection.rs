@@ -0,0 +1,91 @@
+use quick_xml::{Reader, events::{BytesStart, Event}};
+use super::super::xml_utils::attr_value;
+
+/// Protection settings of a worksheet (`<sheetProtection>`).
+///
+/// The flags state which aspects are locked; a sheet without a
+/// `<sheetProtection>` element is not protected at all. The password is never
+/// stored in clear text, only as a hash.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SheetProtection {
+    /// Cell contents are locked (`sheet="1"`).
+    pub sheet: bool,
+    /// Drawing objects are locked (`objects="1"`).
+    pub objects: bool,
+    /// Scenarios are locked (`scenarios="1"`).
+    pub scenarios: bool,
+    /// Password hash, if a password is set: the legacy 16‑bit hex hash
+    /// (`password`) or the base64 `hashValue` of newer files.
+    pub password_hash: Option<String>,
+}
+
+impl SheetProtection {
+    /// Finds and parses the `<sheetProtection>` element of a worksheet part.
+    ///
+    /// Returns `Ok(None)` if the sheet has no protection element.
+    pub(super) fn parse(xml: &[u8]) -> Result<Option<Self>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) if e.local_name().as_ref() == b"sheetProtection" => {
+                    return Ok(Some(Self::from_element(e)));
+                }
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+
+            buf.clear();
+        }
+    }
+
+    /// Reads the flags and password hash from the element's attributes.
+    fn from_element(e: &BytesStart<'_>) -> Self {
+        let flag = |name: &[u8]| attr_value(e, name).is_some_and(|v| v == "1" || v == "true");
+
+        Self {
+            sheet: flag(b"sheet"),
+            objects: flag(b"objects"),
+            scenarios: flag(b"scenarios"),
+            password_hash: attr_value(e, b"password").or_else(|| attr_value(e, b"hashValue")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_protected_sheet() {
+        let xml = br#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+            <sheetData><row r="1"><c r="A1"><v>1</v></c></row></sheetData>
+            <sheetProtection password="CC1A" sheet="1" objects="1" scenarios="0"/>
+        </worksheet>"#;
+
+        let protection = SheetProtection::parse(xml).unwrap().unwrap();
+        assert_eq!(protection, SheetProtection {
+            sheet: true,
+            objects: true,
+            scenarios: false,
+            password_hash: Some("CC1A".into()),
+        });
+    }
+
+    #[test]
+    fn reads_the_hash_of_newer_files() {
+        let xml = br#"<worksheet><sheetProtection algorithmName="SHA-512" hashValue="kG2X+Q==" saltValue="c2FsdA==" spinCount="100000" sheet="true" scenarios="true"/></worksheet>"#;
+
+        let protection = SheetProtection::parse(xml).unwrap().unwrap();
+        assert!(protection.sheet && protection.scenarios && !protection.objects);
+        assert_eq!(protection.password_hash.as_deref(), Some("kG2X+Q=="));
+    }
+
+    #[test]
+    fn unprotected_sheet_has_no_protection() {
+        let xml = br#"<worksheet><sheetData><row r="1"><c r="A1"><v>1</v></c></row></sheetData></worksheet>"#;
+        assert_eq!(SheetProtection::parse(xml).unwrap(), None);
+    }
+}
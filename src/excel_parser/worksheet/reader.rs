@@ -0,0 +1,350 @@
+use quick_xml::{Reader, events::{BytesStart, Event}};
+use std::iter::FusedIterator;
+use super::super::cell_ref::{CellRange, MAX_COL, parse_a1};
+use super::super::xml_utils::{attr_value, decode_escapes_owned, push_entity};
+use super::{Cell, CellType, Row};
+
+/// Pull parser over the `<row>` elements of a worksheet part.
+///
+/// Each call to [`next_row`](Self::next_row) reads exactly one `<row>` and
+/// returns it with its cells; everything outside `<sheetData>` rows is
+/// skipped. Rows and cells without an `r` attribute get the position after
+/// the previous one, as Excel does.
+pub(crate) struct RowReader<'a> {
+    reader: Reader<&'a [u8]>,
+    buf: Vec<u8>,
+    /// Number of the last row read (0 before the first).
+    last_row: u32,
+    /// Used range declared by `<dimension>`, once it has been read.
+    dimension: Option<CellRange>,
+    done: bool,
+}
+
+/// Which part of a `<c>` element text events currently belong to.
+#[derive(Clone, Copy, PartialEq)]
+enum TextTarget {
+    None,
+    /// Inside `<v>`.
+    Value,
+    /// Inside `<t>` of an inline string (`<is>`).
+    InlineText,
+    /// Inside `<f>`.
+    Formula,
+}
+
+impl<'a> RowReader<'a> {
+    pub(crate) fn new(xml: &'a [u8]) -> Self {
+        let mut reader = Reader::from_reader(xml);
+        let config = reader.config_mut();
+        // Cell text is significant, including leading/trailing spaces; as in
+        // `SharedStrings::load`, `xml:space` is ignored and text kept verbatim.
+        config.trim_text(false);
+        config.check_end_names = false;
+
+        Self { reader, buf: Vec::new(), last_row: 0, dimension: None, done: false }
+    }
+
+    /// Returns the range declared by the sheet's `<dimension>` element.
+    ///
+    /// The element precedes `<sheetData>`, so it is known once the first
+    /// row has been read.
+    pub(crate) fn dimension(&self) -> Option<CellRange> {
+        self.dimension
+    }
+
+    /// Reads the next row, or `None` at the end of the sheet.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML; the reader is exhausted
+    /// afterwards.
+    pub(crate) fn next_row(&mut self) -> Result<Option<Row>, quick_xml::Error> {
+        if self.done { return Ok(None); }
+
+        loop {
+            let event = match self.reader.read_event_into(&mut self.buf) {
+                Ok(event) => event,
+                Err(e) => { self.done = true; return Err(e); }
+            };
+
+            match event {
+                Event::Start(ref e) if e.name().as_ref() == b"row" => {
+                    let mut row = Self::start_row(e, self.last_row);
+                    self.last_row = row.index;
+                    self.buf.clear();
+                    self.read_cells(&mut row)?;
+                    return Ok(Some(row));
+                }
+                Event::Empty(ref e) if e.name().as_ref() == b"row" => {
+                    let row = Self::start_row(e, self.last_row);
+                    self.last_row = row.index;
+                    self.buf.clear();
+                    return Ok(Some(row));
+                }
+                Event::Start(ref e) | Event::Empty(ref e) if e.name().as_ref() == b"dimension" => {
+                    self.dimension = attr_value(e, b"ref").and_then(|r| CellRange::parse(&r));
+                }
+                Event::Eof => { self.done = true; return Ok(None); }
+                _ => {}
+            }
+
+            self.buf.clear();
+        }
+    }
+
+    /// Reads only the `<dimension>` of a worksheet part, stopping at
+    /// `<sheetData>`.
+    pub(crate) fn read_dimension(xml: &[u8]) -> Result<Option<CellRange>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) => match e.name().as_ref() {
+                    b"dimension" => return Ok(attr_value(e, b"ref").and_then(|r| CellRange::parse(&r))),
+                    b"sheetData" => return Ok(None),
+                    _ => {}
+                },
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+
+            buf.clear();
+        }
+    }
+
+    /// Creates a row from its start tag, numbered after `last_row` when it
+    /// has no `r` attribute.
+    ///
+    /// The cell vector is pre‑sized from `spans`, which bounds the number of
+    /// cells in the row. The attribute is untrusted (`spans="1:16384"` on a
+    /// row of one cell is valid), so the hint is capped at
+    /// [`MAX_SPANS_CAPACITY`] and longer rows grow as usual.
+    fn start_row(e: &BytesStart<'_>, last_row: u32) -> Row {
+        let index = attr_value(e, b"r")
+            .and_then(|r| r.parse().ok())
+            .unwrap_or(last_row + 1);
+        let spans = attr_value(e, b"spans").and_then(|s| parse_spans(&s));
+        let capacity = spans.map_or(0, |(first, last)| (last - first + 1) as usize).min(MAX_SPANS_CAPACITY);
+        let flag = |name: &[u8]| attr_value(e, name).is_some_and(|v| v == "1" || v == "true");
+
+        Row {
+            index,
+            spans,
+            hidden: flag(b"hidden"),
+            height: attr_value(e, b"ht").and_then(|v| v.trim().parse().ok()),
+            outline_level: attr_value(e, b"outlineLevel").and_then(|v| v.trim().parse().ok()).unwrap_or(0),
+            collapsed: flag(b"collapsed"),
+            cells: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Reads cells until the closing `</row>`.
+    fn read_cells(&mut self, row: &mut Row) -> Result<(), quick_xml::Error> {
+        let mut cell: Option<Cell> = None;
+        let mut text = String::new();
+        let mut formula = String::new();
+        let mut target = TextTarget::None;
+        let mut in_inline = false;
+        // Phonetic runs (`<rPh>`) of an inline string hold their own `<t>`,
+        // which is not part of the text.
+        let mut in_phonetic = false;
+        // Whether the current cell has a `<v>` or `<is>` child (possibly empty).
+        let mut has_value = false;
+
+        loop {
+            let event = match self.reader.read_event_into(&mut self.buf) {
+                Ok(event) => event,
+                Err(e) => { self.done = true; return Err(e); }
+            };
+
+            match event {
+                Event::Start(ref e) => match e.name().as_ref() {
+                    b"c" => {
+                        cell = Some(Self::start_cell(e, row));
+                        text.clear();
+                        formula.clear();
+                        has_value = false;
+                    }
+                    b"f" if cell.is_some() => target = TextTarget::Formula,
+                    b"v" if cell.is_some() => { target = TextTarget::Value; has_value = true; }
+                    b"is" if let Some(c) = cell.as_mut() => {
+                        Self::mark_inline(c);
+                        in_inline = true;
+                        has_value = true;
+                    }
+                    b"rPh" if in_inline => in_phonetic = true,
+                    b"t" if in_inline && !in_phonetic => target = TextTarget::InlineText,
+                    _ => {}
+                },
+                Event::Empty(ref e) => match e.name().as_ref() {
+                    b"c" => row.cells.push(Self::start_cell(e, row)),
+                    b"v" if cell.is_some() => has_value = true,
+                    b"is" if let Some(c) = cell.as_mut() => {
+                        Self::mark_inline(c);
+                        has_value = true;
+                    }
+                    _ => {}
+                },
+                Event::Text(ref e) if target == TextTarget::Formula => {
+                    formula.push_str(&String::from_utf8_lossy(e));
+                }
+                Event::GeneralRef(ref e) if target == TextTarget::Formula => push_entity(&mut formula, e),
+                Event::Text(ref e) if target != TextTarget::None => {
+                    text.push_str(&String::from_utf8_lossy(e));
+                }
+                Event::CData(ref e) if target != TextTarget::None => {
+                    text.push_str(&String::from_utf8_lossy(e));
+                }
+                Event::GeneralRef(ref e) if target != TextTarget::None => push_entity(&mut text, e),
+                Event::End(ref e) => match e.name().as_ref() {
+                    b"v" | b"t" | b"f" => target = TextTarget::None,
+                    b"is" => in_inline = false,
+                    b"rPh" => in_phonetic = false,
+                    b"c" => {
+                        if let Some(mut c) = cell.take() {
+                            if has_value {
+                                let mut raw = std::mem::take(&mut text);
+                                // Like shared strings, inline text escapes
+                                // characters XML can't hold as `_xHHHH_`.
+                                if c.cell_type == CellType::InlineString { raw = decode_escapes_owned(raw); }
+                                c.raw = Some(raw.into_boxed_str());
+                            }
+                            // Followers of a shared formula have an empty
+                            // `<f t="shared" si="0"/>` and get no text.
+                            if !formula.is_empty() { c.formula = Some(std::mem::take(&mut formula).into_boxed_str()); }
+                            row.cells.push(c);
+                        }
+                    }
+                    b"row" => break,
+                    _ => {}
+                },
+                Event::Eof => { self.done = true; break; }
+                _ => {}
+            }
+
+            self.buf.clear();
+        }
+
+        self.buf.clear();
+        Ok(())
+    }
+
+    /// Types a cell with an `<is>` child as an inline string: some writers
+    /// leave out `t="inlineStr"`, which would make its text a number.
+    fn mark_inline(cell: &mut Cell) {
+        if cell.cell_type == CellType::Number { cell.cell_type = CellType::InlineString; }
+    }
+
+    /// Creates a cell from its start tag, positioned after the previous cell
+    /// of the row when it has no `r` attribute.
+    fn start_cell(e: &BytesStart<'_>, row: &Row) -> Cell {
+        let (row_no, col) = attr_value(e, b"r")
+            .and_then(|r| parse_a1(&r))
+            .unwrap_or_else(|| (row.index, row.cells.last().map_or(1, |c| c.col + 1)));
+        let cell_type = e.try_get_attribute(b"t").ok().flatten()
+            .map_or(CellType::Number, |a| CellType::from_attr(&a.value));
+        let style = attr_value(e, b"s").and_then(|s| s.parse().ok()).unwrap_or(0);
+
+        Cell { row: row_no, col, cell_type, style, raw: None, formula: None }
+    }
+}
+
+/// Largest cell vector pre‑allocated from a row's `spans` attribute.
+const MAX_SPANS_CAPACITY: usize = 64;
+
+/// Parses a `spans` attribute into inclusive `(first, last)` column bounds.
+///
+/// The attribute is a space‑separated list of `first:last` ranges (usually a
+/// single one); the result covers all of them. Returns `None` for malformed
+/// values, reversed ranges or columns outside `1..=MAX_COL`.
+fn parse_spans(spans: &str) -> Option<(u32, u32)> {
+    let mut bounds: Option<(u32, u32)> = None;
+    for range in spans.split_ascii_whitespace() {
+        let (first, last) = range.split_once(':')?;
+        let (first, last): (u32, u32) = (first.parse().ok()?, last.parse().ok()?);
+        if first == 0 || first > last || last > MAX_COL { return None; }
+
+        bounds = Some(bounds.map_or((first, last), |(a, b)| (a.min(first), b.max(last))));
+    }
+    bounds
+}
+
+/// Streaming parser over the rows of a worksheet part, created by
+/// [`Worksheet::rows_streaming`](super::Worksheet::rows_streaming).
+///
+/// Yields each `<row>` with its cells as soon as it has been read, so a
+/// sheet can be exported or scanned holding one row at a time instead of
+/// the whole grid. Rows are parsed exactly as by
+/// [`Worksheet::parse`](super::Worksheet::parse), which is built on the same
+/// reader, and come in document order.
+///
+/// After an error the iterator is exhausted; rows yielded before it remain
+/// valid.
+///
+/// # Example
+/// ```no_run
+/// # use excel_parser::{SharedStrings, Worksheet};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let shared = SharedStrings::load(&std::fs::read("xl/sharedStrings.xml")?)?;
+/// let xml = std::fs::read("xl/worksheets/sheet1.xml")?;
+/// let mut total = 0.0;
+/// for row in Worksheet::rows_streaming(&xml) {
+///     total += row?.cells().iter().filter_map(|c| c.value(&shared).as_f64()).sum::<f64>();
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct RowIter<'x> {
+    reader: RowReader<'x>,
+}
+
+impl<'x> RowIter<'x> {
+    pub(super) fn new(xml: &'x [u8]) -> Self {
+        Self { reader: RowReader::new(xml) }
+    }
+}
+
+impl Iterator for RowIter<'_> {
+    type Item = Result<Row, quick_xml::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.next_row().transpose()
+    }
+}
+
+impl FusedIterator for RowIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_row(xml: &str) -> Row {
+        RowReader::new(xml.as_bytes()).next_row().unwrap().unwrap()
+    }
+
+    #[test]
+    fn spans_pre_size_the_cell_vector() {
+        let row = first_row(r#"<sheetData><row r="1" spans="1:3"><c r="A1"><v>1</v></c><c r="B1"><v>2</v></c><c r="C1"><v>3</v></c></row></sheetData>"#);
+        assert_eq!(row.spans, Some((1, 3)));
+        assert_eq!(row.cells.len(), 3);
+        assert_eq!(row.cells.capacity(), 3);
+    }
+
+    #[test]
+    fn spans_capacity_is_capped() {
+        let row = first_row(r#"<sheetData><row r="1" spans="1:16384"><c r="A1"><v>1</v></c></row></sheetData>"#);
+        assert_eq!(row.spans, Some((1, 16384)));
+        assert_eq!(row.cells.len(), 1);
+        assert_eq!(row.cells.capacity(), MAX_SPANS_CAPACITY);
+    }
+
+    #[test]
+    fn malformed_spans_are_ignored() {
+        for spans in ["", "3:1", "0:2", "a:b", "1:99999", "1"] {
+            let row = first_row(&format!(r#"<sheetData><row r="1" spans="{spans}"><c r="A1"><v>1</v></c></row></sheetData>"#));
+            assert_eq!(row.spans, None, "{spans:?}");
+            assert_eq!(row.cells.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use quick_xml::{Reader, events::{BytesStart, Event}};
+use super::super::cell_ref::CellRange;
+use super::super::workbook_info::RangeRef;
+use super::super::xml_utils::{attr_value, push_entity};
+
+/// What a data validation rule allows (`type` attribute).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationType {
+    /// Any value; the rule only shows an input message (no `type`).
+    #[default]
+    Any,
+    /// Whole numbers (`whole`).
+    Whole,
+    /// Numbers (`decimal`).
+    Decimal,
+    /// One of a list of values (`list`), offered in a dropdown.
+    List,
+    /// Dates (`date`).
+    Date,
+    /// Times (`time`).
+    Time,
+    /// Text of a length (`textLength`).
+    TextLength,
+    /// Values for which a formula is true (`custom`).
+    Custom,
+}
+
+impl ValidationType {
+    /// Maps the value of the `type` attribute; unknown values allow anything.
+    fn from_attr(kind: &str) -> Self {
+        match kind {
+            "whole" => Self::Whole,
+            "decimal" => Self::Decimal,
+            "list" => Self::List,
+            "date" => Self::Date,
+            "time" => Self::Time,
+            "textLength" => Self::TextLength,
+            "custom" => Self::Custom,
+            _ => Self::Any,
+        }
+    }
+}
+
+/// How a value is compared with the rule's formulas (`operator` attribute).
+///
+/// Only meaningful for numeric, date, time and text length rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationOperator {
+    /// Between `formula1` and `formula2`, inclusive (the default).
+    #[default]
+    Between,
+    /// Outside `formula1`..=`formula2`.
+    NotBetween,
+    /// Equal to `formula1`.
+    Equal,
+    /// Not equal to `formula1`.
+    NotEqual,
+    /// Greater than `formula1`.
+    GreaterThan,
+    /// Less than `formula1`.
+    LessThan,
+    /// Greater than or equal to `formula1`.
+    GreaterThanOrEqual,
+    /// Less than or equal to `formula1`.
+    LessThanOrEqual,
+}
+
+impl ValidationOperator {
+    /// Maps the value of the `operator` attribute; unknown values are
+    /// treated as `between`.
+    fn from_attr(operator: &str) -> Self {
+        match operator {
+            "notBetween" => Self::NotBetween,
+            "equal" => Self::Equal,
+            "notEqual" => Self::NotEqual,
+            "greaterThan" => Self::GreaterThan,
+            "lessThan" => Self::LessThan,
+            "greaterThanOrEqual" => Self::GreaterThanOrEqual,
+            "lessThanOrEqual" => Self::LessThanOrEqual,
+            _ => Self::Between,
+        }
+    }
+}
+
+/// What happens when an invalid value is entered (`errorStyle` attribute).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationErrorStyle {
+    /// The value is rejected (the default).
+    #[default]
+    Stop,
+    /// The user is warned and may keep the value.
+    Warning,
+    /// The user is informed and the value is kept.
+    Information,
+}
+
+impl ValidationErrorStyle {
+    /// Maps the value of the `errorStyle` attribute; unknown values are
+    /// treated as `stop`.
+    fn from_attr(style: &str) -> Self {
+        match style {
+            "warning" => Self::Warning,
+            "information" => Self::Information,
+            _ => Self::Stop,
+        }
+    }
+}
+
+/// Where the options of a list rule come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListSource {
+    /// Options typed into the rule (`"Yes,No"`).
+    Items(Vec<String>),
+    /// Options taken from a range. A range without a sheet is on the
+    /// validated sheet.
+    Range(RangeRef),
+    /// Any other formula, typically a defined name (`Options`) or
+    /// `INDIRECT(...)`.
+    Formula(String),
+}
+
+/// A data validation rule (`<dataValidation>`) and the cells it applies to.
+///
+/// Rules are read from `<dataValidations>` and, as Excel 2010 writes lists
+/// that refer to other sheets, from `x14:dataValidations` in `<extLst>`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DataValidation {
+    /// Cells the rule applies to (`sqref`).
+    pub ranges: Vec<CellRange>,
+    /// What the rule allows.
+    pub kind: ValidationType,
+    /// How values are compared with the formulas.
+    pub operator: ValidationOperator,
+    /// First formula, without `=`: the list of a list rule (`"\"Yes,No\""`,
+    /// `$D$1:$D$5`), the value or lower bound of a comparison, or the
+    /// condition of a custom rule.
+    pub formula1: Option<String>,
+    /// Second formula: the upper bound of `between` and `notBetween`.
+    pub formula2: Option<String>,
+    /// Whether empty cells are valid (`allowBlank`).
+    pub allow_blank: bool,
+    /// Whether a list rule shows its in‑cell dropdown. Note that Excel's
+    /// `showDropDown="1"` *hides* the dropdown; this is its negation.
+    pub show_dropdown: bool,
+    /// Whether the input message is shown when a cell is selected.
+    pub show_input_message: bool,
+    /// Whether the error alert is shown for invalid values.
+    pub show_error_message: bool,
+    /// Title of the input message (`promptTitle`).
+    pub prompt_title: Option<String>,
+    /// Input message text (`prompt`).
+    pub prompt: Option<String>,
+    /// Title of the error alert (`errorTitle`).
+    pub error_title: Option<String>,
+    /// Error alert text (`error`).
+    pub error: Option<String>,
+    /// What happens on an invalid value.
+    pub error_style: ValidationErrorStyle,
+}
+
+/// Element of a `<dataValidation>` whose text is being read.
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    None,
+    Formula1,
+    Formula2,
+    Sqref,
+}
+
+impl DataValidation {
+    /// Reads all data validation rules of a worksheet part, in document order.
+    pub(super) fn parse_all(xml: &[u8]) -> Result<Vec<Self>, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        let config = reader.config_mut();
+        config.trim_text(false);
+        config.check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut rules = Vec::new();
+        let mut current: Option<DataValidation> = None;
+        let mut field = Field::None;
+        let mut text = String::new();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) => match e.local_name().as_ref() {
+                    b"dataValidation" => current = Some(Self::from_element(e)),
+                    b"formula1" if current.is_some() => field = Field::Formula1,
+                    b"formula2" if current.is_some() => field = Field::Formula2,
+                    // `<xm:sqref>` of x14 rules.
+                    b"sqref" if current.is_some() => field = Field::Sqref,
+                    _ => {}
+                },
+                Event::Empty(ref e) if e.local_name().as_ref() == b"dataValidation" => {
+                    rules.push(Self::from_element(e));
+                }
+                Event::Text(ref e) if field != Field::None => text.push_str(&String::from_utf8_lossy(e)),
+                Event::GeneralRef(ref e) if field != Field::None => push_entity(&mut text, e),
+                Event::End(ref e) => match e.local_name().as_ref() {
+                    b"formula1" | b"formula2" | b"sqref" if field != Field::None => {
+                        if let Some(rule) = current.as_mut() {
+                            let value = std::mem::take(&mut text);
+                            match field {
+                                Field::Formula1 => rule.formula1 = Some(value),
+                                Field::Formula2 => rule.formula2 = Some(value),
+                                Field::Sqref => rule.ranges = parse_sqref(&value),
+                                Field::None => {}
+                            }
+                        }
+                        field = Field::None;
+                        text.clear();
+                    }
+                    b"dataValidation" => rules.extend(current.take()),
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(rules)
+    }
+
+    /// Reads the attributes of a `<dataValidation>` element.
+    fn from_element(e: &BytesStart<'_>) -> Self {
+        let flag = |name: &[u8]| attr_value(e, name).is_some_and(|v| v == "1" || v == "true");
+        Self {
+            ranges: attr_value(e, b"sqref").map(|s| parse_sqref(&s)).unwrap_or_default(),
+            kind: attr_value(e, b"type").map_or_else(ValidationType::default, |v| ValidationType::from_attr(&v)),
+            operator: attr_value(e, b"operator").map_or_else(ValidationOperator::default, |v| ValidationOperator::from_attr(&v)),
+            formula1: None,
+            formula2: None,
+            allow_blank: flag(b"allowBlank"),
+            show_dropdown: !flag(b"showDropDown"),
+            show_input_message: flag(b"showInputMessage"),
+            show_error_message: flag(b"showErrorMessage"),
+            prompt_title: attr_value(e, b"promptTitle"),
+            prompt: attr_value(e, b"prompt"),
+            error_title: attr_value(e, b"errorTitle"),
+            error: attr_value(e, b"error"),
+            error_style: attr_value(e, b"errorStyle").map_or_else(ValidationErrorStyle::default, |v| ValidationErrorStyle::from_attr(&v)),
+        }
+    }
+
+    /// Returns `true` if the rule applies to the cell at 1‑based
+    /// `(row, col)`.
+    pub fn applies_to(&self, row: u32, col: u32) -> bool {
+        self.ranges.iter().any(|r| r.contains(row, col))
+    }
+
+    /// Returns where the options of a list rule come from.
+    ///
+    /// # Returns
+    /// `None` if the rule is not a list or has no formula.
+    pub fn list_source(&self) -> Option<ListSource> {
+        if self.kind != ValidationType::List { return None; }
+        let formula = self.formula1.as_deref()?.trim();
+        let formula = formula.strip_prefix('=').unwrap_or(formula);
+
+        if let Some(items) = formula.strip_prefix('"').and_then(|f| f.strip_suffix('"')) {
+            let items = items.replace("\"\"", "\"");
+            return Some(ListSource::Items(items.split(',').map(str::to_owned).collect()));
+        }
+        Some(match RangeRef::parse(formula) {
+            Some(range) => ListSource::Range(range),
+            None => ListSource::Formula(formula.to_owned()),
+        })
+    }
+}
+
+/// Parses a space‑separated list of ranges (`"A1:A10 C1"`), skipping
+/// invalid entries.
+fn parse_sqref(sqref: &str) -> Vec<CellRange> {
+    sqref.split_whitespace().filter_map(CellRange::parse).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:x14="http://schemas.microsoft.com/office/spreadsheetml/2009/9/main" xmlns:xm="http://schemas.microsoft.com/office/excel/2006/main">
+  <sheetData/>
+  <dataValidations count="3">
+    <dataValidation type="list" allowBlank="1" showInputMessage="1" showErrorMessage="1" sqref="B2:B10 D2" promptTitle="Status" prompt="Pick one">
+      <formula1>"Yes,No,""Maybe"""</formula1>
+    </dataValidation>
+    <dataValidation type="whole" operator="notBetween" errorStyle="warning" showDropDown="1" sqref="C2:C10" error="1 &amp;lt; x">
+      <formula1>1</formula1><formula2>$E$1&amp;""</formula2>
+    </dataValidation>
+    <dataValidation sqref="A1" prompt="Note only"/>
+  </dataValidations>
+  <extLst><ext uri="{CCE6A557-97BC-4b89-ADB6-D9C93CAAB3DF}"><x14:dataValidations count="1">
+    <x14:dataValidation type="list" allowBlank="1">
+      <x14:formula1><xm:f>Lists!$A$1:$A$3</xm:f></x14:formula1>
+      <xm:sqref>F2:F5</xm:sqref>
+    </x14:dataValidation>
+  </x14:dataValidations></ext></extLst>
+</worksheet>"#;
+
+    fn rules() -> Vec<DataValidation> {
+        DataValidation::parse_all(RULES.as_bytes()).unwrap()
+    }
+
+    fn range(a1: &str) -> CellRange {
+        CellRange::parse(a1).unwrap()
+    }
+
+    #[test]
+    fn parse_all_reads_rules_in_document_order() {
+        let rules = rules();
+        assert_eq!(rules.len(), 4);
+
+        let list = &rules[0];
+        assert_eq!((list.kind, list.operator), (ValidationType::List, ValidationOperator::Between));
+        assert_eq!(list.ranges, [range("B2:B10"), range("D2")]);
+        assert!(list.allow_blank && list.show_dropdown && list.show_input_message && list.show_error_message);
+        assert_eq!((list.prompt_title.as_deref(), list.prompt.as_deref()), (Some("Status"), Some("Pick one")));
+
+        let whole = &rules[1];
+        assert_eq!((whole.kind, whole.operator, whole.error_style), (ValidationType::Whole, ValidationOperator::NotBetween, ValidationErrorStyle::Warning));
+        assert_eq!((whole.formula1.as_deref(), whole.formula2.as_deref()), (Some("1"), Some(r#"$E$1&"""#)));
+        assert_eq!(whole.error.as_deref(), Some("1 &lt; x"));
+        assert!(!whole.show_dropdown && !whole.allow_blank);
+
+        assert_eq!(rules[2], DataValidation { ranges: vec![range("A1")], prompt: Some("Note only".into()), show_dropdown: true, ..DataValidation::default() });
+        assert_eq!(rules[3].ranges, [range("F2:F5")]);
+        assert_eq!(rules[3].formula1.as_deref(), Some("Lists!$A$1:$A$3"));
+    }
+
+    #[test]
+    fn applies_to_checks_every_range() {
+        let list = &rules()[0];
+        assert!(list.applies_to(2, 2) && list.applies_to(10, 2) && list.applies_to(2, 4));
+        assert!(!list.applies_to(11, 2) && !list.applies_to(3, 4) && !list.applies_to(2, 3));
+    }
+
+    #[test]
+    fn list_source_tells_items_ranges_and_formulas_apart() {
+        let rules = rules();
+        assert_eq!(rules[0].list_source(), Some(ListSource::Items(vec!["Yes".into(), "No".into(), "\"Maybe\"".into()])));
+        assert_eq!(rules[3].list_source(), Some(ListSource::Range(RangeRef { sheet: Some("Lists".into()), range: range("A1:A3") })));
+        assert_eq!(rules[1].list_source(), None);
+
+        let list = |formula: &str| DataValidation { kind: ValidationType::List, formula1: Some(formula.into()), ..DataValidation::default() };
+        assert_eq!(list("=$D$1:$D$5").list_source(), Some(ListSource::Range(RangeRef { sheet: None, range: range("D1:D5") })));
+        assert_eq!(list("Options").list_source(), Some(ListSource::Formula("Options".into())));
+        assert_eq!(list(r#"INDIRECT("A"&1)"#).list_source(), Some(ListSource::Formula(r#"INDIRECT("A"&1)"#.into())));
+        assert_eq!(DataValidation { kind: ValidationType::List, ..DataValidation::default() }.list_source(), None);
+    }
+}
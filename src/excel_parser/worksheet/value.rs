@@ -0,0 +1,230 @@
+use std::fmt;
+use super::super::datetime::DateTime;
+
+/// Typed value of a cell, as returned by [`Cell::value`](super::Cell::value).
+///
+/// Strings borrow from the sheet or the shared strings table, so a value
+/// lives as long as both.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue<'a> {
+    /// No value: a cell that only carries a style, or a shared string
+    /// reference that is missing from the table.
+    Empty,
+    /// Text: a shared, inline or formula string.
+    String(&'a str),
+    /// A number. Dates stored as serial numbers are numbers too until their
+    /// format is taken into account.
+    Number(f64),
+    /// `TRUE` or `FALSE`.
+    Bool(bool),
+    /// An error value such as `#N/A`.
+    Error(CellError),
+    /// A date or time stored as such (`t="d"`).
+    DateTime(DateTime),
+    /// A formula with the value Excel last calculated for it.
+    ///
+    /// `text` is the formula without the leading `=`. Cells of a shared
+    /// formula other than the first carry no text of their own and are
+    /// returned as their plain cached value instead.
+    Formula {
+        /// Formula text (`SUM(A1:A3)`).
+        text: &'a str,
+        /// Cached result; [`Empty`](CellValue::Empty) if never calculated.
+        cached: Box<CellValue<'a>>,
+    },
+}
+
+impl<'a> CellValue<'a> {
+    /// The value itself, or the cached result of a formula.
+    pub fn resolved(&self) -> &CellValue<'a> {
+        match self {
+            CellValue::Formula { cached, .. } => cached.resolved(),
+            value => value,
+        }
+    }
+
+    /// Returns `true` for [`Empty`](CellValue::Empty), or a formula without a
+    /// cached result.
+    pub fn is_empty(&self) -> bool {
+        matches!(self.resolved(), CellValue::Empty)
+    }
+
+    /// The number, if the value (or a formula's result) is one.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.resolved() {
+            CellValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The text, if the value (or a formula's result) is a string.
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.resolved() {
+            CellValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The boolean, if the value (or a formula's result) is one.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.resolved() {
+            CellValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// The date and time, if the value (or a formula's result) is one.
+    pub fn as_datetime(&self) -> Option<DateTime> {
+        match self.resolved() {
+            CellValue::DateTime(dt) => Some(*dt),
+            _ => None,
+        }
+    }
+
+    /// The error, if the value (or a formula's result) is one.
+    pub fn as_error(&self) -> Option<&CellError> {
+        match self.resolved() {
+            CellValue::Error(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// The formula text, if the cell has one.
+    pub fn formula(&self) -> Option<&'a str> {
+        match self {
+            CellValue::Formula { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CellValue<'_> {
+    /// Formats the value as plain text, the way [`Cell::text`](super::Cell::text)
+    /// would show it: numbers in shortest form, booleans as `TRUE`/`FALSE`,
+    /// errors as their code, dates in ISO 8601 and formulas as their result.
+    /// No number format is applied.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellValue::Empty => Ok(()),
+            CellValue::String(s) => f.write_str(s),
+            CellValue::Number(n) => write!(f, "{n}"),
+            CellValue::Bool(b) => f.write_str(if *b { "TRUE" } else { "FALSE" }),
+            CellValue::Error(e) => e.fmt(f),
+            CellValue::DateTime(dt) => dt.fmt(f),
+            CellValue::Formula { cached, .. } => cached.fmt(f),
+        }
+    }
+}
+
+/// Error value of a cell (`t="e"`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CellError {
+    /// `#NULL!` – intersection of ranges that don't intersect.
+    Null,
+    /// `#DIV/0!` – division by zero.
+    Div0,
+    /// `#VALUE!` – wrong type of argument.
+    Value,
+    /// `#REF!` – reference to a deleted or invalid cell.
+    Ref,
+    /// `#NAME?` – unknown function or name.
+    Name,
+    /// `#NUM!` – invalid numeric result.
+    Num,
+    /// `#N/A` – value not available.
+    NA,
+    /// `#GETTING_DATA` – result still being fetched.
+    GettingData,
+    /// `#SPILL!` – a dynamic array can't spill into occupied cells.
+    Spill,
+    /// `#CALC!` – calculation engine error (e.g. an empty array).
+    Calc,
+    /// `#CONNECT!` – a linked data source can't be reached.
+    Connect,
+    /// `#BLOCKED!` – access to a resource is blocked by settings.
+    Blocked,
+    /// `#UNKNOWN!` – a data type this version doesn't support.
+    Unknown,
+    /// `#FIELD!` – a missing field of a linked data type.
+    Field,
+    /// `#BUSY!` – the result is still being computed.
+    Busy,
+    /// Any other code, as stored.
+    Other(Box<str>),
+}
+
+impl CellError {
+    /// Maps an error code as stored in `<v>`; unknown codes are kept as
+    /// [`Other`](CellError::Other).
+    pub(crate) fn from_code(code: &str) -> Self {
+        match code.trim() {
+            "#NULL!" => CellError::Null,
+            "#DIV/0!" => CellError::Div0,
+            "#VALUE!" => CellError::Value,
+            "#REF!" => CellError::Ref,
+            "#NAME?" => CellError::Name,
+            "#NUM!" => CellError::Num,
+            "#N/A" => CellError::NA,
+            "#GETTING_DATA" => CellError::GettingData,
+            "#SPILL!" => CellError::Spill,
+            "#CALC!" => CellError::Calc,
+            "#CONNECT!" => CellError::Connect,
+            "#BLOCKED!" => CellError::Blocked,
+            "#UNKNOWN!" => CellError::Unknown,
+            "#FIELD!" => CellError::Field,
+            "#BUSY!" => CellError::Busy,
+            code => CellError::Other(code.into()),
+        }
+    }
+
+    /// The error code as Excel shows it (`"#DIV/0!"`).
+    pub fn code(&self) -> &str {
+        match self {
+            CellError::Null => "#NULL!",
+            CellError::Div0 => "#DIV/0!",
+            CellError::Value => "#VALUE!",
+            CellError::Ref => "#REF!",
+            CellError::Name => "#NAME?",
+            CellError::Num => "#NUM!",
+            CellError::NA => "#N/A",
+            CellError::GettingData => "#GETTING_DATA",
+            CellError::Spill => "#SPILL!",
+            CellError::Calc => "#CALC!",
+            CellError::Connect => "#CONNECT!",
+            CellError::Blocked => "#BLOCKED!",
+            CellError::Unknown => "#UNKNOWN!",
+            CellError::Field => "#FIELD!",
+            CellError::Busy => "#BUSY!",
+            CellError::Other(code) => code,
+        }
+    }
+
+    /// The number Excel's `ERROR.TYPE` function returns for the error
+    /// (`#NULL!` = 1 … `#CALC!` = 14), for matching errors the way formulas
+    /// do. `None` for `#BUSY!` and unknown codes.
+    pub fn error_type(&self) -> Option<u8> {
+        Some(match self {
+            CellError::Null => 1,
+            CellError::Div0 => 2,
+            CellError::Value => 3,
+            CellError::Ref => 4,
+            CellError::Name => 5,
+            CellError::Num => 6,
+            CellError::NA => 7,
+            CellError::GettingData => 8,
+            CellError::Spill => 9,
+            CellError::Connect => 10,
+            CellError::Blocked => 11,
+            CellError::Unknown => 12,
+            CellError::Field => 13,
+            CellError::Calc => 14,
+            CellError::Busy | CellError::Other(_) => return None,
+        })
+    }
+}
+
+impl fmt::Display for CellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
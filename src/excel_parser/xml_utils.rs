@@ -0,0 +1,132 @@
+use std::borrow::Cow;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesRef, BytesStart};
+
+/// Returns the unescaped value of an attribute, if present.
+///
+/// Malformed attributes and values with invalid escapes are treated as
+/// absent; Excel parts are machine‑generated, so this only matters for
+/// hand‑edited files where a missing value is the safest interpretation.
+///
+/// # Arguments
+/// * `e` – the start (or empty) element carrying the attribute.
+/// * `name` – the qualified attribute name (e.g., `b"name"` or `b"r:id"`).
+///
+/// # Returns
+/// `Some(String)` with the unescaped value, or `None` if the attribute is missing.
+#[inline]
+pub(crate) fn attr_value(e: &BytesStart<'_>, name: &[u8]) -> Option<String> {
+    e.try_get_attribute(name).ok().flatten()
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+/// Appends the text of a general entity reference (`&amp;`, `&#1234;`, ...) to `out`.
+///
+/// Since quick‑xml 0.38 entity references are reported as separate
+/// `Event::GeneralRef` events instead of being part of `Event::Text`, so every
+/// text accumulator has to resolve them explicitly. Unknown entities are
+/// dropped.
+#[inline]
+pub(crate) fn push_entity(out: &mut String, e: &BytesRef<'_>) {
+    if let Ok(Some(ch)) = e.resolve_char_ref() {
+        out.push(ch);
+    } else if let Ok(name) = e.decode()
+        && let Some(s) = resolve_predefined_entity(&name)
+    {
+        out.push_str(s);
+    }
+}
+
+/// Decodes the `_xHHHH_` escapes OOXML uses for characters that XML 1.0
+/// can't carry, such as carriage returns in `line1_x000D_line2`.
+///
+/// `HHHH` is a UTF‑16 code unit in hex; a surrogate pair is written as two
+/// consecutive escapes. A literal `_x` sequence is protected by escaping its
+/// underscore, so `_x005F_x000D_` decodes to the text `_x000D_`: escapes are
+/// decoded in a single left‑to‑right pass and decoded text is never
+/// rescanned. Sequences that aren't valid escapes are kept as written.
+///
+/// # Returns
+/// `s` unchanged (borrowed) if it contains no `_x`, otherwise the decoded text.
+pub(crate) fn decode_escapes(s: &str) -> Cow<'_, str> {
+    if !s.contains("_x") { return Cow::Borrowed(s); }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find("_x") {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        match escape_at(tail) {
+            Some((ch, len)) => { out.push(ch); rest = &tail[len..]; }
+            None => { out.push('_'); rest = &tail[1..]; }
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Length in bytes of one `_xHHHH_` escape.
+const ESCAPE_LEN: usize = 7;
+
+/// Decodes the escape (or surrogate pair of escapes) at the start of `s`,
+/// returning the character and the number of bytes consumed.
+fn escape_at(s: &str) -> Option<(char, usize)> {
+    let unit = escape_unit(s)?;
+    if let Some(ch) = char::from_u32(u32::from(unit)) { return Some((ch, ESCAPE_LEN)); }
+
+    let low = escape_unit(&s[ESCAPE_LEN..])?;
+    let ch = char::decode_utf16([unit, low]).next()?.ok()?;
+    Some((ch, 2 * ESCAPE_LEN))
+}
+
+/// Reads the code unit of an `_xHHHH_` escape at the start of `s`.
+fn escape_unit(s: &str) -> Option<u16> {
+    let bytes = s.as_bytes();
+    if bytes.len() < ESCAPE_LEN || !bytes.starts_with(b"_x") || bytes[ESCAPE_LEN - 1] != b'_' {
+        return None;
+    }
+    let hex = &bytes[2..ESCAPE_LEN - 1];
+    if !hex.iter().all(u8::is_ascii_hexdigit) { return None; }
+    hex.iter().try_fold(0u16, |unit, &digit| Some(unit << 4 | (digit as char).to_digit(16)? as u16))
+}
+
+/// [`decode_escapes`] for an owned string, reusing it when there is nothing
+/// to decode.
+pub(crate) fn decode_escapes_owned(text: String) -> String {
+    let decoded = match decode_escapes(&text) {
+        Cow::Owned(decoded) => Some(decoded),
+        Cow::Borrowed(_) => None,
+    };
+    decoded.unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_decode_to_the_code_unit() {
+        assert_eq!(decode_escapes("line1_x000D__x000A_line2"), "line1\r\nline2");
+        assert_eq!(decode_escapes("tab_x0009_"), "tab\t");
+        assert_eq!(decode_escapes("_x00e9_t_x00C9_"), "étÉ");
+        // A surrogate pair is two consecutive escapes.
+        assert_eq!(decode_escapes("_xD83D__xDE00_"), "😀");
+        assert!(matches!(decode_escapes("no escapes"), Cow::Borrowed("no escapes")));
+    }
+
+    #[test]
+    fn escaped_underscores_protect_literal_escapes() {
+        assert_eq!(decode_escapes("_x005F_x000D_"), "_x000D_");
+        assert_eq!(decode_escapes("a_x005F_x005F_b"), "a_x005F_b");
+    }
+
+    #[test]
+    fn invalid_escapes_are_kept_as_written() {
+        for text in ["_x", "_x00", "_x000D", "_x00G0_", "_xD83D_", "_xD83D_x", "_xDE00_", "__x_", "snake_x_case"] {
+            assert_eq!(decode_escapes(text), text, "{text:?}");
+        }
+        // Only the broken escape stays.
+        assert_eq!(decode_escapes("_x12_x000D_"), "_x12\r");
+        assert_eq!(decode_escapes_owned("a_x0041_".to_owned()), "aA");
+    }
+}
@@ -0,0 +1,490 @@
+use std::fmt;
+use std::io::{Read, Seek};
+use rustc_hash::FxHashSet;
+use super::{FilterSet, SourceReader, ZipFs, ZipFsError, ZipSource};
+
+/// Builder for [`ZipFs`] with optional loading limits.
+///
+/// [`ZipFs::new`] covers the common case (filter + archive size limit); the
+/// builder exposes every loading option and is the place new options are
+/// added. Options are set in a builder‑style fashion and consumed by
+/// [`build`](Self::build).
+///
+/// # Example
+/// ```no_run
+/// # use excel_parser::{ZipFs, FilterSet, ZipFsError};
+/// # fn main() -> Result<(), ZipFsError> {
+/// let fs = ZipFs::builder()
+///     .filter(FilterSet::new().add_glob("xl/worksheets/*.xml")?)
+///     .max_archive_size(100 * 1024 * 1024)
+///     .max_entries(10_000)
+///     .build(std::fs::File::open("archive.xlsx")?)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct ZipFsBuilder {
+    pub(super) filter: Option<FilterSet>,
+    pub(super) max_archive_size: Option<u64>,
+    pub(super) max_entries: Option<usize>,
+    pub(super) max_entry_size: Option<u64>,
+    pub(super) max_total_size: Option<u64>,
+    pub(super) case_insensitive: bool,
+    pub(super) content_types: Option<FxHashSet<String>>,
+    pub(super) error_on_empty: bool,
+    pub(super) verify_crc: bool,
+    pub(super) decoded_cache: Option<usize>,
+    pub(super) password: Option<Password>,
+    pub(super) parallel: bool,
+    pub(super) duplicates: DuplicatePolicy,
+}
+
+/// How [`ZipFsBuilder`] handles several archive entries with the same
+/// (normalized) path.
+///
+/// Every extra occurrence is reported by [`ZipFs::duplicates`] whatever the
+/// policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    /// Keep the first entry and skip the later ones.
+    FirstWins,
+    /// Replace the earlier entry with the later one, as ZIP tools that
+    /// append updates expect.
+    #[default]
+    LastWins,
+    /// Fail the load with [`ZipFsError::DuplicatePath`].
+    Error,
+    /// Keep every entry, loading later ones under a fresh path with a
+    /// numbered suffix before the extension (`sheet1.xml` → `sheet1~2.xml`).
+    KeepBoth,
+}
+
+/// Archive password, kept out of `Debug` output.
+#[derive(Clone)]
+pub(super) struct Password(Vec<u8>);
+
+impl Password {
+    pub(super) fn as_bytes(&self) -> &[u8] { &self.0 }
+}
+
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Password(..)")
+    }
+}
+
+impl ZipFsBuilder {
+    /// Creates a builder with no filter and no limits.
+    ///
+    /// Equivalent to `ZipFsBuilder::default()` and [`ZipFs::builder()`].
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets the options taken by the [`ZipFs::new`] shorthands.
+    pub(super) fn with_basics(mut self, filter: Option<FilterSet>, max_archive_size: Option<u64>) -> Self {
+        self.filter = filter;
+        self.max_archive_size = max_archive_size;
+        self
+    }
+
+    /// Loads only entries matching `filter`. Without a filter every entry is loaded.
+    pub fn filter(mut self, filter: FilterSet) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Rejects archives larger than `limit` bytes with
+    /// [`ZipFsError::ArchiveTooLarge`] before anything is decompressed.
+    pub fn max_archive_size(mut self, limit: u64) -> Self {
+        self.max_archive_size = Some(limit);
+        self
+    }
+
+    /// Rejects archives that would load more than `limit` files with
+    /// [`ZipFsError::TooManyEntries`].
+    ///
+    /// The total byte size of an archive says nothing about how many entries it
+    /// holds; millions of tiny entries can exhaust memory through per‑file
+    /// metadata alone. Only entries that would actually be loaded (files passing
+    /// the filter) are counted, and the check runs before any decompression.
+    pub fn max_entries(mut self, limit: usize) -> Self {
+        self.max_entries = Some(limit);
+        self
+    }
+
+    /// Fails with [`ZipFsError::EntryTooLarge`] when a single entry
+    /// decompresses to more than `limit` bytes.
+    ///
+    /// [`max_archive_size`](Self::max_archive_size) bounds only the compressed
+    /// input; a few kilobytes of deflate data can expand to gigabytes. The limit
+    /// is checked against the bytes actually produced, not the size declared in
+    /// the entry header, and decompression stops one byte past it.
+    ///
+    /// In lazy mode, an entry over the limit reads as `None` from
+    /// [`ZipFs::get_file`] and fails with an I/O error from
+    /// [`ZipFs::get_file_reader`]. Entries borrowed from a memory map are not
+    /// decompressed and are not checked.
+    pub fn max_entry_size(mut self, limit: u64) -> Self {
+        self.max_entry_size = Some(limit);
+        self
+    }
+
+    /// Fails with [`ZipFsError::TotalTooLarge`] when the loaded entries
+    /// decompress to more than `limit` bytes in total.
+    ///
+    /// A hard memory ceiling for untrusted uploads. Counts actual decompressed
+    /// bytes like [`max_entry_size`](Self::max_entry_size) and stops
+    /// decompressing as soon as the budget is exhausted; the error's
+    /// [`BudgetExceeded`](super::BudgetExceeded) lists every entry that was
+    /// skipped. Applies to loading; a lazily built [`ZipFs`] decompresses
+    /// nothing at load time, so only the per‑entry limit protects its later
+    /// reads.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{ZipFs, ZipFsError};
+    /// # fn demo(upload: std::fs::File) {
+    /// match ZipFs::builder().max_total_size(500 * 1024 * 1024).build(upload) {
+    ///     Err(ZipFsError::TotalTooLarge(details)) => {
+    ///         eprintln!("refused after {} bytes: {:?}", details.decompressed, details.skipped);
+    ///     }
+    ///     _ => {}
+    /// }
+    /// # }
+    /// ```
+    pub fn max_total_size(mut self, limit: u64) -> Self {
+        self.max_total_size = Some(limit);
+        self
+    }
+
+    /// Enables case‑insensitive path lookups.
+    ///
+    /// Exact lookups are tried first; on a miss, the path is normalized like
+    /// every lookup path and then lowercased (Unicode lowercasing, not just
+    /// ASCII) before comparing, so `get_file("XL/Workbook.xml")` finds
+    /// `xl/workbook.xml` and Cyrillic names match regardless of case. Applies to file and directory lookups; filters
+    /// still match case‑sensitively. If two entries differ only by case, the
+    /// first one in archive order wins for case‑insensitive lookups.
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.case_insensitive = enabled;
+        self
+    }
+
+    /// Loads only parts whose content type is in `types`.
+    ///
+    /// `[Content_Types].xml` is read first and each entry's type is looked up
+    /// there (per‑part overrides first, then extension defaults). This is more
+    /// robust than path conventions: e.g. [`ContentTypes::WORKSHEET`](super::ContentTypes::WORKSHEET)
+    /// selects every worksheet, wherever the writer placed it.
+    ///
+    /// Combines with [`filter`](Self::filter): when both are set, an entry must
+    /// pass both. If the archive has no `[Content_Types].xml`, no entries match.
+    ///
+    /// The content types part is read under the same limits as other entries
+    /// ([`max_entry_size`](Self::max_entry_size), [`max_total_size`](Self::max_total_size))
+    /// and decrypted with the [`password`](Self::password); in an encrypted
+    /// archive without one, loading fails with [`ZipFsError::PasswordRequired`].
+    ///
+    /// # Arguments
+    /// * `types` – content types to load.
+    pub fn content_types<I, S>(mut self, types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.content_types = Some(types.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Fails with [`ZipFsError::EmptyArchive`] instead of returning an empty
+    /// [`ZipFs`] when no files are loaded.
+    ///
+    /// An archive with no entries, only directory entries, or nothing left after
+    /// filtering is usually the wrong file rather than a valid empty workbook;
+    /// this turns it into an error at open time instead of "not found" results
+    /// later on. Disabled by default.
+    pub fn error_on_empty(mut self, enabled: bool) -> Self {
+        self.error_on_empty = enabled;
+        self
+    }
+
+    /// Decrypts encrypted entries with `password`.
+    ///
+    /// Some export tools write xlsx files as encrypted ZIP archives. Both
+    /// ZipCrypto and AES (AE‑1/AE‑2) entries are supported; unencrypted
+    /// entries in the same archive are read as usual. Without a password,
+    /// loading an encrypted entry fails with [`ZipFsError::PasswordRequired`].
+    /// The password is checked when an entry is opened, in lazy mode too, and
+    /// a mismatch fails with [`ZipFsError::WrongPassword`].
+    ///
+    /// ZipCrypto's password check is a single byte, so about one wrong
+    /// password in 256 passes it; the entry then fails its CRC check and is
+    /// left out (see [`verify_crc`](Self::verify_crc)). Encrypted entries are
+    /// never memory‑mapped or streamed from the archive; they are decoded into
+    /// memory.
+    ///
+    /// Files protected with Office's own "Encrypt with Password" are not ZIP
+    /// archives at all and fail with [`ZipFsError::Zip`].
+    pub fn password<P: AsRef<[u8]>>(mut self, password: P) -> Self {
+        self.password = Some(Password(password.as_ref().to_vec()));
+        self
+    }
+
+    /// Records entries whose content fails the CRC‑32 check in
+    /// [`ZipFs::crc_mismatches`] instead of dropping them silently.
+    ///
+    /// Decompressed entries are always checked by the zip crate, and a
+    /// corrupted one is left out of the file system; with this option the
+    /// loss is reported (path, expected and actual checksum), so a damaged
+    /// sheet surfaces as corruption instead of a later "not found" or
+    /// confusing XML error. It also extends the check to uncompressed entries
+    /// borrowed from a memory map (`build_mmap`), which
+    /// otherwise aren't read at load time. Applies to loading; in lazy mode a
+    /// corrupted entry reads as `None`. Disabled by default.
+    pub fn verify_crc(mut self, enabled: bool) -> Self {
+        self.verify_crc = enabled;
+        self
+    }
+
+    /// Sets the capacity of the LRU used by [`ZipFs::read_file`] in lazy mode.
+    ///
+    /// Without a cache, `read_file` decompresses the entry on every call. Has
+    /// no effect on eagerly loaded file systems.
+    ///
+    /// # Arguments
+    /// * `capacity` – maximum number of decoded entries kept; `0` disables storing.
+    pub fn decoded_cache(mut self, capacity: usize) -> Self {
+        self.decoded_cache = Some(capacity);
+        self
+    }
+
+    /// Sets how entries sharing a path are handled; see [`DuplicatePolicy`].
+    ///
+    /// Defaults to [`DuplicatePolicy::LastWins`]. Only entries accepted by the
+    /// filters count; a rejected entry never clashes with a loaded one.
+    ///
+    /// The zip crate already merges entries whose stored names are identical
+    /// when it reads the central directory (the last one wins), so outside
+    /// [`build_stream`](Self::build_stream) the policy sees names that only
+    /// become equal once normalized (`/xl/a.xml`, `xl\a.xml`, `xl/a.xml`).
+    pub fn duplicates(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicates = policy;
+        self
+    }
+
+    /// Decompresses the matching entries on all available cores.
+    ///
+    /// The compressed data is still read serially, a batch of entries at a
+    /// time; decoding and the CRC‑32 check of `Stored` and `Deflated` entries
+    /// then run on scoped threads, which speeds up workbooks with many large
+    /// parts. Encrypted entries and other compression methods are decoded
+    /// serially as usual. The loaded file system is the same as without this
+    /// option, and a panic on a decoding thread is resumed on the caller's.
+    ///
+    /// The [`max_total_size`](Self::max_total_size) budget stays a hard
+    /// ceiling: the threads draw decoded bytes from it as they go, and stop
+    /// decoding once it is spent. For an intact archive, whether the load
+    /// fails is the same as without this option, but since entries are decoded out of order,
+    /// [`BudgetExceeded`](super::BudgetExceeded) may name an earlier entry as
+    /// the one that crossed the limit. Applies to [`build`](Self::build); lazy
+    /// and memory‑mapped loading ignore it. Disabled by default.
+    pub fn parallel(mut self, enabled: bool) -> Self {
+        self.parallel = enabled;
+        self
+    }
+
+    /// Opens the archive and loads the matching entries.
+    ///
+    /// # Errors
+    /// * `ZipFsError::ArchiveTooLarge` – archive exceeds the size limit.
+    /// * `ZipFsError::TooManyEntries` – more files than the entry limit.
+    /// * `ZipFsError::EmptyArchive` – no files loaded (only with
+    ///   [`error_on_empty`](Self::error_on_empty)).
+    /// * `ZipFsError::InvalidContentTypes` – `[Content_Types].xml` is malformed
+    ///   (only when filtering by content type).
+    /// * `ZipFsError::PasswordRequired` / `ZipFsError::WrongPassword` – an
+    ///   encrypted entry, no or a wrong password.
+    /// * `ZipFsError::Zip` – malformed ZIP structure.
+    /// * `ZipFsError::Io` – I/O error.
+    pub fn build<R: Read + Seek>(self, reader: R) -> Result<ZipFs, ZipFsError> {
+        ZipFs::load(reader, &self)
+    }
+
+    /// Loads the matching entries from a tokio `AsyncRead + AsyncSeek`
+    /// without blocking the runtime.
+    ///
+    /// The archive is read into memory with async I/O, so it is held in
+    /// compressed form during the load, then decompressed on tokio's blocking
+    /// thread pool as by [`build`](Self::build); the worker running the
+    /// calling task stays free. All options apply, including
+    /// [`parallel`](Self::parallel), and the resulting [`ZipFs`] is the same
+    /// as with `build`. [`max_archive_size`](Self::max_archive_size) is
+    /// checked before anything is read.
+    ///
+    /// Must be awaited within a tokio runtime. Available with the `async`
+    /// feature.
+    ///
+    /// # Errors
+    /// Same as [`build`](Self::build). A panic while loading is resumed on
+    /// the awaiting task.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, ZipFsError};
+    /// # async fn demo() -> Result<(), ZipFsError> {
+    /// let fs = ZipFs::builder()
+    ///     .max_total_size(500 * 1024 * 1024)
+    ///     .build_async(tokio::fs::File::open("huge.xlsx").await?)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn build_async<R>(self, reader: R) -> Result<ZipFs, ZipFsError>
+    where
+        R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+    {
+        ZipFs::load_async(reader, self).await
+    }
+
+    /// Opens the archive in lazy mode: only the central directory is read and
+    /// matching entries are decompressed on first access.
+    ///
+    /// Construction cost no longer depends on the size of the entries, and
+    /// memory holds only what is actually read, which pays off for large
+    /// workbooks where only a few parts are needed. The reader is kept inside
+    /// the [`ZipFs`] (hence the `Send + 'static` bound); reads of different
+    /// entries are serialized on it. Filters, limits and content‑type
+    /// selection apply as in [`build`](Self::build).
+    ///
+    /// # Errors
+    /// Same as [`build`](Self::build). Corrupt entry data is only detected when
+    /// the entry is read, which then yields `None`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, ZipFsError};
+    /// # fn main() -> Result<(), ZipFsError> {
+    /// let fs = ZipFs::builder()
+    ///     .decoded_cache(4)
+    ///     .build_lazy(std::fs::File::open("huge.xlsx")?)?;
+    /// // Only this part is decompressed.
+    /// let workbook = fs.get_file("xl/workbook.xml");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn build_lazy<R: Read + Seek + Send + 'static>(self, reader: R) -> Result<ZipFs, ZipFsError> {
+        ZipFs::load_lazy(Box::new(reader), &self)
+    }
+
+    /// Loads the matching entries of a ZIP archive stored as file `path` of
+    /// `outer`, without writing it to disk.
+    ///
+    /// The inner archive is read from the outer file's content in place (in
+    /// lazy mode, the outer entry is decompressed and kept by `outer`). Nesting
+    /// is one level per call; chain calls for deeper levels.
+    ///
+    /// # Errors
+    /// * `ZipFsError::FileNotFound` – `path` isn't loaded in `outer`.
+    /// * Otherwise the same as [`build`](Self::build).
+    pub fn build_nested(self, outer: &ZipFs, path: &str) -> Result<ZipFs, ZipFsError> {
+        let content = outer.get_file(path).ok_or_else(|| ZipFsError::FileNotFound(path.to_string()))?;
+        self.build(std::io::Cursor::new(content))
+    }
+
+    /// Opens an archive from a [`ZipSource`] in lazy mode.
+    ///
+    /// Only the central directory is read up front; each entry's bytes are
+    /// requested from the source on first access, as with
+    /// [`build_lazy`](Self::build_lazy). This suits remote or otherwise
+    /// expensive sources, where fetching the whole archive would waste
+    /// transfer for the parts that are never read. For an eager load from a
+    /// source, pass a [`SourceReader`] to [`build`](Self::build).
+    ///
+    /// # Errors
+    /// * `ZipFsError::Io` – the source failed to report its size or a read.
+    /// * Otherwise the same as [`build_lazy`](Self::build_lazy).
+    pub fn build_source<S: ZipSource + 'static>(self, source: S) -> Result<ZipFs, ZipFsError> {
+        self.build_lazy(SourceReader::new(source))
+    }
+
+    /// Loads the matching entries from a reader that can't seek, such as
+    /// stdin, a pipe or a socket.
+    ///
+    /// Entries are read in archive order from their local headers, and the
+    /// filter is applied to each one as it passes by; the data of rejected
+    /// entries is skipped without being decompressed. Reading stops at the
+    /// central directory, so the file system holds what the local headers
+    /// describe:
+    ///
+    /// * [`max_archive_size`](Self::max_archive_size) limits the bytes read
+    ///   from `reader`, and [`max_entries`](Self::max_entries) the files
+    ///   loaded so far, as the total isn't known up front.
+    /// * With [`content_types`](Self::content_types), entries are matched
+    ///   against `[Content_Types].xml` once it has been read; Excel writes it
+    ///   first, entries before it are left out.
+    /// * Encrypted entries and entries written with a data descriptor (sizes
+    ///   after the data, common for streaming ZIP writers) can't be read this
+    ///   way and fail the whole load with [`ZipFsError::Zip`].
+    ///
+    /// Other options apply as in [`build`](Self::build); `parallel` is ignored.
+    ///
+    /// # Errors
+    /// Same as [`build`](Self::build).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, FilterSet, ZipFsError};
+    /// # fn main() -> Result<(), ZipFsError> {
+    /// let fs = ZipFs::builder()
+    ///     .filter(FilterSet::new().add_exact("xl/workbook.xml")?)
+    ///     .build_stream(std::io::stdin().lock())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn build_stream<R: Read>(self, reader: R) -> Result<ZipFs, ZipFsError> {
+        ZipFs::load_stream(reader, &self)
+    }
+
+    /// Memory‑maps the archive file at `path` and loads the matching entries.
+    ///
+    /// `Stored` (uncompressed) entries are not copied: their content is a
+    /// slice of the mapping, so pages are shared with the OS file cache and
+    /// only touched when read. xlsx files often store embedded media this
+    /// way. Compressed entries are decompressed into memory as with
+    /// [`build`](Self::build), and all other options apply unchanged. The
+    /// mapping stays alive while the [`ZipFs`] (or a
+    /// [`deep_clone`](ZipFs::deep_clone) of it) holds mapped entries.
+    ///
+    /// Mapped entries are only checked against their CRC‑32 with
+    /// [`verify_crc`](Self::verify_crc).
+    ///
+    /// Available on unix with the `mmap` feature.
+    ///
+    /// # Truncation hazard
+    /// Mapped entries read the file's pages directly, for as long as they
+    /// live. If another process truncates the file meanwhile, reading an
+    /// entry past the new end raises `SIGBUS`, which kills the process; if
+    /// it rewrites the file, entries already handed out as `&[u8]` may change
+    /// under the borrow. Neither can be detected or prevented from here, so
+    /// only map files that nothing else writes to while the [`ZipFs`] lives
+    /// (a private copy, or a file your process owns); use
+    /// [`build`](Self::build) for anything else.
+    ///
+    /// # Errors
+    /// * `ZipFsError::Io` – the file can't be opened or mapped.
+    /// * Otherwise the same as [`build`](Self::build).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, ZipFsError};
+    /// # fn main() -> Result<(), ZipFsError> {
+    /// let fs = ZipFs::builder().max_entries(10_000).build_mmap("report.xlsx")?;
+    /// let image = fs.get_file("xl/media/image1.png"); // no copy if stored
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(all(feature = "mmap", unix))]
+    pub fn build_mmap<P: AsRef<std::path::Path>>(self, path: P) -> Result<ZipFs, ZipFsError> {
+        ZipFs::load_mmap(path.as_ref(), &self)
+    }
+}
@@ -0,0 +1,93 @@
+use quick_xml::{Reader, events::Event};
+use rustc_hash::FxHashMap;
+use super::super::xml_utils::attr_value;
+
+/// Parsed `[Content_Types].xml` of an OPC package (`.xlsx`, `.docx`, ...).
+///
+/// Maps every part to its MIME‑like content type: explicit `<Override>`
+/// entries by part name take precedence over `<Default>` entries by file
+/// extension. Part names and extensions compare case‑insensitively, as OPC
+/// requires.
+///
+/// # XML Structure
+/// ```xml
+/// <Types>
+///   <Default Extension="xml" ContentType="application/xml"/>
+///   <Override PartName="/xl/worksheets/sheet1.xml"
+///             ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+/// </Types>
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ContentTypes {
+    /// Lowercased extension (without dot) → content type.
+    defaults: FxHashMap<String, String>,
+    /// Lowercased part name (without leading `/`) → content type.
+    overrides: FxHashMap<String, String>,
+}
+
+impl ContentTypes {
+    /// Archive path of the content types part.
+    pub const PATH: &'static str = "[Content_Types].xml";
+
+    /// Content type of worksheet parts.
+    pub const WORKSHEET: &'static str = "application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml";
+    /// Content type of the shared strings part.
+    pub const SHARED_STRINGS: &'static str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml";
+    /// Content type of the styles part.
+    pub const STYLES: &'static str = "application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml";
+    /// Content type of the main workbook part (`.xlsx`).
+    pub const WORKBOOK: &'static str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml";
+
+    /// Parses `[Content_Types].xml`.
+    ///
+    /// # Errors
+    /// Returns `quick_xml::Error` for malformed XML.
+    pub fn parse(xml: &[u8]) -> Result<Self, quick_xml::Error> {
+        let mut reader = Reader::from_reader(xml);
+        reader.config_mut().check_end_names = false;
+
+        let mut buf = Vec::new();
+        let mut types = ContentTypes::default();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(ref e) | Event::Empty(ref e) => match e.local_name().as_ref() {
+                    b"Default" => {
+                        if let (Some(ext), Some(ct)) = (attr_value(e, b"Extension"), attr_value(e, b"ContentType")) {
+                            types.defaults.insert(ext.to_lowercase(), ct);
+                        }
+                    }
+                    b"Override" => {
+                        if let (Some(part), Some(ct)) = (attr_value(e, b"PartName"), attr_value(e, b"ContentType")) {
+                            types.overrides.insert(part.trim_start_matches('/').to_lowercase(), ct);
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        Ok(types)
+    }
+
+    /// Returns the content type of a part.
+    ///
+    /// # Arguments
+    /// * `path` – normalized archive path of the part (e.g., `"xl/workbook.xml"`).
+    ///
+    /// # Returns
+    /// The override for the part if present, otherwise the default for its
+    /// extension, otherwise `None`.
+    pub fn get(&self, path: &str) -> Option<&str> {
+        let lower = path.trim_start_matches('/').to_lowercase();
+        if let Some(ct) = self.overrides.get(&lower) { return Some(ct); }
+
+        let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+        let (_, ext) = file_name.rsplit_once('.')?;
+        self.defaults.get(ext).map(String::as_str)
+    }
+}
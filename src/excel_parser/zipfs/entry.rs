@@ -0,0 +1,136 @@
+use std::fmt;
+use std::io::{self, Cursor, Read, Take};
+use flate2::read::DeflateDecoder;
+use zip::{CompressionMethod, read::ZipFile};
+use super::lazy::SharedReader;
+
+/// Header information of a loaded ZIP entry, taken from the central directory.
+///
+/// Recorded for every file loaded into a [`ZipFs`](super::ZipFs) and available
+/// through [`ZipFs::entry_meta`](super::ZipFs::entry_meta). Useful for
+/// diagnostics such as spotting badly compressed folders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryMeta {
+    /// Size of the entry as stored in the archive, in bytes.
+    pub compressed_size: u64,
+    /// Size of the entry after decompression, in bytes (as declared by the header).
+    pub size: u64,
+    /// Compression method (`Stored`, `Deflated`, ...).
+    pub compression: CompressionMethod,
+    /// CRC‑32 checksum of the uncompressed data, as declared by the header.
+    pub crc32: u32,
+    /// Whether the entry is encrypted in the archive.
+    pub encrypted: bool,
+}
+
+impl EntryMeta {
+    /// Captures the metadata of an entry before its content is read.
+    pub(super) fn from_file<R: std::io::Read>(file: &ZipFile<'_, R>) -> Self {
+        Self {
+            compressed_size: file.compressed_size(),
+            size: file.size(),
+            compression: file.compression(),
+            crc32: file.crc32(),
+            encrypted: file.encrypted(),
+        }
+    }
+
+    /// Returns `compressed_size / size`, or `None` for empty entries.
+    ///
+    /// Values close to `1.0` mean the data did not compress (or was stored).
+    pub fn compression_ratio(&self) -> Option<f64> {
+        (self.size > 0).then(|| self.compressed_size as f64 / self.size as f64)
+    }
+}
+
+/// An entry whose decompressed content doesn't match the CRC‑32 declared in
+/// the archive, reported by [`ZipFs::crc_mismatches`](super::ZipFs::crc_mismatches).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrcMismatch {
+    /// Normalized path of the entry.
+    pub path: String,
+    /// Checksum declared by the entry header.
+    pub expected: u32,
+    /// Checksum of the content actually read.
+    pub actual: u32,
+}
+
+/// An archive path that occurred more than once, reported by
+/// [`ZipFs::duplicates`](super::ZipFs::duplicates).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateEntry {
+    /// Normalized path shared by the entries.
+    pub path: String,
+    /// Path the later entry was loaded under with
+    /// [`DuplicatePolicy::KeepBoth`](super::DuplicatePolicy::KeepBoth);
+    /// `None` with the other policies.
+    pub stored_as: Option<String>,
+}
+
+/// Streaming reader over the content of a file in a [`ZipFs`](super::ZipFs),
+/// returned by [`ZipFs::get_file_reader`](super::ZipFs::get_file_reader).
+///
+/// Content the file system already holds is read in place. In lazy mode,
+/// stored and deflated entries are decompressed straight from the archive as
+/// the reader is consumed, so an entry never has to fit in memory at once;
+/// other compression methods are decoded up front.
+pub struct EntryReader<'a> {
+    inner: Inner<'a>,
+    /// Bytes that may still be produced before the per‑entry size limit is hit.
+    remaining: u64,
+}
+
+enum Inner<'a> {
+    /// Content already held by the file system.
+    Held(&'a [u8]),
+    /// Content decoded up front (compression methods without a streaming path).
+    Decoded(Cursor<Vec<u8>>),
+    /// Uncompressed entry data read from the archive.
+    Stored(Take<SharedReader>),
+    /// Deflated entry data decompressed while reading.
+    Deflated(DeflateDecoder<Take<SharedReader>>),
+}
+
+impl<'a> EntryReader<'a> {
+    pub(super) fn held(content: &'a [u8]) -> Self {
+        Self { inner: Inner::Held(content), remaining: u64::MAX }
+    }
+
+    pub(super) fn decoded(content: Vec<u8>) -> Self {
+        Self { inner: Inner::Decoded(Cursor::new(content)), remaining: u64::MAX }
+    }
+
+    pub(super) fn stored(raw: Take<SharedReader>, limit: Option<u64>) -> Self {
+        Self { inner: Inner::Stored(raw), remaining: limit.unwrap_or(u64::MAX) }
+    }
+
+    pub(super) fn deflated(raw: Take<SharedReader>, limit: Option<u64>) -> Self {
+        Self { inner: Inner::Deflated(DeflateDecoder::new(raw)), remaining: limit.unwrap_or(u64::MAX) }
+    }
+}
+
+impl Read for EntryReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = match &mut self.inner {
+            Inner::Held(content) => content.read(buf)?,
+            Inner::Decoded(cursor) => cursor.read(buf)?,
+            Inner::Stored(raw) => raw.read(buf)?,
+            Inner::Deflated(decoder) => decoder.read(buf)?,
+        };
+        self.remaining = self.remaining.checked_sub(n as u64)
+            .ok_or_else(|| io::Error::other("entry exceeds the decompressed size limit"))?;
+        Ok(n)
+    }
+}
+
+impl fmt::Debug for EntryReader<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match self.inner {
+            Inner::Held(_) => "held",
+            Inner::Decoded(_) => "decoded",
+            Inner::Stored(_) => "stored",
+            Inner::Deflated(_) => "deflated",
+        };
+        f.debug_struct("EntryReader").field("source", &source).finish()
+    }
+}
@@ -0,0 +1,234 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use super::{FilterSet, ZipFs, ZipFsError};
+
+impl ZipFs {
+    /// Writes the loaded files matching `filter` below the directory `dir`.
+    ///
+    /// Each file is written to `dir` joined with its archive path, creating
+    /// parent directories as needed and overwriting existing files. Content
+    /// is streamed, so lazily loaded entries are not kept in memory. Files
+    /// that can't be decompressed (lazy mode) are skipped.
+    ///
+    /// Nothing is ever written outside `dir`: every selected path is checked
+    /// before anything is written and must consist of plain names only (no
+    /// `..`, `.`, empty, drive or root components), and symbolic links found
+    /// inside `dir` on the way to a file are refused rather than followed.
+    ///
+    /// # Arguments
+    /// * `dir` – target directory; created if missing.
+    /// * `filter` – optional [`FilterSet`]; without one every file is written.
+    ///
+    /// # Returns
+    /// The number of files written.
+    ///
+    /// # Errors
+    /// * `ZipFsError::UnsafePath` – a selected path is unsafe; nothing has
+    ///   been written.
+    /// * `ZipFsError::Io` – creating a directory or writing a file failed, or
+    ///   a symbolic link or file is in the way (`PermissionDenied`).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, FilterSet, ZipFsError};
+    /// # fn main() -> Result<(), ZipFsError> {
+    /// let fs = ZipFs::open("report.xlsx", None, None)?;
+    /// let written = fs.extract_to("report_parts", Some(&FilterSet::new().add_glob("xl/**")?))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extract_to<P: AsRef<Path>>(&self, dir: P, filter: Option<&FilterSet>) -> Result<usize, ZipFsError> {
+        let mut targets = self.paths()
+            .filter(|path| filter.is_none_or(|f| f.matches_str(path)))
+            .map(|path| Ok((path, relative_path(path)?)))
+            .collect::<Result<Vec<_>, ZipFsError>>()?;
+        targets.sort_unstable();
+
+        fs::create_dir_all(&dir)?;
+        let root = dir.as_ref().canonicalize()?;
+        let mut written = 0;
+        for (path, relative) in targets {
+            let Some(mut reader) = self.get_file_reader(path) else { continue };
+
+            let target = create_parents(&root, &relative, path)?;
+            io::copy(&mut reader, &mut File::create(&target)?)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+}
+
+/// Creates the directories of `relative` below `root` one by one and
+/// returns the file's target path.
+///
+/// Refuses to pass through anything but a real directory, and to replace a
+/// symbolic link with the file, so the result stays inside `root`.
+fn create_parents(root: &Path, relative: &Path, path: &str) -> io::Result<PathBuf> {
+    let refuse = || io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("'{path}' would be written through a link or over a non-file"),
+    );
+
+    let mut target = root.to_path_buf();
+    let mut names = relative.iter().peekable();
+    while let Some(name) = names.next() {
+        target.push(name);
+        let is_file = names.peek().is_none();
+        match fs::symlink_metadata(&target) {
+            // `symlink_metadata` doesn't follow links: a link is neither a file nor a directory.
+            Ok(meta) if (is_file && meta.is_file()) || (!is_file && meta.is_dir()) => {}
+            Ok(_) => return Err(refuse()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !is_file => fs::create_dir(&target)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(target)
+}
+
+/// Converts an archive path into a relative file system path made of plain
+/// names only.
+///
+/// # Errors
+/// Returns `ZipFsError::UnsafePath` for paths with empty, `.`, `..`, root or
+/// prefix components, or names containing `\`, `:` or NUL.
+fn relative_path(path: &str) -> Result<PathBuf, ZipFsError> {
+    let unsafe_path = || ZipFsError::UnsafePath(path.to_string());
+
+    let mut relative = PathBuf::new();
+    for name in path.split('/') {
+        // `\` and `:` would be separators or drive prefixes on Windows.
+        if name.contains(['\\', ':', '\0']) { return Err(unsafe_path()); }
+        let mut components = Path::new(name).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(component)), None) if component == name => relative.push(component),
+            _ => return Err(unsafe_path()),
+        }
+    }
+    Ok(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::excel_parser::test_util::zip_archive;
+
+    /// A fresh, empty scratch directory for one test.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("excel_parser_extract_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Every file below `dir`, as paths relative to it.
+    fn files_below(dir: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in fs::read_dir(current).unwrap() {
+                let path = entry.unwrap().path();
+                if fs::symlink_metadata(&path).unwrap().is_dir() {
+                    pending.push(path);
+                } else {
+                    files.push(path.strip_prefix(dir).unwrap().to_path_buf());
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn relative_path_accepts_plain_names_only() {
+        assert_eq!(relative_path("xl/worksheets/sheet1.xml").unwrap(), Path::new("xl").join("worksheets").join("sheet1.xml"));
+        assert_eq!(relative_path("Листы/Отчёт.xml").unwrap(), Path::new("Листы").join("Отчёт.xml"));
+
+        for path in ["", "..", "a/../b", "/abs.xml", "a//b.xml", "./a.xml", "a/.", "a/", r"a\b.xml", r"..\evil", "C:", "C:/evil.xml", "a:b", "a\0b"] {
+            assert!(matches!(relative_path(path), Err(ZipFsError::UnsafePath(p)) if p == path), "{path:?}");
+        }
+    }
+
+    #[test]
+    fn traversal_entries_are_never_written_outside_the_root() {
+        let scratch = scratch_dir("traversal");
+        let archive = zip_archive(&[
+            ("../evil.txt", b"x"),
+            ("xl/../../evil.txt", b"x"),
+            (r"xl\..\..\evil.txt", b"x"),
+            ("/abs.txt", b"abs"),
+            ("xl/workbook.xml", b"<workbook/>"),
+        ]);
+        let fs = ZipFs::from_bytes(&archive, None, None).unwrap();
+
+        let root = scratch.join("root");
+        assert_eq!(fs.extract_to(&root, None).unwrap(), 2);
+
+        // The traversal entries are dropped at load; the leading slash is stripped.
+        assert_eq!(files_below(&scratch), [Path::new("root").join("abs.txt"), Path::new("root").join("xl").join("workbook.xml")]);
+        assert_eq!(fs::read(root.join("abs.txt")).unwrap(), b"abs");
+        fs::remove_dir_all(&scratch).unwrap();
+    }
+
+    #[test]
+    fn unsafe_paths_fail_before_anything_is_written() {
+        let scratch = scratch_dir("unsafe");
+        let archive = zip_archive(&[("a.xml", b"a"), ("C:/evil.txt", b"x")]);
+        let fs = ZipFs::from_bytes(&archive, None, None).unwrap();
+
+        let root = scratch.join("root");
+        let result = fs.extract_to(&root, None);
+        assert!(matches!(result, Err(ZipFsError::UnsafePath(ref p)) if p == "C:/evil.txt"), "{result:?}");
+        assert_eq!(result.unwrap_err().to_string(), "Unsafe path for extraction: C:/evil.txt");
+        assert!(!root.exists());
+
+        // A filter leaving the unsafe path out makes the rest extractable.
+        let filter = FilterSet::new().add_exact("a.xml").unwrap();
+        assert_eq!(fs.extract_to(&root, Some(&filter)).unwrap(), 1);
+        assert_eq!(files_below(&scratch), [Path::new("root").join("a.xml")]);
+        fs::remove_dir_all(&scratch).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symbolic_links_inside_the_root_are_refused() {
+        use std::os::unix::fs::symlink;
+
+        let scratch = scratch_dir("symlinks");
+        let (root, outside) = (scratch.join("root"), scratch.join("outside"));
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("target.txt"), b"original").unwrap();
+
+        // A linked parent directory.
+        symlink(&outside, root.join("xl")).unwrap();
+        let fs_dir = ZipFs::from_bytes(&zip_archive(&[("xl/evil.xml", b"x")]), None, None).unwrap();
+        let Err(ZipFsError::Io(e)) = fs_dir.extract_to(&root, None) else { panic!("expected an I/O error") };
+        assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+
+        // A linked file.
+        symlink(outside.join("target.txt"), root.join("a.xml")).unwrap();
+        let fs_file = ZipFs::from_bytes(&zip_archive(&[("a.xml", b"x")]), None, None).unwrap();
+        let Err(ZipFsError::Io(e)) = fs_file.extract_to(&root, None) else { panic!("expected an I/O error") };
+        assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+
+        assert_eq!(files_below(&outside), [PathBuf::from("target.txt")]);
+        assert_eq!(fs::read(outside.join("target.txt")).unwrap(), b"original");
+        fs::remove_dir_all(&scratch).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_linked_root_is_resolved_before_writing() {
+        let scratch = scratch_dir("linked_root");
+        let real = scratch.join("real");
+        fs::create_dir_all(&real).unwrap();
+        std::os::unix::fs::symlink(&real, scratch.join("root")).unwrap();
+
+        let fs = ZipFs::from_bytes(&zip_archive(&[("xl/workbook.xml", b"<workbook/>")]), None, None).unwrap();
+        assert_eq!(fs.extract_to(scratch.join("root"), None).unwrap(), 1);
+        assert_eq!(fs::read(real.join("xl").join("workbook.xml")).unwrap(), b"<workbook/>");
+        fs::remove_dir_all(&scratch).unwrap();
+    }
+}
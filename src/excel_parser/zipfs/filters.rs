@@ -2,6 +2,8 @@ use std::sync::Arc;
 use rustc_hash::FxHashSet;
 use super::path_utils::validate_path;
 use super::ZipFsError;
+#[cfg(feature = "regex")]
+use regex::Regex;
 
 /// A set of filters that can match paths either exactly or by glob pattern.
 ///
@@ -12,7 +14,7 @@ use super::ZipFsError;
 ///
 /// # Example
 /// ```
-/// # use zip_fs::filter::FilterSet;
+/// # use excel_parser::FilterSet;
 /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let filter = FilterSet::new()
 ///     .add_exact("xl/workbook.xml")?
@@ -25,6 +27,22 @@ use super::ZipFsError;
 /// # }
 /// ```
 ///
+/// Exclude filters ([`add_exclude_exact`](Self::add_exclude_exact),
+/// [`add_exclude_glob`](Self::add_exclude_glob)) reject paths that an include
+/// filter accepted:
+/// ```
+/// # use excel_parser::FilterSet;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let filter = FilterSet::new()
+///     .add_glob("xl/worksheets/*.xml")?
+///     .add_exclude_glob("xl/worksheets/hidden_*.xml")?;
+///
+/// assert!( filter.matches_str("xl/worksheets/sheet1.xml"));
+/// assert!(!filter.matches_str("xl/worksheets/hidden_1.xml"));
+/// # Ok(())
+/// # }
+/// ```
+///
 /// If no filters are added, the set is considered empty and `matches_str` will
 /// always return `false` (i.e., nothing matches). To match everything, simply
 /// don't use a filter or treat an empty filter as “allow all” at the caller level.
@@ -35,7 +53,14 @@ pub struct FilterSet {
     exact: FxHashSet<Arc<str>>,
     /// Glob patterns, in the order they were added. They are evaluated in sequence
     /// using `fast_glob::glob_match`.
-    globs: Vec<String>
+    globs: Vec<String>,
+    /// Exact paths rejected even when an include filter matches.
+    exclude_exact: FxHashSet<Arc<str>>,
+    /// Glob patterns rejecting paths that an include filter matched.
+    exclude_globs: Vec<String>,
+    /// Regular expressions, in the order they were added.
+    #[cfg(feature = "regex")]
+    regexes: Vec<Regex>,
 }
 
 impl FilterSet {
@@ -68,8 +93,18 @@ impl FilterSet {
     /// The pattern is validated and normalized in the same way as exact paths
     /// (see [`add_exact`](Self::add_exact)). After validation, it is stored
     /// for later matching. Matching is performed with the
-    /// [`fast_glob::glob_match`] function, which supports the usual `*` and `?`
-    /// wildcards.
+    /// [`fast_glob::glob_match`] function, which supports:
+    ///
+    /// * `?` – any single character except `/`;
+    /// * `*` – any run of characters within one path segment;
+    /// * `**` – any number of whole segments, e.g. `"xl/**/*.xml"` matches
+    ///   `xl/workbook.xml` and `xl/worksheets/sheet1.xml`;
+    /// * `{a,b}` – alternation, nestable, e.g. `"xl/{worksheets,tables}/*.xml"`;
+    /// * `[a-z]`, `[!a-z]` – character classes.
+    ///
+    /// Normalization turns backslashes into `/`, so `\` escapes are not
+    /// available; a leading `!` negates the pattern, but
+    /// [`add_exclude_glob`](Self::add_exclude_glob) is usually clearer.
     ///
     /// # Arguments
     /// * `pattern` – A glob pattern (e.g., `"xl/worksheets/*.xml"`).
@@ -83,31 +118,188 @@ impl FilterSet {
         Ok(self)
     }
 
-    /// Checks whether the given path matches any of the filters in the set.
+    /// Adds a regular expression to the filter set.
+    ///
+    /// Available with the `regex` feature. The expression is tested against
+    /// normalized paths (`/` separators, no leading slash) and matches
+    /// anywhere in the path unless anchored, so use `^...$` to match whole
+    /// paths:
+    /// ```
+    /// # use excel_parser::FilterSet;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let filter = FilterSet::new().add_regex(r"^xl/worksheets/sheet([1-9]|10)\.xml$")?;
+    ///
+    /// assert!( filter.matches_str("xl/worksheets/sheet10.xml"));
+    /// assert!(!filter.matches_str("xl/worksheets/sheet11.xml"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The syntax is the [`regex`](https://docs.rs/regex) crate's: no
+    /// backreferences or lookaround, and matching runs in time linear in the
+    /// path length, so patterns from untrusted input are safe to use.
+    ///
+    /// # Arguments
+    /// * `pattern` – A regular expression (e.g., `r"^xl/worksheets/sheet\d+\.xml$"`).
+    ///
+    /// # Errors
+    /// Returns `ZipFsError::InvalidPattern` if the pattern is malformed or
+    /// compiles past the `regex` crate's default size limit.
+    #[cfg(feature = "regex")]
+    pub fn add_regex(mut self, pattern: &str) -> Result<Self, ZipFsError> {
+        let regex = Regex::new(pattern).map_err(|e| ZipFsError::InvalidPattern(format!("regex {pattern:?}: {e}")))?;
+        self.regexes.push(regex);
+        Ok(self)
+    }
+
+    /// Adds an exact path to reject even if an include filter matches it.
+    ///
+    /// The path is validated and normalized like in [`add_exact`](Self::add_exact).
+    ///
+    /// # Arguments
+    /// * `path` – The exact path to exclude (e.g., `"xl/worksheets/sheet3.xml"`).
+    ///
+    /// # Errors
+    /// Returns `ZipFsError::InvalidPattern` if the path is empty, contains `".."`,
+    /// or is otherwise invalid.
+    pub fn add_exclude_exact(mut self, path: &str) -> Result<Self, ZipFsError> {
+        let normalized = validate_path(path)?;
+        self.exclude_exact.insert(Arc::from(normalized));
+        Ok(self)
+    }
+
+    /// Adds a glob pattern whose matches are rejected even if an include
+    /// filter matches them.
+    ///
+    /// The pattern is validated and matched like in [`add_glob`](Self::add_glob).
+    /// Excludes only narrow the includes; a set with excludes alone still
+    /// matches nothing.
+    ///
+    /// # Arguments
+    /// * `pattern` – A glob pattern (e.g., `"xl/worksheets/hidden_*.xml"`).
+    ///
+    /// # Errors
+    /// Returns `ZipFsError::InvalidPattern` if the pattern is empty, contains `".."`,
+    /// or is otherwise invalid.
+    pub fn add_exclude_glob(mut self, pattern: &str) -> Result<Self, ZipFsError> {
+        let normalized = validate_path(pattern)?;
+        self.exclude_globs.push(normalized);
+        Ok(self)
+    }
+
+    /// Checks whether the given path matches the filters in the set.
     ///
-    /// The check is performed in two steps:
+    /// The check is performed in three steps:
     /// 1. Exact match against the set of exact paths (O(1) average).
-    /// 2. If no exact match is found, each glob pattern is tested in order.
+    /// 2. If no exact match is found, each glob pattern is tested in order,
+    ///    then each regular expression (with the `regex` feature).
+    /// 3. A path accepted by step 1 or 2 is rejected if it matches an exclude
+    ///    path or exclude pattern.
     ///
     /// # Arguments
     /// * `path` – The path to test (should already be normalized, e.g., by
     ///   [`validate_path`]).
     ///
     /// # Returns
-    /// `true` if the path matches at least one filter, `false` otherwise.
+    /// `true` if the path matches at least one include filter and no exclude
+    /// filter, `false` otherwise.
     #[inline]
     pub fn matches_str(&self, path: &str) -> bool {
-        if self.exact.contains(path) { return true; }
-        self.globs.iter().any(|g| fast_glob::glob_match(g, path))
+        let included = self.exact.contains(path)
+            || self.globs.iter().any(|g| fast_glob::glob_match(g, path))
+            || self.matches_regex(path);
+        included && !self.is_excluded(path)
+    }
+
+    /// Returns `true` if the path matches an exclude filter.
+    fn is_excluded(&self, path: &str) -> bool {
+        self.exclude_exact.contains(path)
+            || self.exclude_globs.iter().any(|g| fast_glob::glob_match(g, path))
+    }
+
+    /// Returns `true` if a regular expression matches the path.
+    #[cfg(feature = "regex")]
+    fn matches_regex(&self, path: &str) -> bool {
+        self.regexes.iter().any(|r| r.is_match(path))
     }
 
-    /// Returns `true` if no filters have been added to the set.
+    #[cfg(not(feature = "regex"))]
+    fn matches_regex(&self, _path: &str) -> bool { false }
+
+    /// Returns `true` if no include filters have been added to the set.
     ///
-    /// An empty filter set matches **no** paths. If you need a set that matches
+    /// An empty filter set matches **no** paths, whatever excludes it holds. If you need a set that matches
     /// everything, either avoid using a filter or treat the absence of filters
     /// as a special case in your logic.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.exact.is_empty() && self.globs.is_empty()
+        self.exact.is_empty() && self.globs.is_empty() && !self.has_regexes()
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "regex")]
+    fn has_regexes(&self) -> bool { !self.regexes.is_empty() }
+
+    #[cfg(not(feature = "regex"))]
+    fn has_regexes(&self) -> bool { false }
+}
+
+#[cfg(all(test, feature = "regex"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regexes_include_and_excludes_still_apply() {
+        let filter = FilterSet::new()
+            .add_regex(r"^xl/worksheets/sheet\d+\.xml$").unwrap()
+            .add_exclude_exact("xl/worksheets/sheet2.xml").unwrap();
+
+        assert!(!filter.is_empty());
+        assert!( filter.matches_str("xl/worksheets/sheet1.xml"));
+        assert!(!filter.matches_str("xl/worksheets/sheet2.xml"));
+        assert!(!filter.matches_str("xl/worksheets/chart1.xml"));
+        assert!(matches!(FilterSet::new().add_regex("(sheet"), Err(ZipFsError::InvalidPattern(_))));
+    }
+
+    #[test]
+    fn regexes_match_anywhere_unless_anchored() {
+        let unanchored = FilterSet::new().add_regex(r"\.rels$").unwrap();
+        assert!( unanchored.matches_str("xl/_rels/workbook.xml.rels"));
+        assert!(!unanchored.matches_str("xl/_rels/workbook.xml.rels.bak"));
+
+        let anchored = FilterSet::new().add_regex(r"^xl/worksheets/sheet([1-9]|10)\.xml$").unwrap();
+        assert!( anchored.matches_str("xl/worksheets/sheet10.xml"));
+        assert!(!anchored.matches_str("xl/worksheets/sheet11.xml"));
+        assert!(!anchored.matches_str("backup/xl/worksheets/sheet1.xml"));
+    }
+
+    #[test]
+    fn regexes_match_non_ascii_paths() {
+        let filter = FilterSet::new().add_regex(r"^xl/(?:Листы|sheets)/(Отчёт|Report)_\w{2,}\.xml$").unwrap();
+        assert!( filter.matches_str("xl/Листы/Отчёт_январь.xml"));
+        assert!( filter.matches_str("xl/sheets/Report_Q1.xml"));
+        assert!(!filter.matches_str("xl/Листы/Отчёт_1.xml"));
+    }
+
+    #[test]
+    fn regexes_combine_with_exact_paths_and_globs() {
+        let filter = FilterSet::new()
+            .add_exact("xl/workbook.xml").unwrap()
+            .add_glob("xl/media/*.png").unwrap()
+            .add_regex(r"^xl/worksheets/sheet\d+\.xml$").unwrap()
+            .add_exclude_glob("xl/media/secret*").unwrap();
+
+        assert!( filter.matches_str("xl/workbook.xml"));
+        assert!( filter.matches_str("xl/media/image1.png"));
+        assert!( filter.matches_str("xl/worksheets/sheet7.xml"));
+        assert!(!filter.matches_str("xl/media/secret.png"));
+        assert!(!filter.matches_str("xl/styles.xml"));
+    }
+
+    #[test]
+    fn invalid_regexes_are_reported_as_invalid_patterns() {
+        for pattern in ["(sheet", "[z-a]", r"\q", "a{3,2}", "(?<=x)a"] {
+            let result = FilterSet::new().add_regex(pattern);
+            assert!(matches!(result, Err(ZipFsError::InvalidPattern(ref message)) if message.contains(pattern)), "{pattern}");
+        }
+    }
+}
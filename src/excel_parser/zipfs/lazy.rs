@@ -0,0 +1,416 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+use rustc_hash::FxHashMap;
+use zip::{CompressionMethod, ZipArchive, ZipWriter, result::ZipResult};
+use super::{EntryReader, ZipFs, ZipFsBuilder};
+use super::builder::Password;
+
+/// Object‑safe combination of the traits a lazily read archive source needs.
+pub(super) trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+/// Cloneable handle to the reader of a lazily loaded archive.
+///
+/// Every handle keeps its own position and seeks to it before each read, so
+/// the archive and any number of entry streams can share one underlying
+/// reader.
+#[derive(Clone)]
+pub(super) struct SharedReader {
+    inner: Arc<Mutex<Box<dyn ReadSeek>>>,
+    pos: u64,
+}
+
+impl SharedReader {
+    pub(super) fn new(reader: Box<dyn ReadSeek>) -> Self {
+        Self { inner: Arc::new(Mutex::new(reader)), pos: 0 }
+    }
+}
+
+impl Read for SharedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.seek(SeekFrom::Start(self.pos))?;
+        let n = inner.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for SharedReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+            SeekFrom::End(delta) => {
+                let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+                inner.seek(SeekFrom::End(0))?.checked_add_signed(delta)
+            }
+        };
+        self.pos = target.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "seek to a negative position")
+        })?;
+        Ok(self.pos)
+    }
+}
+
+/// The open archive behind a lazily loaded [`ZipFs`], used to decompress
+/// entries on first access.
+///
+/// Shared through an `Arc` so that [`ZipFs::deep_clone`] copies keep reading
+/// from the same source. The archive is behind a `Mutex` because reading an
+/// entry seeks the underlying reader; decompression of different entries is
+/// therefore serialized.
+pub(super) struct LazySource {
+    archive: Mutex<ZipArchive<SharedReader>>,
+    /// Handle to the archive reader, cloned for every entry stream.
+    reader: SharedReader,
+    /// Normalized path → index of the entry in the archive.
+    indices: FxHashMap<Arc<str>, usize>,
+    /// Recently decoded entries handed out by [`ZipFs::read_file`].
+    cache: Option<DecodedCache>,
+    /// Maximum decompressed size of a single entry.
+    max_entry_size: Option<u64>,
+    /// Password for encrypted entries.
+    password: Option<Password>,
+}
+
+impl LazySource {
+    pub(super) fn new(
+        archive: ZipArchive<SharedReader>,
+        reader: SharedReader,
+        indices: FxHashMap<Arc<str>, usize>,
+        options: &ZipFsBuilder,
+    ) -> Self {
+        Self {
+            archive: Mutex::new(archive),
+            reader,
+            indices,
+            cache: options.decoded_cache.map(DecodedCache::new),
+            max_entry_size: options.max_entry_size,
+            password: options.password.clone(),
+        }
+    }
+
+    /// Decompresses the entry stored under the normalized path `key`.
+    ///
+    /// Returns `None` if the entry is unknown, can no longer be read, or
+    /// exceeds the per‑entry size limit.
+    pub(super) fn decode(&self, key: &str) -> Option<Vec<u8>> {
+        let index = *self.indices.get(key)?;
+        let mut archive = self.archive.lock().unwrap_or_else(PoisonError::into_inner);
+        let file = match &self.password {
+            Some(password) => archive.by_index_decrypt(index, password.as_bytes()),
+            None => archive.by_index(index),
+        };
+        let content = ZipFs::try_read_file_content(file.ok()?, self.max_entry_size)?;
+        self.max_entry_size.is_none_or(|max| content.len() as u64 <= max).then_some(content)
+    }
+
+    /// Opens a stream over the entry stored under `key`.
+    ///
+    /// Stored and deflated entries are read directly from the archive data;
+    /// other methods fall back to [`decode`](Self::decode). Reading past the
+    /// per‑entry size limit fails. Encrypted entries are decoded up front.
+    /// Returns `None` for unknown entries.
+    pub(super) fn stream(&self, key: &str) -> Option<EntryReader<'static>> {
+        let index = *self.indices.get(key)?;
+        let (compression, start, len, encrypted) = {
+            let mut archive = self.archive.lock().unwrap_or_else(PoisonError::into_inner);
+            let file = archive.by_index_raw(index).ok()?;
+            (file.compression(), file.data_start(), file.compressed_size(), file.encrypted())
+        };
+        if encrypted { return self.decode(key).map(EntryReader::decoded); }
+
+        let mut raw = self.reader.clone();
+        raw.seek(SeekFrom::Start(start)).ok()?;
+        match compression {
+            CompressionMethod::Stored => Some(EntryReader::stored(raw.take(len), self.max_entry_size)),
+            CompressionMethod::Deflated => Some(EntryReader::deflated(raw.take(len), self.max_entry_size)),
+            _ => self.decode(key).map(EntryReader::decoded),
+        }
+    }
+
+    /// Heap bytes of the decoded entries held by the LRU.
+    pub(super) fn cached_bytes(&self) -> usize {
+        self.cache.as_ref().map_or(0, DecodedCache::bytes)
+    }
+
+    /// Approximate heap bytes of the path → index map (paths are shared
+    /// with the file system).
+    pub(super) fn index_bytes(&self) -> usize {
+        super::memory::map_bytes(&self.indices)
+    }
+
+    /// Copies the entry stored under `key` into `writer` as stored in the
+    /// archive (still compressed), named `name`. Not for encrypted entries:
+    /// the zip crate drops the encryption flag of raw copies.
+    ///
+    /// Returns `None` for unknown entries.
+    pub(super) fn raw_copy<W: Write + Seek>(
+        &self,
+        key: &str,
+        name: &str,
+        writer: &mut ZipWriter<W>,
+    ) -> Option<ZipResult<()>> {
+        let index = *self.indices.get(key)?;
+        let mut archive = self.archive.lock().unwrap_or_else(PoisonError::into_inner);
+        Some(archive.by_index_raw(index).and_then(|file| writer.raw_copy_file_rename(file, name)))
+    }
+
+    /// Returns the entry from the LRU, decoding and caching it on a miss.
+    ///
+    /// Without a cache the entry is decoded on every call.
+    pub(super) fn decode_cached(&self, key: &Arc<str>) -> Option<Arc<[u8]>> {
+        let Some(cache) = &self.cache else { return self.decode(key).map(Arc::from) };
+        if let Some(content) = cache.get(key) { return Some(content); }
+
+        let content: Arc<[u8]> = Arc::from(self.decode(key)?);
+        cache.insert(key.clone(), content.clone());
+        Some(content)
+    }
+}
+
+impl fmt::Debug for LazySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazySource")
+            .field("entries", &self.indices.len())
+            .field("cache", &self.cache)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A decoded entry: normalized path and content.
+type CachedEntry = (Arc<str>, Arc<[u8]>);
+
+/// Small LRU of decoded entries, ordered from least to most recently used.
+///
+/// Lookups are linear, which is fine for the handful of entries it is meant
+/// to hold; entries are `Arc`s, so evicting one never invalidates content a
+/// caller still holds.
+#[derive(Debug)]
+struct DecodedCache {
+    capacity: usize,
+    entries: Mutex<VecDeque<CachedEntry>>,
+}
+
+impl DecodedCache {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, entries: Mutex::new(VecDeque::with_capacity(capacity)) }
+    }
+
+    fn get(&self, key: &str) -> Option<Arc<[u8]>> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let pos = entries.iter().position(|(k, _)| &**k == key)?;
+        let entry = entries.remove(pos)?;
+        let content = entry.1.clone();
+        entries.push_back(entry);
+        Some(content)
+    }
+
+    fn bytes(&self) -> usize {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.iter().map(|(_, content)| content.len()).sum()
+    }
+
+    fn insert(&self, key: Arc<str>, content: Arc<[u8]>) {
+        if self.capacity == 0 { return; }
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.retain(|(k, _)| *k != key);
+        if entries.len() >= self.capacity { entries.pop_front(); }
+        entries.push_back((key, content));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use zip::CompressionMethod;
+    use crate::excel_parser::test_util::{encrypted_zip_archive, zip_archive, zip_archive_with};
+
+    fn entry(content: &str) -> Arc<[u8]> {
+        Arc::from(content.as_bytes())
+    }
+
+    fn cached_keys(cache: &DecodedCache) -> Vec<String> {
+        cache.entries.lock().unwrap().iter().map(|(k, _)| k.to_string()).collect()
+    }
+
+    #[test]
+    fn shared_readers_keep_their_own_position() {
+        let mut first = SharedReader::new(Box::new(Cursor::new(b"0123456789".to_vec())));
+        let mut second = first.clone();
+        second.seek(SeekFrom::Start(6)).unwrap();
+
+        let mut buf = [0u8; 3];
+        first.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"012");
+        second.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"678");
+        first.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"345");
+
+        assert_eq!(first.seek(SeekFrom::End(-1)).unwrap(), 9);
+        assert_eq!(first.seek(SeekFrom::Current(-4)).unwrap(), 5);
+        assert_eq!(first.seek(SeekFrom::Current(-6)).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        // A failed seek leaves the position alone.
+        assert_eq!(first.stream_position().unwrap(), 5);
+    }
+
+    #[test]
+    fn decoded_cache_evicts_the_least_recently_used_entry() {
+        let cache = DecodedCache::new(2);
+        cache.insert(Arc::from("a"), entry("aaaa"));
+        cache.insert(Arc::from("b"), entry("bb"));
+        // Touching `a` makes `b` the eviction candidate.
+        assert_eq!(cache.get("a").as_deref(), Some(&b"aaaa"[..]));
+        cache.insert(Arc::from("c"), entry("c"));
+        assert_eq!(cached_keys(&cache), ["a", "c"]);
+        assert!(cache.get("b").is_none());
+
+        // Re‑inserting a key replaces it instead of taking a second slot.
+        cache.insert(Arc::from("a"), entry("A"));
+        assert_eq!(cached_keys(&cache), ["c", "a"]);
+        assert_eq!(cache.get("a").as_deref(), Some(&b"A"[..]));
+    }
+
+    #[test]
+    fn decoded_cache_counts_the_bytes_it_holds() {
+        let cache = DecodedCache::new(2);
+        assert_eq!(cache.bytes(), 0);
+        cache.insert(Arc::from("a"), entry("aaaa"));
+        cache.insert(Arc::from("b"), entry("bb"));
+        assert_eq!(cache.bytes(), 6);
+        cache.insert(Arc::from("c"), entry("c"));
+        assert_eq!(cache.bytes(), 3);
+        cache.insert(Arc::from("c"), entry("cccccc"));
+        assert_eq!(cache.bytes(), 8);
+
+        let disabled = DecodedCache::new(0);
+        disabled.insert(Arc::from("a"), entry("aaaa"));
+        assert_eq!((disabled.bytes(), disabled.get("a")), (0, None));
+    }
+
+    #[test]
+    fn lazy_source_decodes_like_an_eager_load() {
+        let large = "<c/>".repeat(500);
+        let archive = zip_archive(&[("xl/a.xml", b"<a/>"), ("xl/large.xml", large.as_bytes())]);
+        let eager = ZipFs::builder().build(Cursor::new(&archive)).unwrap();
+        let lazy = ZipFs::builder().max_entry_size(1000).build_lazy(Cursor::new(archive)).unwrap();
+        let source = lazy.source.as_ref().unwrap();
+
+        assert_eq!(source.decode("xl/a.xml").as_deref(), eager.get_file("xl/a.xml"));
+        // Over the per‑entry limit, and not in the archive.
+        assert_eq!(source.decode("xl/large.xml"), None);
+        assert_eq!(source.decode("xl/missing.xml"), None);
+        // Decoding doesn't fill the file's slot.
+        assert!(lazy.files["xl/a.xml"].get().is_none());
+    }
+
+    #[test]
+    fn read_file_goes_through_the_decoded_cache() {
+        let archive = zip_archive(&[("xl/a.xml", b"<a/>"), ("xl/b.xml", b"<bb/>"), ("xl/c.xml", b"<ccc/>")]);
+        let fs = ZipFs::builder().decoded_cache(2).build_lazy(Cursor::new(archive)).unwrap();
+        let source = fs.source.as_ref().unwrap();
+
+        let first = fs.read_file("xl/a.xml").unwrap();
+        assert!(Arc::ptr_eq(&first, &fs.read_file("xl/a.xml").unwrap()));
+        fs.read_file("xl/b.xml").unwrap();
+        fs.read_file("xl/c.xml").unwrap();
+        assert_eq!(source.cached_bytes(), "<bb/>".len() + "<ccc/>".len());
+        assert_eq!(fs.memory_usage().cache_bytes, source.cached_bytes());
+
+        // `a` was evicted: a fresh copy is decoded, the old handle stays valid.
+        let again = fs.read_file("xl/a.xml").unwrap();
+        assert!(!Arc::ptr_eq(&first, &again));
+        assert_eq!((&*first, &*again), (&b"<a/>"[..], &b"<a/>"[..]));
+    }
+
+    fn read_all(mut reader: impl Read) -> io::Result<Vec<u8>> {
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content).map(|_| content)
+    }
+
+    #[test]
+    fn streams_match_the_eager_content() {
+        let large = "<row r=\"1\"><c/></row>".repeat(2000);
+        let archive = zip_archive_with(&[
+            ("xl/stored.xml", large.as_bytes(), CompressionMethod::Stored),
+            ("xl/deflated.xml", large.as_bytes(), CompressionMethod::Deflated),
+            ("xl/bzip2.xml", large.as_bytes(), CompressionMethod::Bzip2),
+            ("xl/empty.xml", b"", CompressionMethod::Deflated),
+        ]);
+        let eager = ZipFs::builder().build(Cursor::new(&archive)).unwrap();
+        let lazy = ZipFs::builder().build_lazy(Cursor::new(archive)).unwrap();
+
+        for (path, source) in [
+            ("xl/stored.xml", "stored"),
+            ("xl/deflated.xml", "deflated"),
+            ("xl/bzip2.xml", "decoded"),
+            ("xl/empty.xml", "deflated"),
+        ] {
+            let reader = lazy.get_file_reader(path).unwrap();
+            assert!(format!("{reader:?}").contains(source), "{path}: {reader:?}");
+            assert_eq!(read_all(reader).unwrap(), eager.get_file(path).unwrap(), "{path}");
+            // Streaming keeps nothing.
+            assert!(lazy.files[path].get().is_none(), "{path}");
+        }
+        assert!(lazy.get_file_reader("xl/missing.xml").is_none());
+    }
+
+    #[test]
+    fn interleaved_streams_share_the_archive_reader() {
+        let (a, b) = ("a".repeat(10_000), "b".repeat(10_000));
+        let archive = zip_archive_with(&[
+            ("xl/a.xml", a.as_bytes(), CompressionMethod::Deflated),
+            ("xl/b.xml", b.as_bytes(), CompressionMethod::Stored),
+        ]);
+        let fs = ZipFs::builder().build_lazy(Cursor::new(archive)).unwrap();
+        let (mut first, mut second) = (fs.get_file_reader("xl/a.xml").unwrap(), fs.get_file_reader("xl/b.xml").unwrap());
+
+        let (mut read_a, mut read_b) = (Vec::new(), Vec::new());
+        let mut buf = [0u8; 777];
+        loop {
+            let n = first.read(&mut buf).unwrap();
+            read_a.extend_from_slice(&buf[..n]);
+            let m = second.read(&mut buf).unwrap();
+            read_b.extend_from_slice(&buf[..m]);
+            if n == 0 && m == 0 { break; }
+        }
+        assert_eq!((read_a, read_b), (a.into_bytes(), b.into_bytes()));
+    }
+
+    #[test]
+    fn streams_of_encrypted_and_held_entries() {
+        let archive = encrypted_zip_archive(&[("xl/a.xml", b"<secret/>")], "pw");
+        let fs = ZipFs::builder().password("pw").build_lazy(Cursor::new(archive)).unwrap();
+        let reader = fs.get_file_reader("xl/a.xml").unwrap();
+        assert!(format!("{reader:?}").contains("decoded"));
+        assert_eq!(read_all(reader).unwrap(), b"<secret/>");
+
+        // Once held, the content is read in place.
+        fs.get_file("xl/a.xml").unwrap();
+        let reader = fs.get_file_reader("xl/a.xml").unwrap();
+        assert!(format!("{reader:?}").contains("held"));
+        assert_eq!(read_all(reader).unwrap(), b"<secret/>");
+    }
+
+    #[test]
+    fn streams_stop_at_the_entry_size_limit() {
+        let large = "x".repeat(5000);
+        let archive = zip_archive_with(&[
+            ("xl/stored.xml", large.as_bytes(), CompressionMethod::Stored),
+            ("xl/deflated.xml", large.as_bytes(), CompressionMethod::Deflated),
+        ]);
+        for limit in [4999, 5000] {
+            let fs = ZipFs::builder().max_entry_size(limit).build_lazy(Cursor::new(archive.clone())).unwrap();
+            for path in ["xl/stored.xml", "xl/deflated.xml"] {
+                let result = read_all(fs.get_file_reader(path).unwrap());
+                assert_eq!(result.is_ok(), limit == 5000, "{path} under {limit}");
+            }
+        }
+    }
+}
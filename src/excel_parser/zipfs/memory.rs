@@ -0,0 +1,105 @@
+use std::mem::size_of;
+use std::sync::Arc;
+use rustc_hash::FxHashMap;
+use super::ZipFs;
+
+/// Memory held by a [`ZipFs`], returned by [`ZipFs::memory_usage`].
+///
+/// Content sizes are exact (buffer capacities); index sizes are estimates
+/// from the capacity of each map and the length of the strings it owns,
+/// ignoring allocator overhead.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MemoryReport {
+    /// Heap bytes of content held per file, largest first. Files not
+    /// decoded yet (lazy mode) and memory‑mapped files hold `0`.
+    pub entries: Vec<(String, usize)>,
+    /// Heap bytes of all file contents (the sum of `entries`).
+    pub content_bytes: usize,
+    /// Bytes of content borrowed from a memory map. Backed by the OS page
+    /// cache rather than the heap, and not part of [`total`](Self::total).
+    pub mapped_bytes: usize,
+    /// Approximate heap bytes of paths, entry metadata, the directory and
+    /// case‑insensitive indexes, and load diagnostics.
+    pub index_bytes: usize,
+    /// Heap bytes of entries held by the lazy mode decoded cache. Shared
+    /// with [`deep_clone`](ZipFs::deep_clone) copies.
+    pub cache_bytes: usize,
+}
+
+impl MemoryReport {
+    /// Total heap bytes: content, index and cache.
+    pub fn total(&self) -> usize {
+        self.content_bytes + self.index_bytes + self.cache_bytes
+    }
+}
+
+/// Approximate heap bytes of a hash map's table, not counting what the keys
+/// and values own.
+pub(super) fn map_bytes<K, V>(map: &FxHashMap<K, V>) -> usize {
+    // One control byte per slot.
+    map.capacity() * (size_of::<(K, V)>() + 1)
+}
+
+/// Heap bytes of a shared string: the two reference counts and the text.
+fn arc_str_bytes(s: &Arc<str>) -> usize {
+    2 * size_of::<usize>() + s.len()
+}
+
+impl ZipFs {
+    /// Reports the memory held by this file system, per file and in total.
+    ///
+    /// Meant for attributing memory to individual workbooks when many are
+    /// open in one process. Paths are counted once although several indexes
+    /// share them.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, ZipFsError};
+    /// # fn main() -> Result<(), ZipFsError> {
+    /// let fs = ZipFs::open("report.xlsx", None, None)?;
+    /// let report = fs.memory_usage();
+    /// println!("{} bytes, largest part: {:?}", report.total(), report.entries.first());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn memory_usage(&self) -> MemoryReport {
+        let mut report = MemoryReport::default();
+
+        for (path, slot) in &self.files {
+            let held = slot.get().map_or(0, |content| content.heap_size());
+            if held == 0 && let Some(content) = slot.get() {
+                report.mapped_bytes += content.as_slice().len();
+            }
+            report.content_bytes += held;
+            report.index_bytes += arc_str_bytes(path);
+            report.entries.push((path.to_string(), held));
+        }
+        report.entries.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        report.index_bytes += map_bytes(&self.files) + map_bytes(&self.meta) + map_bytes(&self.dir_index);
+        report.index_bytes += self.dir_index.values()
+            .map(|files| files.capacity() * size_of::<Arc<str>>())
+            .sum::<usize>();
+        // Directory keys are shared with `parent_cache`, which also owns a copy of each.
+        report.index_bytes += map_bytes(&self.parent_cache);
+        report.index_bytes += self.parent_cache.iter()
+            .map(|(key, dir)| key.capacity() + arc_str_bytes(dir))
+            .sum::<usize>();
+        if let Some(index) = &self.case_index {
+            report.index_bytes += map_bytes(&index.files) + map_bytes(&index.dirs);
+            report.index_bytes += index.files.keys().chain(index.dirs.keys()).map(|k| k.len()).sum::<usize>();
+        }
+        report.index_bytes += self.crc_mismatches.iter().map(|m| size_of_val(m) + m.path.capacity()).sum::<usize>();
+        report.index_bytes += self.duplicates.iter()
+            .map(|d| size_of_val(d) + d.path.capacity() + d.stored_as.as_ref().map_or(0, String::capacity))
+            .sum::<usize>();
+
+        report.index_bytes += self.modified.capacity() * (size_of::<Arc<str>>() + 1);
+
+        if let Some(source) = &self.source {
+            report.index_bytes += source.index_bytes();
+            report.cache_bytes = source.cached_bytes();
+        }
+        report
+    }
+}
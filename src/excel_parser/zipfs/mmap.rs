@@ -0,0 +1,212 @@
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::ptr;
+use std::sync::Arc;
+
+/// Read‑only, private memory map of a whole file.
+///
+/// The mapping lives as long as the last [`MappedSlice`] pointing into it.
+/// Like every file mapping, its contents are only stable while no other
+/// process truncates or rewrites the file.
+pub(super) struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+// SAFETY: the mapping is read‑only and owned exclusively by this value; the
+// pointer is only used to build shared slices.
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    /// Maps the file at `path`. Empty files get an empty mapping.
+    pub(super) fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file too large to map"))?;
+        if len == 0 { return Ok(Self { ptr: ptr::null_mut(), len }); }
+
+        // SAFETY: a fresh read‑only private mapping of a valid descriptor; the
+        // descriptor may be closed afterwards without affecting the mapping.
+        let ptr = unsafe {
+            libc::mmap(ptr::null_mut(), len, libc::PROT_READ, libc::MAP_PRIVATE, file.as_raw_fd(), 0)
+        };
+        if ptr == libc::MAP_FAILED { return Err(io::Error::last_os_error()); }
+        Ok(Self { ptr, len })
+    }
+
+    pub(super) fn as_slice(&self) -> &[u8] {
+        if self.len == 0 { return &[]; }
+        // SAFETY: `ptr` points to `len` readable bytes mapped until `self` is
+        // dropped. The mapping can't outlive or shrink with the file
+        // descriptor, but the file itself can: pages past a later truncation
+        // raise SIGBUS on access, and writes by other processes may show
+        // through. That is outside this process's control, so it is a
+        // documented precondition of `ZipFsBuilder::build_mmap` (the only way
+        // to create a mapping) that the file is neither truncated nor
+        // rewritten while mapped.
+        unsafe { std::slice::from_raw_parts(self.ptr.cast::<u8>(), self.len) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: `ptr`/`len` describe a mapping created in `open` and not yet unmapped.
+            unsafe { libc::munmap(self.ptr, self.len); }
+        }
+    }
+}
+
+impl fmt::Debug for Mmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mmap").field("len", &self.len).finish()
+    }
+}
+
+/// A byte range of a shared [`Mmap`].
+#[derive(Debug, Clone)]
+pub(super) struct MappedSlice {
+    map: Arc<Mmap>,
+    range: Range<usize>,
+}
+
+impl MappedSlice {
+    /// Returns the slice `start..start + len` of `map`, or `None` if it is out of bounds.
+    pub(super) fn new(map: &Arc<Mmap>, start: u64, len: u64) -> Option<Self> {
+        let start = usize::try_from(start).ok()?;
+        let end = start.checked_add(usize::try_from(len).ok()?)?;
+        (end <= map.len).then(|| Self { map: map.clone(), range: start..end })
+    }
+
+    pub(super) fn as_slice(&self) -> &[u8] {
+        &self.map.as_slice()[self.range.clone()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::path::PathBuf;
+    use zip::CompressionMethod;
+    use crate::excel_parser::test_util::zip_archive_with;
+    use super::super::{ZipFs, ZipFsError};
+
+    /// Writes `content` to a fresh temporary file named after the test.
+    fn temp_file(name: &str, content: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("excel_parser_mmap_{name}_{}", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn maps_the_whole_file() {
+        let content: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let path = temp_file("whole", &content);
+        let map = Mmap::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Removing the file doesn't affect an existing mapping.
+        assert_eq!(map.as_slice(), content.as_slice());
+    }
+
+    #[test]
+    fn empty_files_get_an_empty_mapping() {
+        let path = temp_file("empty", b"");
+        let map = Mmap::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(map.ptr.is_null());
+        assert_eq!(map.as_slice(), b"");
+        assert!(MappedSlice::new(&Arc::new(map), 0, 0).is_some_and(|s| s.as_slice().is_empty()));
+    }
+
+    #[test]
+    fn slices_are_bounds_checked() {
+        let path = temp_file("bounds", b"0123456789");
+        let map = Arc::new(Mmap::open(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(MappedSlice::new(&map, 2, 3).unwrap().as_slice(), b"234");
+        assert_eq!(MappedSlice::new(&map, 10, 0).unwrap().as_slice(), b"");
+        assert!(MappedSlice::new(&map, 8, 3).is_none());
+        assert!(MappedSlice::new(&map, 11, 0).is_none());
+        assert!(MappedSlice::new(&map, u64::MAX, 2).is_none());
+    }
+
+    /// Whether `/proc/self/maps` lists a mapping of `path`.
+    #[cfg(target_os = "linux")]
+    fn is_mapped(path: &Path) -> bool {
+        std::fs::read_to_string("/proc/self/maps").unwrap().contains(path.to_str().unwrap())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn the_last_slice_dropped_unmaps_the_file() {
+        let path = temp_file("unmap", b"0123456789");
+        let map = Arc::new(Mmap::open(&path).unwrap());
+        let slice = MappedSlice::new(&map, 0, 4).unwrap();
+        assert!(is_mapped(&path));
+
+        drop(map);
+        assert!(is_mapped(&path));
+        assert_eq!(slice.clone().as_slice(), b"0123");
+
+        drop(slice);
+        assert!(!is_mapped(&path));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn build_mmap_matches_build() {
+        let media: Vec<u8> = (0..4096u32).map(|i| (i * 7) as u8).collect();
+        let archive = zip_archive_with(&[
+            ("xl/media/image1.png", &media, CompressionMethod::Stored),
+            ("xl/workbook.xml", b"<workbook/>", CompressionMethod::Deflated),
+        ]);
+        let path = temp_file("build", &archive);
+
+        let mapped = ZipFs::open_mmap(&path).unwrap();
+        let copied = ZipFs::builder().build(Cursor::new(&archive)).unwrap();
+        for file in ["xl/media/image1.png", "xl/workbook.xml"] {
+            assert_eq!(mapped.get_file(file), copied.get_file(file), "{file}");
+        }
+
+        // Only the stored entry borrows from the mapping.
+        let usage = mapped.memory_usage();
+        assert_eq!(usage.mapped_bytes, media.len());
+        assert_eq!(usage.content_bytes, b"<workbook/>".len());
+
+        // The mapping outlives the file system while an entry is held.
+        let image = mapped.deep_clone();
+        drop(mapped);
+        assert_eq!(image.get_file("xl/media/image1.png"), Some(media.as_slice()));
+
+        let too_small = ZipFs::builder().max_archive_size(10).build_mmap(&path);
+        assert!(matches!(too_small, Err(ZipFsError::ArchiveTooLarge(_, 10))));
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(ZipFs::open_mmap(&path), Err(ZipFsError::Io(_))));
+    }
+
+    #[test]
+    fn mapped_entries_are_crc_checked_with_verify_crc() {
+        let mut archive = zip_archive_with(&[("xl/media/image1.png", b"original", CompressionMethod::Stored)]);
+        // Corrupt the stored data in place; headers keep the original CRC.
+        let at = archive.windows(8).position(|w| w == b"original").unwrap();
+        archive[at] = b'O';
+        let path = temp_file("crc", &archive);
+
+        let unchecked = ZipFs::open_mmap(&path).unwrap();
+        assert_eq!(unchecked.get_file("xl/media/image1.png"), Some(&b"Original"[..]));
+
+        let checked = ZipFs::builder().verify_crc(true).build_mmap(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!checked.contains("xl/media/image1.png"));
+        assert_eq!(checked.crc_mismatches().len(), 1);
+    }
+}
@@ -1,12 +1,37 @@
+mod builder;
+mod content_types;
+mod entry;
+mod extract;
 mod filters;
+mod lazy;
+mod memory;
+#[cfg(all(feature = "mmap", unix))]
+mod mmap;
+mod parallel;
 mod path_utils;
+mod source;
+mod storage;
+mod stream;
+mod write;
 
-pub use path_utils::{normalize_path, parent_dir, normalize_dir, is_safe_path};
-use std::{io::{Read, Seek, SeekFrom}, borrow::Cow, sync::Arc};
+pub use path_utils::{normalize_path, normalize_path_ci, parent_dir, normalize_dir, is_safe_path};
+use std::{io::{Read, Seek, SeekFrom}, borrow::Cow, sync::{Arc, OnceLock}};
+use std::hash::{BuildHasher, DefaultHasher, Hasher, RandomState};
 use zip::{result::ZipError, ZipArchive, read::ZipFile};
 pub use filters::FilterSet;
-use rustc_hash::FxHashMap;
+pub use builder::{DuplicatePolicy, ZipFsBuilder};
+pub use entry::{CrcMismatch, DuplicateEntry, EntryMeta, EntryReader};
+pub use content_types::ContentTypes;
+pub use memory::MemoryReport;
+pub use source::{SourceReader, ZipSource};
+use rustc_hash::{FxHashMap, FxHashSet};
 use thiserror::Error;
+use builder::Password;
+use lazy::{LazySource, ReadSeek, SharedReader};
+use path_utils::validate_path;
+use storage::Content;
+#[cfg(all(feature = "mmap", unix))]
+use mmap::{MappedSlice, Mmap};
 
 // ---------------------------------------------------------------------------
 // Custom error type (thiserror)
@@ -26,15 +51,82 @@ pub enum ZipFsError {
     #[error("Archive size {0} exceeds limit {1}")]
     ArchiveTooLarge(u64, u64),
 
-    /// A path or glob pattern was invalid (empty, contains "..", etc.).
+    /// An entry decompressed to more bytes than the per‑entry limit
+    /// (path, limit).
+    #[error("Entry '{0}' decompresses to more than {1} bytes")]
+    EntryTooLarge(String, u64),
+
+    /// The loaded entries decompress to more than the total limit; details
+    /// list the entries left unloaded.
+    #[error("Decompressed size exceeds total limit {}; {} entries skipped", .0.limit, .0.skipped.len())]
+    TotalTooLarge(BudgetExceeded),
+
+    /// An entry to be loaded is encrypted and no password was given (path).
+    #[error("Entry '{0}' is encrypted and no password was provided")]
+    PasswordRequired(String),
+
+    /// The password doesn't decrypt an entry (path).
+    #[error("Wrong password for entry '{0}'")]
+    WrongPassword(String),
+
+    /// An entry uses an encryption scheme that can't be decrypted
+    /// (path, reason).
+    #[error("Unsupported encryption for entry '{0}': {1}")]
+    UnsupportedEncryption(String, String),
+
+    /// The archive holds more loadable entries than the configured maximum.
+    #[error("Archive has {0} entries, exceeding limit {1}")]
+    TooManyEntries(usize, usize),
+
+    /// No files were loaded (the archive is empty, holds only directories, or
+    /// every entry was filtered out) and the builder requested an error for that.
+    #[error("Archive has no loadable entries")]
+    EmptyArchive,
+
+    /// `[Content_Types].xml` could not be parsed while filtering by content type.
+    #[error("Invalid [Content_Types].xml: {0}")]
+    InvalidContentTypes(quick_xml::Error),
+
+    /// A path, glob or regex pattern was invalid (empty, contains "..", malformed, etc.).
     #[error("Invalid glob pattern: {0}")]
     InvalidPattern(String),
 
+    /// A path can't be extracted safely: it has empty, `.`, `..`, root or
+    /// drive components, or contains `\`, `:` or NUL (path).
+    #[error("Unsafe path for extraction: {0}")]
+    UnsafePath(String),
+
+    /// An archive path occurs more than once and the builder's
+    /// [`DuplicatePolicy`] is `Error` (path).
+    #[error("Entry '{0}' occurs more than once in the archive")]
+    DuplicatePath(String),
+
+    /// A file the operation needs is not in the file system (path).
+    #[error("File '{0}' not found")]
+    FileNotFound(String),
+
     /// An I/O error while reading the archive.
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
 
+/// Details of a load aborted by [`ZipFsBuilder::max_total_size`].
+///
+/// Once the budget is exhausted no further entry is decompressed; the rest of
+/// the central directory is still walked so that callers can report exactly
+/// which parts of an untrusted upload were refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    /// The configured total limit in bytes.
+    pub limit: u64,
+    /// Bytes decompressed by the entries that fit in the budget.
+    pub decompressed: u64,
+    /// Paths of the entries that were not loaded, in archive order: the one
+    /// that crossed the limit, then every remaining entry that would have
+    /// been loaded.
+    pub skipped: Vec<String>,
+}
+
 // ---------------------------------------------------------------------------
 // ZipFs – in-memory virtual file system from a ZIP archive
 // ---------------------------------------------------------------------------
@@ -47,8 +139,8 @@ pub enum ZipFsError {
 /// - Optional archive size limit (protection against OOM).
 ///
 /// # Example
-/// ```
-/// # use zip_fs::{ZipFs, FilterSet, ZipFsError};
+/// ```no_run
+/// # use excel_parser::{ZipFs, FilterSet, ZipFsError};
 /// # fn main() -> Result<(), ZipFsError> {
 /// let data = std::fs::File::open("archive.zip")?;
 /// let filter = FilterSet::new()
@@ -67,12 +159,35 @@ pub enum ZipFsError {
 /// ```
 #[derive(Debug)]
 pub struct ZipFs {
-    /// File storage: normalized path → raw content.
-    files: FxHashMap<Arc<str>, Vec<u8>>,
+    /// File storage: normalized path → raw content. Slots are filled at load
+    /// time, or on first access in lazy mode.
+    files: FxHashMap<Arc<str>, OnceLock<Content>>,
+    /// Entry metadata: normalized path → header information (sizes, method, CRC).
+    meta: FxHashMap<Arc<str>, EntryMeta>,
     /// Directory index: normalized directory path → list of full file paths in it.
     dir_index: FxHashMap<Arc<str>, Vec<Arc<str>>>,
     /// Cache for parent directory strings to avoid repeated allocations.
-    parent_cache: FxHashMap<String, Arc<str>>
+    parent_cache: FxHashMap<String, Arc<str>>,
+    /// Case‑folded lookup tables, present only in case‑insensitive mode.
+    case_index: Option<CaseIndex>,
+    /// The open archive, kept only in lazy mode to fill empty slots.
+    source: Option<Arc<LazySource>>,
+    /// Entries rejected at load time because their content failed the CRC check.
+    crc_mismatches: Vec<CrcMismatch>,
+    /// Archive paths that occurred more than once, handled per the duplicate policy.
+    duplicates: Vec<DuplicateEntry>,
+    /// Files replaced or inserted since loading; the others can be copied
+    /// from the archive as stored.
+    modified: FxHashSet<Arc<str>>,
+    /// Password the archive was loaded with, to encrypt entries again on write.
+    password: Option<Password>,
+}
+
+/// Case‑folded path → canonical key, for case‑insensitive lookups.
+#[derive(Debug, Default, Clone)]
+struct CaseIndex {
+    files: FxHashMap<Box<str>, Arc<str>>,
+    dirs: FxHashMap<Box<str>, Arc<str>>,
 }
 
 impl ZipFs {
@@ -93,23 +208,280 @@ impl ZipFs {
         filter: Option<FilterSet>,
         max_archive_size: Option<u64>,
     ) -> Result<Self, ZipFsError> {
-        let reader = Self::check_archive_size(reader, max_archive_size)?;
+        ZipFs::load(reader, &ZipFsBuilder::new().with_basics(filter, max_archive_size))
+    }
 
-        let archive = ZipArchive::new(reader)?;
-        let mut fs = ZipFs {
-            files: FxHashMap::with_capacity_and_hasher(archive.len(), Default::default()),
-            dir_index: FxHashMap::with_capacity_and_hasher(archive.len() / 5, Default::default()),
-            parent_cache: FxHashMap::with_capacity_and_hasher(64, Default::default())
-        };
+    /// Like [`new`](Self::new), for archives with encrypted entries.
+    ///
+    /// Both ZipCrypto and AES (AE‑1/AE‑2) entries are decrypted with
+    /// `password`; unencrypted entries are read as usual. Shorthand for the
+    /// builder with [`password`](ZipFsBuilder::password) set.
+    ///
+    /// # Arguments
+    /// * `reader` – source of ZIP data (must implement `Read + Seek`).
+    /// * `filter` – optional [`FilterSet`] with exact paths and/or glob patterns.
+    /// * `max_archive_size` – optional maximum allowed archive size in bytes.
+    /// * `password` – the archive password.
+    ///
+    /// # Errors
+    /// * `ZipFsError::WrongPassword` – the password doesn't match an entry.
+    /// * `ZipFsError::UnsupportedEncryption` – an entry's encryption can't be handled.
+    /// * Otherwise the same as [`new`](Self::new).
+    pub fn new_with_password<R: Read + Seek>(
+        reader: R,
+        filter: Option<FilterSet>,
+        max_archive_size: Option<u64>,
+        password: &[u8],
+    ) -> Result<Self, ZipFsError> {
+        ZipFs::load(reader, &ZipFsBuilder::new().password(password).with_basics(filter, max_archive_size))
+    }
+
+    /// Opens the archive file at `path` and loads files matching `filter`.
+    ///
+    /// Shorthand for [`new`](Self::new) with a [`File`](std::fs::File).
+    ///
+    /// # Arguments
+    /// * `path` – path of the ZIP (xlsx) file.
+    /// * `filter` – optional [`FilterSet`] with exact paths and/or glob patterns.
+    /// * `max_archive_size` – optional maximum allowed archive size in bytes.
+    ///
+    /// # Errors
+    /// * `ZipFsError::Io` – the file can't be opened or read.
+    /// * Otherwise the same as [`new`](Self::new).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, ZipFsError};
+    /// # fn main() -> Result<(), ZipFsError> {
+    /// let fs = ZipFs::open("report.xlsx", None, Some(100 * 1024 * 1024))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open<P: AsRef<std::path::Path>>(
+        path: P,
+        filter: Option<FilterSet>,
+        max_archive_size: Option<u64>,
+    ) -> Result<Self, ZipFsError> {
+        ZipFs::new(std::fs::File::open(path)?, filter, max_archive_size)
+    }
+
+    /// Loads files matching `filter` from an archive held in memory.
+    ///
+    /// The size limit is checked against `bytes.len()` directly, without
+    /// seeking. The content is copied out of `bytes`, so the buffer can be
+    /// dropped afterwards.
+    ///
+    /// # Arguments
+    /// * `bytes` – the complete ZIP data.
+    /// * `filter` – optional [`FilterSet`] with exact paths and/or glob patterns.
+    /// * `max_archive_size` – optional maximum allowed archive size in bytes.
+    ///
+    /// # Errors
+    /// Same as [`new`](Self::new), except that no I/O errors occur.
+    pub fn from_bytes(
+        bytes: &[u8],
+        filter: Option<FilterSet>,
+        max_archive_size: Option<u64>,
+    ) -> Result<Self, ZipFsError> {
+        let size = bytes.len() as u64;
+        if let Some(limit) = max_archive_size && size > limit {
+            return Err(ZipFsError::ArchiveTooLarge(size, limit));
+        }
+        ZipFs::load(std::io::Cursor::new(bytes), &ZipFsBuilder::new().with_basics(filter, None))
+    }
+
+    /// Loads files matching `filter` from a ZIP stream that can't seek, such
+    /// as stdin or a socket.
+    ///
+    /// Shorthand for `ZipFs::builder().build_stream(reader)`; see
+    /// [`ZipFsBuilder::build_stream`] for what stream loading supports.
+    ///
+    /// # Arguments
+    /// * `reader` – source of ZIP data, read once from start to end.
+    /// * `filter` – optional [`FilterSet`] with exact paths and/or glob patterns.
+    /// * `max_archive_size` – optional limit on the bytes read from `reader`.
+    ///
+    /// # Errors
+    /// Same as [`new`](Self::new).
+    pub fn from_stream<R: Read>(
+        reader: R,
+        filter: Option<FilterSet>,
+        max_archive_size: Option<u64>,
+    ) -> Result<Self, ZipFsError> {
+        ZipFs::load_stream(reader, &ZipFsBuilder::new().with_basics(filter, max_archive_size))
+    }
+
+    /// Returns a [`ZipFsBuilder`] for configuring additional loading options.
+    pub fn builder() -> ZipFsBuilder {
+        ZipFsBuilder::new()
+    }
+
+    /// Memory‑maps the archive at `path` and loads all entries.
+    ///
+    /// Shorthand for `ZipFs::builder().build_mmap(path)`; see
+    /// [`ZipFsBuilder::build_mmap`], including its truncation hazard: the
+    /// file must not be truncated or rewritten while the result lives.
+    ///
+    /// # Errors
+    /// * `ZipFsError::Io` – the file can't be opened or mapped.
+    /// * `ZipFsError::Zip` – malformed ZIP structure.
+    #[cfg(all(feature = "mmap", unix))]
+    pub fn open_mmap<P: AsRef<std::path::Path>>(path: P) -> Result<Self, ZipFsError> {
+        ZipFsBuilder::new().build_mmap(path)
+    }
+
+    /// Loads a ZIP archive stored as a file of this one (an xlsx delivered
+    /// inside an outer `.zip`, for example), without a temporary file.
+    ///
+    /// The inner archive is read straight from the outer file's content; use
+    /// [`ZipFsBuilder::build_nested`] for the other loading options.
+    ///
+    /// # Arguments
+    /// * `path` – path of the inner archive within this file system.
+    /// * `filter` – optional [`FilterSet`] applied to the inner archive.
+    /// * `max_archive_size` – optional maximum size of the inner archive in bytes.
+    ///
+    /// # Errors
+    /// * `ZipFsError::FileNotFound` – `path` isn't loaded (or can't be decompressed).
+    /// * Otherwise the same as [`new`](Self::new).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, FilterSet, ZipFsError};
+    /// # fn main() -> Result<(), ZipFsError> {
+    /// let outer = ZipFs::open("delivery.zip", Some(FilterSet::new().add_glob("*.xlsx")?), None)?;
+    /// let workbook = outer.open_nested("report.xlsx", None, Some(100 * 1024 * 1024))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_nested(
+        &self,
+        path: &str,
+        filter: Option<FilterSet>,
+        max_archive_size: Option<u64>,
+    ) -> Result<ZipFs, ZipFsError> {
+        ZipFsBuilder::new().with_basics(filter, max_archive_size).build_nested(self, path)
+    }
+
+    /// Opens the archive and loads entries according to the builder options.
+    fn load<R: Read + Seek>(reader: R, options: &ZipFsBuilder) -> Result<Self, ZipFsError> {
+        let reader = Self::check_archive_size(reader, options.max_archive_size)?;
+
+        let mut archive = ZipArchive::new(reader)?;
+        let mut fs = ZipFs::with_capacity(archive.len(), options);
+        fs.load_entries(&mut archive, options, &LoadMode::Eager)?;
+        fs.check_not_empty(options)?;
+        Ok(fs)
+    }
+
+    /// Opens the archive and indexes entries without decompressing them; the
+    /// archive is kept to decode entries on first access.
+    fn load_lazy(reader: Box<dyn ReadSeek>, options: &ZipFsBuilder) -> Result<Self, ZipFsError> {
+        let reader = SharedReader::new(Self::check_archive_size(reader, options.max_archive_size)?);
+
+        let mut archive = ZipArchive::new(reader.clone())?;
+        let mut fs = ZipFs::with_capacity(archive.len(), options);
+        let indices = fs.load_entries(&mut archive, options, &LoadMode::Lazy)?;
+        fs.check_not_empty(options)?;
+        fs.source = Some(Arc::new(LazySource::new(archive, reader, indices, options)));
+        Ok(fs)
+    }
 
-        fs.load_entries(archive, filter.as_ref());
+    /// Reads the archive from `reader` with async I/O, then loads it like
+    /// [`load`](Self::load) on tokio's blocking thread pool.
+    #[cfg(feature = "async")]
+    async fn load_async<R>(mut reader: R, options: ZipFsBuilder) -> Result<Self, ZipFsError>
+    where
+        R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+    {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let size = reader.seek(SeekFrom::End(0)).await?;
+        if let Some(limit) = options.max_archive_size && size > limit {
+            return Err(ZipFsError::ArchiveTooLarge(size, limit));
+        }
+        reader.seek(SeekFrom::Start(0)).await?;
+        let mut bytes = Vec::with_capacity(usize::try_from(size).unwrap_or(0));
+        // The reader may grow after the size was taken: never read past it.
+        (&mut reader).take(size).read_to_end(&mut bytes).await?;
+
+        let load = tokio::task::spawn_blocking(move || Self::load(std::io::Cursor::new(bytes), &options));
+        match load.await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(ZipFsError::Io(std::io::Error::other(e))),
+        }
+    }
+
+    /// Maps the archive file into memory and loads entries; `Stored` entries
+    /// point into the mapping instead of being copied.
+    #[cfg(all(feature = "mmap", unix))]
+    fn load_mmap(path: &std::path::Path, options: &ZipFsBuilder) -> Result<Self, ZipFsError> {
+        let map = Arc::new(Mmap::open(path)?);
+        let size = map.as_slice().len() as u64;
+        if let Some(limit) = options.max_archive_size && size > limit {
+            return Err(ZipFsError::ArchiveTooLarge(size, limit));
+        }
+
+        let mut archive = ZipArchive::new(std::io::Cursor::new(map.as_slice()))?;
+        let mut fs = ZipFs::with_capacity(archive.len(), options);
+        fs.load_entries(&mut archive, options, &LoadMode::Mapped(map.clone()))?;
+        fs.check_not_empty(options)?;
         Ok(fs)
     }
 
+    /// Creates an empty file system sized for an archive of `entries` entries.
+    ///
+    /// The count comes from the untrusted central directory, so it is capped
+    /// at [`max_entries`](ZipFsBuilder::max_entries) (checked when loading).
+    fn with_capacity(entries: usize, options: &ZipFsBuilder) -> Self {
+        let entries = options.max_entries.map_or(entries, |limit| entries.min(limit));
+        ZipFs {
+            files: FxHashMap::with_capacity_and_hasher(entries, Default::default()),
+            meta: FxHashMap::with_capacity_and_hasher(entries, Default::default()),
+            dir_index: FxHashMap::with_capacity_and_hasher(entries / 5, Default::default()),
+            parent_cache: FxHashMap::with_capacity_and_hasher(64, Default::default()),
+            case_index: options.case_insensitive.then(CaseIndex::default),
+            source: None,
+            crc_mismatches: Vec::new(),
+            duplicates: Vec::new(),
+            modified: FxHashSet::default(),
+            password: options.password.clone(),
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Public API
     // -------------------------------------------------------------------------
 
+    /// Returns `true` if a file is loaded under `path`.
+    ///
+    /// Resolved like [`get_file`](Self::get_file), but never decompresses
+    /// anything in lazy mode.
+    pub fn contains(&self, path: &str) -> bool {
+        self.file_key(path).is_some()
+    }
+
+    /// Returns the number of loaded files.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns `true` if no files are loaded.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns an iterator over the paths of all loaded files, in no
+    /// particular order.
+    ///
+    /// Paths are normalized (`"xl/workbook.xml"`) and can be passed back to
+    /// [`get_file`](Self::get_file). Collect and sort them for a stable order,
+    /// or use [`walk`](Self::walk) for a sorted listing by directory.
+    pub fn paths(&self) -> impl Iterator<Item = &str> + '_ {
+        self.files.keys().map(AsRef::as_ref)
+    }
+
     /// Returns the **full paths** of files that are **immediate children** of `dir_path`.
     ///
     /// Subdirectories are **not** traversed. To list files in a subdirectory,
@@ -123,155 +495,1582 @@ impl ZipFs {
     /// the given directory. If the directory does not exist or contains no files,
     /// an empty vector is returned.
     pub fn list_files(&self, dir_path: &str) -> Vec<&str> {
-        let normalized = normalize_dir(dir_path);
-        self.dir_index
-            .get(&*normalized)
+        self.dir_entries(dir_path)
             .map(|v| v.iter().map(AsRef::as_ref).collect())
             .unwrap_or_default()
     }
 
+    /// Returns the number of files that are **immediate children** of `dir_path`.
+    ///
+    /// Equivalent to `list_files(dir_path).len()` but without allocating the
+    /// path vector.
+    ///
+    /// # Arguments
+    /// * `dir_path` – a directory path (e.g., `"xl/worksheets"`).
+    ///
+    /// # Returns
+    /// The file count, or `0` if the directory does not exist.
+    pub fn dir_file_count(&self, dir_path: &str) -> usize {
+        self.dir_entries(dir_path).map_or(0, Vec::len)
+    }
+
+    /// Returns the full paths of all files below `dir_path`, at any depth.
+    ///
+    /// Unlike [`list_files`](Self::list_files), subdirectories are traversed.
+    /// Directories are visited in sorted order; files of one directory come in
+    /// archive order. Pass `""` to walk the whole archive.
+    ///
+    /// # Arguments
+    /// * `dir_path` – the directory to walk (e.g., `"xl"`).
+    /// * `max_depth` – how many directory levels below `dir_path` to descend;
+    ///   `Some(0)` yields only immediate children, `None` means unlimited.
+    ///
+    /// # Returns
+    /// An iterator over file paths; empty if the directory does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::ZipFs;
+    /// # fn demo(fs: &ZipFs) {
+    /// // xl/workbook.xml, xl/worksheets/sheet1.xml, xl/worksheets/_rels/sheet1.xml.rels, ...
+    /// for path in fs.walk("xl", None) {
+    ///     println!("{}", path);
+    /// }
+    /// # }
+    /// ```
+    pub fn walk(&self, dir_path: &str, max_depth: Option<usize>) -> impl Iterator<Item = &str> + '_ {
+        let root = normalize_dir(dir_path);
+        let root_ci = self.case_index.is_some().then(|| normalize_path_ci(&root).into_owned());
+
+        let mut dirs: Vec<(&str, &Vec<Arc<str>>)> = self.dir_index.iter()
+            .filter(|(dir, _)| {
+                let depth = depth_below(&root, dir).or_else(|| {
+                    depth_below(root_ci.as_deref()?, &normalize_path_ci(dir))
+                });
+                depth.is_some_and(|d| max_depth.is_none_or(|max| d <= max))
+            })
+            .map(|(dir, files)| (&**dir, files))
+            .collect();
+        dirs.sort_unstable_by_key(|&(dir, _)| dir);
+
+        dirs.into_iter().flat_map(|(_, files)| files.iter().map(AsRef::as_ref))
+    }
+
     /// Returns the raw content of a file, if loaded.
     ///
+    /// In lazy mode the file is decompressed on the first call and kept for the
+    /// lifetime of the `ZipFs`.
+    ///
     /// # Arguments
     /// * `path` – the normalized path of the file (e.g., `"doc.txt"`).
     ///
     /// # Returns
     /// `Some(&[u8])` containing the file's data, or `None` if the file was not
-    /// found (either because it wasn't in the archive or it was filtered out).
+    /// found (either because it wasn't in the archive or it was filtered out)
+    /// or, in lazy mode, could not be decompressed.
     pub fn get_file(&self, path: &str) -> Option<&[u8]> {
-        let normalized = normalize_path(path);
-        self.files.get(&*normalized).map(|v| v.as_slice())
-    }
+        let key = self.file_key(path)?;
+        let slot = self.files.get(key)?;
+        if let Some(content) = slot.get() { return Some(content.as_slice()); }
 
-    // -------------------------------------------------------------------------
-    // Internal helpers
-    // -------------------------------------------------------------------------
+        // Lazy mode: another thread may fill the slot first; either value is the same.
+        let content = self.source.as_ref()?.decode(key)?;
+        Some(slot.get_or_init(|| Content::Owned(content)).as_slice())
+    }
 
-    /// Indexes a file under its **immediate** parent directory.
+    /// Returns a shared handle to a file's content without keeping it in the
+    /// file system.
     ///
-    /// Updates `dir_index` so that the file's path is recorded under the
-    /// normalized parent directory key. The root directory is represented by
-    /// an empty string.
+    /// Intended for lazy mode with a
+    /// [`decoded_cache`](ZipFsBuilder::decoded_cache): content decoded here goes
+    /// to a bounded LRU instead of the file's slot, so touching many large
+    /// parts one after another doesn't accumulate them all in memory. Files
+    /// already held (eagerly loaded, or read through
+    /// [`get_file`](Self::get_file)) are copied into the returned `Arc`.
     ///
     /// # Arguments
-    /// * `file_path` – the full normalized path of the file (as an `Arc<str>`).
-    #[inline]
-    fn index_file(&mut self, file_path: Arc<str>) {
-        let parent = parent_dir(file_path.as_ref());
-        let parent_key = if parent.is_empty() {
-            self.parent_cache
-                .entry(String::new())
-                .or_insert_with(|| Arc::from(""))
-                .clone()
-        } else {
-            self.parent_cache
-                .entry(parent.to_string())
-                .or_insert_with(|| Arc::from(parent))
-                .clone()
-        };
-        
-        self.dir_index.entry(parent_key)
-            .or_default()
-            .push(file_path);
+    /// * `path` – the path of the file.
+    ///
+    /// # Returns
+    /// The content, or `None` if the file is not loaded or can't be decompressed.
+    pub fn read_file(&self, path: &str) -> Option<Arc<[u8]>> {
+        let key = self.file_key(path)?;
+        match self.files.get(key)?.get() {
+            Some(content) => Some(Arc::from(content.as_slice())),
+            None => self.source.as_ref()?.decode_cached(key),
+        }
     }
 
-    /// Tries to read the entire file content into memory.
+    /// Returns a streaming reader over a file's content.
     ///
-    /// Performs basic capacity checks to avoid allocation failures for very
-    /// large files. Returns `None` if the file size exceeds `usize::MAX` or if
-    /// memory reservation fails.
+    /// For lazily loaded file systems this avoids materializing large parts:
+    /// a multi‑hundred‑MB `xl/sharedStrings.xml` can be fed to a streaming XML
+    /// reader without first holding the whole decompressed entry next to its
+    /// parsed form. Nothing is kept after the reader is dropped; the file's
+    /// slot stays empty. Content the file system already holds is read in
+    /// place.
+    ///
+    /// Unlike [`get_file`](Self::get_file), a lazy stream doesn't verify the
+    /// entry's CRC‑32; corrupt deflate data surfaces as a read error.
     ///
     /// # Arguments
-    /// * `file` – the ZIP file entry to read.
-    /// * `name` – the normalized path as an `Arc<str>` (to be reused in the result).
+    /// * `path` – the path of the file.
     ///
     /// # Returns
-    /// An optional tuple `(Arc<str>, Vec<u8>)` containing the path and the file
-    /// content, or `None` if reading failed.
-    fn try_read_file_content<R: Read>(mut file: ZipFile<R>, name: Arc<str>) -> Option<(Arc<str>, Vec<u8>)> {
-        let size = file.size();
-        if size > usize::MAX as u64 { return None; }
-        
-        let mut content = Vec::new();
-        if content.try_reserve_exact(size as usize).is_err() { return None; }
-        
-        file.read_to_end(&mut content).ok()?;
-        Some((name, content))
+    /// The reader, or `None` if the file is not loaded or, in lazy mode, is
+    /// encrypted or can't be located in the archive.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::io::Read;
+    /// # use excel_parser::ZipFs;
+    /// # fn demo(fs: &ZipFs) -> std::io::Result<()> {
+    /// if let Some(mut reader) = fs.get_file_reader("xl/sharedStrings.xml") {
+    ///     let mut head = [0u8; 64];
+    ///     let n = reader.read(&mut head)?;
+    ///     println!("{}", String::from_utf8_lossy(&head[..n]));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_file_reader(&self, path: &str) -> Option<EntryReader<'_>> {
+        let key = self.file_key(path)?;
+        match self.files.get(key)?.get() {
+            Some(content) => Some(EntryReader::held(content.as_slice())),
+            None => self.source.as_ref()?.stream(key),
+        }
     }
 
-    /// Iterates over all ZIP entries, applies filters, and loads matching files.
+    /// Returns `true` if the file system decompresses entries on demand
+    /// (built with [`ZipFsBuilder::build_lazy`]).
+    pub fn is_lazy(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Returns a byte range of a file's content, if loaded.
     ///
-    /// This method populates `files` and `dir_index` with entries that are not
-    /// directories, have safe paths, and (if a filter is provided) match the filter.
-    /// Corrupted entries are silently skipped.
+    /// Handy for sniffing headers without touching the whole part. The range is
+    /// clamped to the end of the file, so a `len` larger than what remains
+    /// simply yields a shorter slice.
     ///
     /// # Arguments
-    /// * `archive` – the opened ZIP archive.
-    /// * `filter` – optional reference to a [`FilterSet`].
-    fn load_entries<R: Read + Seek>(
-        &mut self,
-        mut archive: ZipArchive<R>,
-        filter: Option<&FilterSet>,
-    ) {
-        // Pre-allocate storage for files with known capacity.
-        self.files.reserve(archive.len());
+    /// * `path` – the path of the file.
+    /// * `start` – offset of the first byte.
+    /// * `len` – maximum number of bytes to return.
+    ///
+    /// # Returns
+    /// `Some(&[u8])` with at most `len` bytes starting at `start`, or `None` if
+    /// the file is not loaded or `start` is past the end of the file.
+    /// `start == file length` yields an empty slice.
+    pub fn get_file_range(&self, path: &str, start: usize, len: usize) -> Option<&[u8]> {
+        let content = self.get_file(path)?;
+        let rest = content.get(start..)?;
+        Some(&rest[..len.min(rest.len())])
+    }
 
-        for i in 0..archive.len() {
-            // Silently skip corrupted entries.
-            let file = match archive.by_index(i) {
-                Ok(f) => f,
-                Err(_) => continue,
-            };
+    /// Returns the header metadata of a loaded file.
+    ///
+    /// # Arguments
+    /// * `path` – the path of the file (e.g., `"xl/workbook.xml"`).
+    ///
+    /// # Returns
+    /// `Some(&EntryMeta)` for loaded files, `None` otherwise.
+    pub fn entry_meta(&self, path: &str) -> Option<&EntryMeta> {
+        self.meta.get(self.file_key(path)?)
+    }
 
-            // Normalize the entry name without allocating if already clean.
-            let name_cow = normalize_path(file.name());
-            let name_str: &str = name_cow.as_ref();
+    /// Returns a hash of a loaded file's content, keyed randomly per process.
+    ///
+    /// Intended as a cache key for derived data (parsed sheets, search
+    /// indices). The hash is SipHash with a key chosen at random the first
+    /// time it is needed, so it is stable for the lifetime of the process but
+    /// differs between runs. Because the key is unknown to outside parties,
+    /// they cannot craft files whose hashes collide, which matters when one
+    /// cache serves uploads from several tenants. Use
+    /// [`content_hash_seeded`](Self::content_hash_seeded) when hashes must be
+    /// reproducible.
+    ///
+    /// # Arguments
+    /// * `path` – the path of the file (e.g., `"xl/sharedStrings.xml"`).
+    ///
+    /// # Returns
+    /// The 64‑bit hash, or `None` if the file is not loaded.
+    pub fn content_hash(&self, path: &str) -> Option<u64> {
+        static STATE: OnceLock<RandomState> = OnceLock::new();
+        let content = self.get_file(path)?;
+        Some(STATE.get_or_init(RandomState::new).hash_one(content))
+    }
 
-            // Skip directories (ZIP entries ending with '/') and unsafe paths.
-            if name_str.ends_with('/') || !is_safe_path(name_str) { continue; }
+    /// Returns a hash of a loaded file's content that is deterministic for a
+    /// given `seed`.
+    ///
+    /// Equal content and seed always give equal hashes within one build, which
+    /// suits tests and caches shared between processes; different seeds give
+    /// unrelated hashes. The tradeoff is collision resistance: anyone who knows
+    /// the seed can search for colliding inputs, so in multi‑tenant settings
+    /// keep the seed secret or use [`content_hash`](Self::content_hash). The
+    /// underlying algorithm is the standard library's default hasher, which
+    /// may change between Rust releases, so don't persist these hashes.
+    ///
+    /// # Arguments
+    /// * `path` – the path of the file.
+    /// * `seed` – value mixed into the hash before the content.
+    ///
+    /// # Returns
+    /// The 64‑bit hash, or `None` if the file is not loaded.
+    pub fn content_hash_seeded(&self, path: &str, seed: u64) -> Option<u64> {
+        let content = self.get_file(path)?;
+        let mut hasher = DefaultHasher::new();
+        hasher.write_u64(seed);
+        hasher.write(content);
+        Some(hasher.finish())
+    }
 
-            // Apply filter if present – allocation‑free matching on normalized path.
-            if let Some(filter) = filter {
-                if !filter.matches_str(name_str) { continue; }
-            }
+    /// Returns the aggregate compression ratio of the files directly in `dir_path`.
+    ///
+    /// Computed as total compressed size divided by total uncompressed size of
+    /// the immediate children (subdirectories are not included). Values near
+    /// `1.0` indicate content that does not compress, such as media folders of
+    /// already‑compressed images; XML parts typically land well below `0.3`.
+    ///
+    /// # Arguments
+    /// * `dir_path` – a directory path (e.g., `"xl/media"`).
+    ///
+    /// # Returns
+    /// The ratio, or `None` if the directory does not exist or its files are
+    /// all empty.
+    pub fn dir_compression_ratio(&self, dir_path: &str) -> Option<f64> {
+        let (compressed, size) = self.dir_entries(dir_path)?.iter()
+            .filter_map(|path| self.meta.get(path))
+            .fold((0u64, 0u64), |(c, s), m| (c + m.compressed_size, s + m.size));
 
-            // Convert to Arc<str> without extra copy if the name is already owned.
-            let name_arc = match name_cow {
-                Cow::Borrowed(s) => Arc::from(s),
-                Cow::Owned(s) => Arc::from(s),
-            };
+        (size > 0).then(|| compressed as f64 / size as f64)
+    }
 
-            // Try to read the file content.
-            if let Some((name, content)) = Self::try_read_file_content(file, name_arc) {
-                self.files.insert(name.clone(), content);
-                self.index_file(name);
+    /// Returns every directory of the loaded files, at any depth, sorted.
+    ///
+    /// Unlike the directory index used by [`list_files`](Self::list_files),
+    /// which only knows directories that directly contain files, this includes
+    /// all intermediate directories: a single file `xl/worksheets/sheet1.xml`
+    /// yields `["xl", "xl/worksheets"]`. The archive root (`""`) is not listed.
+    /// Useful for building a complete tree view in one pass.
+    ///
+    /// # Returns
+    /// Directory paths without trailing `/`, in lexicographic order.
+    pub fn all_dirs(&self) -> Vec<&str> {
+        let mut dirs = FxHashSet::default();
+        for key in self.dir_index.keys() {
+            let mut dir: &str = key;
+            // Ancestors already seen were added together with their own ancestors.
+            while !dir.is_empty() && dirs.insert(dir) {
+                dir = parent_dir(dir);
             }
         }
+
+        let mut dirs: Vec<_> = dirs.into_iter().collect();
+        dirs.sort_unstable();
+        dirs
     }
 
-    /// Checks whether the archive size exceeds the optional limit.
+    /// Returns every loaded file with its content length, largest first.
     ///
-    /// If a limit is provided, the reader is seeked to the end to obtain the
-    /// total size. After the check, the reader is rewound to the beginning
-    /// so that it can be used to construct the ZIP archive.
+    /// Useful to see which parts dominate memory use (typically the biggest
+    /// worksheets, `xl/sharedStrings.xml` and embedded media). Lengths are
+    /// those of the content held in memory, i.e. uncompressed sizes.
+    ///
+    /// # Returns
+    /// `(path, len)` pairs sorted by `len` descending; ties are ordered by path.
+    pub fn entries_by_size(&self) -> Vec<(&str, usize)> {
+        let mut entries: Vec<_> = self.files.iter()
+            .map(|(path, slot)| {
+                // Entries not yet decoded in lazy mode report their declared size.
+                let len = slot.get().map_or_else(|| self.meta.get(path).map_or(0, |m| m.size as usize), |c| c.as_slice().len());
+                (&**path, len)
+            })
+            .collect();
+        entries.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        entries
+    }
+
+    /// Replaces the content of a loaded file.
+    ///
+    /// Only files that are already present can be replaced; the directory
+    /// index is unaffected. [`entry_meta`](Self::entry_meta) keeps describing
+    /// the entry as stored in the archive.
     ///
     /// # Arguments
-    /// * `reader` – the data source.
-    /// * `max_archive_size` – optional maximum size in bytes.
+    /// * `path` – path of a loaded file (resolved like [`get_file`](Self::get_file)).
+    /// * `content` – the new content.
+    ///
+    /// # Returns
+    /// The previous content, or `None` (and `content` is dropped) if no such
+    /// file is loaded.
+    pub fn replace_file(&mut self, path: &str, content: Vec<u8>) -> Option<Vec<u8>> {
+        let key = self.file_key(path)?.clone();
+        let previous = self.files.insert(key.clone(), OnceLock::from(Content::Owned(content)))?;
+        self.modified.insert(key.clone());
+        previous.into_inner().map(Content::into_vec).or_else(|| self.source.as_ref()?.decode(&key))
+    }
+
+    /// Adds a file, or replaces the content of an existing one.
+    ///
+    /// A new file is indexed like a loaded one and listed by
+    /// [`list_files`](Self::list_files), [`walk`](Self::walk) and the other
+    /// listings; it has no [`entry_meta`](Self::entry_meta), as it isn't
+    /// stored in any archive yet. An existing file is replaced as by
+    /// [`replace_file`](Self::replace_file). Write the result with
+    /// [`write_to`](Self::write_to).
+    ///
+    /// # Arguments
+    /// * `path` – path of the file; normalized like archive entry names.
+    /// * `content` – the file content.
+    ///
+    /// # Returns
+    /// The previous content if the file existed, `None` for a new file.
     ///
     /// # Errors
-    /// Returns `ZipFsError::ArchiveTooLarge` if the size exceeds the limit,
-    /// or `ZipFsError::Io` if seeking fails.
-    fn check_archive_size<R: Read + Seek>(
-        mut reader: R,
-        max_archive_size: Option<u64>,
-    ) -> Result<R, ZipFsError> {
-        if let Some(limit) = max_archive_size {
-            let size = reader.seek(SeekFrom::End(0))?;
-            if size > limit { return Err(ZipFsError::ArchiveTooLarge(size, limit)); }
+    /// Returns `ZipFsError::InvalidPattern` if `path` is empty, names a
+    /// directory (trailing `/`) or contains `..`.
+    pub fn insert_file(&mut self, path: &str, content: Vec<u8>) -> Result<Option<Vec<u8>>, ZipFsError> {
+        let normalized = validate_path(path)?;
+        if normalized.ends_with('/') {
+            return Err(ZipFsError::InvalidPattern(format!("not a file path: {path}")));
+        }
+        if self.file_key(&normalized).is_some() {
+            return Ok(self.replace_file(&normalized, content));
+        }
+
+        let key: Arc<str> = Arc::from(normalized);
+        self.files.insert(key.clone(), OnceLock::from(Content::Owned(content)));
+        self.modified.insert(key.clone());
+        self.index_file(key);
+        Ok(None)
+    }
+
+    /// Removes a file from the file system without reading its content.
+    ///
+    /// Like [`take_file`](Self::take_file), for when the content isn't
+    /// needed: in lazy mode nothing is decompressed.
+    ///
+    /// # Returns
+    /// `true` if the file was present.
+    pub fn remove_file(&mut self, path: &str) -> bool {
+        let Some(key) = self.file_key(path).cloned() else { return false };
+        self.files.remove(&key);
+        self.meta.remove(&key);
+        self.modified.remove(&key);
+        self.unindex_file(&key);
+        true
+    }
+
+    /// Removes a file from the file system and returns its content.
+    ///
+    /// Ownership of the buffer moves to the caller without copying, so a part
+    /// can be parsed and dropped while the rest of the archive stays loaded
+    /// (e.g. take `xl/sharedStrings.xml`, build the table, free the XML). The
+    /// file disappears from every listing and from
+    /// [`entry_meta`](Self::entry_meta). In lazy mode an undecoded file is
+    /// decompressed here; memory‑mapped content is copied.
+    ///
+    /// # Arguments
+    /// * `path` – path of a loaded file (resolved like [`get_file`](Self::get_file)).
+    ///
+    /// # Returns
+    /// The content, or `None` if no such file is loaded or, in lazy mode, it
+    /// can't be decompressed (the file is removed either way).
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::{ZipFs, SharedStrings};
+    /// # fn demo(fs: &mut ZipFs) -> Result<(), quick_xml::Error> {
+    /// if let Some(xml) = fs.take_file("xl/sharedStrings.xml") {
+    ///     let shared = SharedStrings::load(&xml)?;
+    ///     drop(xml); // only the parsed table stays in memory
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn take_file(&mut self, path: &str) -> Option<Vec<u8>> {
+        let key = self.file_key(path)?.clone();
+        let slot = self.files.remove(&key)?;
+        self.meta.remove(&key);
+        self.modified.remove(&key);
+        self.unindex_file(&key);
+        slot.into_inner().map(Content::into_vec).or_else(|| self.source.as_ref()?.decode(&key))
+    }
+
+    /// Consumes the file system and returns every file with its content.
+    ///
+    /// Buffers are moved out without copying. In lazy mode undecoded files
+    /// are decompressed as the iterator advances, and files that fail to
+    /// decompress are skipped; memory‑mapped content is copied.
+    ///
+    /// # Returns
+    /// `(path, content)` pairs in no particular order.
+    pub fn into_files(self) -> impl Iterator<Item = (Arc<str>, Vec<u8>)> {
+        let source = self.source;
+        self.files.into_iter().filter_map(move |(path, slot)| {
+            let content = match slot.into_inner() {
+                Some(content) => content.into_vec(),
+                None => source.as_ref()?.decode(&path)?,
+            };
+            Some((path, content))
+        })
+    }
+
+    /// Returns the entries that were not loaded because their content doesn't
+    /// match the CRC‑32 declared in the archive.
+    ///
+    /// Only filled when loading with
+    /// [`verify_crc`](ZipFsBuilder::verify_crc); a corrupted entry is then
+    /// absent from the file system and listed here, so a damaged sheet is
+    /// reported as such rather than as a confusing "not found" or XML error.
+    ///
+    /// # Returns
+    /// Mismatches in archive order; empty if every entry checked out.
+    pub fn crc_mismatches(&self) -> &[CrcMismatch] {
+        &self.crc_mismatches
+    }
+
+    /// Returns the archive paths that occurred more than once.
+    ///
+    /// ZIP archives may legally hold several entries with the same name
+    /// (typically an appended update of a part). They are resolved by the
+    /// builder's [`duplicates`](ZipFsBuilder::duplicates) policy, and every
+    /// extra occurrence is listed here, including under
+    /// [`DuplicatePolicy::KeepBoth`] the path it was loaded under.
+    ///
+    /// # Returns
+    /// Duplicates in archive order; empty if every path was unique.
+    pub fn duplicates(&self) -> &[DuplicateEntry] {
+        &self.duplicates
+    }
+
+    /// Returns an independent copy of the file system.
+    ///
+    /// `ZipFs` is deliberately not `Clone`: copying duplicates every loaded
+    /// file's content, which can be hundreds of megabytes. Use this when a
+    /// pipeline needs a private copy to modify (e.g. with
+    /// [`replace_file`](Self::replace_file)) while keeping the original intact.
+    /// Path keys are `Arc<str>` and shared between both copies; indices are
+    /// duplicated. In lazy mode both copies read from the same open archive,
+    /// and entries not yet decoded are decoded separately by each copy.
+    ///
+    /// # Example
+    /// ```
+    /// # use excel_parser::ZipFs;
+    /// # fn demo(fs: &ZipFs) {
+    /// let mut copy = fs.deep_clone();
+    /// copy.replace_file("xl/sharedStrings.xml", b"<sst/>".to_vec());
+    /// assert_ne!(copy.get_file("xl/sharedStrings.xml"), fs.get_file("xl/sharedStrings.xml"));
+    /// # }
+    /// ```
+    pub fn deep_clone(&self) -> ZipFs {
+        ZipFs {
+            files: self.files.clone(),
+            meta: self.meta.clone(),
+            dir_index: self.dir_index.clone(),
+            parent_cache: self.parent_cache.clone(),
+            case_index: self.case_index.clone(),
+            source: self.source.clone(),
+            crc_mismatches: self.crc_mismatches.clone(),
+            duplicates: self.duplicates.clone(),
+            modified: self.modified.clone(),
+            password: self.password.clone(),
+        }
+    }
+
+    /// Releases excess capacity held by the internal maps.
+    ///
+    /// Construction pre‑allocates `files` and `dir_index` from the archive's
+    /// entry count, which over‑reserves when a filter rejects most entries.
+    /// Removing entries also leaves their capacity behind. Call this once the
+    /// file set is final to return that memory to the allocator.
+    pub fn shrink_to_fit(&mut self) {
+        self.files.shrink_to_fit();
+        self.meta.shrink_to_fit();
+        self.dir_index.values_mut().for_each(Vec::shrink_to_fit);
+        self.dir_index.shrink_to_fit();
+        self.parent_cache.shrink_to_fit();
+    }
+
+    // -------------------------------------------------------------------------
+    // Internal helpers
+    // -------------------------------------------------------------------------
+
+    /// Resolves a user‑supplied path to the key of a loaded file.
+    ///
+    /// Tries the normalized path first and, in case‑insensitive mode, falls
+    /// back to the case‑folded index.
+    #[inline]
+    fn file_key(&self, path: &str) -> Option<&Arc<str>> {
+        let normalized = normalize_path(path);
+        if let Some((key, _)) = self.files.get_key_value(&*normalized) { return Some(key); }
+        self.case_index.as_ref()?.files.get(&*normalize_path_ci(path))
+    }
+
+    /// Resolves a user‑supplied directory path to its list of immediate files.
+    #[inline]
+    fn dir_entries(&self, dir_path: &str) -> Option<&Vec<Arc<str>>> {
+        let normalized = normalize_dir(dir_path);
+        self.dir_index.get(&*normalized).or_else(|| {
+            let key = self.case_index.as_ref()?.dirs.get(&*normalize_path_ci(&normalized))?;
+            self.dir_index.get(key)
+        })
+    }
+
+    /// Removes a file from `dir_index` and the case‑folded lookups.
+    ///
+    /// Directories left without files are dropped. In case‑insensitive mode a
+    /// folded path that pointed at the removed file or directory is handed to
+    /// another loaded one with the same folding, if any.
+    fn unindex_file(&mut self, file_path: &Arc<str>) {
+        let parent = parent_dir(file_path);
+        let dir_emptied = match self.dir_index.get_mut(parent) {
+            Some(files) => {
+                files.retain(|f| f != file_path);
+                files.is_empty()
+            }
+            None => false,
+        };
+        if dir_emptied { self.dir_index.remove(parent); }
+
+        let Some(index) = &mut self.case_index else { return };
+        let folded = normalize_path_ci(file_path);
+        if index.files.get(&*folded) == Some(file_path) {
+            index.files.remove(&*folded);
+            if let Some(other) = self.files.keys().find(|k| normalize_path_ci(k) == folded) {
+                index.files.insert(folded.into(), other.clone());
+            }
+        }
+        if dir_emptied {
+            let folded = normalize_path_ci(parent);
+            if index.dirs.get(&*folded).is_some_and(|d| &**d == parent) {
+                index.dirs.remove(&*folded);
+                if let Some(other) = self.dir_index.keys().find(|k| normalize_path_ci(k) == folded) {
+                    index.dirs.insert(folded.into(), other.clone());
+                }
+            }
+        }
+    }
+
+    /// Indexes a file under its **immediate** parent directory.
+    ///
+    /// Updates `dir_index` so that the file's path is recorded under the
+    /// normalized parent directory key. The root directory is represented by
+    /// an empty string. In case‑insensitive mode the folded file and directory
+    /// paths are recorded as well.
+    ///
+    /// # Arguments
+    /// * `file_path` – the full normalized path of the file (as an `Arc<str>`).
+    #[inline]
+    fn index_file(&mut self, file_path: Arc<str>) {
+        let parent = parent_dir(file_path.as_ref());
+        let parent_key = if parent.is_empty() {
+            self.parent_cache
+                .entry(String::new())
+                .or_insert_with(|| Arc::from(""))
+                .clone()
+        } else {
+            self.parent_cache
+                .entry(parent.to_string())
+                .or_insert_with(|| Arc::from(parent))
+                .clone()
+        };
+        
+        // In case-insensitive mode the first of several case-colliding paths wins.
+        if let Some(index) = &mut self.case_index {
+            index.dirs.entry(normalize_path_ci(&parent_key).into()).or_insert_with(|| parent_key.clone());
+            index.files.entry(normalize_path_ci(&file_path).into()).or_insert_with(|| file_path.clone());
+        }
+
+        self.dir_index.entry(parent_key)
+            .or_default()
+            .push(file_path);
+    }
+
+    /// Tries to read the entire file content into memory.
+    ///
+    /// Performs basic capacity checks to avoid allocation failures for very
+    /// large files. Returns `None` if the file size exceeds `usize::MAX` or if
+    /// memory reservation fails.
+    ///
+    /// With a `limit`, at most `limit + 1` bytes are read whatever the header
+    /// declares, so the caller detects oversized entries by the returned
+    /// length without the full expansion ever being held in memory.
+    ///
+    /// # Arguments
+    /// * `file` – the ZIP file entry to read.
+    /// * `limit` – maximum number of bytes the caller accepts.
+    ///
+    /// # Returns
+    /// The file content, or `None` if reading failed.
+    fn try_read_file_content<R: Read>(file: ZipFile<R>, limit: Option<u64>) -> Option<Vec<u8>> {
+        Self::read_file_content(file, limit).ok()
+    }
+
+    /// Like [`try_read_file_content`](Self::try_read_file_content), but on
+    /// failure returns the bytes decompressed so far.
+    ///
+    /// The zip crate compares the CRC only once the entry is fully read and
+    /// reports a mismatch as a read error; keeping the bytes lets the caller
+    /// tell that case apart from truncated or undecodable data.
+    fn read_file_content<R: Read>(file: ZipFile<R>, limit: Option<u64>) -> Result<Vec<u8>, Vec<u8>> {
+        // The declared size is untrusted: never reserve more than the limit allows.
+        let size = limit.map_or(file.size(), |l| file.size().min(l));
+        if size > usize::MAX as u64 { return Err(Vec::new()); }
+
+        let mut content = Vec::new();
+        if content.try_reserve_exact(size as usize).is_err() { return Err(content); }
+
+        match file.take(limit.map_or(u64::MAX, |l| l.saturating_add(1))).read_to_end(&mut content) {
+            Ok(_) => Ok(content),
+            Err(_) => Err(content),
+        }
+    }
+
+    /// Opens entry `index` for reading, decrypting with the configured
+    /// password, or without decompression if `raw`.
+    fn open_entry<'a, R: Read + Seek>(
+        archive: &'a mut ZipArchive<R>,
+        index: usize,
+        raw: bool,
+        options: &ZipFsBuilder,
+    ) -> Result<ZipFile<'a, R>, ZipError> {
+        match &options.password {
+            _ if raw => archive.by_index_raw(index),
+            Some(password) => archive.by_index_decrypt(index, password.as_bytes()),
+            None => archive.by_index(index),
+        }
+    }
+
+    /// Turns the failure to open entry `index` into a password or encryption
+    /// error; other failures mean a corrupted entry, which is skipped.
+    ///
+    /// # Errors
+    /// * `ZipFsError::PasswordRequired` – encrypted entry, no password given.
+    /// * `ZipFsError::WrongPassword` – the password was rejected.
+    /// * `ZipFsError::UnsupportedEncryption` – the entry is encrypted in a way
+    ///   the zip crate can't decrypt.
+    fn entry_error<R: Read + Seek>(
+        archive: &mut ZipArchive<R>,
+        index: usize,
+        error: ZipError,
+        path: &str,
+    ) -> Result<(), ZipFsError> {
+        match error {
+            ZipError::InvalidPassword => Err(ZipFsError::WrongPassword(path.to_string())),
+            ZipError::UnsupportedArchive(reason) if reason == ZipError::PASSWORD_REQUIRED => {
+                Err(ZipFsError::PasswordRequired(path.to_string()))
+            }
+            ZipError::UnsupportedArchive(reason) if archive.by_index_raw(index).is_ok_and(|f| f.encrypted()) => {
+                Err(ZipFsError::UnsupportedEncryption(path.to_string(), reason.to_string()))
+            }
+            // Silently skip corrupted entries.
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies the duplicate policy to an entry about to be loaded as `name`.
+    ///
+    /// # Returns
+    /// The path to load the entry under (`name`, or a fresh one with
+    /// `KeepBoth`), or `None` if it is to be skipped.
+    ///
+    /// # Errors
+    /// Returns `ZipFsError::DuplicatePath` under `DuplicatePolicy::Error`.
+    fn resolve_duplicate(&mut self, name: Arc<str>, options: &ZipFsBuilder) -> Result<Option<Arc<str>>, ZipFsError> {
+        if !self.files.contains_key(&name) { return Ok(Some(name)); }
+
+        let (keep, stored_as) = match options.duplicates {
+            DuplicatePolicy::FirstWins => (None, None),
+            // The earlier file is replaced when the later one is inserted.
+            DuplicatePolicy::LastWins => (Some(name.clone()), None),
+            DuplicatePolicy::Error => return Err(ZipFsError::DuplicatePath(name.to_string())),
+            DuplicatePolicy::KeepBoth => {
+                let renamed: Arc<str> = self.unused_name(&name).into();
+                (Some(renamed.clone()), Some(renamed.to_string()))
+            }
+        };
+        self.duplicates.push(DuplicateEntry { path: name.to_string(), stored_as });
+        Ok(keep)
+    }
+
+    /// First free path of the form `dir/stem~N.ext` (N ≥ 2) for a duplicate of `path`.
+    fn unused_name(&self, path: &str) -> String {
+        let name_start = path.rfind('/').map_or(0, |i| i + 1);
+        let split = path[name_start..].rfind('.').filter(|&i| i > 0).map_or(path.len(), |i| name_start + i);
+        let (stem, ext) = path.split_at(split);
+        (2..)
+            .map(|n| format!("{stem}~{n}{ext}"))
+            .find(|candidate| !self.files.contains_key(candidate.as_str()))
+            .expect("unbounded range")
+    }
+
+    /// Adds a loaded file, replacing an earlier one with the same path
+    /// (duplicates under `DuplicatePolicy::LastWins`).
+    fn insert_loaded(&mut self, path: Arc<str>, slot: OnceLock<Content>, meta: EntryMeta) {
+        if self.files.contains_key(&path) { self.unindex_file(&path); }
+        self.files.insert(path.clone(), slot);
+        self.meta.insert(path.clone(), meta);
+        self.index_file(path);
+    }
+
+    /// With [`verify_crc`](ZipFsBuilder::verify_crc), records `content` as a
+    /// mismatch if it is complete but its CRC‑32 differs from the header.
+    ///
+    /// # Returns
+    /// `true` if a mismatch was recorded.
+    fn check_crc(&mut self, options: &ZipFsBuilder, path: &str, meta: &EntryMeta, content: &[u8]) -> bool {
+        if !options.verify_crc || content.len() as u64 != meta.size { return false; }
+        let actual = crc32fast::hash(content);
+        if actual == meta.crc32 { return false; }
+
+        self.crc_mismatches.push(CrcMismatch { path: path.to_string(), expected: meta.crc32, actual });
+        true
+    }
+
+    /// Iterates over all ZIP entries, applies filters, and loads matching files.
+    ///
+    /// This method populates `files` and `dir_index` with entries that are not
+    /// directories, have safe paths, and (if a filter is provided) match the filter.
+    /// Corrupted entries are silently skipped. Decompressed bytes are counted
+    /// against the per‑entry and total limits as they are read.
+    ///
+    /// # Arguments
+    /// * `archive` – the opened ZIP archive.
+    /// * `options` – builder options (filter and limits).
+    /// * `mode` – how file slots are filled.
+    ///
+    /// # Returns
+    /// In lazy mode, the archive index of every indexed entry; empty otherwise.
+    ///
+    /// # Errors
+    /// * `ZipFsError::TooManyEntries` – more entries match than allowed.
+    /// * `ZipFsError::EntryTooLarge` – an entry exceeded the per‑entry limit.
+    /// * `ZipFsError::TotalTooLarge` – the total budget ran out; reported after
+    ///   the remaining entries have been listed.
+    fn load_entries<R: Read + Seek>(
+        &mut self,
+        archive: &mut ZipArchive<R>,
+        options: &ZipFsBuilder,
+        mode: &LoadMode,
+    ) -> Result<FxHashMap<Arc<str>, usize>, ZipFsError> {
+        let mut loader = self.start_loading(archive, options, mode)?;
+        for i in 0..archive.len() {
+            self.load_entry(archive, i, options, mode, &mut loader)?;
+        }
+        loader.finish()
+    }
+
+    /// Prepares [`load_entries`](Self::load_entries): selects entries, checks
+    /// the entry limit and decodes ahead with [`ZipFsBuilder::parallel`].
+    ///
+    /// # Errors
+    /// Returns `ZipFsError::TooManyEntries` if more entries match than allowed.
+    fn start_loading<'o, R: Read + Seek>(
+        &mut self,
+        archive: &mut ZipArchive<R>,
+        options: &'o ZipFsBuilder,
+        mode: &LoadMode,
+    ) -> Result<EntryLoader<'o>, ZipFsError> {
+        let selector = EntrySelector::new(archive, options)?;
+
+        // Count loadable entries from the central directory before decompressing anything.
+        if let Some(limit) = options.max_entries {
+            let count = archive.file_names()
+                .filter(|name| selector.accepts(&normalize_path(name)))
+                .count();
+            if count > limit { return Err(ZipFsError::TooManyEntries(count, limit)); }
+        }
+
+        let predecoded = (options.parallel && matches!(mode, LoadMode::Eager))
+            .then(|| parallel::predecode(archive, &selector, options));
+
+        Ok(EntryLoader { selector, predecoded, indices: FxHashMap::default(), total: 0, exceeded: None })
+    }
+
+    /// Loads archive entry `i` if it is selected, one step of
+    /// [`load_entries`](Self::load_entries).
+    ///
+    /// # Errors
+    /// Same as [`load_entries`](Self::load_entries), except that an exceeded
+    /// budget is only recorded in `loader`.
+    fn load_entry<R: Read + Seek>(
+        &mut self,
+        archive: &mut ZipArchive<R>,
+        i: usize,
+        options: &ZipFsBuilder,
+        mode: &LoadMode,
+        loader: &mut EntryLoader<'_>,
+    ) -> Result<(), ZipFsError> {
+        let Some(name) = archive.name_for_index(i) else { return Ok(()) };
+
+        // Normalize the entry name without allocating if already clean.
+        let name_cow = normalize_path(name);
+
+        // Skip directories, unsafe paths and entries rejected by the filters.
+        if !loader.selector.accepts(&name_cow) { return Ok(()); }
+        // Taken now so that an entry skipped below gives its bytes back to the budget.
+        let decoded = loader.predecoded.as_mut().and_then(|p| p.take(i));
+
+        // Convert to Arc<str> without extra copy if the name is already owned.
+        let name_arc: Arc<str> = match name_cow {
+            Cow::Borrowed(s) => Arc::from(s),
+            Cow::Owned(s) => Arc::from(s),
+        };
+        let Some(name_arc) = self.resolve_duplicate(name_arc, options)? else { return Ok(()) };
+
+        // Past the budget, entries are only listed.
+        if let Some(exceeded) = loader.exceeded.as_mut() {
+            exceeded.skipped.push(name_arc.to_string());
+            return Ok(());
+        }
+
+        // The raw reader gives the metadata without setting up decompression.
+        let lazy = matches!(mode, LoadMode::Lazy);
+        let file = 'open: {
+            // A `ZipFile` borrows the archive until dropped: the failed
+            // result must be gone before `entry_error` uses the archive.
+            let error = match Self::open_entry(archive, i, lazy, options) {
+                Ok(file) => break 'open file,
+                Err(e) => e,
+            };
+            return Self::entry_error(archive, i, error, &name_arc);
+        };
+
+        // Try to read the file content, or leave the slot empty in lazy mode.
+        let meta = EntryMeta::from_file(&file);
+        let slot = match mode {
+            LoadMode::Lazy => {
+                // Check the password now rather than on first access.
+                let encrypted = file.encrypted();
+                drop(file);
+                if encrypted && let Err(e) = Self::open_entry(archive, i, false, options).map(drop) {
+                    return Self::entry_error(archive, i, e, &name_arc);
+                }
+                loader.indices.insert(name_arc.clone(), i);
+                OnceLock::new()
+            }
+            #[cfg(all(feature = "mmap", unix))]
+            LoadMode::Mapped(map) if file.compression() == zip::CompressionMethod::Stored && !file.encrypted() => {
+                match MappedSlice::new(map, file.data_start(), file.size()) {
+                    Some(slice) if !self.check_crc(options, &name_arc, &meta, slice.as_slice()) => {
+                        OnceLock::from(Content::Mapped(slice))
+                    }
+                    _ => return Ok(()),
+                }
+            }
+            _ => {
+                let reserved = loader.predecoded.as_ref().map_or(0, parallel::Predecoded::pending);
+                let remaining = options.max_total_size.map(|l| l.saturating_sub(loader.total + reserved));
+                let limit = options.max_entry_size.into_iter().chain(remaining).min();
+                let content = match decoded.unwrap_or_else(|| Self::read_file_content(file, limit)) {
+                    Ok(content) => content,
+                    Err(partial) => {
+                        // A successful read was already checked by the zip crate.
+                        self.check_crc(options, &name_arc, &meta, &partial);
+                        return Ok(());
+                    }
+                };
+
+                let checked = Self::check_limits(options, &name_arc, content, &mut loader.total, reserved, &mut loader.exceeded)?;
+                let Some(content) = checked else { return Ok(()) };
+                OnceLock::from(Content::Owned(content))
+            }
+        };
+
+        self.insert_loaded(name_arc, slot, meta);
+        Ok(())
+    }
+
+    /// Applies the per‑entry and total limits to the content of `path`, read
+    /// with at most the smaller of the entry limit and the remaining budget
+    /// (plus one byte).
+    ///
+    /// `reserved` bytes of the budget are held by entries decoded ahead by
+    /// [`ZipFsBuilder::parallel`] and not yet counted in `total`.
+    ///
+    /// # Returns
+    /// The content if it fits, adding its size to `total`; `None` if it
+    /// crossed the total limit, which is then recorded in `exceeded`.
+    ///
+    /// # Errors
+    /// Returns `ZipFsError::EntryTooLarge` if the content exceeds the per‑entry limit.
+    fn check_limits(
+        options: &ZipFsBuilder,
+        path: &str,
+        content: Vec<u8>,
+        total: &mut u64,
+        reserved: u64,
+        exceeded: &mut Option<BudgetExceeded>,
+    ) -> Result<Option<Vec<u8>>, ZipFsError> {
+        let read = content.len() as u64;
+        if let Some(max) = options.max_entry_size && read > max {
+            return Err(ZipFsError::EntryTooLarge(path.to_string(), max));
+        }
+        if let Some(max) = options.max_total_size && read > max.saturating_sub(*total + reserved) {
+            *exceeded = Some(BudgetExceeded { limit: max, decompressed: *total, skipped: vec![path.to_string()] });
+            return Ok(None);
+        }
+        *total += read;
+        Ok(Some(content))
+    }
+
+    /// Fails with `ZipFsError::EmptyArchive` if requested and nothing was loaded.
+    fn check_not_empty(&self, options: &ZipFsBuilder) -> Result<(), ZipFsError> {
+        if options.error_on_empty && self.files.is_empty() {
+            return Err(ZipFsError::EmptyArchive);
+        }
+        Ok(())
+    }
+
+    /// Checks whether the archive size exceeds the optional limit.
+    ///
+    /// If a limit is provided, the reader is seeked to the end to obtain the
+    /// total size. After the check, the reader is rewound to the beginning
+    /// so that it can be used to construct the ZIP archive.
+    ///
+    /// # Arguments
+    /// * `reader` – the data source.
+    /// * `max_archive_size` – optional maximum size in bytes.
+    ///
+    /// # Errors
+    /// Returns `ZipFsError::ArchiveTooLarge` if the size exceeds the limit,
+    /// or `ZipFsError::Io` if seeking fails.
+    fn check_archive_size<R: Read + Seek>(
+        mut reader: R,
+        max_archive_size: Option<u64>,
+    ) -> Result<R, ZipFsError> {
+        if let Some(limit) = max_archive_size {
+            let size = reader.seek(SeekFrom::End(0))?;
+            if size > limit { return Err(ZipFsError::ArchiveTooLarge(size, limit)); }
+        }
+        
+        reader.seek(SeekFrom::Start(0))?;
+        Ok(reader)
+    }
+}
+
+/// Number of directory levels `dir` lies below `root` (`0` for `root`
+/// itself), or `None` if it is not inside `root`. The empty root contains
+/// every directory.
+fn depth_below(root: &str, dir: &str) -> Option<usize> {
+    let rest = if root.is_empty() {
+        dir
+    } else {
+        let rest = dir.strip_prefix(root)?;
+        if rest.is_empty() { return Some(0); }
+        rest.strip_prefix('/')?
+    };
+    Some(if rest.is_empty() { 0 } else { rest.matches('/').count() + 1 })
+}
+
+/// How [`ZipFs::load_entries`] fills file slots.
+enum LoadMode {
+    /// Decompress every entry into memory.
+    Eager,
+    /// Leave slots empty and record archive indices for decoding on access.
+    Lazy,
+    /// Like `Eager`, but `Stored` entries borrow from the mapped archive.
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(Arc<Mmap>),
+}
+
+// ---------------------------------------------------------------------------
+// EntrySelector – decides which archive entries get loaded
+// ---------------------------------------------------------------------------
+
+/// State of [`ZipFs::load_entries`] between two entries.
+struct EntryLoader<'o> {
+    selector: EntrySelector<'o>,
+    /// Content decoded ahead with [`ZipFsBuilder::parallel`].
+    predecoded: Option<parallel::Predecoded>,
+    /// Archive index of every entry indexed in lazy mode.
+    indices: FxHashMap<Arc<str>, usize>,
+    /// Decompressed bytes counted against the total limit so far.
+    total: u64,
+    exceeded: Option<BudgetExceeded>,
+}
+
+impl EntryLoader<'_> {
+    /// Ends the load.
+    ///
+    /// # Returns
+    /// In lazy mode, the archive index of every indexed entry; empty otherwise.
+    ///
+    /// # Errors
+    /// Returns `ZipFsError::TotalTooLarge` if the total budget ran out.
+    fn finish(self) -> Result<FxHashMap<Arc<str>, usize>, ZipFsError> {
+        match self.exceeded {
+            Some(exceeded) => Err(ZipFsError::TotalTooLarge(exceeded)),
+            None => Ok(self.indices),
+        }
+    }
+}
+
+/// Combines the path filter and the optional content‑type whitelist of a
+/// [`ZipFsBuilder`] into a single per‑entry decision.
+struct EntrySelector<'a> {
+    filter: Option<&'a FilterSet>,
+    /// Parsed `[Content_Types].xml` and the accepted types, if filtering by type.
+    content_types: Option<(ContentTypes, &'a FxHashSet<String>)>,
+}
+
+impl<'a> EntrySelector<'a> {
+    /// Prepares the selector, reading `[Content_Types].xml` from the archive
+    /// when a content‑type whitelist is configured.
+    ///
+    /// The part is decrypted with the configured password like any other
+    /// entry, and read under the per‑entry and total limits.
+    ///
+    /// # Errors
+    /// * `ZipFsError::InvalidContentTypes` – the content types part is malformed.
+    /// * `ZipFsError::EntryTooLarge` / `ZipFsError::TotalTooLarge` – the part
+    ///   exceeds the size limits.
+    /// * `ZipFsError::PasswordRequired` / `ZipFsError::WrongPassword` – the
+    ///   part is encrypted and no or a wrong password was given.
+    fn new<R: Read + Seek>(archive: &mut ZipArchive<R>, options: &'a ZipFsBuilder) -> Result<Self, ZipFsError> {
+        let content_types = match &options.content_types {
+            Some(accepted) => {
+                let types = match archive.index_for_name(ContentTypes::PATH) {
+                    Some(index) => Self::read_content_types(archive, index, options)?,
+                    None => ContentTypes::default(),
+                };
+                Some((types, accepted))
+            }
+            None => None,
+        };
+
+        Ok(Self { filter: options.filter.as_ref(), content_types })
+    }
+
+    /// Reads and parses the content types part at archive `index`.
+    ///
+    /// A corrupted part is treated as missing, so no entries match.
+    fn read_content_types<R: Read + Seek>(
+        archive: &mut ZipArchive<R>,
+        index: usize,
+        options: &ZipFsBuilder,
+    ) -> Result<ContentTypes, ZipFsError> {
+        let file = 'open: {
+            // The failed result borrows the archive: drop it before `entry_error`.
+            let error = match ZipFs::open_entry(archive, index, false, options) {
+                Ok(file) => break 'open file,
+                Err(e) => e,
+            };
+            ZipFs::entry_error(archive, index, error, ContentTypes::PATH)?;
+            return Ok(ContentTypes::default());
+        };
+
+        let limit = options.max_entry_size.into_iter().chain(options.max_total_size).min();
+        let mut xml = Vec::new();
+        file.take(limit.map_or(u64::MAX, |l| l.saturating_add(1))).read_to_end(&mut xml)?;
+
+        let mut exceeded = None;
+        let Some(xml) = ZipFs::check_limits(options, ContentTypes::PATH, xml, &mut 0, 0, &mut exceeded)? else {
+            return Err(ZipFsError::TotalTooLarge(exceeded.expect("check_limits records the exceeded budget")));
+        };
+        ContentTypes::parse(&xml).map_err(ZipFsError::InvalidContentTypes)
+    }
+
+    /// Decides whether a normalized entry name should be loaded.
+    ///
+    /// Directories (ZIP entries ending with `/`) and unsafe paths are always
+    /// rejected; otherwise the filter and the content‑type whitelist, if
+    /// present, must both accept the entry. Matching is allocation‑free on the
+    /// normalized path unless content types are checked.
+    #[inline]
+    fn accepts(&self, name: &str) -> bool {
+        if name.ends_with('/') || !is_safe_path(name) { return false; }
+        if self.filter.is_some_and(|f| !f.matches_str(name)) { return false; }
+        self.content_types.as_ref()
+            .is_none_or(|(types, accepted)| types.get(name).is_some_and(|ct| accepted.contains(ct)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::excel_parser::test_util::{encrypted_zip_archive, zip_archive, zip_archive_with, zipcrypto_zip_archive};
+
+    fn load(entries: &[(&str, &[u8])]) -> ZipFs {
+        ZipFs::from_bytes(&zip_archive(entries), None, None).unwrap()
+    }
+
+    /// The ways an archive can be loaded, for options every mode honours.
+    #[derive(Debug, Clone, Copy)]
+    enum Mode { Eager, Lazy, Stream, Parallel }
+
+    impl Mode {
+        const ALL: [Mode; 4] = [Mode::Eager, Mode::Lazy, Mode::Stream, Mode::Parallel];
+
+        fn build(self, builder: ZipFsBuilder, archive: &[u8]) -> Result<ZipFs, ZipFsError> {
+            let reader = Cursor::new(archive.to_vec());
+            match self {
+                Mode::Eager => builder.build(reader),
+                Mode::Lazy => builder.build_lazy(reader),
+                Mode::Stream => builder.build_stream(reader),
+                Mode::Parallel => builder.parallel(true).build(reader),
+            }
+        }
+    }
+
+    #[test]
+    fn shrink_to_fit_releases_capacity_after_removals() {
+        let names: Vec<String> = (0..200).map(|i| format!("dir{}/file{i}.xml", i % 20)).collect();
+        let entries: Vec<_> = names.iter().map(|n| (n.as_str(), &b"<x/>"[..])).collect();
+        let mut fs = load(&entries);
+
+        for name in &names[10..] { assert!(fs.remove_file(name)); }
+        let (files, meta, dirs) = (fs.files.capacity(), fs.meta.capacity(), fs.dir_index.capacity());
+        fs.shrink_to_fit();
+
+        assert!(fs.files.capacity() < files);
+        assert!(fs.meta.capacity() < meta);
+        assert!(fs.dir_index.capacity() <= dirs);
+        assert!(fs.dir_index.values().all(|v| v.capacity() == v.len()));
+        assert_eq!(fs.len(), 10);
+        assert_eq!(fs.get_file("dir0/file0.xml"), Some(&b"<x/>"[..]));
+    }
+
+    #[test]
+    fn max_entries_rejects_archives_over_the_limit() {
+        let archive = zip_archive(&[("a.xml", b"1"), ("b.xml", b"2"), ("c.xml", b"3"), ("dir/", b"")]);
+
+        let err = ZipFs::builder().max_entries(2).build(Cursor::new(&archive)).unwrap_err();
+        assert!(matches!(err, ZipFsError::TooManyEntries(3, 2)), "{err:?}");
+
+        // Directories and filtered‑out entries don't count.
+        let fs = ZipFs::builder().max_entries(3).build(Cursor::new(&archive)).unwrap();
+        assert_eq!(fs.len(), 3);
+        let filter = FilterSet::new().add_glob("[ab].xml").unwrap();
+        assert!(ZipFs::builder().filter(filter).max_entries(2).build(Cursor::new(&archive)).is_ok());
+    }
+
+    #[test]
+    fn dir_file_count_matches_list_files() {
+        let fs = load(&[
+            ("root.xml", b""),
+            ("xl/workbook.xml", b""),
+            ("xl/styles.xml", b""),
+            ("xl/worksheets/sheet1.xml", b""),
+            ("xl/worksheets/sheet2.xml", b""),
+            ("xl/worksheets/_rels/sheet1.xml.rels", b""),
+        ]);
+
+        for dir in ["", "xl", "xl/", "/xl/worksheets", "xl/worksheets/_rels", "missing"] {
+            assert_eq!(fs.dir_file_count(dir), fs.list_files(dir).len(), "{dir}");
+        }
+        assert_eq!(fs.dir_file_count("xl"), 2);
+        assert_eq!(fs.dir_file_count("xl/worksheets"), 2);
+        assert_eq!(fs.dir_file_count("missing"), 0);
+    }
+
+    #[test]
+    fn dir_compression_ratio_separates_xml_from_stored_media() {
+        let xml = "<row><c><v>1</v></c></row>".repeat(200);
+        // Pseudo‑random bytes, stored as already‑compressed media would be.
+        let media: Vec<u8> = (0u32..4096).map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8).collect();
+        let archive = zip_archive_with(&[
+            ("xl/worksheets/sheet1.xml", xml.as_bytes(), zip::CompressionMethod::Deflated),
+            ("xl/worksheets/sheet2.xml", xml.as_bytes(), zip::CompressionMethod::Deflated),
+            ("xl/media/image1.png", &media, zip::CompressionMethod::Stored),
+            ("xl/empty/blank.txt", b"", zip::CompressionMethod::Stored),
+        ]);
+        let fs = ZipFs::from_bytes(&archive, None, None).unwrap();
+
+        let xml_ratio = fs.dir_compression_ratio("xl/worksheets").unwrap();
+        assert!(xml_ratio < 0.3, "{xml_ratio}");
+        assert_eq!(fs.dir_compression_ratio("xl/media"), Some(1.0));
+        assert_eq!(fs.entry_meta("xl/media/image1.png").unwrap().compression, zip::CompressionMethod::Stored);
+        // Subdirectories are not included, empty files give no ratio.
+        assert_eq!(fs.dir_compression_ratio("xl"), None);
+        assert_eq!(fs.dir_compression_ratio("xl/empty"), None);
+        assert_eq!(fs.dir_compression_ratio("missing"), None);
+    }
+
+    #[test]
+    fn get_file_range_in_range() {
+        let fs = load(&[("doc.xml", b"<?xml version=\"1.0\"?><doc/>")]);
+        assert_eq!(fs.get_file_range("doc.xml", 0, 5), Some(&b"<?xml"[..]));
+        assert_eq!(fs.get_file_range("doc.xml", 21, 6), Some(&b"<doc/>"[..]));
+    }
+
+    #[test]
+    fn get_file_range_clamps_to_the_end() {
+        let fs = load(&[("doc.xml", b"0123456789")]);
+        assert_eq!(fs.get_file_range("doc.xml", 7, 100), Some(&b"789"[..]));
+        assert_eq!(fs.get_file_range("doc.xml", 10, 4), Some(&b""[..]));
+    }
+
+    #[test]
+    fn get_file_range_out_of_range() {
+        let fs = load(&[("doc.xml", b"0123456789")]);
+        assert_eq!(fs.get_file_range("doc.xml", 11, 1), None);
+        assert_eq!(fs.get_file_range("other.xml", 0, 1), None);
+    }
+
+    #[test]
+    fn case_insensitive_lookup_finds_cyrillic_paths() {
+        let archive = zip_archive(&[("xl/Листы/Отчёт.xml", b"<report/>"), ("xl/workbook.xml", b"<workbook/>")]);
+        let fs = ZipFs::builder().case_insensitive(true).build(Cursor::new(&archive)).unwrap();
+
+        for path in ["xl/Листы/Отчёт.xml", "xl/листы/отчёт.xml", "XL/ЛИСТЫ/ОТЧЁТ.XML", "/Xl/лИсТы/оТчЁт.Xml"] {
+            assert_eq!(fs.get_file(path), Some(&b"<report/>"[..]), "{path}");
+        }
+        assert_eq!(fs.list_files("XL/ЛИСТЫ"), ["xl/Листы/Отчёт.xml"]);
+        assert_eq!(fs.get_file("XL/Workbook.XML"), Some(&b"<workbook/>"[..]));
+
+        // Without the option only the stored case matches.
+        let exact = ZipFs::from_bytes(&archive, None, None).unwrap();
+        assert!(exact.get_file("xl/Листы/Отчёт.xml").is_some());
+        assert!(exact.get_file("xl/листы/отчёт.xml").is_none());
+    }
+
+    const WORKBOOK_TYPES: &[u8] = br#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+        <Default Extension="xml" ContentType="application/xml"/>
+        <Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>
+        <Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+        <Override PartName="/xl/custom/data.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+    </Types>"#;
+
+    fn workbook_entries() -> Vec<(&'static str, &'static [u8])> {
+        vec![
+            (ContentTypes::PATH, WORKBOOK_TYPES),
+            ("xl/workbook.xml", b"<workbook/>"),
+            ("xl/worksheets/sheet1.xml", b"<worksheet/>"),
+            ("xl/custom/data.xml", b"<worksheet/>"),
+            ("xl/styles.xml", b"<styleSheet/>"),
+        ]
+    }
+
+    fn file_names(fs: &ZipFs) -> Vec<&str> {
+        let mut names: Vec<&str> = fs.files.keys().map(|k| &**k).collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn content_types_load_only_worksheet_parts() {
+        let archive = zip_archive(&workbook_entries());
+        let fs = ZipFs::builder().content_types([ContentTypes::WORKSHEET]).build(Cursor::new(archive)).unwrap();
+
+        assert_eq!(file_names(&fs), ["xl/custom/data.xml", "xl/worksheets/sheet1.xml"]);
+    }
+
+    #[test]
+    fn content_types_part_is_read_within_the_entry_limit() {
+        let archive = zip_archive(&workbook_entries());
+        let result = ZipFs::builder()
+            .content_types([ContentTypes::WORKSHEET])
+            .max_entry_size(64)
+            .build(Cursor::new(archive));
+
+        assert!(matches!(result, Err(ZipFsError::EntryTooLarge(path, 64)) if path == ContentTypes::PATH));
+    }
+
+    #[test]
+    fn content_types_part_of_an_encrypted_archive_needs_the_password() {
+        let archive = encrypted_zip_archive(&workbook_entries(), "secret");
+
+        let result = ZipFs::builder().content_types([ContentTypes::WORKSHEET]).build(Cursor::new(archive.clone()));
+        assert!(matches!(result, Err(ZipFsError::PasswordRequired(path)) if path == ContentTypes::PATH));
+
+        let fs = ZipFs::builder()
+            .content_types([ContentTypes::WORKSHEET])
+            .password("secret")
+            .build(Cursor::new(archive))
+            .unwrap();
+        assert_eq!(file_names(&fs), ["xl/custom/data.xml", "xl/worksheets/sheet1.xml"]);
+    }
+
+    #[test]
+    fn error_on_empty_rejects_an_archive_of_directories() {
+        let archive = zip_archive(&[("xl/", b""), ("xl/worksheets/", b"")]);
+
+        let fs = ZipFs::builder().build(Cursor::new(archive.clone())).unwrap();
+        assert!(fs.is_empty());
+
+        let result = ZipFs::builder().error_on_empty(true).build(Cursor::new(archive));
+        assert!(matches!(result, Err(ZipFsError::EmptyArchive)));
+    }
+
+    #[test]
+    fn deep_clone_is_independent_of_the_original() {
+        let fs = load(&[("xl/sharedStrings.xml", b"<sst>old</sst>"), ("xl/workbook.xml", b"<workbook/>")]);
+        let mut copy = fs.deep_clone();
+
+        assert_eq!(copy.replace_file("xl/sharedStrings.xml", b"<sst>new</sst>".to_vec()), Some(b"<sst>old</sst>".to_vec()));
+        assert!(copy.remove_file("xl/workbook.xml"));
+
+        assert_eq!(fs.get_file("xl/sharedStrings.xml"), Some(&b"<sst>old</sst>"[..]));
+        assert_eq!(fs.get_file("xl/workbook.xml"), Some(&b"<workbook/>"[..]));
+        assert_eq!(fs.list_files("xl").len(), 2);
+        assert_eq!(copy.get_file("xl/sharedStrings.xml"), Some(&b"<sst>new</sst>"[..]));
+        assert_eq!(copy.list_files("xl").len(), 1);
+    }
+
+    #[test]
+    fn entries_by_size_lists_the_largest_first() {
+        let big = vec![b'x'; 4096];
+        let fs = load(&[
+            ("xl/styles.xml", &[b'x'; 100][..]),
+            ("xl/worksheets/sheet1.xml", &big),
+            ("xl/workbook.xml", &[b'x'; 10][..]),
+            ("xl/sharedStrings.xml", &[b'x'; 100][..]),
+        ]);
+
+        let entries = fs.entries_by_size();
+        assert_eq!(entries[0], ("xl/worksheets/sheet1.xml", 4096));
+        assert!(entries.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert_eq!(entries, [
+            ("xl/worksheets/sheet1.xml", 4096),
+            ("xl/sharedStrings.xml", 100),
+            ("xl/styles.xml", 100),
+            ("xl/workbook.xml", 10),
+        ]);
+    }
+
+    #[test]
+    fn all_dirs_lists_intermediate_directories() {
+        let fs = load(&[
+            ("[Content_Types].xml", b"<Types/>"),
+            ("xl/workbook.xml", b"<workbook/>"),
+            ("xl/worksheets/sheet1.xml", b"<worksheet/>"),
+            ("xl/worksheets/_rels/sheet1.xml.rels", b"<Relationships/>"),
+            ("customXml/a/b/c/item1.xml", b"<item/>"),
+        ]);
+
+        assert_eq!(fs.all_dirs(), [
+            "customXml",
+            "customXml/a",
+            "customXml/a/b",
+            "customXml/a/b/c",
+            "xl",
+            "xl/worksheets",
+            "xl/worksheets/_rels",
+        ]);
+        assert!(load(&[("a.xml", b"<a/>")]).all_dirs().is_empty());
+    }
+
+    #[test]
+    fn content_hash_seeded_is_deterministic_per_seed() {
+        let entries: &[(&str, &[u8])] = &[("a.xml", b"<sst>same</sst>"), ("b.xml", b"<sst>same</sst>"), ("c.xml", b"<sst>other</sst>")];
+        let (fs, again) = (load(entries), load(entries));
+
+        let hash = fs.content_hash_seeded("a.xml", 42).unwrap();
+        assert_eq!(again.content_hash_seeded("a.xml", 42), Some(hash));
+        assert_eq!(fs.content_hash_seeded("b.xml", 42), Some(hash));
+        assert_ne!(fs.content_hash_seeded("a.xml", 43), Some(hash));
+        assert_ne!(fs.content_hash_seeded("c.xml", 42), Some(hash));
+        assert_eq!(fs.content_hash_seeded("missing.xml", 42), None);
+    }
+
+    #[test]
+    fn content_hash_is_stable_within_the_process() {
+        let fs = load(&[("a.xml", b"<sst>same</sst>"), ("b.xml", b"<sst>same</sst>"), ("c.xml", b"<sst>other</sst>")]);
+
+        let hash = fs.content_hash("a.xml").unwrap();
+        assert_eq!(fs.content_hash("a.xml"), Some(hash));
+        assert_eq!(fs.content_hash("b.xml"), Some(hash));
+        assert_ne!(fs.content_hash("c.xml"), Some(hash));
+    }
+
+    /// Runs `future` on a current‑thread tokio runtime.
+    #[cfg(feature = "async")]
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn build_async_matches_build() {
+        let archive = zip_archive(&[("xl/workbook.xml", b"<workbook/>"), ("xl/worksheets/sheet1.xml", b"<worksheet/>"), ("docProps/app.xml", b"<app/>")]);
+        let builder = || ZipFs::builder().filter(FilterSet::new().add_glob("xl/**/*.xml").unwrap());
+
+        for parallel in [false, true] {
+            let future = builder().parallel(parallel).build_async(Cursor::new(archive.clone()));
+            fn assert_send<T: Send>(_: &T) {}
+            assert_send(&future);
+
+            let fs = block_on(future).unwrap();
+            let expected = builder().build(Cursor::new(&archive)).unwrap();
+            assert_eq!(fs.len(), 2);
+            for path in ["xl/workbook.xml", "xl/worksheets/sheet1.xml"] {
+                assert_eq!(fs.get_file(path), expected.get_file(path));
+            }
+            assert!(!fs.contains("docProps/app.xml"));
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn build_async_reads_a_tokio_file() {
+        let path = std::env::temp_dir().join(format!("excel_parser_build_async_{}.zip", std::process::id()));
+        std::fs::write(&path, zip_archive(&[("xl/workbook.xml", b"<workbook/>")])).unwrap();
+
+        let fs = block_on(async {
+            let file = tokio::fs::File::open(&path).await.unwrap();
+            ZipFs::builder().build_async(file).await
+        });
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(fs.unwrap().get_file("xl/workbook.xml"), Some(&b"<workbook/>"[..]));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn build_async_reports_errors_like_build() {
+        let archive = zip_archive(&[("a.xml", &[0; 600]), ("b.xml", &[0; 600])]);
+
+        let result = block_on(ZipFs::builder().max_archive_size(10).build_async(Cursor::new(&archive)));
+        assert!(matches!(result, Err(ZipFsError::ArchiveTooLarge(size, 10)) if size == archive.len() as u64));
+
+        let result = block_on(ZipFs::builder().max_total_size(1_000).build_async(Cursor::new(&archive)));
+        let Err(ZipFsError::TotalTooLarge(exceeded)) = result else { panic!("expected TotalTooLarge, got {result:?}") };
+        assert_eq!(exceeded.skipped, ["b.xml"]);
+
+        let result = block_on(ZipFs::builder().build_async(Cursor::new(b"not a zip".to_vec())));
+        assert!(matches!(result, Err(ZipFsError::Zip(_))));
+    }
+
+    #[test]
+    fn max_entry_size_admits_an_entry_of_exactly_the_limit() {
+        let archive = zip_archive(&[("a.xml", &[b'a'; 100]), ("b.xml", &[b'b'; 50])]);
+        for mode in Mode::ALL {
+            let fs = mode.build(ZipFs::builder().max_entry_size(100), &archive).unwrap();
+            assert_eq!(fs.get_file("a.xml"), Some(&[b'a'; 100][..]), "{mode:?}");
+            assert_eq!(fs.get_file("b.xml"), Some(&[b'b'; 50][..]), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn max_entry_size_rejects_an_entry_one_byte_over() {
+        let archive = zip_archive(&[("a.xml", &[b'a'; 100]), ("b.xml", &[b'b'; 50])]);
+        for mode in [Mode::Eager, Mode::Stream, Mode::Parallel] {
+            let result = mode.build(ZipFs::builder().max_entry_size(99), &archive);
+            assert!(matches!(result, Err(ZipFsError::EntryTooLarge(ref path, 99)) if path == "a.xml"), "{mode:?}: {result:?}");
+        }
+
+        // Lazy mode decompresses nothing at load: the oversized entry fails on access.
+        let fs = Mode::Lazy.build(ZipFs::builder().max_entry_size(99), &archive).unwrap();
+        assert_eq!(fs.get_file("a.xml"), None);
+        assert_eq!(fs.get_file("b.xml"), Some(&[b'b'; 50][..]));
+        let mut content = Vec::new();
+        assert!(fs.get_file_reader("a.xml").unwrap().read_to_end(&mut content).is_err());
+    }
+
+    #[test]
+    fn max_total_size_admits_entries_summing_to_exactly_the_limit() {
+        let archive = zip_archive(&[("a.xml", &[b'a'; 100]), ("b.xml", &[b'b'; 50]), ("c.xml", &[b'c'; 30])]);
+        for mode in Mode::ALL {
+            let fs = mode.build(ZipFs::builder().max_total_size(180), &archive).unwrap();
+            assert_eq!(fs.len(), 3, "{mode:?}");
+            assert_eq!(fs.get_file("c.xml"), Some(&[b'c'; 30][..]), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn max_total_size_reports_the_skipped_entries_one_byte_over() {
+        let archive = zip_archive(&[("a.xml", &[b'a'; 100]), ("b.xml", &[b'b'; 50]), ("c.xml", &[b'c'; 30])]);
+        for mode in [Mode::Eager, Mode::Stream] {
+            let result = mode.build(ZipFs::builder().max_total_size(179), &archive);
+            let Err(ZipFsError::TotalTooLarge(exceeded)) = result else { panic!("{mode:?}: expected TotalTooLarge, got {result:?}") };
+            assert_eq!(exceeded, BudgetExceeded { limit: 179, decompressed: 150, skipped: vec!["c.xml".into()] }, "{mode:?}");
+        }
+
+        // Workers draw from the budget in any order: which entries fit varies, the ceiling doesn't.
+        let result = Mode::Parallel.build(ZipFs::builder().max_total_size(179), &archive);
+        let Err(ZipFsError::TotalTooLarge(exceeded)) = result else { panic!("expected TotalTooLarge, got {result:?}") };
+        assert_eq!(exceeded.limit, 179);
+        assert!(exceeded.decompressed <= 179 && !exceeded.skipped.is_empty());
+
+        // Lazy mode decompresses nothing at load, so the total limit doesn't apply.
+        let fs = Mode::Lazy.build(ZipFs::builder().max_total_size(179), &archive).unwrap();
+        assert_eq!(fs.len(), 3);
+    }
+
+    #[test]
+    fn max_total_size_lists_every_remaining_entry_once_exhausted() {
+        let archive = zip_archive(&[("a.xml", &[b'a'; 100]), ("b.xml", &[b'b'; 50]), ("c.xml", &[b'c'; 30]), ("d.xml", b"d")]);
+        let result = Mode::Eager.build(ZipFs::builder().max_total_size(120), &archive);
+        let Err(ZipFsError::TotalTooLarge(exceeded)) = result else { panic!("expected TotalTooLarge, got {result:?}") };
+        assert_eq!(exceeded.decompressed, 100);
+        assert_eq!(exceeded.skipped, ["b.xml", "c.xml", "d.xml"]);
+    }
+
+    #[test]
+    fn max_entries_admits_exactly_the_limit_in_every_mode() {
+        let names: Vec<String> = (0..5).map(|i| format!("xl/part{i}.xml")).collect();
+        let entries: Vec<_> = names.iter().map(|n| (n.as_str(), &b"<x/>"[..])).collect();
+        let archive = zip_archive(&entries);
+
+        for mode in Mode::ALL {
+            let fs = mode.build(ZipFs::builder().max_entries(5), &archive).unwrap();
+            assert_eq!(fs.len(), 5, "{mode:?}");
+            let result = mode.build(ZipFs::builder().max_entries(4), &archive);
+            assert!(matches!(result, Err(ZipFsError::TooManyEntries(_, 4))), "{mode:?}: {result:?}");
+        }
+        let result = Mode::Eager.build(ZipFs::builder().max_entries(4), &archive);
+        assert!(matches!(result, Err(ZipFsError::TooManyEntries(5, 4))));
+    }
+
+    #[test]
+    fn max_entries_bounds_the_preallocated_tables() {
+        let names: Vec<String> = (0..500).map(|i| format!("xl/media/image{i}.png")).collect();
+        let mut entries: Vec<_> = names.iter().map(|n| (n.as_str(), &b"."[..])).collect();
+        entries.push(("xl/workbook.xml", b"<workbook/>"));
+        let archive = zip_archive(&entries);
+
+        let fs = ZipFs::builder()
+            .filter(FilterSet::new().add_exact("xl/workbook.xml").unwrap())
+            .max_entries(8)
+            .build(Cursor::new(&archive))
+            .unwrap();
+        assert_eq!(fs.len(), 1);
+        assert!(fs.files.capacity() < 100 && fs.meta.capacity() < 100, "{}", fs.files.capacity());
+    }
+
+    /// An AES and a ZipCrypto archive holding the same two parts under `password`.
+    fn encrypted_archives(password: &str) -> [(&'static str, Vec<u8>); 2] {
+        let entries: [(&str, &[u8]); 2] = [("xl/workbook.xml", b"<workbook/>"), ("xl/worksheets/sheet1.xml", b"<worksheet/>")];
+        [("AES", encrypted_zip_archive(&entries, password)), ("ZipCrypto", zipcrypto_zip_archive(&entries, password))]
+    }
+
+    #[test]
+    fn encrypted_archives_open_with_the_password() {
+        for (scheme, archive) in encrypted_archives("s3cret") {
+            let fs = ZipFs::new_with_password(Cursor::new(&archive), None, None, b"s3cret").unwrap();
+            assert_eq!(fs.get_file("xl/workbook.xml"), Some(&b"<workbook/>"[..]), "{scheme}");
+            assert_eq!(fs.get_file("xl/worksheets/sheet1.xml"), Some(&b"<worksheet/>"[..]), "{scheme}");
+
+            // Lazy mode decrypts on access with the same password.
+            let lazy = ZipFs::builder().password("s3cret").build_lazy(Cursor::new(archive)).unwrap();
+            assert_eq!(lazy.get_file("xl/worksheets/sheet1.xml"), Some(&b"<worksheet/>"[..]), "{scheme}");
+        }
+    }
+
+    #[test]
+    fn encrypted_archives_report_a_wrong_password() {
+        for (scheme, archive) in encrypted_archives("s3cret") {
+            for mode in [Mode::Eager, Mode::Lazy, Mode::Parallel] {
+                let result = mode.build(ZipFs::builder().password("wrong"), &archive);
+                assert!(matches!(result, Err(ZipFsError::WrongPassword(ref p)) if p == "xl/workbook.xml"), "{scheme} {mode:?}: {result:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn encrypted_archives_report_a_missing_password() {
+        for (scheme, archive) in encrypted_archives("s3cret") {
+            for mode in [Mode::Eager, Mode::Lazy, Mode::Parallel] {
+                let result = mode.build(ZipFs::builder(), &archive);
+                assert!(matches!(result, Err(ZipFsError::PasswordRequired(ref p)) if p == "xl/workbook.xml"), "{scheme} {mode:?}: {result:?}");
+            }
+            // Filtered‑out encrypted entries need no password.
+            let filter = FilterSet::new().add_exact("docProps/app.xml").unwrap();
+            assert!(ZipFs::new(Cursor::new(&archive), Some(filter), None).unwrap().is_empty(), "{scheme}");
         }
-        
-        reader.seek(SeekFrom::Start(0))?;
-        Ok(reader)
     }
 }
@@ -0,0 +1,256 @@
+use std::io::{ErrorKind, Read, Seek};
+use std::panic;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+use flate2::read::DeflateDecoder;
+use rustc_hash::FxHashMap;
+use zip::{CompressionMethod, ZipArchive};
+use super::{EntrySelector, ZipFsBuilder, normalize_path};
+
+/// Entries decoded per worker thread between two serial reads of compressed
+/// data; bounds the compressed bytes held at once.
+const JOBS_PER_WORKER: usize = 4;
+
+/// Size of the buffer decoded bytes are drawn from the budget by.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The content of an entry, or the bytes decoded before it turned out to be
+/// corrupt (same contract as `ZipFs::read_file_content`).
+type Decoded = Result<Vec<u8>, Vec<u8>>;
+
+/// Content decoded ahead of the load loop.
+#[derive(Default)]
+pub(super) struct Predecoded {
+    /// Decoded entries by archive index.
+    entries: FxHashMap<usize, Decoded>,
+    /// Bytes held by `entries`, still to be counted by the load loop.
+    pending: u64,
+}
+
+impl Predecoded {
+    /// Removes the content of entry `index`, if it was decoded ahead.
+    pub(super) fn take(&mut self, index: usize) -> Option<Decoded> {
+        let content = self.entries.remove(&index)?;
+        self.pending -= content.as_ref().map_or_else(Vec::len, Vec::len) as u64;
+        Some(content)
+    }
+
+    /// Bytes of the total budget held by entries not taken yet.
+    pub(super) fn pending(&self) -> u64 {
+        self.pending
+    }
+
+    fn insert(&mut self, index: usize, content: Decoded) {
+        self.pending += content.as_ref().map_or_else(Vec::len, Vec::len) as u64;
+        self.entries.insert(index, content);
+    }
+}
+
+/// Compressed data of an entry, read serially from the archive.
+struct Job {
+    index: usize,
+    compression: CompressionMethod,
+    crc32: u32,
+    raw: Vec<u8>,
+}
+
+impl Job {
+    /// Reads the compressed data of entry `index`; `None` if that fails.
+    fn read<R: Read + Seek>(archive: &mut ZipArchive<R>, index: usize) -> Option<Self> {
+        let mut file = archive.by_index_raw(index).ok()?;
+        let (compression, crc32) = (file.compression(), file.crc32());
+        let mut raw = Vec::new();
+        file.read_to_end(&mut raw).ok()?;
+        Some(Self { index, compression, crc32, raw })
+    }
+}
+
+/// Decompresses the selected `Stored` and `Deflated` entries on all
+/// available cores, for [`ZipFsBuilder::parallel`].
+///
+/// Entries are handled in batches: the compressed bytes of a batch are read
+/// one entry after the other (the archive has a single reader), then decoded
+/// and checked against their CRC‑32 by a pool of scoped threads. Encrypted
+/// entries, other compression methods, entries whose compressed size alone
+/// exceeds the limits and entries that can't be opened are left out and go
+/// through the serial path, which also reports their errors.
+///
+/// Each entry is read up to `limit + 1` bytes, `limit` being the smaller of
+/// the per‑entry and total limits, so oversized entries are still detected
+/// by length. With a total limit, decoded bytes are drawn from a budget
+/// shared by the workers, so all decoded entries together never exceed it;
+/// an entry that doesn't fit is dropped and left to the serial path, which
+/// charges [`Predecoded::pending`] against the same limit. No batch is read
+/// once the budget is spent.
+///
+/// A panic in a worker is resumed on the calling thread.
+pub(super) fn predecode<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    selector: &EntrySelector<'_>,
+    options: &ZipFsBuilder,
+) -> Predecoded {
+    let limit = options.max_entry_size.into_iter().chain(options.max_total_size).min();
+
+    let mut candidates = Vec::new();
+    for i in 0..archive.len() {
+        let accepted = archive.name_for_index(i).is_some_and(|name| selector.accepts(&normalize_path(name)));
+        if !accepted { continue; }
+
+        let Ok(file) = archive.by_index_raw(i) else { continue };
+        let decodable = !file.encrypted()
+            && matches!(file.compression(), CompressionMethod::Stored | CompressionMethod::Deflated)
+            && limit.is_none_or(|l| file.compressed_size() <= l);
+        if decodable { candidates.push(i); }
+    }
+
+    let workers = thread::available_parallelism().map_or(1, |n| n.get()).min(candidates.len().max(1));
+    let budget = options.max_total_size.map(AtomicU64::new);
+    let mut predecoded = Predecoded::default();
+
+    for batch in candidates.chunks(workers * JOBS_PER_WORKER) {
+        if budget.as_ref().is_some_and(|b| b.load(Ordering::Relaxed) == 0) { break; }
+
+        let jobs: Vec<_> = batch.iter().filter_map(|&i| Job::read(archive, i)).collect();
+        for (index, content) in decode_all(&jobs, workers, limit, budget.as_ref()) {
+            predecoded.insert(index, content);
+        }
+    }
+    predecoded
+}
+
+/// Decodes `jobs` on up to `workers` scoped threads.
+fn decode_all(
+    jobs: &[Job],
+    workers: usize,
+    limit: Option<u64>,
+    budget: Option<&AtomicU64>,
+) -> Vec<(usize, Decoded)> {
+    let next = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers.min(jobs.len()))
+            .map(|_| scope.spawn(|| {
+                let mut done = Vec::new();
+                while let Some(job) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    done.extend(decode(job, limit, budget).map(|content| (job.index, content)));
+                }
+                done
+            }))
+            .collect();
+
+        handles.into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_else(|payload| panic::resume_unwind(payload)))
+            .collect()
+    })
+}
+
+/// Decodes one entry, failing like the zip crate does on a CRC mismatch.
+///
+/// # Returns
+/// `None` if the budget ran out; the bytes drawn for the entry are given back.
+fn decode(job: &Job, limit: Option<u64>, budget: Option<&AtomicU64>) -> Option<Decoded> {
+    let take = limit.map_or(u64::MAX, |l| l.saturating_add(1));
+    let content = match job.compression {
+        CompressionMethod::Deflated => read_budgeted(DeflateDecoder::new(&job.raw[..]).take(take), budget)?,
+        _ => read_budgeted((&job.raw[..]).take(take), budget)?,
+    };
+    let Ok(content) = content else { return Some(content) };
+
+    // Truncated at the limit: the caller rejects the entry by its length.
+    if limit.is_some_and(|l| content.len() as u64 > l) { return Some(Ok(content)); }
+    if crc32fast::hash(&content) != job.crc32 { return Some(Err(content)); }
+    Some(Ok(content))
+}
+
+/// Reads `reader` to the end, drawing every chunk from `budget` first.
+///
+/// # Returns
+/// The content, or the bytes read before an error; `None` if the budget
+/// ran out, after giving back what was drawn.
+fn read_budgeted(mut reader: impl Read, budget: Option<&AtomicU64>) -> Option<Decoded> {
+    let mut content = Vec::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        let n = match reader.read(&mut chunk) {
+            Ok(0) => return Some(Ok(content)),
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(_) => return Some(Err(content)),
+        };
+        if let Some(budget) = budget
+            && budget.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| left.checked_sub(n as u64)).is_err()
+        {
+            budget.fetch_add(content.len() as u64, Ordering::Relaxed);
+            return None;
+        }
+        content.extend_from_slice(&chunk[..n]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::excel_parser::test_util::zip_archive;
+    use super::super::{ZipFs, ZipFsError};
+
+    /// `count` deflated parts of `size` bytes each, distinct per part.
+    fn parts(count: usize, size: usize) -> Vec<(String, Vec<u8>)> {
+        (0..count)
+            .map(|i| (format!("xl/worksheets/sheet{i}.xml"), (0..size).map(|b| (b * 7 + i) as u8).collect()))
+            .collect()
+    }
+
+    fn archive(parts: &[(String, Vec<u8>)]) -> Vec<u8> {
+        let entries: Vec<_> = parts.iter().map(|(path, data)| (path.as_str(), data.as_slice())).collect();
+        zip_archive(&entries)
+    }
+
+    #[test]
+    fn parallel_load_matches_serial_load() {
+        let parts = parts(40, 10_000);
+        let archive = archive(&parts);
+
+        let serial = ZipFs::builder().build(Cursor::new(&archive)).unwrap();
+        let parallel = ZipFs::builder().parallel(true).build(Cursor::new(&archive)).unwrap();
+
+        assert_eq!(parallel.len(), parts.len());
+        for (path, data) in &parts {
+            assert_eq!(parallel.get_file(path), Some(data.as_slice()));
+            assert_eq!(parallel.get_file(path), serial.get_file(path));
+        }
+    }
+
+    #[test]
+    fn parallel_load_keeps_the_total_budget() {
+        let parts = parts(40, 10_000);
+        let archive = archive(&parts);
+
+        let fits = ZipFs::builder().parallel(true).max_total_size(400_000).build(Cursor::new(&archive)).unwrap();
+        assert_eq!(fits.len(), parts.len());
+
+        let result = ZipFs::builder().parallel(true).max_total_size(250_000).build(Cursor::new(&archive));
+        let Err(ZipFsError::TotalTooLarge(exceeded)) = result else { panic!("expected TotalTooLarge, got {result:?}") };
+        assert_eq!(exceeded.limit, 250_000);
+        assert!(exceeded.decompressed <= 250_000);
+        assert!(exceeded.skipped.len() >= 15);
+    }
+
+    #[test]
+    fn decoding_draws_from_the_budget_and_gives_back_on_overrun() {
+        let job = Job { index: 0, compression: CompressionMethod::Stored, crc32: crc32fast::hash(&[1; 100]), raw: vec![1; 100] };
+
+        let budget = AtomicU64::new(150);
+        assert_eq!(decode(&job, None, Some(&budget)), Some(Ok(vec![1; 100])));
+        assert_eq!(budget.load(Ordering::Relaxed), 50);
+
+        assert_eq!(decode(&job, None, Some(&budget)), None);
+        assert_eq!(budget.load(Ordering::Relaxed), 50);
+    }
+
+    #[test]
+    fn decoding_reports_crc_mismatches() {
+        let job = Job { index: 0, compression: CompressionMethod::Stored, crc32: 0, raw: vec![1; 100] };
+        assert_eq!(decode(&job, None, None), Some(Err(vec![1; 100])));
+    }
+}
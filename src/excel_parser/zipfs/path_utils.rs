@@ -100,6 +100,37 @@ pub fn normalize_path(path: &str) -> Cow<'_, str> {
     }
 }
 
+/// Normalizes a path for case‑insensitive comparison.
+///
+/// Applies [`normalize_path`] and then Unicode lowercasing via
+/// [`str::to_lowercase`], so `"XL/Листы/Отчёт.XML"` and `"xl/листы/отчёт.xml"`
+/// compare equal. Paths that are already lowercase ASCII are returned without
+/// allocating.
+///
+/// # Limitations
+/// - Lowercasing is not full Unicode case folding: characters whose folded
+///   form differs from their lowercase form (`ß` vs `SS`, final sigma) may not
+///   match across cases.
+/// - Mapping is locale‑independent: Turkish `İ` lowercases to `i̇`
+///   (`i` + combining dot), not to `i`, and `I` lowercases to `i`, not `ı`.
+///
+/// # Arguments
+///
+/// * `path` - The raw path string to normalize.
+///
+/// # Returns
+///
+/// A normalized, lowercased path, possibly borrowed or owned.
+#[inline]
+pub fn normalize_path_ci(path: &str) -> Cow<'_, str> {
+    let normalized = normalize_path(path);
+    if normalized.bytes().any(|b| b.is_ascii_uppercase() || !b.is_ascii()) {
+        normalized.to_lowercase().into()
+    } else {
+        normalized
+    }
+}
+
 /// Normalizes a directory path by removing leading and trailing slashes.
 ///
 /// This function strips any `/` characters from the start and end of the
@@ -121,4 +152,19 @@ pub fn normalize_dir(dir: &str) -> Cow<'_, str> {
     } else {
         trimmed.to_string().into()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_path_ci_lowercases_cyrillic() {
+        assert_eq!(normalize_path_ci("/XL/Листы/Отчёт.XML"), "xl/листы/отчёт.xml");
+        assert_eq!(normalize_path_ci("xl/листы/ОТЧЁТ.xml"), normalize_path_ci("XL/ЛИСТЫ/отчёт.XML"));
+    }
+
+    #[test]
+    fn normalize_path_ci_borrows_lowercase_ascii() {
+        assert!(matches!(normalize_path_ci("xl/workbook.xml"), Cow::Borrowed(_)));
+    }
+}
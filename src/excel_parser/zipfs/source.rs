@@ -0,0 +1,146 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+/// Random‑access source of archive bytes.
+///
+/// A ZIP archive is read through ranged reads: the central directory at the
+/// end first, then each entry at its own offset. Implement this trait to
+/// load archives from storage other than a local file (an object store or
+/// HTTP server with range requests, a database blob, ...) without fetching
+/// the whole archive first; [`ZipFsBuilder::build_source`](super::ZipFsBuilder::build_source)
+/// then requests only the ranges that are actually needed.
+///
+/// Implementations are provided for [`File`], byte buffers (`Vec<u8>`,
+/// `Arc<[u8]>`, `&'static [u8]`) and `Arc<S>` of any source.
+///
+/// # Example
+/// ```
+/// # use std::io;
+/// # use excel_parser::ZipSource;
+/// /// A blob fetched in ranges from a remote store.
+/// struct Remote { /* client, object key, size */ }
+///
+/// impl ZipSource for Remote {
+///     fn len(&self) -> io::Result<u64> { todo!("object size") }
+///     fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+///         todo!("ranged GET of buf.len() bytes at offset")
+///     }
+/// }
+/// ```
+pub trait ZipSource: Send + Sync {
+    /// Total size of the archive in bytes.
+    fn len(&self) -> io::Result<u64>;
+
+    /// Reads bytes starting at `offset` into `buf`, returning how many were
+    /// read; `0` only at or past the end of the archive.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Returns `true` if the archive is empty.
+    fn is_empty(&self) -> io::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+}
+
+impl ZipSource for File {
+    fn len(&self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+
+    #[cfg(unix)]
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        std::os::unix::fs::FileExt::read_at(self, buf, offset)
+    }
+
+    #[cfg(windows)]
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        std::os::windows::fs::FileExt::seek_read(self, buf, offset)
+    }
+}
+
+/// Copies from an in‑memory archive.
+fn read_slice(data: &[u8], offset: u64, buf: &mut [u8]) -> usize {
+    let start = usize::try_from(offset).map_or(data.len(), |o| o.min(data.len()));
+    let n = buf.len().min(data.len() - start);
+    buf[..n].copy_from_slice(&data[start..start + n]);
+    n
+}
+
+impl ZipSource for Vec<u8> {
+    fn len(&self) -> io::Result<u64> { Ok(self.as_slice().len() as u64) }
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> { Ok(read_slice(self, offset, buf)) }
+}
+
+impl ZipSource for Arc<[u8]> {
+    fn len(&self) -> io::Result<u64> { Ok(<[u8]>::len(self) as u64) }
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> { Ok(read_slice(self, offset, buf)) }
+}
+
+impl ZipSource for &'static [u8] {
+    fn len(&self) -> io::Result<u64> { Ok(<[u8]>::len(self) as u64) }
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> { Ok(read_slice(self, offset, buf)) }
+}
+
+impl<S: ZipSource + ?Sized> ZipSource for Arc<S> {
+    fn len(&self) -> io::Result<u64> { (**self).len() }
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> { (**self).read_at(offset, buf) }
+}
+
+impl<S: ZipSource + ?Sized> ZipSource for Box<S> {
+    fn len(&self) -> io::Result<u64> { (**self).len() }
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> { (**self).read_at(offset, buf) }
+}
+
+/// Adapts a [`ZipSource`] to `Read + Seek`, for the eager constructors.
+///
+/// Each reader keeps its own position and turns reads into
+/// [`read_at`](ZipSource::read_at) calls, so it never copies the archive.
+///
+/// # Example
+/// ```no_run
+/// # use excel_parser::{SourceReader, ZipFs, ZipFsError};
+/// # fn main() -> Result<(), ZipFsError> {
+/// let bytes: std::sync::Arc<[u8]> = std::fs::read("report.xlsx")?.into();
+/// let fs = ZipFs::new(SourceReader::new(bytes), None, None)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SourceReader<S> {
+    source: S,
+    pos: u64,
+}
+
+impl<S: ZipSource> SourceReader<S> {
+    /// Creates a reader positioned at the start of `source`.
+    pub fn new(source: S) -> Self {
+        Self { source, pos: 0 }
+    }
+
+    /// Returns the wrapped source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: ZipSource> Read for SourceReader<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.source.read_at(self.pos, buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<S: ZipSource> Seek for SourceReader<S> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+            SeekFrom::End(delta) => self.source.len()?.checked_add_signed(delta),
+        };
+        self.pos = target.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "seek to a negative position")
+        })?;
+        Ok(self.pos)
+    }
+}
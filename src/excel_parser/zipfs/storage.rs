@@ -0,0 +1,40 @@
+#[cfg(all(feature = "mmap", unix))]
+use super::mmap::MappedSlice;
+
+/// Content of a loaded file.
+#[derive(Debug, Clone)]
+pub(super) enum Content {
+    /// Decompressed (or copied) into memory owned by the file system.
+    Owned(Vec<u8>),
+    /// A `Stored` entry borrowed from a memory‑mapped archive.
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(MappedSlice),
+}
+
+impl Content {
+    pub(super) fn as_slice(&self) -> &[u8] {
+        match self {
+            Content::Owned(bytes) => bytes,
+            #[cfg(all(feature = "mmap", unix))]
+            Content::Mapped(slice) => slice.as_slice(),
+        }
+    }
+
+    /// Heap bytes held by the content (its capacity); mapped data holds none.
+    pub(super) fn heap_size(&self) -> usize {
+        match self {
+            Content::Owned(bytes) => bytes.capacity(),
+            #[cfg(all(feature = "mmap", unix))]
+            Content::Mapped(_) => 0,
+        }
+    }
+
+    /// Returns the content as an owned buffer, copying mapped data.
+    pub(super) fn into_vec(self) -> Vec<u8> {
+        match self {
+            Content::Owned(bytes) => bytes,
+            #[cfg(all(feature = "mmap", unix))]
+            Content::Mapped(slice) => slice.as_slice().to_vec(),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::io::Read;
+use std::sync::{Arc, OnceLock};
+use zip::read::read_zipfile_from_stream;
+use super::{BudgetExceeded, ContentTypes, EntryMeta, EntrySelector, ZipFs, ZipFsBuilder, ZipFsError, normalize_path};
+use super::storage::Content;
+
+impl ZipFs {
+    /// Reads the archive sequentially from its local headers, for
+    /// [`ZipFsBuilder::build_stream`].
+    pub(super) fn load_stream<R: Read>(reader: R, options: &ZipFsBuilder) -> Result<Self, ZipFsError> {
+        // Read one byte past the limit to tell an oversized stream from one that just fits.
+        let budget = options.max_archive_size.map_or(u64::MAX, |l| l.saturating_add(1));
+        let mut reader = reader.take(budget);
+
+        let mut fs = ZipFs::with_capacity(0, options);
+        let result = fs.load_stream_entries(&mut reader, options);
+
+        let consumed = budget - reader.limit();
+        if let Some(limit) = options.max_archive_size && consumed > limit {
+            return Err(ZipFsError::ArchiveTooLarge(consumed, limit));
+        }
+        result?;
+        fs.check_not_empty(options)?;
+        Ok(fs)
+    }
+
+    /// Loads the matching entries of a stream positioned at its first local
+    /// header, stopping at the central directory.
+    ///
+    /// # Errors
+    /// As `load_entries`, plus `ZipFsError::Zip` for entries the stream
+    /// reader can't handle.
+    fn load_stream_entries<R: Read>(&mut self, reader: &mut R, options: &ZipFsBuilder) -> Result<(), ZipFsError> {
+        // `[Content_Types].xml` is only known once it passes by.
+        let mut selector = EntrySelector {
+            filter: options.filter.as_ref(),
+            content_types: options.content_types.as_ref().map(|accepted| (ContentTypes::default(), accepted)),
+        };
+        let mut awaiting_types = selector.content_types.is_some();
+        let mut total: u64 = 0;
+        let mut exceeded: Option<BudgetExceeded> = None;
+
+        while let Some(file) = read_zipfile_from_stream(reader)? {
+            let name: Arc<str> = Arc::from(normalize_path(file.name()));
+            let types_part = awaiting_types && &*name == ContentTypes::PATH;
+
+            // Dropping a rejected entry skips its data.
+            if !types_part && !selector.accepts(&name) { continue; }
+            let Some(name) = self.resolve_duplicate(name, options)? else { continue };
+
+            if let Some(exceeded) = exceeded.as_mut() {
+                exceeded.skipped.push(name.to_string());
+                continue;
+            }
+            if let Some(limit) = options.max_entries && self.files.len() >= limit {
+                return Err(ZipFsError::TooManyEntries(limit + 1, limit));
+            }
+
+            let meta = EntryMeta::from_file(&file);
+            let remaining = options.max_total_size.map(|l| l.saturating_sub(total));
+            let limit = options.max_entry_size.into_iter().chain(remaining).min();
+            let content = match Self::read_file_content(file, limit) {
+                Ok(content) => content,
+                Err(partial) => {
+                    self.check_crc(options, &name, &meta, &partial);
+                    continue;
+                }
+            };
+
+            if types_part {
+                let types = ContentTypes::parse(&content).map_err(ZipFsError::InvalidContentTypes)?;
+                if let Some((parsed, _)) = selector.content_types.as_mut() { *parsed = types; }
+                awaiting_types = false;
+                if !selector.accepts(&name) { continue; }
+            }
+
+            let Some(content) = Self::check_limits(options, &name, content, &mut total, 0, &mut exceeded)? else {
+                continue;
+            };
+            self.insert_loaded(name, OnceLock::from(Content::Owned(content)), meta);
+        }
+
+        match exceeded {
+            Some(exceeded) => Err(ZipFsError::TotalTooLarge(exceeded)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use zip::CompressionMethod;
+    use crate::excel_parser::test_util::{zip_archive, zip_archive_with};
+
+    /// Offset of the central directory, where a stream load stops reading.
+    fn central_directory_offset(archive: &[u8]) -> usize {
+        archive.windows(4).position(|w| w == 0x0201_4b50u32.to_le_bytes()).unwrap()
+    }
+
+    #[test]
+    fn stored_and_deflated_entries_load_like_build() {
+        let large = "<row/>".repeat(1000);
+        let archive = zip_archive_with(&[
+            ("xl/stored.xml", large.as_bytes(), CompressionMethod::Stored),
+            ("xl/deflated.xml", large.as_bytes(), CompressionMethod::Deflated),
+            ("xl/empty.xml", b"", CompressionMethod::Deflated),
+        ]);
+
+        let streamed = ZipFs::builder().build_stream(Cursor::new(&archive)).unwrap();
+        let built = ZipFs::builder().build(Cursor::new(&archive)).unwrap();
+        for path in ["xl/stored.xml", "xl/deflated.xml", "xl/empty.xml"] {
+            assert_eq!(streamed.get_file(path), built.get_file(path), "{path}");
+            assert_eq!(streamed.entry_meta(path), built.entry_meta(path), "{path}");
+        }
+        assert_eq!(streamed.get_file("xl/stored.xml"), Some(large.as_bytes()));
+        assert_eq!(streamed.entry_meta("xl/stored.xml").unwrap().compression, CompressionMethod::Stored);
+        assert_eq!(streamed.entry_meta("xl/deflated.xml").unwrap().compression, CompressionMethod::Deflated);
+        assert!(streamed.entry_meta("xl/deflated.xml").unwrap().compressed_size < large.len() as u64);
+    }
+
+    #[test]
+    fn data_descriptor_entries_fail_the_load() {
+        let mut archive = zip_archive(&[("xl/workbook.xml", b"<workbook/>")]);
+        // General purpose flag bit 3: sizes and CRC follow the data.
+        archive[6] |= 1 << 3;
+        assert!(matches!(ZipFs::builder().build_stream(Cursor::new(archive)), Err(ZipFsError::Zip(_))));
+    }
+
+    #[test]
+    fn truncated_streams_fail_instead_of_panicking() {
+        let archive = zip_archive_with(&[
+            ("xl/stored.xml", b"<worksheet>stored</worksheet>", CompressionMethod::Stored),
+            ("xl/deflated.xml", b"<worksheet>deflated</worksheet>", CompressionMethod::Deflated),
+        ]);
+        // Past the last entry the central directory is never read, so every
+        // shorter stream is missing part of an entry or the terminator.
+        for cut in 0..central_directory_offset(&archive) {
+            let result = ZipFs::builder().build_stream(Cursor::new(&archive[..cut]));
+            assert!(result.is_err(), "cut at {cut}: {result:?}");
+        }
+        assert!(ZipFs::builder().build_stream(Cursor::new(&archive)).is_ok());
+    }
+}
@@ -0,0 +1,182 @@
+use std::io::{Seek, Write};
+use zip::{AesMode, CompressionMethod, ZipWriter, write::SimpleFileOptions};
+use super::{ContentTypes, ZipFs, ZipFsError};
+
+impl ZipFs {
+    /// Writes the file system as a new ZIP archive and returns the writer.
+    ///
+    /// Every file is written under its normalized path, with
+    /// `[Content_Types].xml` first (as Excel does) and the rest sorted by
+    /// path. In lazy mode files that weren't replaced or inserted are copied
+    /// from the source archive as stored, without being decompressed. Other
+    /// files are compressed again: entries stored uncompressed in the archive
+    /// stay `Stored`, everything else is `Deflated`.
+    ///
+    /// Entries that were encrypted are encrypted again with the password the
+    /// archive was loaded with, using AES‑256 whatever the original scheme
+    /// (ZipCrypto can't be written).
+    ///
+    /// Only loaded files are written: entries left out by the filters are
+    /// not part of the new archive.
+    ///
+    /// # Arguments
+    /// * `writer` – destination of the archive.
+    ///
+    /// # Errors
+    /// * `ZipFsError::Zip` – writing an entry failed, or a lazily loaded
+    ///   entry couldn't be copied from the source archive.
+    /// * `ZipFsError::UnsupportedEncryption` – an encrypted entry can't be
+    ///   encrypted again because the password isn't valid UTF‑8.
+    /// * `ZipFsError::Io` – I/O error on `writer`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use excel_parser::{ZipFs, ZipFsError};
+    /// # fn main() -> Result<(), ZipFsError> {
+    /// let mut fs = ZipFs::builder().build_lazy(std::fs::File::open("report.xlsx")?)?;
+    /// fs.remove_file("xl/printerSettings/printerSettings1.bin");
+    /// fs.insert_file("docProps/custom.xml", b"<Properties/>".to_vec())?;
+    /// fs.write_to(std::fs::File::create("report_clean.xlsx")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_to<W: Write + Seek>(&self, writer: W) -> Result<W, ZipFsError> {
+        let mut paths: Vec<_> = self.files.keys().collect();
+        paths.sort_unstable_by_key(|path| (&***path != ContentTypes::PATH, *path));
+
+        let mut zip = ZipWriter::new(writer);
+        for path in paths {
+            let meta = self.meta.get(path);
+            let encrypted = meta.is_some_and(|m| m.encrypted);
+
+            // Raw copies lose the encryption flag, so encrypted entries are always re-encoded.
+            if !encrypted && !self.modified.contains(path) && let Some(source) = &self.source
+                && let Some(copied) = source.raw_copy(path, path, &mut zip)
+            {
+                copied?;
+                continue;
+            }
+
+            // Inserted and replaced files, and content held in memory.
+            let Some(content) = self.get_file(path) else { continue };
+            let compression = match meta {
+                Some(meta) if meta.compression == CompressionMethod::Stored => CompressionMethod::Stored,
+                _ => CompressionMethod::Deflated,
+            };
+            let options = SimpleFileOptions::default()
+                .compression_method(compression)
+                .large_file(content.len() as u64 >= u32::MAX as u64);
+            let options = match &self.password {
+                Some(password) if encrypted => {
+                    let password = std::str::from_utf8(password.as_bytes()).map_err(|_| {
+                        ZipFsError::UnsupportedEncryption(path.to_string(), "AES needs a UTF-8 password".into())
+                    })?;
+                    options.with_aes_encryption(AesMode::Aes256, password)
+                }
+                _ => options,
+            };
+            zip.start_file(&**path, options)?;
+            zip.write_all(content)?;
+        }
+        Ok(zip.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use zip::ZipArchive;
+    use crate::excel_parser::test_util::{encrypted_zip_archive, zip_archive_with, zipcrypto_zip_archive};
+
+    fn sample_archive() -> Vec<u8> {
+        zip_archive_with(&[
+            ("xl/workbook.xml", b"<workbook/>", CompressionMethod::Deflated),
+            ("[Content_Types].xml", b"<Types/>", CompressionMethod::Deflated),
+            ("xl/media/image1.png", b"\x89PNG....", CompressionMethod::Stored),
+            ("xl/printerSettings/printerSettings1.bin", b"\0\0\0", CompressionMethod::Deflated),
+        ])
+    }
+
+    fn write(fs: &ZipFs) -> Vec<u8> {
+        fs.write_to(Cursor::new(Vec::new())).unwrap().into_inner()
+    }
+
+    fn entry_names(archive: &[u8]) -> Vec<String> {
+        let mut archive = ZipArchive::new(Cursor::new(archive)).unwrap();
+        (0..archive.len()).map(|i| archive.by_index_raw(i).unwrap().name().to_owned()).collect()
+    }
+
+    fn edit(fs: &mut ZipFs) {
+        assert!(fs.remove_file("xl/printerSettings/printerSettings1.bin"));
+        fs.insert_file("xl/workbook.xml", b"<workbook><sheets/></workbook>".to_vec()).unwrap();
+        fs.insert_file("docProps/custom.xml", b"<Properties/>".to_vec()).unwrap();
+    }
+
+    #[test]
+    fn written_archives_read_back_with_the_same_files() {
+        for lazy in [false, true] {
+            let builder = ZipFs::builder();
+            let mut fs = if lazy {
+                builder.build_lazy(Cursor::new(sample_archive())).unwrap()
+            } else {
+                builder.build(Cursor::new(sample_archive())).unwrap()
+            };
+            edit(&mut fs);
+            let written = write(&fs);
+
+            // `[Content_Types].xml` first, then sorted by path.
+            assert_eq!(
+                entry_names(&written),
+                ["[Content_Types].xml", "docProps/custom.xml", "xl/media/image1.png", "xl/workbook.xml"],
+                "lazy: {lazy}"
+            );
+            let read_back = ZipFs::new(Cursor::new(written), None, None).unwrap();
+            assert_eq!(read_back.len(), fs.len(), "lazy: {lazy}");
+            for path in fs.paths() {
+                assert_eq!(read_back.get_file(path), fs.get_file(path), "lazy: {lazy}, {path}");
+            }
+            assert_eq!(read_back.get_file("xl/workbook.xml"), Some(&b"<workbook><sheets/></workbook>"[..]));
+            assert!(read_back.get_file("xl/printerSettings/printerSettings1.bin").is_none());
+
+            let compression = |path| read_back.entry_meta(path).unwrap().compression;
+            assert_eq!(compression("xl/media/image1.png"), CompressionMethod::Stored, "lazy: {lazy}");
+            assert_eq!(compression("docProps/custom.xml"), CompressionMethod::Deflated, "lazy: {lazy}");
+        }
+    }
+
+    #[test]
+    fn untouched_lazy_entries_are_copied_without_recompressing() {
+        let fs = ZipFs::builder().build_lazy(Cursor::new(sample_archive())).unwrap();
+        let read_back = ZipFs::new(Cursor::new(write(&fs)), None, None).unwrap();
+        for path in fs.paths() {
+            assert_eq!(read_back.entry_meta(path), fs.entry_meta(path), "{path}");
+        }
+        // Nothing had to be decoded to write the copy.
+        assert!(fs.files.values().all(|slot| slot.get().is_none()));
+    }
+
+    #[test]
+    fn encrypted_entries_are_written_with_aes() {
+        let entries: [(&str, &[u8]); 1] = [("xl/workbook.xml", b"<workbook/>")];
+        for archive in [encrypted_zip_archive(&entries, "pw"), zipcrypto_zip_archive(&entries, "pw")] {
+            for lazy in [false, true] {
+                let builder = ZipFs::builder().password("pw");
+                let fs = if lazy {
+                    builder.build_lazy(Cursor::new(archive.clone())).unwrap()
+                } else {
+                    builder.build(Cursor::new(&archive)).unwrap()
+                };
+                let written = write(&fs);
+
+                assert!(matches!(
+                    ZipFs::new(Cursor::new(&written), None, None),
+                    Err(ZipFsError::PasswordRequired(_))
+                ));
+                let read_back = ZipFs::new_with_password(Cursor::new(&written), None, None, b"pw").unwrap();
+                assert_eq!(read_back.get_file("xl/workbook.xml"), Some(&b"<workbook/>"[..]), "lazy: {lazy}");
+                assert!(read_back.entry_meta("xl/workbook.xml").unwrap().encrypted);
+            }
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Reading `.xlsx` workbooks: the archive (`ZipFs`), shared strings,
+//! worksheets, styles and the other workbook parts.
+
+mod excel_parser;
+
+pub use excel_parser::*;
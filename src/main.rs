@@ -1,29 +1,44 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::time::Instant;
 
-mod excel_parser;
-use excel_parser::{ZipFs, FilterSet, ZipFsError, SharedStrings};
+use excel_parser::{ZipFs, FilterSet, ZipFsError, SharedStrings, PivotCacheDef, CacheField, Workbook, Worksheet, CellType};
 
 struct ExcelParser {
     excel_fs: ZipFs,
     shared_strings: Option<SharedStrings>,
+    workbook: Option<Workbook>,
+    /// Worksheets parsed so far, by sheet name
+    worksheets: HashMap<String, Worksheet>,
+}
+
+/// Wraps an XML parsing failure of one workbook part into a `ZipFsError`
+fn xml_error(part: &str, e: quick_xml::Error) -> ZipFsError {
+    ZipFsError::Io(std::io::Error::other(format!("Failed to parse {}: {}", part, e)))
 }
 
 impl ExcelParser {
     pub fn new(excel_file: File, size_limit: u64) -> Result<Self, ZipFsError> {
         let filters = FilterSet::new()
             .add_exact("xl/sharedStrings.xml")?
-            .add_glob("xl/worksheets/*.xml")?;
+            .add_glob("xl/worksheets/*.xml")?
+            .add_glob(PivotCacheDef::GLOB)?
+            .add_exact(Workbook::PATH)?
+            .add_exact(Workbook::RELS_PATH)?
+            .add_glob(Workbook::EXTERNAL_LINKS_GLOB)?;
 
-        let fs = ZipFs::new(
-            excel_file,
-            Some(filters),
-            Some(size_limit))?; 
+        let fs = ZipFs::builder()
+            .filter(filters)
+            .max_archive_size(size_limit)
+            .error_on_empty(true)
+            .build(excel_file)?;
 
-        Ok(ExcelParser { excel_fs: fs, shared_strings: None })
+        Ok(ExcelParser { excel_fs: fs, shared_strings: None, workbook: None, worksheets: HashMap::new() })
     }
 
     pub fn parse(&mut self) -> Result<(), ZipFsError> {
+        let workbook = Workbook::load(&self.excel_fs).map_err(|e| xml_error("workbook", e))?;
+        self.workbook = Some(workbook);
         Ok(())
     }
 
@@ -34,7 +49,7 @@ impl ExcelParser {
         if let Some(content) = self.excel_fs.get_file("xl/sharedStrings.xml") {
             match SharedStrings::load(content) {
                 Ok(s) => self.shared_strings = Some(s),
-                Err(e) => return Err(ZipFsError::Io(std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to parse shared strings: {}", e)))),
+                Err(e) => return Err(xml_error("shared strings", e)),
             }
         }
         
@@ -45,11 +60,53 @@ impl ExcelParser {
         
         Ok(())
     }
+
+    /// Parse the source field lists of all pivot caches in the Excel file
+    pub fn parse_pivot_caches(&self) -> Result<Vec<Vec<CacheField>>, ZipFsError> {
+        self.excel_fs.list_files("xl/pivotCache").into_iter()
+            .filter(|path| path.ends_with(".xml"))
+            .filter_map(|path| self.excel_fs.get_file(path))
+            .map(|content| PivotCacheDef::parse(content).map_err(|e| xml_error("pivot cache", e)))
+            .collect()
+    }
+
+    /// Collect every text cell (shared or inline string) of a sheet with its A1 reference.
+    /// Shared strings resolve only if `parse_shared_strings` was called; the sheet is parsed once and kept
+    pub fn sheet_texts(&mut self, sheet_name: &str) -> Result<Vec<(String, &str)>, ZipFsError> {
+        if self.workbook.is_none() { self.parse()?; }
+
+        if !self.worksheets.contains_key(sheet_name) {
+            let not_found = |what: &str| ZipFsError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound, format!("{} not found: {}", what, sheet_name)));
+
+            let part = self.workbook.as_ref()
+                .and_then(|w| w.sheet_part(sheet_name))
+                .ok_or_else(|| not_found("Sheet"))?;
+            let content = self.excel_fs.get_file(part).ok_or_else(|| not_found("Worksheet part of"))?;
+            let sheet = Worksheet::parse(content).map_err(|e| xml_error("worksheet", e))?;
+            self.worksheets.insert(sheet_name.to_owned(), sheet);
+        }
+
+        let shared = self.shared_strings.as_ref();
+        let texts = self.worksheets[sheet_name].rows().iter()
+            .flat_map(|row| row.cells())
+            .filter_map(|cell| {
+                let text = match cell.cell_type() {
+                    CellType::SharedString => cell.text(shared?)?,
+                    CellType::InlineString => cell.raw()?,
+                    _ => return None,
+                };
+                Some((cell.reference(), text))
+            })
+            .collect();
+
+        Ok(texts)
+    }
 }
 
 fn run_fuzzy_search(shared: &SharedStrings, query: &str, threshold: i64) {
     let start = Instant::now();
-    let results = shared.fuzzy_find(query, threshold);
+    let results = shared.fuzzy_find_strings(query, threshold);
     let elapsed = start.elapsed();
     
     println!("\n🔍 Fuzzy search for \"{}\" (threshold: {}):", query, threshold);
@@ -59,10 +116,8 @@ fn run_fuzzy_search(shared: &SharedStrings, query: &str, threshold: i64) {
         println!("   No matches found.");
     } else {
         // Show top 10 results
-        for (i, (idx, score)) in results.iter().take(10).enumerate() {
-            if let Some(s) = shared.get(*idx) {
-                println!("   [{}] (score: {:4}) {}", i, score, s);
-            }
+        for (i, (s, score)) in results.iter().take(10).enumerate() {
+            println!("   [{}] (score: {:4}) {}", i, score, s);
         }
         if results.len() > 10 {
             println!("   ... and {} more", results.len() - 10);
@@ -79,6 +134,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Parse shared strings with benchmark
     parser.parse_shared_strings()?;
     
+    // List the text cells of the first sheet
+    let first_sheet = parser.workbook.as_ref()
+        .and_then(|w| w.sheet_names().first().map(|name| name.to_string()));
+    if let Some(name) = first_sheet {
+        let texts = parser.sheet_texts(&name)?;
+        println!("📝 Sheet \"{}\": {} text cells", name, texts.len());
+        if let Some((cell, text)) = texts.first() {
+            println!("   {}: {}", cell, text);
+        }
+    }
+
     // Get shared strings reference
     let shared = match parser.shared_strings {
         Some(ref s) => s,
@@ -89,6 +155,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     println!("\n📊 Loaded {} shared strings", shared.len());
+
+    if let Some(workbook) = &parser.workbook {
+        for link in workbook.external_links() {
+            println!("🔗 External link: {}", link);
+        }
+    }
+
+    for fields in parser.parse_pivot_caches()? {
+        let names: Vec<_> = fields.iter().map(|f| f.name.as_str()).collect();
+        println!("📎 Pivot cache fields: {}", names.join(", "));
+    }
     
     // Run fuzzy search tests
     println!("\n========================================");
@@ -135,3 +212,56 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    const WORKBOOK: &str = r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+  <sheets><sheet name="Расписание" sheetId="1" r:id="rId1"/></sheets>
+</workbook>"#;
+    const RELS: &str = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
+</Relationships>"#;
+    const SHARED: &str = r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><si><t>Курс</t></si><si><t>Суббота</t></si></sst>"#;
+    const SHEET: &str = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>
+  <row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1"><v>3</v></c><c r="C1" t="inlineStr"><is><t>Лекция</t></is></c></row>
+  <row r="3"><c r="B3" t="s"><v>1</v></c><c r="D3" t="b"><v>1</v></c><c r="E3" t="str"><f>A1</f><v>Курс</v></c></row>
+</sheetData></worksheet>"#;
+
+    /// Writes a minimal workbook with one sheet to a temporary file and opens it.
+    fn open_workbook(name: &str) -> ExcelParser {
+        let path = std::env::temp_dir().join(format!("excel_parser_{}_{name}.xlsx", std::process::id()));
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        for (part, xml) in [(Workbook::PATH, WORKBOOK), (Workbook::RELS_PATH, RELS), ("xl/sharedStrings.xml", SHARED), ("xl/worksheets/sheet1.xml", SHEET)] {
+            zip.start_file(part, SimpleFileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        // Entries are loaded eagerly, so the file can go once the parser is built.
+        let parser = ExcelParser::new(File::open(&path).unwrap(), 1 << 20);
+        std::fs::remove_file(&path).unwrap();
+        parser.unwrap()
+    }
+
+    #[test]
+    fn sheet_texts_returns_shared_and_inline_text_cells() {
+        let mut parser = open_workbook("texts");
+        parser.parse_shared_strings().unwrap();
+
+        let texts = parser.sheet_texts("Расписание").unwrap();
+        assert_eq!(texts, [("A1".to_owned(), "Курс"), ("C1".to_owned(), "Лекция"), ("B3".to_owned(), "Суббота")]);
+    }
+
+    #[test]
+    fn sheet_texts_without_shared_strings_keeps_inline_text() {
+        let mut parser = open_workbook("inline");
+
+        let texts = parser.sheet_texts("Расписание").unwrap();
+        assert_eq!(texts, [("C1".to_owned(), "Лекция")]);
+        assert!(parser.sheet_texts("Missing").is_err());
+    }
+}